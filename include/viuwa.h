@@ -0,0 +1,38 @@
+/* C bindings for rendering images to ANSI with viuwa, built with the `capi` feature:
+ *
+ *     cargo rustc --lib --crate-type cdylib --release --features capi
+ */
+#ifndef VIUWA_H
+#define VIUWA_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Called with each chunk of rendered ANSI and the user_data given to the render function,
+ * returning anything but 0 stops rendering */
+typedef int (*viuwa_write_fn)(const uint8_t *bytes, size_t len, void *user_data);
+
+/* Render the image file at path into an area of columns and rows, passing the ANSI to write.
+ * options is NULL or the toml of a config file, e.g. "color = \"ansi-color\"\nrenderer = \"glyph\"".
+ * Returns 0 on success, or -1 with the reason from viuwa_last_error(). */
+int viuwa_render_path(const char *path, const char *options, uint16_t columns, uint16_t rows, viuwa_write_fn write,
+                      void *user_data);
+
+/* Render an encoded image of len bytes into an area of columns and rows, passing the ANSI to write.
+ * The format is guessed from the bytes, options are as for viuwa_render_path. */
+int viuwa_render_buffer(const uint8_t *bytes, size_t len, const char *options, uint16_t columns, uint16_t rows,
+                        viuwa_write_fn write, void *user_data);
+
+/* The message of the last error of a render on this thread, or NULL if it succeeded.
+ * Owned by viuwa and valid until the next render on this thread. */
+const char *viuwa_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* VIUWA_H */
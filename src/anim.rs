@@ -0,0 +1,216 @@
+//! Looping GIF animations in the windowed viewer. Each frame is converted to ANSI the first time it's drawn,
+//! and the loops after write the cached ANSI, so a looping animation costs little more than writing it.
+use std::time::{Duration, Instant};
+
+use image::{codecs::gif::GifDecoder, AnimationDecoder, Frame, Frames};
+
+use super::*;
+
+/// GIF frames shown for less than this are shown for [`DEFAULT_FRAME_DELAY`] instead, as browsers do
+pub const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+/// How long GIF frames without a (reasonable) delay are shown
+pub const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+/// How many bytes of converted frames are cached without a memory budget
+pub const FRAME_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// How long a GIF frame is shown, its own delay unless it's shorter than [`MIN_FRAME_DELAY`]
+/// ```
+/// use image::{Delay, Frame, RgbaImage};
+/// let frame = |ms| Frame::from_parts(RgbaImage::new(1, 1), 0, 0, Delay::from_numer_denom_ms(ms, 1));
+/// assert_eq!(viuwa::frame_delay(&frame(50)).as_millis(), 50);
+/// assert_eq!(viuwa::frame_delay(&frame(0)), viuwa::DEFAULT_FRAME_DELAY);
+/// ```
+#[inline]
+pub fn frame_delay(frame: &Frame) -> Duration {
+    let delay = Duration::from(frame.delay());
+    if delay < MIN_FRAME_DELAY {
+        DEFAULT_FRAME_DELAY
+    } else {
+        delay
+    }
+}
+
+/// Converts a decoded frame to the pixels of the viewer
+pub type ConvertFrame<P> = Box<dyn Fn(DynamicImage) -> ImageBuffer<P, Vec<<P as viuwa_image::Pixel>::Scalar>>>;
+
+/// A looping GIF animation, decoded a frame at a time in order, with the ANSI of the frames drawn
+/// cached within a memory limit until the image is drawn differently
+pub struct Animation<P: Pixel>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    path: PathBuf,
+    convert: ConvertFrame<P>,
+    /// The decoder, and the index of the frame it decodes next
+    frames: Option<(Frames<'static>, usize)>,
+    /// How long each frame is shown, the default until the frame is decoded
+    delays: Vec<Duration>,
+    /// The ANSI of each frame as it was last drawn, if it's cached
+    cache: Vec<Option<Vec<u8>>>,
+    /// The bytes of ANSI cached
+    cached: u64,
+    /// The most bytes of ANSI cached
+    limit: u64,
+    /// The index of the displayed frame
+    pub current: usize,
+    /// When the displayed frame was due, the next is due its delay at the playback speed after
+    start: Instant,
+    /// The playback speed, a multiple of the frames' own delays
+    pub speed: f32,
+    /// Whether the animation is held on the displayed frame, e.g. while the help is shown
+    pub paused: bool,
+    /// The average time writing and flushing a frame takes
+    latency: Duration,
+    /// The frames skipped to keep up with the frame delays
+    pub dropped: usize,
+}
+impl<P: Pixel> Animation<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// The animation of `count` frames of the GIF at `path`, displaying the frame at `current`,
+    /// with at most `limit` bytes of converted frames cached
+    pub fn new(path: PathBuf, count: usize, current: usize, limit: u64, convert: ConvertFrame<P>) -> Result<Self> {
+        let mut anim = Self {
+            path,
+            convert,
+            frames: None,
+            delays: vec![DEFAULT_FRAME_DELAY; count],
+            cache: vec![None; count],
+            cached: 0,
+            limit,
+            current,
+            start: Instant::now(),
+            speed: 1.,
+            paused: false,
+            latency: Duration::ZERO,
+            dropped: 0,
+        };
+        // only for its delay, the displayed frame is already decoded
+        anim.decode(current)?;
+        anim.restart();
+        Ok(anim)
+    }
+    /// The number of frames
+    #[inline]
+    pub fn count(&self) -> usize { self.delays.len() }
+    /// How long the frame at `index` is shown at the playback speed
+    #[inline]
+    fn delay(&self, index: usize) -> Duration { self.delays[index].div_f32(self.speed) }
+    /// When the next frame is due, recomputed from the frames' own delays if the speed changes
+    #[inline]
+    pub fn due(&self) -> Instant { self.start + self.delay(self.current) }
+    /// Move on to the next frame due, back to the first after the last.
+    ///
+    /// Frames that would be replaced before they're written, from how long writing a frame takes, are skipped,
+    /// so a slow terminal drops frames instead of slowing the animation down.
+    pub fn advance(&mut self) {
+        self.start = self.due();
+        self.current = (self.current + 1) % self.count();
+        let done = Instant::now() + self.latency;
+        let mut skipped = 0;
+        while self.due() <= done {
+            // more than a loop behind, e.g. after waiting at the command prompt, start over from this frame
+            if skipped + 1 == self.count() {
+                self.start = Instant::now();
+                break;
+            }
+            self.start = self.due();
+            self.current = (self.current + 1) % self.count();
+            skipped += 1;
+        }
+        self.dropped += skipped;
+    }
+    /// Display the frame at `index` for its whole delay from now
+    #[inline]
+    pub fn seek(&mut self, index: usize) {
+        self.current = index % self.count();
+        self.restart();
+    }
+    /// The index of the frame shown `time` into the animation at its own speed, looping after the last frame.
+    /// Frames not decoded yet count for [`DEFAULT_FRAME_DELAY`].
+    pub fn frame_at(&self, time: Duration) -> usize {
+        let total: Duration = self.delays.iter().sum();
+        let mut time = Duration::from_nanos((time.as_nanos() % total.as_nanos().max(1)) as u64);
+        for (index, &delay) in self.delays.iter().enumerate() {
+            match time.checked_sub(delay) {
+                Some(left) => time = left,
+                None => return index,
+            }
+        }
+        0
+    }
+    /// Record how long writing and flushing the displayed frame took
+    #[inline]
+    pub fn wrote(&mut self, took: Duration) {
+        self.latency = if self.latency.is_zero() { took } else { (self.latency * 7 + took) / 8 };
+    }
+    /// Decode the frame at `index`, in order from the frame decoded last or from the start of the file
+    fn decode(&mut self, index: usize) -> Result<ImageBuffer<P, Vec<P::Scalar>>> {
+        let (frames, next) = match &mut self.frames {
+            Some((frames, next)) if *next <= index => (frames, next),
+            frames => {
+                let decoder = GifDecoder::new(BufReader::new(File::open(&self.path)?))?;
+                let (frames, next) = frames.insert((decoder.into_frames(), 0));
+                (frames, next)
+            }
+        };
+        let frame = frames
+            .nth(index - *next)
+            .ok_or_else(|| anyhow!("GIF frame {} is out of range", index + 1))?
+            .context("Failed to decode GIF frame")?;
+        *next = index + 1;
+        self.delays[index] = frame_delay(&frame);
+        Ok((self.convert)(DynamicImage::ImageRgba8(frame.into_buffer())))
+    }
+    /// Decode the displayed frame
+    #[inline]
+    pub fn decode_current(&mut self) -> Result<ImageBuffer<P, Vec<P::Scalar>>> { self.decode(self.current) }
+    /// Take the cached ANSI of the displayed frame, to be [cached](Self::cache) again once it's drawn
+    #[inline]
+    pub fn take_cached(&mut self) -> Option<Vec<u8>> {
+        let ansi = self.cache[self.current].take()?;
+        self.cached -= ansi.len() as u64;
+        Some(ansi)
+    }
+    /// Cache the ANSI of the displayed frame as drawn, if it fits within the limit
+    #[inline]
+    pub fn cache(&mut self, ansi: Vec<u8>) {
+        if self.cached + ansi.len() as u64 <= self.limit {
+            self.cached += ansi.len() as u64;
+            self.cache[self.current] = Some(ansi);
+        }
+    }
+    /// Drop the cached frames, when the image is drawn at another size or with other settings
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.cache.iter_mut().for_each(|ansi| *ansi = None);
+        self.cached = 0;
+    }
+    /// Show the displayed frame for its whole delay from now, when it's drawn again or the animation is resumed
+    #[inline]
+    pub fn restart(&mut self) { self.start = Instant::now(); }
+}
+
+/// Create a new viuwa instance looping the configured GIF of `count` frames from `orig`, the configured frame, and spawn it
+pub fn animated(orig: DynamicImage, config: Config, count: usize) -> Result<()> {
+    trace!("animated");
+    let path = config.image.clone();
+    // half the budget, leaving the rest for decoding and resizing the frames
+    let limit = Budget::new(&config).map_or(FRAME_CACHE_BYTES, |b| b.0 / 2);
+    // decoded the same way as the displayed frame
+    let premultiply = config.renderer == Renderer::Foreground;
+    let premultiplied = move |frame| if premultiply { premultiplied(frame) } else { frame };
+    // GIF frames are always 8-bit, and every frame is displayed in color if the displayed one is
+    if orig.color().has_color() {
+        let anim = Animation::new(path, count, config.page, limit, Box::new(move |frame| premultiplied(frame).into_rgb8()))?;
+        Viuwa::builder().image(orig.into_rgb8()).animation(anim).config(config).build()?.spawn()?;
+    } else {
+        let anim =
+            Animation::new(path, count, config.page, limit, Box::new(move |frame| premultiplied(frame).into_luma8()))?;
+        Viuwa::builder().image(orig.into_luma8()).animation(anim).config(config).build()?.spawn()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,442 @@
+//! `viuwa art.ans`, classic ANSI art (CP437 characters colored with SGR sequences) parsed into a buffer of cells
+//! and displayed with the VGA palette it was drawn in.
+//!
+//! Inlined, the art is printed whole. Windowed, it's scrolled through a screen at a time, as art is often taller than the terminal.
+use std::path::Path;
+
+use viuwa_ansi::{
+    color::{luma, rgb_to_ansi},
+    consts::SGR_DEFAULT,
+    AnsiSequencer, RgbSequencer, Sequencer,
+};
+
+use super::*;
+
+/// The width of ANSI art without a SAUCE record saying otherwise, that of DOS terminals
+pub const DEFAULT_ART_WIDTH: usize = 80;
+/// The 16 colors of VGA text mode, which ANSI art was drawn in, the bright colors after the normal ones
+pub const VGA_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [170, 0, 0],
+    [0, 170, 0],
+    [170, 85, 0],
+    [0, 0, 170],
+    [170, 0, 170],
+    [0, 170, 170],
+    [170, 170, 170],
+    [85, 85, 85],
+    [255, 85, 85],
+    [85, 255, 85],
+    [255, 255, 85],
+    [85, 85, 255],
+    [255, 85, 255],
+    [85, 255, 255],
+    [255, 255, 255],
+];
+/// The characters of code page 437 by byte, the control characters as the glyphs VGA text mode draws for them
+/// ```
+/// assert_eq!((viuwa::CP437[b'A' as usize], viuwa::CP437[0xDB], viuwa::CP437[0x01]), ('A', '█', '☺'));
+/// ```
+pub const CP437: [char; 256] = [
+    ' ', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', '►', '◄', '↕', '‼', '¶', '§', '▬', '↨',
+    '↑', '↓', '→', '←', '∟', '↔', '▲', '▼', ' ', '!', '"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?', '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G',
+    'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_',
+    '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w',
+    'x', 'y', 'z', '{', '|', '}', '~', '⌂', 'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º',
+    '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫',
+    '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', ' ',
+];
+
+/// Whether a path has the extension of ANSI art
+/// ```
+/// assert!(viuwa::is_ansi_art("ACID-LOGO.ANS".as_ref()));
+/// assert!(!viuwa::is_ansi_art("logo.png".as_ref()));
+/// ```
+#[inline]
+pub fn is_ansi_art(path: &Path) -> bool { path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ans")) }
+
+/// A character cell, its colors indices into [`VGA_PALETTE`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: u8,
+    pub bg: u8,
+}
+impl Default for Cell {
+    #[inline]
+    fn default() -> Self { Self { ch: ' ', fg: 7, bg: 0 } }
+}
+
+/// The SAUCE record appended to ANSI art, describing it and how it's meant to be displayed
+/// ```
+/// let mut record = b"SAUCE00".to_vec();
+/// record.extend(format!("{:35}{:20}{:20}", "Title", "Artist", "Group").bytes());
+/// record.extend([0; 12]);
+/// record.extend([1, 1, 160, 0, 50, 0, 0, 0, 0, 0, 0, 1]);
+/// record.extend([0; 22]);
+/// let sauce = viuwa::Sauce::parse(&record).unwrap();
+/// assert_eq!((sauce.title.as_str(), sauce.author.as_str()), ("Title", "Artist"));
+/// assert_eq!((sauce.width, sauce.ice_colors), (Some(160), true));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sauce {
+    pub title: String,
+    pub author: String,
+    /// The width in columns the art was drawn at, if it's a character file that says
+    pub width: Option<usize>,
+    /// Whether blinking backgrounds are bright backgrounds instead (iCE colors)
+    pub ice_colors: bool,
+}
+impl Sauce {
+    /// The length of a SAUCE record
+    pub const LEN: usize = 128;
+    /// Parse the SAUCE record at the end of a file, if it has one
+    pub fn parse(file: &[u8]) -> Option<Self> {
+        let record = file.get(file.len().checked_sub(Self::LEN)?..)?;
+        if !record.starts_with(b"SAUCE") {
+            return None;
+        }
+        let text =
+            |range: Range<usize>| record[range].iter().map(|&b| CP437[b as usize]).collect::<String>().trim().to_string();
+        // the data type and file type of ANSI art are both 1, where the first type info is the width
+        let width = u16::from_le_bytes([record[96], record[97]]) as usize;
+        let width = (record[94] == 1 && width > 0).then_some(width);
+        Some(Self { title: text(7..42), author: text(42..62), width, ice_colors: record[105] & 1 != 0 })
+    }
+}
+
+/// ANSI art parsed into rows of cells
+/// ```
+/// use viuwa::{AnsiArt, Cell};
+/// let art = AnsiArt::parse(b"\x1b[1;31mA\x1b[0;44m\xdb\r\n\x1b[5CB\x1a");
+/// assert_eq!((art.width, art.height()), (80, 2));
+/// assert_eq!(art.row(0)[..2], [Cell { ch: 'A', fg: 9, bg: 0 }, Cell { ch: '█', fg: 7, bg: 4 }]);
+/// assert_eq!(art.row(1)[5].ch, 'B');
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnsiArt {
+    /// The columns of each row
+    pub width: usize,
+    /// The cells, row by row
+    pub cells: Vec<Cell>,
+    pub sauce: Option<Sauce>,
+}
+impl AnsiArt {
+    /// Read and [`parse`](Self::parse) an ANSI art file
+    pub fn open(path: &Path) -> io::Result<Self> { Ok(Self::parse(&std::fs::read(path)?)) }
+    /// Parse ANSI art, drawing it as a DOS terminal would until the end of file character before its SAUCE record
+    pub fn parse(file: &[u8]) -> Self {
+        trace!("AnsiArt::parse");
+        let sauce = Sauce::parse(file);
+        let width = sauce.as_ref().and_then(|s| s.width).unwrap_or(DEFAULT_ART_WIDTH);
+        let ice_colors = sauce.as_ref().is_some_and(|s| s.ice_colors);
+        let mut art = Self { width, cells: Vec::new(), sauce };
+        let mut pen = Pen::default();
+        let (mut x, mut y, mut saved) = (0usize, 0usize, (0, 0));
+        let mut bytes = file.iter().copied();
+        while let Some(b) = bytes.next() {
+            match b {
+                0x1A => break,
+                b'\r' => x = 0,
+                b'\n' => (x, y) = (0, y + 1),
+                b'\t' => x = ((x / 8 + 1) * 8).min(width - 1),
+                0x1B => {
+                    let Some((params, end)) = csi_bytes(&mut bytes) else { continue };
+                    if end == b'm' {
+                        pen.sgr(&params);
+                        continue;
+                    }
+                    // missing and zero counts and positions are both one
+                    let mut params = params.split(';').map(|p| p.parse::<usize>().unwrap_or(0));
+                    let mut n = || params.next().unwrap_or(1).max(1);
+                    match end {
+                        b'A' => y = y.saturating_sub(n()),
+                        b'B' => y += n(),
+                        b'C' => x = (x + n()).min(width - 1),
+                        b'D' => x = x.saturating_sub(n()),
+                        b'H' | b'f' => (y, x) = (n() - 1, (n() - 1).min(width - 1)),
+                        b'J' if n() == 2 => {
+                            art.cells.clear();
+                            (x, y) = (0, 0);
+                        }
+                        b'K' => {
+                            art.reserve(y);
+                            art.cells[y * width + x..(y + 1) * width].fill(Cell::default());
+                        }
+                        b's' => saved = (x, y),
+                        b'u' => (x, y) = saved,
+                        _ => (),
+                    }
+                }
+                b => {
+                    art.reserve(y);
+                    art.cells[y * width + x] = pen.cell(CP437[b as usize], ice_colors);
+                    x += 1;
+                    if x == width {
+                        (x, y) = (0, y + 1);
+                    }
+                }
+            }
+        }
+        art
+    }
+    /// Add blank rows until there's a row `y`
+    fn reserve(&mut self, y: usize) {
+        if self.cells.len() < (y + 1) * self.width {
+            self.cells.resize((y + 1) * self.width, Cell::default());
+        }
+    }
+    /// The number of rows
+    #[inline]
+    pub fn height(&self) -> usize { self.cells.len() / self.width }
+    /// The cells of row `y`
+    #[inline]
+    pub fn row(&self, y: usize) -> &[Cell] { &self.cells[y * self.width..(y + 1) * self.width] }
+    /// Write the first `columns` cells of row `y` in `color`, resetting the attributes after
+    pub fn write_row_to(
+        &self,
+        out: &mut impl Write,
+        y: usize,
+        columns: usize,
+        color: ColorType,
+        attrs: ColorAttributes,
+    ) -> io::Result<()> {
+        let mut last = None;
+        for cell in self.row(y).iter().take(columns) {
+            if last != Some((cell.fg, cell.bg)) {
+                let [fg, bg] = [cell.fg, cell.bg].map(|c| VGA_PALETTE[c as usize]);
+                match color {
+                    ColorType::Color => out.write_all(RgbSequencer::full(fg, bg).as_slice())?,
+                    ColorType::AnsiColor => {
+                        out.write_all(AnsiSequencer::full(rgb_to_ansi(fg, attrs), rgb_to_ansi(bg, attrs)).as_slice())?
+                    }
+                    ColorType::Gray => out.write_all(RgbSequencer::full([luma(fg); 3], [luma(bg); 3]).as_slice())?,
+                    ColorType::AnsiGray => {
+                        let [fg, bg] = [fg, bg].map(|c| attrs.gray_ramp.ansi(luma(c)));
+                        out.write_all(AnsiSequencer::full(fg, bg).as_slice())?
+                    }
+                }
+                last = Some((cell.fg, cell.bg));
+            }
+            out.write_all(cell.ch.encode_utf8(&mut [0; 4]).as_bytes())?;
+        }
+        out.write_all(SGR_DEFAULT.as_bytes())
+    }
+    /// A line describing the art from its SAUCE record, if it has a title or author
+    pub fn description(&self) -> Option<String> {
+        let sauce = self.sauce.as_ref()?;
+        match (sauce.title.is_empty(), sauce.author.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(sauce.title.clone()),
+            (true, false) => Some(format!("by {}", sauce.author)),
+            (false, false) => Some(format!("{} by {}", sauce.title, sauce.author)),
+        }
+    }
+}
+
+/// The colors and attributes characters are drawn with, changed by SGR sequences
+#[derive(Debug, Clone, Copy)]
+struct Pen {
+    fg: u8,
+    bg: u8,
+    bold: bool,
+    blink: bool,
+    reverse: bool,
+}
+impl Default for Pen {
+    #[inline]
+    fn default() -> Self { Self { fg: 7, bg: 0, bold: false, blink: false, reverse: false } }
+}
+impl Pen {
+    /// Apply the parameters of an SGR sequence, ANSI art only uses the 8 colors and bold for their bright versions
+    fn sgr(&mut self, params: &str) {
+        for p in params.split(';').map(|p| p.parse::<u8>().unwrap_or(0)) {
+            match p {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                5 => self.blink = true,
+                7 => self.reverse = true,
+                22 => self.bold = false,
+                25 => self.blink = false,
+                27 => self.reverse = false,
+                30..=37 => self.fg = p - 30,
+                39 => self.fg = 7,
+                40..=47 => self.bg = p - 40,
+                49 => self.bg = 0,
+                _ => (),
+            }
+        }
+    }
+    /// A cell of `ch`, blinking backgrounds drawn bright with iCE colors and steady otherwise
+    fn cell(self, ch: char, ice_colors: bool) -> Cell {
+        let fg = self.fg + if self.bold { 8 } else { 0 };
+        let bg = self.bg + if self.blink && ice_colors { 8 } else { 0 };
+        if self.reverse {
+            Cell { ch, fg: bg, bg: fg }
+        } else {
+            Cell { ch, fg, bg }
+        }
+    }
+}
+
+/// Consume a CSI sequence after its escape, returning its parameters and final byte
+fn csi_bytes(bytes: &mut impl Iterator<Item = u8>) -> Option<(String, u8)> {
+    if bytes.next() != Some(b'[') {
+        return None;
+    }
+    let mut params = String::new();
+    for b in bytes {
+        if (0x40..=0x7e).contains(&b) {
+            return Some((params, b));
+        }
+        params.push(b as char);
+    }
+    None
+}
+
+/// Scroll through ANSI art in the alternate screen
+pub struct AnsiArtViewer<W: Terminal = BufWriter<StdoutLock<'static>>> {
+    pub art: AnsiArt,
+    pub conf: Config,
+    /// The size of the terminal
+    sz: (u16, u16),
+    lock: W,
+    attrs: ColorAttributes,
+    /// The row of the art at the top of the terminal
+    top: usize,
+    /// Where terminal events are read from and how raw mode is entered
+    #[cfg(not(target_family = "wasm"))]
+    backend: Box<dyn Backend>,
+}
+impl AnsiArtViewer {
+    /// View `art` on stdout
+    pub fn new(art: AnsiArt, conf: Config) -> Self {
+        let mut lock = BufWriter::new(stdout().lock());
+        let sz = terminal_size(&mut lock, &conf);
+        let attrs = conf.color_attributes();
+        #[cfg(not(target_family = "wasm"))]
+        let backend = conf.backend.backend();
+        Self {
+            art,
+            conf,
+            sz,
+            lock,
+            attrs,
+            top: 0,
+            #[cfg(not(target_family = "wasm"))]
+            backend,
+        }
+    }
+}
+impl<W: Terminal> AnsiArtViewer<W> {
+    /// The rows of the terminal for the art, leaving the last row for the status line
+    #[inline]
+    fn rows(&self) -> usize { self.sz.1.saturating_sub(1).max(1) as usize }
+    /// Start viewing, the terminal is always cleaned up even if the loop fails
+    pub fn spawn(mut self) -> io::Result<()> {
+        trace!("AnsiArtViewer::spawn");
+        install_restore_hooks();
+        set_terminal_active(true);
+        // there's no raw mode on wasm
+        #[cfg(not(target_family = "wasm"))]
+        let raw = self.backend.enable_raw_mode();
+        #[cfg(target_family = "wasm")]
+        let raw = Ok(());
+        let res = raw
+            .and_then(|_| execute!(self.lock, enter_alt_screen(), cursor_hide(), disable_line_wrap(), flush()))
+            .and_then(|_| self.run());
+        set_terminal_active(false);
+        let cleanup = execute!(self.lock, enable_line_wrap(), cursor_show(), exit_alt_screen(), flush());
+        #[cfg(not(target_family = "wasm"))]
+        let cleanup = cleanup.and(self.backend.disable_raw_mode());
+        res.and(cleanup).and(execute!(self.lock, soft_reset(), flush()))
+    }
+    /// Draw the rows of the art from the top row, and the status line
+    fn draw(&mut self) -> io::Result<()> {
+        _execute!(self.lock, clear());
+        let bottom = (self.top + self.rows()).min(self.art.height());
+        for (i, y) in (self.top..bottom).enumerate() {
+            self.lock.cursor_to(0, i as u16)?;
+            self.art.write_row_to(&mut self.lock, y, self.sz.0 as usize, self.conf.color, self.attrs)?;
+        }
+        let rows = format!("{}-{}/{}", (self.top + 1).min(bottom), bottom, self.art.height());
+        let status = match self.art.description() {
+            Some(description) => format!("{rows} {description}"),
+            None => format!("{rows} {}", self.conf.image.display()),
+        };
+        _execute!(self.lock, cursor_to(0, self.sz.1.saturating_sub(1)), write_all(status.as_bytes()));
+        execute!(self.lock, flush())
+    }
+    /// Scroll by `by` rows, stopping with the last row of the art at the bottom
+    fn scroll(&mut self, by: isize) {
+        let last = self.art.height().saturating_sub(self.rows());
+        self.top = self.top.saturating_add_signed(by).min(last);
+    }
+    /// The main viewing loop
+    fn run(&mut self) -> io::Result<()> {
+        self.draw()?;
+        loop {
+            let page = self.rows() as isize;
+            #[cfg(not(target_family = "wasm"))]
+            match self.backend.read()? {
+                Event::Key(e) if e.kind == KeyEventKind::Press => match e.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+                    KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+                    KeyCode::PageUp | KeyCode::Char('p') => self.scroll(-page),
+                    KeyCode::PageDown | KeyCode::Char('n') | KeyCode::Char(' ') => self.scroll(page),
+                    KeyCode::Home | KeyCode::Char('g') => self.top = 0,
+                    KeyCode::End | KeyCode::Char('G') => self.scroll(isize::MAX),
+                    _ => continue,
+                },
+                Event::Resize(w, h) => {
+                    self.sz = (w, h);
+                    self.scroll(0);
+                }
+                _ => continue,
+            }
+            // without raw mode, read whole lines
+            #[cfg(target_family = "wasm")]
+            {
+                use std::io::BufRead;
+                let mut buf = String::new();
+                if std::io::stdin().lock().read_line(&mut buf)? == 0 {
+                    return Ok(());
+                }
+                match buf.trim() {
+                    "q" => return Ok(()),
+                    "k" => self.scroll(-1),
+                    "j" => self.scroll(1),
+                    "p" => self.scroll(-page),
+                    "" | "n" => self.scroll(page),
+                    "g" => self.top = 0,
+                    "G" => self.scroll(isize::MAX),
+                    _ => continue,
+                }
+            }
+            self.draw()?;
+        }
+    }
+}
+
+/// Display ANSI art, printed whole inlined or scrolled through windowed
+pub fn ansi_art(conf: Config) -> Result<()> {
+    trace!("ansi_art");
+    let art = AnsiArt::open(&conf.image).with_context(|| format!("Failed to read {}", conf.image.display()))?;
+    if conf.inline {
+        let mut lock = BufWriter::new(stdout().lock());
+        let columns = terminal_size(&mut lock, &conf).0 as usize;
+        for y in 0..art.height() {
+            art.write_row_to(&mut lock, y, columns, conf.color, conf.color_attributes())?;
+            lock.write_all(b"\n")?;
+        }
+        _execute!(lock, flush());
+    } else {
+        AnsiArtViewer::new(art, conf).spawn()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! AVIF decoding with libavif and dav1d (feature `avif`), the AV1 still image format of modern phones and browsers.
+use std::io::Read;
+
+use image::RgbaImage;
+
+use super::*;
+
+/// Decode the primary image of an AVIF file into 8 bit RGBA, with its alpha if it has any
+pub fn decode(conf: &Config, display: impl FnOnce((usize, usize)) -> Option<(usize, usize)>) -> Result<DynamicImage> {
+    trace!("avif::decode");
+    let mut data = Vec::new();
+    Counted::new(File::open(&conf.image)?).read_to_end(&mut data)?;
+    let image = libavif::decode(&data)?;
+    drop(data);
+    let img_sz = (image.width() as usize, image.height() as usize);
+    // libavif can't downscale while decoding
+    if let Some(b) = Budget::new(conf).map(|b| b.for_decode(display(img_sz))).transpose()?.filter(|b| !b.fits(img_sz)) {
+        return Err(b.exceeded(img_sz));
+    }
+    let rgb = libavif::RgbPixels::from(image);
+    let (w, h) = (rgb.width(), rgb.height());
+    let pixels = rgb.as_slice();
+    // rows may be padded to the stride
+    let stride = pixels.len() / (h as usize).max(1);
+    let row = w as usize * 4;
+    let data = pixels.chunks(stride).take(h as usize).flat_map(|r| &r[..row]).copied().collect();
+    RgbaImage::from_raw(w, h, data)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| anyhow!("libavif decoded a truncated {}x{} image", w, h))
+}
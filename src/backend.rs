@@ -0,0 +1,261 @@
+//! Where terminal events are read from and how raw mode is entered, crossterm by default or termion.
+//!
+//! Events from every backend are crossterm's, so keybinds and the viewers match on the same keys whichever reads them.
+//! On wasm there's neither raw mode nor an event source to choose, input is read from stdin a line at a time instead.
+use std::time::Duration;
+
+use crossterm::event::Event;
+
+use super::*;
+
+/// The source of terminal events and the control of raw mode for the TUI
+pub trait Backend {
+    /// Stop the terminal echoing input and buffering it by line
+    fn enable_raw_mode(&mut self) -> io::Result<()>;
+    /// Restore the terminal's mode from before raw mode was enabled
+    fn disable_raw_mode(&mut self) -> io::Result<()>;
+    /// Wait up to `timeout` for an event, returning whether one is ready to read
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+    /// Wait for the next event
+    fn read(&mut self) -> io::Result<Event>;
+}
+
+/// Which [`Backend`] to use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BackendKind {
+    /// [`Crossterm`], available everywhere but wasm
+    #[default]
+    Crossterm,
+    /// [`Termion`], on unix with the `termion` feature
+    #[cfg(all(unix, feature = "termion"))]
+    Termion,
+}
+impl BackendKind {
+    /// A new backend of this kind
+    #[inline]
+    pub fn backend(self) -> Box<dyn Backend> {
+        match self {
+            Self::Crossterm => Box::new(Crossterm),
+            #[cfg(all(unix, feature = "termion"))]
+            Self::Termion => Box::new(Termion::default()),
+        }
+    }
+}
+impl FromStr for BackendKind {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "crossterm" => Ok(Self::Crossterm),
+            #[cfg(all(unix, feature = "termion"))]
+            "termion" => Ok(Self::Termion),
+            _ => Err(ConfigError::InvalidBackend(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for BackendKind {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+impl fmt::Display for BackendKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Crossterm => "crossterm",
+            #[cfg(all(unix, feature = "termion"))]
+            Self::Termion => "termion",
+        })
+    }
+}
+impl Serialize for BackendKind {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Events and raw mode from crossterm
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crossterm;
+impl Backend for Crossterm {
+    #[inline]
+    fn enable_raw_mode(&mut self) -> io::Result<()> { crossterm::terminal::enable_raw_mode() }
+    #[inline]
+    fn disable_raw_mode(&mut self) -> io::Result<()> { crossterm::terminal::disable_raw_mode() }
+    #[inline]
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> { crossterm::event::poll(timeout) }
+    #[inline]
+    fn read(&mut self) -> io::Result<Event> { crossterm::event::read() }
+}
+
+/// Events and raw mode from termion, events read from the tty by a thread and resizes noticed from `SIGWINCH`
+#[cfg(all(unix, feature = "termion"))]
+#[derive(Default)]
+pub struct Termion {
+    /// Stdout in raw mode, restoring the terminal when dropped
+    raw: Option<termion::raw::RawTerminal<io::Stdout>>,
+}
+/// The events read from the tty, and the next one if it was polled
+#[cfg(all(unix, feature = "termion"))]
+type TermionEvents = std::sync::Mutex<(std::sync::mpsc::Receiver<io::Result<Event>>, Option<io::Result<Event>>)>;
+#[cfg(all(unix, feature = "termion"))]
+impl Termion {
+    /// The events read so far, shared by every termion backend,
+    /// since only one thread can read the tty without the events being split between them
+    fn events() -> &'static TermionEvents {
+        use std::sync::{mpsc, Mutex, OnceLock};
+
+        use termion::input::TermRead;
+        static EVENTS: OnceLock<TermionEvents> = OnceLock::new();
+        EVENTS.get_or_init(|| {
+            let (tx, rx) = mpsc::channel();
+            let resized = tx.clone();
+            std::thread::spawn(move || {
+                // the tty rather than stdin, which may be the image
+                let tty = match termion::get_tty() {
+                    Ok(tty) => tty,
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return;
+                    }
+                };
+                for event in tty.events() {
+                    let event = match event {
+                        Ok(event) => match termion_event(event) {
+                            Some(event) => Ok(event),
+                            None => continue,
+                        },
+                        Err(e) => Err(e),
+                    };
+                    if tx.send(event).is_err() {
+                        break;
+                    }
+                }
+            });
+            match signal_hook::iterator::Signals::new([signal_hook::consts::SIGWINCH]) {
+                Ok(mut signals) => {
+                    std::thread::spawn(move || {
+                        for _ in signals.forever() {
+                            if resized.send(termion::terminal_size().map(|(w, h)| Event::Resize(w, h))).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+                Err(e) => warn!("could not install the resize handler: {}", e),
+            }
+            Mutex::new((rx, None))
+        })
+    }
+}
+#[cfg(all(unix, feature = "termion"))]
+impl Backend for Termion {
+    fn enable_raw_mode(&mut self) -> io::Result<()> {
+        use termion::raw::IntoRawMode;
+        if self.raw.is_none() {
+            self.raw = Some(stdout().into_raw_mode()?);
+        }
+        Ok(())
+    }
+    #[inline]
+    fn disable_raw_mode(&mut self) -> io::Result<()> {
+        // the mode from before is restored when dropped
+        self.raw = None;
+        Ok(())
+    }
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        use std::sync::mpsc::RecvTimeoutError;
+        let mut events = Self::events().lock().unwrap_or_else(|e| e.into_inner());
+        let (rx, next) = &mut *events;
+        if next.is_some() {
+            return Ok(true);
+        }
+        match rx.recv_timeout(timeout) {
+            Ok(event) => *next = Some(event),
+            Err(RecvTimeoutError::Timeout) => return Ok(false),
+            Err(RecvTimeoutError::Disconnected) => *next = Some(Err(io::ErrorKind::UnexpectedEof.into())),
+        }
+        Ok(true)
+    }
+    fn read(&mut self) -> io::Result<Event> {
+        let mut events = Self::events().lock().unwrap_or_else(|e| e.into_inner());
+        let (rx, next) = &mut *events;
+        match next.take() {
+            Some(event) => event,
+            None => rx.recv().unwrap_or_else(|_| Err(io::ErrorKind::UnexpectedEof.into())),
+        }
+    }
+}
+
+/// A termion event as crossterm's, if crossterm has an equivalent
+#[cfg(all(unix, feature = "termion"))]
+fn termion_event(event: termion::event::Event) -> Option<Event> {
+    use crossterm::event::{KeyCode::*, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+    use termion::event::{Event as E, Key as K, MouseButton, MouseEvent as M};
+    let none = KeyModifiers::empty();
+    let (code, modifiers) = match event {
+        E::Key(key) => match key {
+            K::Backspace => (Backspace, none),
+            K::Left => (Left, none),
+            K::ShiftLeft => (Left, KeyModifiers::SHIFT),
+            K::AltLeft => (Left, KeyModifiers::ALT),
+            K::CtrlLeft => (Left, KeyModifiers::CONTROL),
+            K::Right => (Right, none),
+            K::ShiftRight => (Right, KeyModifiers::SHIFT),
+            K::AltRight => (Right, KeyModifiers::ALT),
+            K::CtrlRight => (Right, KeyModifiers::CONTROL),
+            K::Up => (Up, none),
+            K::ShiftUp => (Up, KeyModifiers::SHIFT),
+            K::AltUp => (Up, KeyModifiers::ALT),
+            K::CtrlUp => (Up, KeyModifiers::CONTROL),
+            K::Down => (Down, none),
+            K::ShiftDown => (Down, KeyModifiers::SHIFT),
+            K::AltDown => (Down, KeyModifiers::ALT),
+            K::CtrlDown => (Down, KeyModifiers::CONTROL),
+            K::Home => (Home, none),
+            K::CtrlHome => (Home, KeyModifiers::CONTROL),
+            K::End => (End, none),
+            K::CtrlEnd => (End, KeyModifiers::CONTROL),
+            K::PageUp => (PageUp, none),
+            K::PageDown => (PageDown, none),
+            // crossterm always sends shift with backtab
+            K::BackTab => (BackTab, KeyModifiers::SHIFT),
+            K::Delete => (Delete, none),
+            K::Insert => (Insert, none),
+            K::F(n) => (F(n), none),
+            K::Char('\n') => (Enter, none),
+            K::Char('\t') => (Tab, none),
+            // as crossterm reports them, so they match keybinds like shift+g
+            K::Char(c) if c.is_uppercase() => (Char(c), KeyModifiers::SHIFT),
+            K::Char(c) => (Char(c), none),
+            K::Alt(c) => (Char(c), KeyModifiers::ALT),
+            K::Ctrl(c) => (Char(c), KeyModifiers::CONTROL),
+            K::Null => (Null, none),
+            K::Esc => (Esc, none),
+            _ => return None,
+        },
+        E::Mouse(M::Press(button, column, row)) => {
+            // crossterm has no sideways scrolling, the wheel scrolls sideways with shift held instead
+            let (kind, modifiers) = match button {
+                MouseButton::WheelUp => (MouseEventKind::ScrollUp, none),
+                MouseButton::WheelDown => (MouseEventKind::ScrollDown, none),
+                MouseButton::WheelLeft => (MouseEventKind::ScrollUp, KeyModifiers::SHIFT),
+                MouseButton::WheelRight => (MouseEventKind::ScrollDown, KeyModifiers::SHIFT),
+                _ => return None,
+            };
+            // termion's positions start at 1
+            let (column, row) = (column.saturating_sub(1), row.saturating_sub(1));
+            return Some(Event::Mouse(MouseEvent { kind, column, row, modifiers }));
+        }
+        _ => return None,
+    };
+    Some(Event::Key(KeyEvent::new(code, modifiers)))
+}
@@ -0,0 +1,155 @@
+//! `viuwa bench <image>`, times decoding, resizing, and ANSI conversion, to quantify configuration trade-offs.
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use super::*;
+
+/// Every filter, in cycle order
+const FILTERS: [FilterType; 7] = [
+    FilterType::Nearest,
+    FilterType::Box,
+    FilterType::Triangle,
+    FilterType::Hamming,
+    FilterType::Catmull,
+    FilterType::Mitchell,
+    FilterType::Lanczos,
+];
+/// Every color type
+const COLORS: [ColorType; 4] = [ColorType::Color, ColorType::AnsiColor, ColorType::Gray, ColorType::AnsiGray];
+
+/// Timings of repeated runs of one operation
+struct Timing {
+    stage: &'static str,
+    variant: String,
+    runs: Vec<Duration>,
+}
+impl Timing {
+    /// Run `f` `iterations` times
+    fn run<T>(stage: &'static str, variant: impl Into<String>, iterations: usize, mut f: impl FnMut() -> T) -> Self {
+        let runs = (0..iterations)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(f());
+                start.elapsed()
+            })
+            .collect();
+        Self { stage, variant: variant.into(), runs }
+    }
+    fn min(&self) -> Duration { self.runs.iter().min().copied().unwrap_or_default() }
+    fn max(&self) -> Duration { self.runs.iter().max().copied().unwrap_or_default() }
+    fn mean(&self) -> Duration { self.runs.iter().sum::<Duration>() / self.runs.len().max(1) as u32 }
+}
+
+/// Run the benchmarks for the configured image and print a timing table
+pub fn bench(conf: &Config, iterations: usize) -> Result<()> {
+    trace!("bench");
+    let iterations = iterations.max(1);
+    let mut timings = Vec::new();
+    info!("benchmarking decode...");
+    let orig = image::io::Reader::open(&conf.image)?.with_guessed_format()?.decode()?;
+    timings.push(Timing::run("decode", "full", iterations, || {
+        image::io::Reader::open(&conf.image).and_then(|r| r.with_guessed_format()).map(|r| r.decode())
+    }));
+    timings.push(Timing::run("decode", "viuwa", iterations, || decode(conf)));
+    let img_sz = (orig.width() as usize, orig.height() as usize);
+    let term_sz = terminal_size(&mut stdout(), conf);
+    let dims = dimensions(term_sz, conf, img_sz);
+    info!("benchmarking resize and ansi conversion to {}x{}...", dims.0, dims.1);
+    if orig.color().has_color() {
+        bench_pixels(Image::from(orig.into_rgb8()), dims, conf, iterations, &mut timings);
+    } else {
+        bench_pixels(Image::from(orig.into_luma8()), dims, conf, iterations, &mut timings);
+    }
+    print_table(img_sz, dims, iterations, &timings);
+    Ok(())
+}
+
+/// Benchmark every resizer with every filter, and every ansi conversion
+fn bench_pixels<P: Pixel>(orig: Image<P>, dims: (usize, usize), conf: &Config, iterations: usize, timings: &mut Vec<Timing>)
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let (w, h) = dims;
+    for filter in FILTERS {
+        timings.push(Timing::run("resize", format!("supersize {:?}", filter), iterations, || {
+            orig.supersize(w, h, filter, SUPERSAMPLE as f32)
+        }));
+        #[cfg(feature = "rayon")]
+        timings.push(Timing::run("resize", format!("par_supersize {:?}", filter), iterations, || {
+            orig.par_supersize(w, h, filter, SUPERSAMPLE as f32)
+        }));
+        #[cfg(feature = "fir")]
+        timings.push(Timing::run("resize", format!("fir_supersize {:?}", filter), iterations, || {
+            orig.fir_supersize(w, h, &filter, SUPERSAMPLE as u8)
+        }));
+    }
+    let buf = resize(&orig, dims, conf.resize_filter());
+    let attrs = conf.color_attributes();
+    fn rows<P: Pixel, C: Converter>(mut ansi: AnsiImage<P, C>, attrs: ColorAttributes) -> usize
+    where
+        P::Scalar: CompatScalar,
+        P::Repr: CompatPixelRepr,
+    {
+        let mut n = 0;
+        for row in ansi.rows_upper(attrs, None) {
+            match row {
+                AnsiRow::Full(row) => row.for_each(|p| n += black_box(p.as_bytes()).len()),
+                AnsiRow::Half(row) => row.for_each(|p| n += black_box(p.as_bytes()).len()),
+            }
+        }
+        n
+    }
+    #[cfg(feature = "rayon")]
+    fn par_rows<P: Pixel, C: Converter + Send>(mut ansi: AnsiImage<P, C>, attrs: ColorAttributes) -> usize
+    where
+        P::Scalar: CompatScalar,
+        P::Repr: CompatPixelRepr,
+    {
+        use rayon::prelude::*;
+        ansi.par_rows_upper(attrs, None)
+            .map(|row| match row {
+                AnsiRow::Full(row) => row.map(|p| black_box(p.as_bytes()).len()).sum::<usize>(),
+                AnsiRow::Half(row) => row.map(|p| black_box(p.as_bytes()).len()).sum::<usize>(),
+            })
+            .sum()
+    }
+    for color in COLORS {
+        timings.push(Timing::run("ansi", format!("{:?}", color), iterations, || {
+            match DynamicAnsiImage::new(buf.view(), color) {
+                DynamicAnsiImage::Color(a) => rows(a, attrs),
+                DynamicAnsiImage::Gray(a) => rows(a, attrs),
+                DynamicAnsiImage::AnsiColor(a) => rows(a, attrs),
+                DynamicAnsiImage::AnsiGray(a) => rows(a, attrs),
+            }
+        }));
+        #[cfg(feature = "rayon")]
+        timings.push(Timing::run("ansi", format!("par {:?}", color), iterations, || {
+            match DynamicAnsiImage::new(buf.view(), color) {
+                DynamicAnsiImage::Color(a) => par_rows(a, attrs),
+                DynamicAnsiImage::Gray(a) => par_rows(a, attrs),
+                DynamicAnsiImage::AnsiColor(a) => par_rows(a, attrs),
+                DynamicAnsiImage::AnsiGray(a) => par_rows(a, attrs),
+            }
+        }));
+        if let Some(glyphs) = conf.renderer.glyphs(conf.symbols) {
+            timings.push(Timing::run("ansi", format!("glyph {:?}", color), iterations, || {
+                let mut out = Vec::new();
+                DynamicAnsiImage::new(buf.view(), color).write_glyphs_to(&mut out, attrs, &glyphs).map(|_| out.len())
+            }));
+        }
+    }
+}
+
+/// Print the timings as an aligned table in milliseconds
+fn print_table(img_sz: (usize, usize), dims: (usize, usize), iterations: usize, timings: &[Timing]) {
+    let ms = |d: Duration| format!("{:.3}", d.as_secs_f64() * 1000.);
+    let width = timings.iter().map(|t| t.variant.len()).max().unwrap_or(0).max("variant".len());
+    println!("{}x{} image resized to {}x{}, {} iterations", img_sz.0, img_sz.1, dims.0, dims.1, iterations);
+    println!("{:<8} {:<width$} {:>12} {:>12} {:>12}", "stage", "variant", "min (ms)", "mean (ms)", "max (ms)");
+    for t in timings {
+        println!("{:<8} {:<width$} {:>12} {:>12} {:>12}", t.stage, t.variant, ms(t.min()), ms(t.mean()), ms(t.max()));
+    }
+}
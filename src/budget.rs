@@ -0,0 +1,60 @@
+//! Memory budget for the decoded image and its resized buffers, set with `--max-memory`.
+use super::*;
+
+/// Projected bytes per pixel of a decoded image, the decoded pixel (assumed 4 bytes) plus the 8-bit copy displayed
+pub const DECODED_BYTES_PER_PIXEL: u64 = 7;
+
+/// A memory budget in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget(pub u64);
+impl Budget {
+    /// The configured budget, if any
+    #[inline]
+    pub fn new(conf: &Config) -> Option<Self> { conf.max_memory.map(|mb| Self(mb.saturating_mul(1024 * 1024))) }
+    /// Projected bytes of the buffers needed to display an image at `dims`,
+    /// the supersampling scratch buffer, the displayed buffer, and the cached buffers
+    #[inline]
+    pub fn resize_bytes(dims: (usize, usize)) -> u64 {
+        let px = dims.0 as u64 * dims.1 as u64;
+        px * 3 * ((SUPERSAMPLE * SUPERSAMPLE) as u64 + 1 + CACHE_CAPACITY as u64)
+    }
+    /// Projected bytes of a decoded image at `dims`
+    #[inline]
+    pub fn decoded_bytes(dims: (usize, usize)) -> u64 { dims.0 as u64 * dims.1 as u64 * DECODED_BYTES_PER_PIXEL }
+    /// The budget left over for decoding after reserving the resized buffers for `display` dimensions
+    pub fn for_decode(self, display: Option<(usize, usize)>) -> Result<Self> {
+        let reserved = display.map_or(0, Self::resize_bytes);
+        match self.0.checked_sub(reserved) {
+            Some(left) if left > 0 => Ok(Self(left)),
+            _ => Err(anyhow!(
+                "The resized buffers alone need ~{} MB, exceeding the memory budget of {} MB",
+                mb(reserved),
+                mb(self.0)
+            )),
+        }
+    }
+    /// Whether an image decoded at `dims` fits in the budget
+    #[inline]
+    pub fn fits(self, dims: (usize, usize)) -> bool { Self::decoded_bytes(dims) <= self.0 }
+    /// The largest dimensions with the aspect ratio of `img_sz` that fit in the budget
+    pub fn fit(self, img_sz: (usize, usize)) -> (usize, usize) {
+        let px = (self.0 / DECODED_BYTES_PER_PIXEL) as f64;
+        let ratio = (px / (img_sz.0 as f64 * img_sz.1 as f64)).sqrt().min(1.);
+        let scale = |n: usize| ((n as f64 * ratio) as usize).clamp(1, n);
+        (scale(img_sz.0), scale(img_sz.1))
+    }
+    /// The error for an image at `dims` that can't be downscaled to fit in the budget
+    pub fn exceeded(self, dims: (usize, usize)) -> anyhow::Error {
+        anyhow!(
+            "Decoding the {}x{} image needs ~{} MB, exceeding the memory budget of {} MB, and its format can't be downscaled while decoding",
+            dims.0,
+            dims.1,
+            mb(Self::decoded_bytes(dims)),
+            mb(self.0)
+        )
+    }
+}
+
+/// Bytes to megabytes, rounded up
+#[inline(always)]
+fn mb(bytes: u64) -> u64 { bytes.div_ceil(1024 * 1024) }
@@ -0,0 +1,147 @@
+//! C bindings rendering images to ANSI, for embedding viuwa in tools that aren't written in Rust, see `include/viuwa.h`.
+//!
+//! Build the shared library with `cargo rustc --lib --crate-type cdylib --release --features capi`.
+//! The rendered ANSI is passed to a callback in chunks instead of being written to stdout,
+//! and the options are a config file's toml, so every setting of [`RenderOptions::from_config`] can be given.
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use super::*;
+
+/// Called with each chunk of rendered ANSI and the `user_data` given to the render function,
+/// returning anything but 0 stops rendering
+pub type ViuwaWriteFn = unsafe extern "C" fn(bytes: *const u8, len: usize, user_data: *mut c_void) -> c_int;
+
+thread_local! {
+    /// The message of the last error on this thread, kept until the next render
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Render the image file at the NUL terminated `path` into an area of `columns` and `rows`, passing the ANSI to `write`.
+///
+/// `options` is NULL or the NUL terminated toml of a config file. Returns 0 on success,
+/// or -1 with the reason from [`viuwa_last_error`].
+///
+/// # Safety
+/// `path` and `options` (if not NULL) must be valid NUL terminated strings,
+/// and `write` must be safe to call with `user_data`
+#[no_mangle]
+pub unsafe extern "C" fn viuwa_render_path(
+    path: *const c_char,
+    options: *const c_char,
+    columns: u16,
+    rows: u16,
+    write: Option<ViuwaWriteFn>,
+    user_data: *mut c_void,
+) -> c_int {
+    ffi(|| {
+        if path.is_null() {
+            return Err(anyhow!("path is NULL"));
+        }
+        let path = CStr::from_ptr(path).to_str().context("path is not UTF-8")?;
+        let conf = Config { image: PathBuf::from(path), ..options_config(options)? };
+        let opts = options_for(&conf, columns, rows)?;
+        let image = decode_for(&conf, |img_sz| Some(opts.dimensions(img_sz)))?;
+        render_to(image, &conf, &opts, write, user_data)
+    })
+}
+
+/// Render an encoded image of `len` bytes at `bytes` into an area of `columns` and `rows`, passing the ANSI to `write`.
+///
+/// The format is guessed from the bytes, `options` is NULL or the NUL terminated toml of a config file.
+/// Returns 0 on success, or -1 with the reason from [`viuwa_last_error`].
+///
+/// # Safety
+/// `bytes` must be valid for reads of `len` bytes, `options` (if not NULL) must be a valid NUL terminated string,
+/// and `write` must be safe to call with `user_data`
+#[no_mangle]
+pub unsafe extern "C" fn viuwa_render_buffer(
+    bytes: *const u8,
+    len: usize,
+    options: *const c_char,
+    columns: u16,
+    rows: u16,
+    write: Option<ViuwaWriteFn>,
+    user_data: *mut c_void,
+) -> c_int {
+    ffi(|| {
+        if bytes.is_null() {
+            return Err(anyhow!("bytes is NULL"));
+        }
+        let conf = options_config(options)?;
+        let opts = options_for(&conf, columns, rows)?;
+        let image = image::load_from_memory(std::slice::from_raw_parts(bytes, len)).context("Failed to load image")?;
+        let image = if conf.renderer == Renderer::Foreground { premultiplied(image) } else { image };
+        render_to(image, &conf, &opts, write, user_data)
+    })
+}
+
+/// The message of the last error of a render on this thread, or NULL if it succeeded.
+///
+/// The string is owned by viuwa and valid until the next render on this thread.
+#[no_mangle]
+pub extern "C" fn viuwa_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |e| e.as_ptr()))
+}
+
+/// Run a render, keeping its error (or panic) as the last error instead of unwinding into C
+fn ffi(f: impl FnOnce() -> Result<()>) -> c_int {
+    let (code, err) = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => (0, None),
+        Ok(Err(e)) => (-1, Some(format!("{e:#}"))),
+        Err(_) => (-1, Some("viuwa panicked while rendering".to_string())),
+    };
+    // messages can't contain a NUL, but if one did the rest is dropped rather than the message
+    let err = err.map(|e| CString::new(e.split('\0').next().unwrap_or_default()).unwrap_or_default());
+    LAST_ERROR.with(|e| *e.borrow_mut() = err);
+    code
+}
+
+/// The config of the `options` toml, or the default config if NULL
+unsafe fn options_config(options: *const c_char) -> Result<Config> {
+    if options.is_null() {
+        return Ok(Config::default());
+    }
+    let options = CStr::from_ptr(options).to_str().context("options are not UTF-8")?;
+    Ok(Config::from_toml(options)?)
+}
+
+/// The render options of `conf` for an area of `columns` and `rows`
+fn options_for(conf: &Config, columns: u16, rows: u16) -> Result<RenderOptions> {
+    if columns == 0 || rows == 0 {
+        return Err(anyhow!("columns and rows must be greater than 0"));
+    }
+    Ok(RenderOptions::from_config(conf, (columns, rows)))
+}
+
+/// Render a decoded image, passing the ANSI to `write` in chunks
+fn render_to(
+    image: DynamicImage,
+    conf: &Config,
+    opts: &RenderOptions,
+    write: Option<ViuwaWriteFn>,
+    user_data: *mut c_void,
+) -> Result<()> {
+    let write = write.context("write callback is NULL")?;
+    let mut out = BufWriter::new(Callback { write, user_data });
+    render(&tone_mapped(image, conf), opts, &mut out).and_then(|_| out.flush()).context("Failed to write rendered image")
+}
+
+/// A C callback as a writer
+struct Callback {
+    write: ViuwaWriteFn,
+    user_data: *mut c_void,
+}
+impl Write for Callback {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match unsafe { (self.write)(buf.as_ptr(), buf.len(), self.user_data) } {
+            0 => Ok(buf.len()),
+            code => Err(io::Error::other(format!("write callback returned {code}"))),
+        }
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
@@ -0,0 +1,112 @@
+//! `viuwa cast`, recording animations and slideshows to asciicast v2 files, replayed with `asciinema play`.
+use std::time::Duration;
+
+use image::{codecs::gif::GifDecoder, AnimationDecoder, ImageFormat};
+use viuwa_ansi::consts::{CLEAR_SCREEN, CURSOR_HOME, HIDE_CURSOR, SGR_DEFAULT, SHOW_CURSOR};
+
+use super::*;
+
+/// An asciicast v2 recording, the output written to a terminal of a fixed size and when
+/// ```
+/// use std::time::Duration;
+/// use viuwa::Asciicast;
+/// let mut cast = Asciicast::new((80, 24));
+/// cast.push("\x1b[H▀", Duration::from_millis(500));
+/// cast.push("\x1b[H▄", Duration::from_millis(500));
+/// let mut out = Vec::new();
+/// cast.write_to(&mut out).unwrap();
+/// let out = String::from_utf8(out).unwrap();
+/// assert_eq!(out.lines().next(), Some(r#"{"version": 2, "width": 80, "height": 24}"#));
+/// assert_eq!(out.lines().nth(2), Some(r#"[0.500000, "o", "\u001b[H▄"]"#));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Asciicast {
+    /// The columns and rows of the terminal
+    pub size: (u16, u16),
+    /// The output written and how long it's shown before the next
+    pub events: Vec<(String, Duration)>,
+}
+impl Asciicast {
+    /// An empty recording of a terminal of `size` columns and rows
+    #[inline]
+    pub fn new(size: (u16, u16)) -> Self { Self { size, events: Vec::new() } }
+    /// Write `output`, shown for `duration` before the next output
+    #[inline]
+    pub fn push(&mut self, output: impl Into<String>, duration: Duration) { self.events.push((output.into(), duration)) }
+    /// How long the recording plays
+    #[inline]
+    pub fn duration(&self) -> Duration { self.events.iter().map(|(_, d)| *d).sum() }
+    /// Write the recording as asciicast v2, a header line and an output event per line
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{{\"version\": 2, \"width\": {}, \"height\": {}}}", self.size.0, self.size.1)?;
+        let mut time = Duration::ZERO;
+        for (output, duration) in &self.events {
+            writeln!(out, "[{:.6}, \"o\", {}]", time.as_secs_f64(), json_string(output))?;
+            time += *duration;
+        }
+        Ok(())
+    }
+}
+
+/// `s` as a JSON string, with quotes
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Record `images` to the asciicast file `out` at `size` columns and rows, GIF frames with their own delays,
+/// and every other image (or TIFF page, or ICO image) as a slide shown for `interval`
+pub fn write_cast(images: &[PathBuf], out: &Path, size: (u16, u16), interval: Duration, conf: &Config) -> Result<()> {
+    trace!("write_cast");
+    let mut cast = Asciicast::new(size);
+    cast.push(format!("{HIDE_CURSOR}{CLEAR_SCREEN}"), Duration::ZERO);
+    for image in images {
+        let conf = Config { image: image.clone(), ..conf.clone() };
+        let opts = RenderOptions::from_config(&conf, size);
+        // each image starts on a cleared screen, since it may be smaller than the last
+        let mut clear = true;
+        let mut slide = |frame: DynamicImage, duration: Duration| -> Result<()> {
+            let mut ansi = Vec::new();
+            render(&tone_mapped(frame, &conf), &opts, &mut ansi)?;
+            let home =
+                if std::mem::take(&mut clear) { [CLEAR_SCREEN, CURSOR_HOME].concat() } else { CURSOR_HOME.to_string() };
+            // the player is a terminal, where a line feed alone doesn't return to the first column
+            cast.push(format!("{home}{}", String::from_utf8_lossy(&ansi).replace('\n', "\r\n")), duration);
+            Ok(())
+        };
+        match multi_frame_format(image) {
+            Some(ImageFormat::Gif) => {
+                for frame in GifDecoder::new(BufReader::new(File::open(image)?))?.into_frames() {
+                    let frame = frame?;
+                    let delay = frame_delay(&frame);
+                    slide(DynamicImage::ImageRgba8(frame.into_buffer()), delay)?;
+                }
+            }
+            Some(format) => {
+                for i in 0..frame_count(image, format)? {
+                    slide(decode_frame(image, format, i)?, interval)?;
+                }
+            }
+            None => slide(decode_for(&conf, |img_sz| Some(opts.dimensions(img_sz)))?, interval)?,
+        }
+        info!("recorded {}", image.display());
+    }
+    cast.push(format!("{SGR_DEFAULT}{SHOW_CURSOR}"), Duration::ZERO);
+    let mut file = BufWriter::new(File::create(out).with_context(|| format!("Failed to create {}", out.display()))?);
+    cast.write_to(&mut file).and_then(|_| file.flush()).with_context(|| format!("Failed to write {}", out.display()))?;
+    info!("wrote {:.1}s cast to {}", cast.duration().as_secs_f64(), out.display());
+    Ok(())
+}
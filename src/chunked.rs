@@ -0,0 +1,102 @@
+//! Decoding the strips or tiles of a TIFF page in parallel with rayon (feature `rayon`), so scanner output and aerial
+//! imagery, usually stored as many compressed strips or tiles, load in about a core count's fraction of the time.
+//!
+//! Every thread reads the file with a decoder of its own, as a decoder reads its chunks through a single reader.
+//! The scans of progressive JPEGs can't be decoded apart, each one refining those before it, but with the feature
+//! the JPEG decoder converts and upsamples the color components of the decoded scans in parallel.
+use rayon::prelude::*;
+use tiff::{
+    decoder::{ChunkType, Decoder as TiffDecoder, DecodingResult},
+    tags::Tag,
+    ColorType as TiffColor,
+};
+
+use super::*;
+
+/// Pages stored in fewer strips or tiles than this are decoded by a single thread, not worth opening the file again for
+pub const MIN_CHUNKS: u32 = 4;
+
+/// A decoder of the page at `index` of the TIFF at `path`, counting the bytes it reads toward the progress of loading
+fn open(path: &Path, index: usize) -> Result<TiffDecoder<BufReader<Counted<File>>>> {
+    let mut decoder = TiffDecoder::new(BufReader::new(Counted::new(File::open(path)?)))?;
+    decoder.seek_to_image(index)?;
+    Ok(decoder)
+}
+
+/// Decode the page at `index` (starting at 0) of the TIFF at `path` a few strips or tiles per thread,
+/// or `None` if it's stored in too few of them, as separate planes, or in a color type left to the single threaded decoder
+pub fn decode(path: &Path, index: usize) -> Result<Option<DynamicImage>> {
+    trace!("chunked::decode");
+    let mut decoder = open(path, index)?;
+    let (w, h) = decoder.dimensions()?;
+    let color = decoder.colortype()?;
+    let (samples, bits) = match color {
+        TiffColor::Gray(bits @ (8 | 16)) => (1, bits),
+        TiffColor::GrayA(bits @ (8 | 16)) => (2, bits),
+        TiffColor::RGB(bits @ (8 | 16)) => (3, bits),
+        TiffColor::RGBA(bits @ (8 | 16)) => (4, bits),
+        _ => return Ok(None),
+    };
+    // separate planes (2) are stored a chunk per plane, rather than a chunk of every sample
+    if decoder.find_tag_unsigned::<u16>(Tag::PlanarConfiguration)?.is_some_and(|planar| planar != 1) {
+        return Ok(None);
+    }
+    let (kind, chunks) = match decoder.get_chunk_type() {
+        ChunkType::Strip => ("strips", decoder.strip_count()?),
+        ChunkType::Tile => ("tiles", decoder.tile_count()?),
+    };
+    if chunks < MIN_CHUNKS {
+        return Ok(None);
+    }
+    debug!("decode", "decoding {}x{} tiff in {} {} on {} threads", w, h, chunks, kind, rayon::current_num_threads());
+    let page = Page { path, index, dims: (w, h), chunk: decoder.chunk_dimensions(), samples };
+    let data = match bits {
+        8 => DecodingResult::U8(page.bands(|data| match data {
+            DecodingResult::U8(data) => Some(data),
+            _ => None,
+        })?),
+        _ => DecodingResult::U16(page.bands(|data| match data {
+            DecodingResult::U16(data) => Some(data),
+            _ => None,
+        })?),
+    };
+    tiff_image((w, h), color, data, index).map(Some)
+}
+
+/// A TIFF page decoded in bands of rows a chunk high, every band a row of tiles (or a single strip)
+struct Page<'a> {
+    path: &'a Path,
+    index: usize,
+    dims: (u32, u32),
+    /// The dimensions of every chunk, but those cut off at the right and bottom edges
+    chunk: (u32, u32),
+    /// Samples per pixel
+    samples: usize,
+}
+impl Page<'_> {
+    /// Decode every band on the thread pool into the samples of the whole page, taking the samples of each chunk decoded
+    fn bands<T: Copy + Default + Send>(&self, take: impl Fn(DecodingResult) -> Option<Vec<T>> + Sync) -> Result<Vec<T>> {
+        let ((w, h), (cw, ch)) = (self.dims, self.chunk);
+        let across = w.div_ceil(cw.max(1));
+        let row = w as usize * self.samples;
+        let mut out = vec![T::default(); row * h as usize];
+        out.par_chunks_mut(row * ch.max(1) as usize).enumerate().try_for_each_init(
+            || open(self.path, self.index),
+            |decoder, (band, rows)| -> Result<()> {
+                let decoder = decoder.as_mut().map_err(|e| anyhow!("{:#}", e))?;
+                for col in 0..across {
+                    let i = band as u32 * across + col;
+                    let (dw, dh) = decoder.chunk_data_dimensions(i);
+                    let data = take(decoder.read_chunk(i)?)
+                        .ok_or_else(|| anyhow!("TIFF chunk {} has a different sample format than its page", i))?;
+                    let (x, len) = ((col * cw) as usize * self.samples, dw as usize * self.samples);
+                    for (r, src) in data.chunks_exact(len).take(dh as usize).enumerate() {
+                        rows[r * row + x..][..len].copy_from_slice(src);
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        Ok(out)
+    }
+}
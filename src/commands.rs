@@ -13,9 +13,10 @@ pub enum Cyclic {
     Color,
     ColorDepth,
     ColorSpace,
+    FitMode,
 }
 impl FromStr for Cyclic {
-    type Err = String;
+    type Err = CommandError;
     #[inline]
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
@@ -23,7 +24,8 @@ impl FromStr for Cyclic {
             "color" => Ok(Self::Color),
             "color_depth" => Ok(Self::ColorDepth),
             "color_space" => Ok(Self::ColorSpace),
-            _ => Err(format!("{s:?} is not a valid Cyclic")),
+            "fit_mode" | "fit" => Ok(Self::FitMode),
+            _ => Err(CommandError::InvalidCycle(s.to_string())),
         }
     }
 }
@@ -36,6 +38,349 @@ impl<'de> Deserialize<'de> for Cyclic {
         String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
     }
 }
+impl fmt::Display for Cyclic {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Filter => "filter",
+            Self::Color => "color",
+            Self::ColorDepth => "color_depth",
+            Self::ColorSpace => "color_space",
+            Self::FitMode => "fit_mode",
+        })
+    }
+}
+impl Serialize for Cyclic {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Accepted arguments to the zoom command.
+/// ```
+/// use viuwa::Zoom;
+/// use std::str::FromStr;
+/// assert_eq!(Zoom::from_str("in"), Ok(Zoom::In));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum Zoom {
+    In,
+    Out,
+    Reset,
+}
+impl FromStr for Zoom {
+    type Err = CommandError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "in" => Ok(Self::In),
+            "out" => Ok(Self::Out),
+            "reset" => Ok(Self::Reset),
+            _ => Err(CommandError::InvalidZoom(s.to_string())),
+        }
+    }
+}
+impl fmt::Display for Zoom {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::In => "in",
+            Self::Out => "out",
+            Self::Reset => "reset",
+        })
+    }
+}
+
+/// Accepted arguments to the pan command.
+/// ```
+/// use viuwa::Pan;
+/// use std::str::FromStr;
+/// assert_eq!(Pan::from_str("left"), Ok(Pan::Left));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum Pan {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+impl FromStr for Pan {
+    type Err = CommandError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            _ => Err(CommandError::InvalidPan(s.to_string())),
+        }
+    }
+}
+impl fmt::Display for Pan {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Up => "up",
+            Self::Down => "down",
+        })
+    }
+}
+
+/// Accepted arguments to the fit command, the axis of the image spanning the terminal.
+/// ```
+/// use viuwa::Fit;
+/// use std::str::FromStr;
+/// assert_eq!(Fit::from_str("width"), Ok(Fit::Width));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum Fit {
+    Width,
+    Height,
+}
+impl FromStr for Fit {
+    type Err = CommandError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "width" => Ok(Self::Width),
+            "height" => Ok(Self::Height),
+            _ => Err(CommandError::InvalidFit(s.to_string())),
+        }
+    }
+}
+impl fmt::Display for Fit {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Width => "width",
+            Self::Height => "height",
+        })
+    }
+}
+
+/// Accepted arguments to the page command, page numbers start at 1.
+/// ```
+/// use viuwa::Page;
+/// use std::str::FromStr;
+/// assert_eq!(Page::from_str("next"), Ok(Page::Next));
+/// assert_eq!(Page::from_str("3"), Ok(Page::To(3)));
+/// assert!(Page::from_str("0").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum Page {
+    Next,
+    Prev,
+    To(usize),
+}
+impl FromStr for Page {
+    type Err = CommandError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "next" => Ok(Self::Next),
+            "prev" => Ok(Self::Prev),
+            _ => match s.parse() {
+                Ok(n) if n > 0 => Ok(Self::To(n)),
+                _ => Err(CommandError::InvalidPage(s.to_string())),
+            },
+        }
+    }
+}
+impl fmt::Display for Page {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Next => f.write_str("next"),
+            Self::Prev => f.write_str("prev"),
+            Self::To(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Accepted arguments to the seek command, a frame number starting at 1 or a time into the animation.
+/// ```
+/// use std::{str::FromStr, time::Duration};
+/// use viuwa::Seek;
+/// assert_eq!(Seek::from_str("12"), Ok(Seek::Frame(12)));
+/// assert_eq!(Seek::from_str("1.5s"), Ok(Seek::Time(Duration::from_millis(1500))));
+/// assert_eq!(Seek::from_str("250ms"), Ok(Seek::Time(Duration::from_millis(250))));
+/// assert!(Seek::from_str("0").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum Seek {
+    Frame(usize),
+    Time(std::time::Duration),
+}
+impl FromStr for Seek {
+    type Err = CommandError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let secs = |secs: &str, scale: f64| {
+            secs.parse::<f64>()
+                .ok()
+                .and_then(|secs| std::time::Duration::try_from_secs_f64(secs * scale).ok())
+                .map(Self::Time)
+        };
+        let seek = match s.strip_suffix("ms") {
+            Some(ms) => secs(ms, 1e-3),
+            None => match s.strip_suffix('s') {
+                Some(s) => secs(s, 1.),
+                None => s.parse().ok().filter(|&n| n > 0).map(Self::Frame),
+            },
+        };
+        seek.ok_or_else(|| CommandError::InvalidSeek(s.to_string()))
+    }
+}
+impl fmt::Display for Seek {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Frame(n) => write!(f, "{n}"),
+            Self::Time(time) => write!(f, "{}ms", time.as_millis()),
+        }
+    }
+}
+
+/// Accepted arguments to the step command.
+/// ```
+/// use viuwa::Step;
+/// use std::str::FromStr;
+/// assert_eq!(Step::from_str("prev"), Ok(Step::Prev));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum Step {
+    Next,
+    Prev,
+}
+impl FromStr for Step {
+    type Err = CommandError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "next" => Ok(Self::Next),
+            "prev" => Ok(Self::Prev),
+            _ => Err(CommandError::InvalidStep(s.to_string())),
+        }
+    }
+}
+impl fmt::Display for Step {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Next => "next",
+            Self::Prev => "prev",
+        })
+    }
+}
+
+/// Implement the comparisons, hashing, and display of a float newtype by its bits, so it can be a [`Setting`]
+macro_rules! impl_float_setting {
+    ($T:ident) => {
+        impl PartialEq for $T {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool { self.0.to_bits() == other.0.to_bits() }
+        }
+        impl Eq for $T {}
+        impl PartialOrd for $T {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+        }
+        impl Ord for $T {
+            #[inline]
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.0.total_cmp(&other.0) }
+        }
+        impl std::hash::Hash for $T {
+            #[inline]
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) { self.0.to_bits().hash(state) }
+        }
+        impl fmt::Display for $T {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.0) }
+        }
+    };
+}
+
+/// An exposure compensation in stops, compared by its bits so it can be set like other settings.
+/// ```
+/// use viuwa::Stops;
+/// use std::str::FromStr;
+/// assert_eq!(Stops::from_str("-1.5").unwrap(), Stops(-1.5));
+/// assert!(Stops::from_str("20").is_err());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Stops(pub f32);
+impl_float_setting!(Stops);
+impl FromStr for Stops {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> { parse_exposure_str(s).map(Self) }
+}
+
+/// A luma correction, 0.0..=100.0, compared by its bits so it can be set like other settings.
+/// ```
+/// use viuwa::Correction;
+/// use std::str::FromStr;
+/// assert_eq!(Correction::from_str("82.5").unwrap(), Correction(82.5));
+/// assert!(Correction::from_str("101").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Correction(pub f32);
+impl_float_setting!(Correction);
+impl FromStr for Correction {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> { parse_luma_correct_str(s).map(Self) }
+}
+
+/// An amount of unsharp masking, 0.0..=1.0, compared by its bits so it can be set like other settings.
+/// ```
+/// use viuwa::Sharpness;
+/// use std::str::FromStr;
+/// assert_eq!(Sharpness::from_str("0.5").unwrap(), Sharpness(0.5));
+/// assert!(Sharpness::from_str("2").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sharpness(pub f32);
+impl_float_setting!(Sharpness);
+impl FromStr for Sharpness {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> { parse_sharpen_str(s).map(Self) }
+}
+
+/// A playback speed multiplier of animations, above 0 and at most 16, optionally followed by an `x`.
+/// ```
+/// use viuwa::Speed;
+/// use std::str::FromStr;
+/// assert_eq!(Speed::from_str("0.5x").unwrap(), Speed(0.5));
+/// assert!(Speed::from_str("0").is_err());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Speed(pub f32);
+impl_float_setting!(Speed);
+impl FromStr for Speed {
+    type Err = CommandError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.strip_suffix('x').unwrap_or(s).parse() {
+            Ok(speed) if speed > 0. && speed <= 16. => Ok(Self(speed)),
+            _ => Err(CommandError::InvalidSpeed(s.to_string())),
+        }
+    }
+}
 
 /// possible set command key values
 /// ```
@@ -49,46 +394,180 @@ impl<'de> Deserialize<'de> for Cyclic {
 pub enum Setting {
     Log(LogLevel),
     Filter(FilterType),
+    FilterCustom(FilterPreset),
+    Sharpen(Sharpness),
     ColorSpace(ColorSpace),
     ColorDepth(ColorDepth),
     Color(ColorType),
     Width(Dimension),
     Height(Dimension),
-    LumaCorrect(u8),
+    Padding(Padding),
+    Align(Align),
+    LumaCorrect(Correction),
+    LumaCurve(LumaCurve),
+    GrayRamp(GrayRamp),
+    Exposure(Stops),
+    ToneMap(ToneMap),
+    Colormap(Colormap),
+    Renderer(Renderer),
+    Symbols(Symbols),
 }
 impl FromStr for Setting {
-    type Err = String;
+    type Err = CommandError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        /// Parse the value of a setting
+        fn value<T: FromStr>(value: Option<&str>, setting: &'static str) -> Result<T, CommandError>
+        where
+            T::Err: std::fmt::Display,
+        {
+            value
+                .ok_or(CommandError::MissingValue(setting))?
+                .parse()
+                .map_err(|e: T::Err| CommandError::InvalidValue { setting, reason: e.to_string() })
+        }
         // Assume whitespace is already cleaned
         let mut split = s.splitn(2, |c: char| c.is_ascii_whitespace());
         // Parse the command
         match split.next() {
             Some(s1) => match s1 {
-                "log" => Ok(Self::Log(split.next().ok_or(String::from("missing arguments to set log"))?.parse()?)),
-                "filter" => Ok(Self::Filter(split.next().ok_or(String::from("missing arguments to set filter"))?.parse()?)),
-                "color_space" => {
-                    Ok(Self::ColorSpace(split.next().ok_or(String::from("missing arguments to set color_space"))?.parse()?))
-                }
-                "color_depth" => {
-                    Ok(Self::ColorDepth(split.next().ok_or(String::from("missing arguments to set color_depth"))?.parse()?))
-                }
-                "color" => Ok(Self::Color(split.next().ok_or(String::from("missing arguments to set color"))?.parse()?)),
-                "width" => Ok(Self::Width(split.next().ok_or(String::from("missing arguments to set width"))?.parse()?)),
-                "height" => Ok(Self::Height(split.next().ok_or(String::from("missing arguments to set height"))?.parse()?)),
-                "luma_correct" | "correct" => Ok(Self::LumaCorrect(
-                    split
-                        .next()
-                        .ok_or(String::from("missing arguments to set luma_correct"))?
-                        .parse()
-                        .map_err(|e| format!("{e}"))?,
-                )),
-                _ => Err(format!("{s:?} is not a valid SetCommand")),
+                "log" => Ok(Self::Log(value(split.next(), "log")?)),
+                "filter" => Ok(Self::Filter(value(split.next(), "filter")?)),
+                "filter_custom" => Ok(Self::FilterCustom(value(split.next(), "filter_custom")?)),
+                "sharpen" => Ok(Self::Sharpen(value(split.next(), "sharpen")?)),
+                "color_space" => Ok(Self::ColorSpace(value(split.next(), "color_space")?)),
+                "color_depth" => Ok(Self::ColorDepth(value(split.next(), "color_depth")?)),
+                "color" => Ok(Self::Color(value(split.next(), "color")?)),
+                "width" => Ok(Self::Width(value(split.next(), "width")?)),
+                "height" => Ok(Self::Height(value(split.next(), "height")?)),
+                "padding" => Ok(Self::Padding(value(split.next(), "padding")?)),
+                "align" => Ok(Self::Align(value(split.next(), "align")?)),
+                "luma_correct" | "correct" => Ok(Self::LumaCorrect(value(split.next(), "luma_correct")?)),
+                "luma_curve" => Ok(Self::LumaCurve(value(split.next(), "luma_curve")?)),
+                "gray_ramp" => Ok(Self::GrayRamp(value(split.next(), "gray_ramp")?)),
+                "exposure" => Ok(Self::Exposure(value(split.next(), "exposure")?)),
+                "tone_map" => Ok(Self::ToneMap(value(split.next(), "tone_map")?)),
+                "colormap" => Ok(Self::Colormap(value(split.next(), "colormap")?)),
+                "renderer" => Ok(Self::Renderer(value(split.next(), "renderer")?)),
+                "symbols" => Ok(Self::Symbols(value(split.next(), "symbols")?)),
+                _ => Err(CommandError::InvalidSetting(s1.to_string())),
             },
-            None => Err(format!("empty SetCommand")),
+            None => Err(CommandError::MissingArguments("set")),
+        }
+    }
+}
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Log(level) => write!(f, "log {level}"),
+            Self::Filter(filter) => write!(f, "filter {filter}"),
+            Self::FilterCustom(preset) => write!(f, "filter_custom {preset}"),
+            Self::Sharpen(amount) => write!(f, "sharpen {amount}"),
+            Self::ColorSpace(space) => write!(f, "color_space {space}"),
+            Self::ColorDepth(depth) => write!(f, "color_depth {depth}"),
+            Self::Color(color) => write!(f, "color {color}"),
+            Self::Width(width) => write!(f, "width {width}"),
+            Self::Height(height) => write!(f, "height {height}"),
+            Self::Padding(padding) => write!(f, "padding {padding}"),
+            Self::Align(align) => write!(f, "align {align}"),
+            Self::LumaCorrect(correct) => write!(f, "luma_correct {correct}"),
+            Self::LumaCurve(curve) => write!(f, "luma_curve {curve}"),
+            Self::GrayRamp(ramp) => write!(f, "gray_ramp {ramp}"),
+            Self::Exposure(stops) => write!(f, "exposure {stops}"),
+            Self::ToneMap(op) => write!(f, "tone_map {op}"),
+            Self::Colormap(colormap) => write!(f, "colormap {colormap}"),
+            Self::Renderer(renderer) => write!(f, "renderer {renderer}"),
+            Self::Symbols(symbols) => write!(f, "symbols {symbols}"),
+        }
+    }
+}
+impl Setting {
+    /// Set the setting in `conf`, without redrawing anything
+    pub fn apply(self, conf: &mut Config) {
+        match self {
+            Self::Log(level) => conf.log = level,
+            Self::Filter(filter) => {
+                conf.filter = filter;
+                conf.filter_custom = None;
+            }
+            Self::FilterCustom(preset) => conf.filter_custom = Some(preset),
+            Self::Sharpen(Sharpness(amount)) => conf.sharpen = amount,
+            Self::ColorSpace(space) if conf.color.space() != space => conf.color = conf.color.cycle_space(),
+            Self::ColorDepth(depth) if conf.color.depth() != depth => conf.color = conf.color.cycle_depth(),
+            Self::ColorSpace(_) | Self::ColorDepth(_) => (),
+            Self::Color(color) => conf.color = color,
+            Self::Width(width) => conf.width = width,
+            Self::Height(height) => conf.height = height,
+            Self::Padding(padding) => conf.padding = padding,
+            Self::Align(align) => conf.align = Some(align),
+            Self::LumaCorrect(Correction(correct)) => conf.luma_correct = correct,
+            Self::LumaCurve(curve) => conf.luma_curve = curve,
+            Self::GrayRamp(ramp) => conf.gray_ramp = ramp,
+            Self::Exposure(Stops(ev)) => conf.exposure = ev,
+            Self::ToneMap(op) => conf.tone_map = op,
+            Self::Colormap(colormap) => conf.colormap = colormap,
+            Self::Renderer(renderer) => conf.renderer = renderer,
+            Self::Symbols(symbols) => conf.symbols = symbols,
         }
     }
 }
 
+/// Settings switched to together with the preset command, from a `[preset.<name>]` table of the config file
+/// of any settings of the set command, e.g. `filter = "nearest"` and `luma-correct = 80`
+/// ```
+/// use viuwa::{Config, Preset};
+/// use viuwa_image::FilterType;
+/// let preset: Preset = toml::from_str("filter = \"Nearest\"\nsharpen = 0.5\nluma-correct = 80").unwrap();
+/// let mut conf = Config::default();
+/// preset.apply(&mut conf);
+/// assert_eq!((conf.filter, conf.sharpen, conf.luma_correct), (FilterType::Nearest, 0.5, 80.));
+/// assert!(toml::from_str::<Preset>("dither = \"none\"").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preset(pub Vec<Setting>);
+impl Preset {
+    /// Set every setting of the preset in `conf`, in order
+    #[inline]
+    pub fn apply(&self, conf: &mut Config) { self.0.iter().for_each(|setting| setting.clone().apply(conf)) }
+}
+impl<'de> Deserialize<'de> for Preset {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let table = BTreeMap::<String, ::toml::Value>::deserialize(deserializer)?;
+        let settings = table.into_iter().map(|(key, value)| {
+            let value = match value {
+                ::toml::Value::String(s) => s,
+                value => value.to_string(),
+            };
+            // the set command takes the keys in snake case, and its arguments lowercased
+            format!("{} {value}", key.replace('-', "_")).to_ascii_lowercase().parse::<Setting>()
+        });
+        settings.collect::<Result<_, _>>().map(Self).map_err(de::Error::custom)
+    }
+}
+impl Serialize for Preset {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for setting in &self.0 {
+            let setting = setting.to_string();
+            let (key, value) = setting.split_once(' ').unwrap_or((&setting, ""));
+            let key = key.replace('_', "-");
+            // numbers are written as numbers, as they'd be written by hand
+            match (value.parse::<i64>(), value.parse::<f64>()) {
+                (Ok(n), _) => map.serialize_entry(&key, &n)?,
+                (_, Ok(n)) if n.is_finite() => map.serialize_entry(&key, &n)?,
+                _ => map.serialize_entry(&key, value)?,
+            }
+        }
+        map.end()
+    }
+}
+
 /// KeyEvent ignoring kind and state
 /// ```
 /// use viuwa::KeyBind;
@@ -96,18 +575,18 @@ impl FromStr for Setting {
 /// use std::str::FromStr;
 /// assert_eq!(KeyBind::from_str("ctrl+q"), Ok(KeyBind(KeyEvent::new(KeyCode::Char('q')), KeyModifiers::CONTROL)));
 /// ```
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
 #[derive(Debug, Clone, Copy, Eq)]
 #[repr(transparent)]
 pub struct KeyBind(pub KeyEvent);
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
 impl FromStr for KeyBind {
-    type Err = String;
+    type Err = CommandError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         use KeyCode::*;
         macro_rules! bail {
             () => {
-                return Err(format!("{s:?} is not a valid keybind"))
+                return Err(CommandError::InvalidKeyBind(s.to_string()))
             };
         }
         if s.is_empty() {
@@ -172,7 +651,7 @@ impl FromStr for KeyBind {
         )))
     }
 }
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
 impl<'de> Deserialize<'de> for KeyBind {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -181,25 +660,74 @@ impl<'de> Deserialize<'de> for KeyBind {
         String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
     }
 }
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
+impl fmt::Display for KeyBind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use KeyCode::*;
+        let Self(KeyEvent { code, modifiers, .. }) = self;
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("ctrl+")?;
+        }
+        if modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("alt+")?;
+        }
+        // backtab is always sent with shift
+        if modifiers.contains(KeyModifiers::SHIFT) && *code != BackTab {
+            f.write_str("shift+")?;
+        }
+        match code {
+            Char(' ') => f.write_str("space"),
+            Char('+') => f.write_str("plus"),
+            Char(c) => write!(f, "{}", c.to_ascii_lowercase()),
+            F(n) => write!(f, "f{n}"),
+            Backspace => f.write_str("backspace"),
+            BackTab => f.write_str("backtab"),
+            Delete => f.write_str("delete"),
+            Down => f.write_str("down"),
+            End => f.write_str("end"),
+            Enter => f.write_str("enter"),
+            Esc => f.write_str("esc"),
+            Home => f.write_str("home"),
+            Insert => f.write_str("insert"),
+            Left => f.write_str("left"),
+            PageDown => f.write_str("pagedown"),
+            PageUp => f.write_str("pageup"),
+            Right => f.write_str("right"),
+            Tab => f.write_str("tab"),
+            Up => f.write_str("up"),
+            code => write!(f, "{}", format!("{code:?}").to_ascii_lowercase()),
+        }
+    }
+}
+#[cfg(not(target_family = "wasm"))]
+impl Serialize for KeyBind {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+#[cfg(not(target_family = "wasm"))]
 impl PartialEq for KeyBind {
     fn eq(&self, Self(KeyEvent { code, modifiers, .. }): &Self) -> bool {
         let Self(KeyEvent { code: c, modifiers: m, .. }) = self;
         (code, modifiers) == (c, m)
     }
 }
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
 impl PartialOrd for KeyBind {
     fn partial_cmp(&self, Self(KeyEvent { code, modifiers, .. }): &Self) -> Option<std::cmp::Ordering> {
         let Self(KeyEvent { code: c, modifiers: m, .. }) = self;
         (code, modifiers).partial_cmp(&(c, m)).or(Some(std::cmp::Ordering::Equal))
     }
 }
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
 impl Ord for KeyBind {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.partial_cmp(other).unwrap() }
 }
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
 impl core::hash::Hash for KeyBind {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         let Self(KeyEvent { code, modifiers, .. }) = self;
@@ -223,29 +751,68 @@ pub enum Command {
     Refresh,
     /// Refill the image buffer and redraw.
     Reload,
+    /// Read the config file again, merged with the command line arguments, and apply it.
+    ReloadConfig,
     /// Set a config value. (e.g. `set log debug`)
     Set(Setting),
     /// Bind a key to a command.
     Bind(
-        #[cfg(not(target_os = "wasi"))] KeyBind,
-        #[cfg(target_os = "wasi")] String,
+        #[cfg(not(target_family = "wasm"))] KeyBind,
+        #[cfg(target_family = "wasm")] String,
         /// The command to bind to be parsed later. (fail quietly if invalid)
         Action,
     ),
     /// Unbind a key
-    Unbind(#[cfg(not(target_os = "wasi"))] KeyBind, #[cfg(target_os = "wasi")] String),
+    Unbind(#[cfg(not(target_family = "wasm"))] KeyBind, #[cfg(target_family = "wasm")] String),
     /// Cycle through color or filter modes.
     Cycle(
         /// The mode to cycle.
         Cyclic,
     ),
+    /// Write the current config to a file, or the config file if no path is given.
+    WriteConfig(Option<String>),
+    /// Save the image to a file, in the format of its extension.
+    Save(String),
+    /// Save what the terminal shows of the image to a PNG, each cell rasterized as the pixels it draws.
+    Screenshot(String),
+    /// Swap the sides of the compared images.
+    Swap,
+    /// Zoom the image, or the compared images.
+    Zoom(Zoom),
+    /// Pan the zoomed compared images, or the image larger than the terminal.
+    Pan(Pan),
+    /// Scroll the image a column or row at a time.
+    Scroll(Pan),
+    /// Fit the image to the width or height of the terminal, panning over the rest.
+    Fit(Fit),
+    /// Go to a page of a multi-page document, or a frame of a multi-image file.
+    Page(Page),
+    /// Toggle the overlay of the time spent decoding, resizing, converting, and writing the last frame.
+    Stats,
+    /// Play animations at a multiple of their own speed.
+    Speed(Speed),
+    /// Go to a frame of the animation, or to the frame shown at a time into it.
+    Seek(Seek),
+    /// Pause the animation and step to the next or previous frame.
+    Step(Step),
+    /// Pause or resume the animation.
+    Pause,
+    /// Set the operations applied to the image, as their normalized expression (empty for none),
+    /// or edit the current ones if `None`.
+    Ops(Option<String>),
+    /// Switch to the settings of a preset of the config file, or list the presets if `None`.
+    Preset(Option<String>),
+    /// Choose an image viewed recently to open from a menu.
+    Recent,
+    /// Open the next or previous image of the grid the image was opened from.
+    Image(Step),
 }
 impl FromStr for Command {
-    type Err = String;
+    type Err = CommandError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Clean whitespace
         let mut in_space = false;
-        let s = s
+        let clean = s
             .trim_matches(|c: char| c.is_ascii_whitespace())
             .chars()
             .filter(move |c| {
@@ -254,8 +821,8 @@ impl FromStr for Command {
                 in_space = space;
                 r
             })
-            .collect::<String>()
-            .to_ascii_lowercase();
+            .collect::<String>();
+        let s = clean.to_ascii_lowercase();
         let mut split = s.splitn(2, |c: char| c.is_ascii_whitespace());
         // Parse the command
         match split.next() {
@@ -264,31 +831,71 @@ impl FromStr for Command {
                 "help" => Ok(Self::Help),
                 "refresh" => Ok(Self::Refresh),
                 "reload" => Ok(Self::Reload),
-                "cycle" => Ok(Self::Cycle(split.next().ok_or(String::from("missing arguments to cycle"))?.parse()?)),
+                "reloadconfig" => Ok(Self::ReloadConfig),
+                // paths are case sensitive, so take it from before lowercasing
+                "writeconfig" => Ok(Self::WriteConfig(
+                    clean.split_once(|c: char| c.is_ascii_whitespace()).map(|(_, path)| path.to_string()),
+                )),
+                "save" => Ok(Self::Save(
+                    clean
+                        .split_once(|c: char| c.is_ascii_whitespace())
+                        .map(|(_, path)| path.to_string())
+                        .ok_or(CommandError::MissingArguments("save"))?,
+                )),
+                "screenshot" => Ok(Self::Screenshot(
+                    clean
+                        .split_once(|c: char| c.is_ascii_whitespace())
+                        .map(|(_, path)| path.to_string())
+                        .ok_or(CommandError::MissingArguments("screenshot"))?,
+                )),
+                "cycle" => Ok(Self::Cycle(split.next().ok_or(CommandError::MissingArguments("cycle"))?.parse()?)),
+                "swap" => Ok(Self::Swap),
+                "zoom" => Ok(Self::Zoom(split.next().ok_or(CommandError::MissingArguments("zoom"))?.parse()?)),
+                "pan" => Ok(Self::Pan(split.next().ok_or(CommandError::MissingArguments("pan"))?.parse()?)),
+                "scroll" => Ok(Self::Scroll(split.next().ok_or(CommandError::MissingArguments("scroll"))?.parse()?)),
+                "fit" => Ok(Self::Fit(split.next().ok_or(CommandError::MissingArguments("fit"))?.parse()?)),
+                "page" => Ok(Self::Page(split.next().ok_or(CommandError::MissingArguments("page"))?.parse()?)),
+                "frame" => Ok(Self::Page(split.next().ok_or(CommandError::MissingArguments("frame"))?.parse()?)),
+                "stats" => Ok(Self::Stats),
+                "speed" => Ok(Self::Speed(split.next().ok_or(CommandError::MissingArguments("speed"))?.parse()?)),
+                "seek" => Ok(Self::Seek(split.next().ok_or(CommandError::MissingArguments("seek"))?.parse()?)),
+                "step" => Ok(Self::Step(split.next().ok_or(CommandError::MissingArguments("step"))?.parse()?)),
+                "pause" => Ok(Self::Pause),
+                "ops" => Ok(Self::Ops(match split.next() {
+                    None => None,
+                    Some("none") => Some(String::new()),
+                    Some(ops) => Some(ops.parse::<Ops>().map_err(CommandError::InvalidOps)?.to_string()),
+                })),
+                // preset names are keys of the config file, so they're case sensitive
+                "preset" => {
+                    Ok(Self::Preset(clean.split_once(|c: char| c.is_ascii_whitespace()).map(|(_, name)| name.to_string())))
+                }
+                "recent" => Ok(Self::Recent),
+                "image" => Ok(Self::Image(split.next().ok_or(CommandError::MissingArguments("image"))?.parse()?)),
                 "unbind" => Ok(Self::Unbind({
-                    #[cfg(target_os = "wasi")]
+                    #[cfg(target_family = "wasm")]
                     {
-                        split.next().ok_or(String::from("missing arguments to unbind"))?.to_string()
+                        split.next().ok_or(CommandError::MissingArguments("unbind"))?.to_string()
                     }
-                    #[cfg(not(target_os = "wasi"))]
+                    #[cfg(not(target_family = "wasm"))]
                     {
-                        split.next().ok_or(String::from("missing arguments to unbind"))?.parse()?
+                        split.next().ok_or(CommandError::MissingArguments("unbind"))?.parse()?
                     }
                 })),
-                "set" => Ok(Self::Set(split.next().ok_or(String::from("missing arguments to set"))?.parse()?)),
+                "set" => Ok(Self::Set(split.next().ok_or(CommandError::MissingArguments("set"))?.parse()?)),
                 "bind" => {
                     let mut split = split
                         .next()
-                        .ok_or(String::from("missing arguments to bind"))?
+                        .ok_or(CommandError::MissingArguments("bind"))?
                         .splitn(2, |c: char| c.is_ascii_whitespace());
                     let key = {
-                        #[cfg(target_os = "wasi")]
+                        #[cfg(target_family = "wasm")]
                         {
-                            split.next().ok_or(String::from("missing arguments to unbind"))?.to_string()
+                            split.next().ok_or(CommandError::MissingArguments("bind"))?.to_string()
                         }
-                        #[cfg(not(target_os = "wasi"))]
+                        #[cfg(not(target_family = "wasm"))]
                         {
-                            split.next().ok_or(String::from("missing arguments to unbind"))?.parse()?
+                            split.next().ok_or(CommandError::MissingArguments("bind"))?.parse()?
                         }
                     };
                     Ok(match split.next() {
@@ -296,9 +903,9 @@ impl FromStr for Command {
                         None => Self::Unbind(key),
                     })
                 }
-                _ => Err(format!("{s:?} is not a valid Command")),
+                _ => Err(CommandError::Unknown(s1.to_string())),
             },
-            None => Err(format!("empty Command")),
+            None => Err(CommandError::Empty),
         }
     }
 }
@@ -312,6 +919,104 @@ impl<'de> Deserialize<'de> for Command {
     }
 }
 
+/// The usage and description of every command of the pseudo command line, for the help screen and cheatsheet
+pub const COMMANDS: [(&str, &str); 28] = [
+    ("quit", "exit the current screen"),
+    ("help", "show this help screen"),
+    ("refresh", "redraw the image"),
+    ("reload", "reload the image buffer and refresh"),
+    ("reloadconfig", "read the config file again and apply it"),
+    ("cycle <config>", "cycle through a cyclable config"),
+    ("set <config> <value>", "set a config value"),
+    ("bind <keybind> <command>", "bind a keybind to a command"),
+    ("unbind <keybind>", "unbind a keybind"),
+    ("writeconfig [path]", "write the current config to a file"),
+    ("save <path>", "save the image to a file"),
+    ("screenshot <path>", "save what the terminal shows as a png"),
+    ("swap", "swap the sides of, or flip between, compared images"),
+    ("zoom <in|out|reset>", "zoom the image, reset also unfits it"),
+    ("pan <direction>", "pan the zoomed, fit, or oversized image"),
+    ("scroll <direction>", "scroll the image a cell at a time"),
+    ("fit <width|height>", "fit the image to the terminal width or height"),
+    ("page <next|prev|n>", "go to a page of a document"),
+    ("frame <next|prev|n>", "go to a frame of a multi-image file"),
+    ("stats", "toggle the frame timings and frame rate"),
+    ("speed <x>", "play animations x times as fast"),
+    ("seek <frame|time>", "go to a frame, or a time like 1.5s, of an animation"),
+    ("step <next|prev>", "pause an animation and step a frame"),
+    ("pause", "pause or resume an animation"),
+    ("ops [ops|none]", "set or edit the operations, e.g. rotate=90;gray"),
+    ("preset [name]", "switch to the settings of a preset, or list them"),
+    ("recent", "choose a recently viewed image to open"),
+    ("image <next|prev>", "open the next or previous image of the grid"),
+];
+
+/// Commands of the pseudo command line of the grid, changing which images it shows and in what order,
+/// and the file actions acting on the selected image.
+/// ```
+/// use viuwa::GridCommand;
+/// use std::str::FromStr;
+/// assert_eq!(GridCommand::from_str("mv Keep/"), Ok(GridCommand::Move(String::from("Keep/"))));
+/// assert_eq!(GridCommand::from_str("filter none"), Ok(GridCommand::Filter(None)));
+/// assert!(GridCommand::from_str("cp").is_err());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum GridCommand {
+    /// Leave the grid.
+    Quit,
+    /// Put the images in an order.
+    Sort(SortOrder),
+    /// Only show the images matching comma separated globs, or every image if `None`.
+    Filter(Option<String>),
+    /// Move the selected image into a directory, or to a path, relative to the grid's directory.
+    Move(String),
+    /// Copy the selected image into a directory, or to a path, relative to the grid's directory.
+    Copy(String),
+    /// Rename the selected image within its directory.
+    Rename(String),
+    /// Remove the selected image once confirmed, to the trash with the `trash` feature.
+    Remove,
+}
+impl FromStr for GridCommand {
+    type Err = CommandError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_matches(|c: char| c.is_ascii_whitespace());
+        let (name, arg) = match s.split_once(|c: char| c.is_ascii_whitespace()) {
+            Some((name, arg)) => (name, Some(arg.trim_start_matches(|c: char| c.is_ascii_whitespace()).to_string())),
+            None => (s, None),
+        };
+        // paths are case sensitive, so only the name of the command is lowercased
+        match name.to_ascii_lowercase().as_str() {
+            "" => Err(CommandError::Empty),
+            "quit" | "q" => Ok(Self::Quit),
+            "sort" => Ok(Self::Sort(
+                arg.ok_or(CommandError::MissingArguments("sort"))?
+                    .to_ascii_lowercase()
+                    .parse()
+                    .map_err(|e: ConfigError| CommandError::InvalidValue { setting: "sort", reason: e.to_string() })?,
+            )),
+            "filter" => Ok(Self::Filter(arg.filter(|globs| !globs.is_empty() && globs != "none"))),
+            "mv" | "move" => Ok(Self::Move(arg.ok_or(CommandError::MissingArguments("mv"))?)),
+            "cp" | "copy" => Ok(Self::Copy(arg.ok_or(CommandError::MissingArguments("cp"))?)),
+            "rename" => Ok(Self::Rename(arg.ok_or(CommandError::MissingArguments("rename"))?)),
+            "rm" | "trash" => Ok(Self::Remove),
+            _ => Err(CommandError::Unknown(name.to_string())),
+        }
+    }
+}
+
+/// The usage and description of every command of the grid's pseudo command line, for the cheatsheet
+pub const GRID_COMMANDS: [(&str, &str); 7] = [
+    ("quit", "leave the grid"),
+    ("sort <order>", "order the images by name, mtime, size, or random"),
+    ("filter [globs|none]", "only show images matching globs, e.g. *.png,IMG_*"),
+    ("mv <dir|path>", "move the selected image, with --file-actions"),
+    ("cp <dir|path>", "copy the selected image, with --file-actions"),
+    ("rename <name>", "rename the selected image, with --file-actions"),
+    ("rm", "trash (or delete) the selected image once confirmed, with --file-actions"),
+];
+
 /// The commands that a key can be bound to.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[non_exhaustive]
@@ -320,11 +1025,27 @@ pub enum Action {
     Help,
     Refresh,
     Reload,
+    ReloadConfig,
     Set(Setting),
     Cycle(Cyclic),
+    Swap,
+    Zoom(Zoom),
+    Pan(Pan),
+    Scroll(Pan),
+    Fit(Fit),
+    Page(Page),
+    Stats,
+    Speed(Speed),
+    Seek(Seek),
+    Step(Step),
+    Pause,
+    Ops(Option<String>),
+    Preset(Option<String>),
+    Recent,
+    Image(Step),
 }
 impl FromStr for Action {
-    type Err = String;
+    type Err = CommandError;
     #[inline]
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match Command::from_str(s) {
@@ -333,9 +1054,25 @@ impl FromStr for Action {
                 Command::Help => Ok(Self::Help),
                 Command::Refresh => Ok(Self::Refresh),
                 Command::Reload => Ok(Self::Reload),
+                Command::ReloadConfig => Ok(Self::ReloadConfig),
                 Command::Set(setting) => Ok(Self::Set(setting)),
                 Command::Cycle(cycle) => Ok(Self::Cycle(cycle)),
-                _ => Err(format!("{s:?} cannot be bound to a key")),
+                Command::Swap => Ok(Self::Swap),
+                Command::Zoom(zoom) => Ok(Self::Zoom(zoom)),
+                Command::Pan(pan) => Ok(Self::Pan(pan)),
+                Command::Scroll(pan) => Ok(Self::Scroll(pan)),
+                Command::Fit(fit) => Ok(Self::Fit(fit)),
+                Command::Page(page) => Ok(Self::Page(page)),
+                Command::Stats => Ok(Self::Stats),
+                Command::Speed(speed) => Ok(Self::Speed(speed)),
+                Command::Seek(seek) => Ok(Self::Seek(seek)),
+                Command::Step(step) => Ok(Self::Step(step)),
+                Command::Pause => Ok(Self::Pause),
+                Command::Ops(ops) => Ok(Self::Ops(ops)),
+                Command::Preset(name) => Ok(Self::Preset(name)),
+                Command::Recent => Ok(Self::Recent),
+                Command::Image(step) => Ok(Self::Image(step)),
+                _ => Err(CommandError::Unbindable(s.to_string())),
             },
             Err(e) => Err(e),
         }
@@ -350,6 +1087,46 @@ impl<'de> Deserialize<'de> for Action {
         String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
     }
 }
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Quit => f.write_str("quit"),
+            Self::Help => f.write_str("help"),
+            Self::Refresh => f.write_str("refresh"),
+            Self::Reload => f.write_str("reload"),
+            Self::ReloadConfig => f.write_str("reloadconfig"),
+            Self::Set(setting) => write!(f, "set {setting}"),
+            Self::Cycle(cycle) => write!(f, "cycle {cycle}"),
+            Self::Swap => f.write_str("swap"),
+            Self::Zoom(zoom) => write!(f, "zoom {zoom}"),
+            Self::Pan(pan) => write!(f, "pan {pan}"),
+            Self::Scroll(pan) => write!(f, "scroll {pan}"),
+            Self::Fit(fit) => write!(f, "fit {fit}"),
+            Self::Page(page) => write!(f, "page {page}"),
+            Self::Stats => f.write_str("stats"),
+            Self::Speed(speed) => write!(f, "speed {speed}"),
+            Self::Seek(seek) => write!(f, "seek {seek}"),
+            Self::Step(step) => write!(f, "step {step}"),
+            Self::Pause => f.write_str("pause"),
+            Self::Ops(None) => f.write_str("ops"),
+            Self::Ops(Some(ops)) if ops.is_empty() => f.write_str("ops none"),
+            Self::Ops(Some(ops)) => write!(f, "ops {ops}"),
+            Self::Preset(None) => f.write_str("preset"),
+            Self::Preset(Some(name)) => write!(f, "preset {name}"),
+            Self::Recent => f.write_str("recent"),
+            Self::Image(step) => write!(f, "image {step}"),
+        }
+    }
+}
+impl Serialize for Action {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
 
 impl From<Action> for Command {
     #[inline]
@@ -359,8 +1136,24 @@ impl From<Action> for Command {
             Action::Help => Self::Help,
             Action::Refresh => Self::Refresh,
             Action::Reload => Self::Reload,
+            Action::ReloadConfig => Self::ReloadConfig,
             Action::Set(setting) => Self::Set(setting),
             Action::Cycle(cycle) => Self::Cycle(cycle),
+            Action::Swap => Self::Swap,
+            Action::Zoom(zoom) => Self::Zoom(zoom),
+            Action::Pan(pan) => Self::Pan(pan),
+            Action::Scroll(pan) => Self::Scroll(pan),
+            Action::Fit(fit) => Self::Fit(fit),
+            Action::Page(page) => Self::Page(page),
+            Action::Stats => Self::Stats,
+            Action::Speed(speed) => Self::Speed(speed),
+            Action::Seek(seek) => Self::Seek(seek),
+            Action::Step(step) => Self::Step(step),
+            Action::Pause => Self::Pause,
+            Action::Ops(ops) => Self::Ops(ops),
+            Action::Preset(name) => Self::Preset(name),
+            Action::Recent => Self::Recent,
+            Action::Image(step) => Self::Image(step),
         }
     }
 }
@@ -0,0 +1,173 @@
+//! Comparing two images side by side (or top and bottom) or flipping between them in place, with a synced zoom and pan.
+use super::*;
+
+/// The most the compared images can be zoomed in
+pub const MAX_ZOOM: u16 = 32;
+
+/// How the two images of a [`Comparison`] are arranged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    SideBySide,
+    TopBottom,
+    /// One image at a time over the whole terminal, both resized to the same dimensions
+    Flip,
+}
+
+/// A second image compared against the main image of a [`Viuwa`], sharing its zoom and pan
+pub struct Comparison<P: Pixel>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// The image compared against the main image
+    pub other: Image<P>,
+    /// The resized crops of the main and the other image
+    pub bufs: [Image<P>; 2],
+    /// The ANSI of both buffers while flipping, so flipping only has to write it
+    rendered: [Vec<u8>; 2],
+    /// The buffers the rows are converted into while pre-rendering
+    row_bufs: RowBuffers,
+    /// How the images are arranged, whichever displays them larger unless flipping
+    pub layout: Layout,
+    /// Whether the other image is drawn first, or shown while flipping
+    pub swapped: bool,
+    /// The zoom level, 1 shows the whole images
+    pub zoom: u16,
+    /// The center of the visible crop, relative to the size of each image
+    pub center: (f64, f64),
+}
+impl<P: Pixel> Comparison<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// Compare the main image against `other`, zoomed out, flipping between them if `flip` is set
+    pub fn new(other: Image<P>, flip: bool) -> Self {
+        Self {
+            other,
+            bufs: [Image::default(), Image::default()],
+            rendered: [Vec::new(), Vec::new()],
+            row_bufs: RowBuffers::default(),
+            layout: if flip { Layout::Flip } else { Layout::SideBySide },
+            swapped: false,
+            zoom: 1,
+            center: (0.5, 0.5),
+        }
+    }
+    /// The columns and rows of each pane of a layout, leaving a column or row between the panes
+    #[inline]
+    pub fn pane(layout: Layout, sz: (u16, u16)) -> (u16, u16) {
+        match layout {
+            Layout::SideBySide => (sz.0.saturating_sub(1) / 2, sz.1),
+            Layout::TopBottom => (sz.0, sz.1.saturating_sub(1) / 2),
+            Layout::Flip => sz,
+        }
+    }
+    /// The top left corner of the `i`th pane of the current layout
+    #[inline]
+    pub fn pane_origin(&self, sz: (u16, u16), i: u16) -> (u16, u16) {
+        let pane = Self::pane(self.layout, sz);
+        match self.layout {
+            Layout::SideBySide => ((pane.0 + 1) * i, 0),
+            Layout::TopBottom => (0, (pane.1 + 1) * i),
+            Layout::Flip => (0, 0),
+        }
+    }
+    /// The resized crops in the order they are drawn
+    #[inline]
+    pub fn drawn(&self) -> [&Image<P>; 2] {
+        if self.swapped {
+            [&self.bufs[1], &self.bufs[0]]
+        } else {
+            [&self.bufs[0], &self.bufs[1]]
+        }
+    }
+    /// The x, y, width, and height of the visible crop of an image of `size` at the current zoom and pan
+    pub fn crop(&self, (w, h): (usize, usize)) -> (usize, usize, usize, usize) {
+        let zoom = self.zoom as f64;
+        let cw = ((w as f64 / zoom).round() as usize).clamp(1, w.max(1));
+        let ch = ((h as f64 / zoom).round() as usize).clamp(1, h.max(1));
+        let x = (self.center.0 * w as f64 - cw as f64 / 2.).round().clamp(0., w.saturating_sub(cw) as f64) as usize;
+        let y = (self.center.1 * h as f64 - ch as f64 / 2.).round().clamp(0., h.saturating_sub(ch) as f64) as usize;
+        (x, y, cw, ch)
+    }
+    /// The pre-rendered ANSI of the shown image while flipping
+    #[inline]
+    pub fn shown(&self) -> &[u8] { &self.rendered[self.swapped as usize] }
+    /// Render both buffers as ANSI aligned in the area of `sz` at `origin`, so flipping between them is instant
+    pub fn prerender(
+        &mut self,
+        origin: (u16, u16),
+        sz: (u16, u16),
+        attrs: ColorAttributes,
+        conf: &Config,
+    ) -> io::Result<()> {
+        trace!("Comparison::prerender");
+        for (rendered, buf) in self.rendered.iter_mut().zip(&self.bufs) {
+            rendered.clear();
+            draw_aligned(rendered, buf, origin, sz, attrs, conf, 0..u16::MAX, &mut self.row_bufs)?;
+        }
+        Ok(())
+    }
+    /// Resize the visible crops of both images to fit the terminal, in whichever layout displays them larger.
+    ///
+    /// While flipping, the other image is resized to the dimensions of the main image so they line up.
+    pub fn reload(&mut self, sz: (u16, u16), conf: &Config, orig: &Image<P>) {
+        trace!("Comparison::reload");
+        let crops = [self.crop(orig.dimensions()), self.crop(self.other.dimensions())];
+        let dims = if self.layout == Layout::Flip {
+            let dims = dimensions(sz, conf, (crops[0].2, crops[0].3));
+            [dims, dims]
+        } else {
+            let area = |layout| {
+                let pane = Self::pane(layout, sz);
+                crops
+                    .iter()
+                    .map(|&(_, _, w, h)| {
+                        let (w, h) = dimensions(pane, conf, (w, h));
+                        w * h
+                    })
+                    .sum::<usize>()
+            };
+            self.layout =
+                if area(Layout::SideBySide) >= area(Layout::TopBottom) { Layout::SideBySide } else { Layout::TopBottom };
+            let pane = Self::pane(self.layout, sz);
+            crops.map(|(_, _, w, h)| dimensions(pane, conf, (w, h)))
+        };
+        for ((buf, dims), (image, (x, y, w, h))) in
+            self.bufs.iter_mut().zip(dims).zip([(orig, crops[0]), (&self.other, crops[1])])
+        {
+            let view = image.view().subview(x, y, w, h).expect("the crop is within the image");
+            *buf = resize_view(&view, dims, conf.resize_filter());
+            sharpen(buf, (w, h), conf.sharpen);
+        }
+    }
+    /// Zoom both images, keeping the center of the crop
+    pub fn zoom(&mut self, zoom: Zoom) {
+        match zoom {
+            Zoom::In => self.zoom = self.zoom.saturating_mul(2).min(MAX_ZOOM),
+            Zoom::Out => self.zoom = (self.zoom / 2).max(1),
+            Zoom::Reset => {
+                self.zoom = 1;
+                self.center = (0.5, 0.5);
+            }
+        }
+        self.clamp_center();
+    }
+    /// Pan both images by a quarter of the visible crop
+    pub fn pan(&mut self, pan: Pan) {
+        let step = 0.25 / self.zoom as f64;
+        match pan {
+            Pan::Left => self.center.0 -= step,
+            Pan::Right => self.center.0 += step,
+            Pan::Up => self.center.1 -= step,
+            Pan::Down => self.center.1 += step,
+        }
+        self.clamp_center();
+    }
+    /// Keep the crop within the images, so panning back from an edge responds immediately
+    fn clamp_center(&mut self) {
+        let half = 0.5 / self.zoom as f64;
+        self.center = (self.center.0.clamp(half, 1. - half), self.center.1.clamp(half, 1. - half));
+    }
+}
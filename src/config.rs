@@ -1,21 +1,25 @@
 use clap::{value_parser, Parser};
+use unicode_width::UnicodeWidthChar;
 
 use super::*;
 
-/// A dimension, either a limit or "fit" or "fill"
+/// A dimension, either a limit or "fit", "fill", or "stretch"
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Dimension {
     #[default]
     Fit,
     Fill,
+    /// The whole extent of the terminal, ignoring the aspect ratio of the image
+    Stretch,
     Limit(u16),
 }
 impl FromStr for Dimension {
-    type Err = String;
+    type Err = ConfigError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "fit" => Ok(Self::Fit),
             "fill" => Ok(Self::Fill),
+            "stretch" => Ok(Self::Stretch),
             _ => {
                 if let Ok(dim) = s.parse::<i16>() {
                     if dim <= 0 {
@@ -24,7 +28,7 @@ impl FromStr for Dimension {
                         Ok(Self::Limit(dim as u16))
                     }
                 } else {
-                    Err("invalid dimension, must be 'fit' or 'fill' or an integer limit".to_string())
+                    Err(ConfigError::InvalidDimension(s.to_string()))
                 }
             }
         }
@@ -47,21 +51,646 @@ impl<'de> Deserialize<'de> for Dimension {
                 StrOrInt::Int(dim) => Ok(if dim <= 0 { Self::Fit } else { Self::Limit(dim as u16) }),
             }
         } else {
-            Err(de::Error::custom("invalid dimension, must be 'fit' or 'fill' or an integer limit"))
+            Err(de::Error::custom("invalid dimension, must be 'fit', 'fill', 'stretch', or an integer limit"))
         }
     }
 }
 
+impl fmt::Display for Dimension {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fit => f.write_str("fit"),
+            Self::Fill => f.write_str("fill"),
+            Self::Stretch => f.write_str("stretch"),
+            Self::Limit(dim) => write!(f, "{dim}"),
+        }
+    }
+}
+impl Serialize for Dimension {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Limit(dim) => serializer.serialize_u16(*dim),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+/// How the image is sized within the terminal, setting both its width and height [`Dimension`]s together
+/// ```
+/// use viuwa::{Dimension, FitMode};
+/// assert_eq!("80x24".parse::<FitMode>().unwrap().dimensions(), (Dimension::Limit(80), Dimension::Limit(24)));
+/// assert!("80x0".parse::<FitMode>().is_err());
+/// assert_eq!(FitMode::of(Dimension::Fit, Dimension::Fit).map(FitMode::cycle), Some(FitMode::Fill));
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FitMode {
+    /// The largest size that fits, keeping the aspect ratio
+    #[default]
+    Fit,
+    /// The smallest size that fills, keeping the aspect ratio
+    Fill,
+    /// The whole terminal, ignoring the aspect ratio
+    Stretch,
+    /// Exactly a width and height, in columns and half block rows
+    Exact(u16, u16),
+}
+impl FitMode {
+    /// The width and height of the mode
+    #[inline]
+    pub const fn dimensions(self) -> (Dimension, Dimension) {
+        match self {
+            Self::Fit => (Dimension::Fit, Dimension::Fit),
+            Self::Fill => (Dimension::Fill, Dimension::Fill),
+            Self::Stretch => (Dimension::Stretch, Dimension::Stretch),
+            Self::Exact(w, h) => (Dimension::Limit(w), Dimension::Limit(h)),
+        }
+    }
+    /// The mode of a width and height, if they're the same kind of dimension
+    #[inline]
+    pub const fn of(width: Dimension, height: Dimension) -> Option<Self> {
+        match (width, height) {
+            (Dimension::Fit, Dimension::Fit) => Some(Self::Fit),
+            (Dimension::Fill, Dimension::Fill) => Some(Self::Fill),
+            (Dimension::Stretch, Dimension::Stretch) => Some(Self::Stretch),
+            (Dimension::Limit(w), Dimension::Limit(h)) => Some(Self::Exact(w, h)),
+            _ => None,
+        }
+    }
+    /// The next of fit, fill, and stretch, going back to fit from an exact size
+    #[inline]
+    pub const fn cycle(self) -> Self {
+        match self {
+            Self::Fit => Self::Fill,
+            Self::Fill => Self::Stretch,
+            Self::Stretch | Self::Exact(..) => Self::Fit,
+        }
+    }
+}
+impl FromStr for FitMode {
+    type Err = ConfigError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fit" => Ok(Self::Fit),
+            "fill" => Ok(Self::Fill),
+            "stretch" => Ok(Self::Stretch),
+            _ => match s.split_once('x').map(|(w, h)| (w.trim().parse::<u16>(), h.trim().parse::<u16>())) {
+                Some((Ok(w), Ok(h))) if w > 0 && h > 0 => Ok(Self::Exact(w, h)),
+                _ => Err(ConfigError::InvalidFitMode(s.to_string())),
+            },
+        }
+    }
+}
+impl fmt::Display for FitMode {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fit => f.write_str("fit"),
+            Self::Fill => f.write_str("fill"),
+            Self::Stretch => f.write_str("stretch"),
+            Self::Exact(w, h) => write!(f, "{w}x{h}"),
+        }
+    }
+}
+
+/// Empty cells kept around the image on each side, parsed like CSS from 1, 2 (vertical, horizontal),
+/// or 4 (top, right, bottom, left) comma separated numbers of cells
+/// ```
+/// use viuwa::Padding;
+/// let padding: Padding = "1,2".parse().unwrap();
+/// assert_eq!(padding, Padding { top: 1, right: 2, bottom: 1, left: 2 });
+/// assert_eq!(padding.to_string(), "1,2");
+/// // the area left within an 80x24 terminal
+/// assert_eq!(padding.area((80, 24)), ((2, 1), (76, 22)));
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Padding {
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+    pub left: u16,
+}
+impl Padding {
+    /// The top left corner and the columns and rows of the area left within `sz` cells, at least one cell
+    #[inline]
+    pub fn area(self, sz: (u16, u16)) -> ((u16, u16), (u16, u16)) {
+        let w = sz.0.saturating_sub(self.left.saturating_add(self.right)).max(1);
+        let h = sz.1.saturating_sub(self.top.saturating_add(self.bottom)).max(1);
+        ((self.left.min(sz.0.saturating_sub(w)), self.top.min(sz.1.saturating_sub(h))), (w, h))
+    }
+}
+impl FromStr for Padding {
+    type Err = ConfigError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells: Result<Vec<u16>, _> = s.split(',').map(|n| n.trim().parse()).collect();
+        match cells.as_deref() {
+            Ok(&[n]) => Ok(Self { top: n, right: n, bottom: n, left: n }),
+            Ok(&[v, h]) => Ok(Self { top: v, right: h, bottom: v, left: h }),
+            Ok(&[top, right, bottom, left]) => Ok(Self { top, right, bottom, left }),
+            _ => Err(ConfigError::InvalidPadding(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Padding {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+impl fmt::Display for Padding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { top, right, bottom, left } = *self;
+        if top == bottom && left == right {
+            if top == left {
+                write!(f, "{top}")
+            } else {
+                write!(f, "{top},{left}")
+            }
+        } else {
+            write!(f, "{top},{right},{bottom},{left}")
+        }
+    }
+}
+impl Serialize for Padding {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Where the image is placed within the area it's drawn in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Align {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+impl Align {
+    /// Every alignment and its name
+    const NAMES: [(Self, &'static str); 9] = [
+        (Self::TopLeft, "top-left"),
+        (Self::Top, "top"),
+        (Self::TopRight, "top-right"),
+        (Self::Left, "left"),
+        (Self::Center, "center"),
+        (Self::Right, "right"),
+        (Self::BottomLeft, "bottom-left"),
+        (Self::Bottom, "bottom"),
+        (Self::BottomRight, "bottom-right"),
+    ];
+    /// The offset of something of `size` cells aligned within an `area` of cells, nothing if it doesn't fit
+    /// ```
+    /// use viuwa::Align;
+    /// assert_eq!(Align::Center.offset((80, 24), (40, 10)), (20, 7));
+    /// assert_eq!(Align::BottomLeft.offset((80, 24), (40, 10)), (0, 14));
+    /// ```
+    #[inline]
+    pub fn offset(self, area: (u16, u16), size: (u16, u16)) -> (u16, u16) {
+        let (free_x, free_y) = (area.0.saturating_sub(size.0), area.1.saturating_sub(size.1));
+        let x = match self {
+            Self::TopLeft | Self::Left | Self::BottomLeft => 0,
+            Self::Top | Self::Center | Self::Bottom => free_x / 2,
+            Self::TopRight | Self::Right | Self::BottomRight => free_x,
+        };
+        let y = match self {
+            Self::TopLeft | Self::Top | Self::TopRight => 0,
+            Self::Left | Self::Center | Self::Right => free_y / 2,
+            Self::BottomLeft | Self::Bottom | Self::BottomRight => free_y,
+        };
+        (x, y)
+    }
+}
+impl FromStr for Align {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.replace('_', "-");
+        let name = if name == "middle" { "center" } else { &name };
+        Self::NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|&(align, _)| align)
+            .ok_or_else(|| ConfigError::InvalidAlign(s.to_string()))
+    }
+}
+impl<'de> Deserialize<'de> for Align {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+impl fmt::Display for Align {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(Self::NAMES.iter().find(|(a, _)| a == self).map_or("center", |&(_, n)| n))
+    }
+}
+impl Serialize for Align {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// How the white of a RAW photo is balanced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WhiteBalance {
+    /// As shot, the white balance the camera recorded with the photo
+    #[default]
+    Camera,
+    /// Automatic, scaling the colors so the average of the photo is gray
+    Auto,
+}
+impl FromStr for WhiteBalance {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "camera" => Ok(Self::Camera),
+            "auto" => Ok(Self::Auto),
+            _ => Err(ConfigError::InvalidWhiteBalance(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for WhiteBalance {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+impl fmt::Display for WhiteBalance {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Camera => "camera",
+            Self::Auto => "auto",
+        })
+    }
+}
+impl Serialize for WhiteBalance {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// How the character cells of the image are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Renderer {
+    /// Two pixels per cell with `'▀'`, the fastest and the most compatible with fonts
+    #[default]
+    Half,
+    /// Whichever block, wedge, or diagonal [`glyph`](viuwa_ansi::glyph) reproduces the pixels of each cell best,
+    /// keeping the structure of edges within cells
+    Glyph,
+    /// Two pixels per cell with `'▀'`, `'▄'`, `'█'`, or a space, never setting the background color,
+    /// so the terminal's own background (e.g. a theme or background image) shows through dark and transparent regions
+    Foreground,
+    /// One pixel per cell painted with the background color of a space, halving the vertical resolution,
+    /// for fonts and terminals that draw half blocks with gaps between them
+    Full,
+}
+impl Renderer {
+    /// Pixels with a luma of at most this are left to the terminal background when drawn with [`Foreground`](Self::Foreground)
+    pub const BACKGROUND_LUMA: u8 = 16;
+    /// The dimensions in pixels of the renderer of an image sized to `(w, h)` pixels when drawn with half blocks
+    #[inline]
+    pub const fn pixels(self, (w, h): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::Half | Self::Foreground => (w, h),
+            Self::Glyph => (w * viuwa_ansi::glyph::CELL.0, h * viuwa_ansi::glyph::CELL.0),
+            Self::Full => (w, h.div_ceil(2)),
+        }
+    }
+    /// The columns and rows of the cells drawing an image of `(w, h)` pixels of the renderer
+    #[inline]
+    pub const fn cells(self, (w, h): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::Half | Self::Foreground => (w, h.div_ceil(2)),
+            Self::Glyph => (w.div_ceil(viuwa_ansi::glyph::CELL.0), h.div_ceil(viuwa_ansi::glyph::CELL.1)),
+            Self::Full => (w, h),
+        }
+    }
+    /// The candidate glyphs of the `symbols` classes, or `None` when not drawn with glyphs
+    #[inline]
+    pub fn glyphs(self, symbols: Symbols) -> Option<Vec<Glyph>> {
+        match self {
+            Self::Half | Self::Foreground | Self::Full => None,
+            Self::Glyph => Some(symbols.glyphs()),
+        }
+    }
+}
+impl FromStr for Renderer {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "half" => Ok(Self::Half),
+            "glyph" => Ok(Self::Glyph),
+            "foreground" | "fg" => Ok(Self::Foreground),
+            "full" => Ok(Self::Full),
+            _ => Err(ConfigError::InvalidRenderer(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Renderer {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+impl fmt::Display for Renderer {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Half => "half",
+            Self::Glyph => "glyph",
+            Self::Foreground => "foreground",
+            Self::Full => "full",
+        })
+    }
+}
+impl Serialize for Renderer {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// What the letterbox around the image is filled with when viewing windowed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Letterbox {
+    /// Blank cells in the terminal's background, or the letterbox background if it's set
+    #[default]
+    Default,
+    /// Full blocks in the letterbox color, a solid fill even where the terminal doesn't draw the background of blanks
+    Solid,
+    /// A dot in every cell in the letterbox color, over the letterbox background
+    Dots,
+    /// Any character one column wide in every cell in the letterbox color, over the letterbox background
+    Char(char),
+}
+impl Letterbox {
+    /// The color of the character filling the letterbox when there's none set
+    pub const COLOR: UiColor = UiColor::Ansi(8);
+    /// The character every cell of the letterbox is filled with, `None` for blanks
+    /// ```
+    /// use viuwa::Letterbox;
+    /// assert_eq!("dots".parse::<Letterbox>().unwrap().glyph(), Some('·'));
+    /// assert_eq!("░".parse::<Letterbox>().unwrap().glyph(), Some('░'));
+    /// assert_eq!(Letterbox::Default.glyph(), None);
+    /// // wide and zero-width characters would misalign the rows they fill
+    /// assert!("猫".parse::<Letterbox>().is_err());
+    /// assert!("\u{301}".parse::<Letterbox>().is_err());
+    /// ```
+    #[inline]
+    pub const fn glyph(self) -> Option<char> {
+        match self {
+            Self::Default => None,
+            Self::Solid => Some('█'),
+            Self::Dots => Some('·'),
+            Self::Char(c) => Some(c),
+        }
+    }
+}
+impl FromStr for Letterbox {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match s {
+            "default" => Ok(Self::Default),
+            "solid" => Ok(Self::Solid),
+            "dots" => Ok(Self::Dots),
+            _ => match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_control() && c.width() == Some(1) => Ok(Self::Char(c)),
+                _ => Err(ConfigError::InvalidLetterbox(s.to_string())),
+            },
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Letterbox {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+impl fmt::Display for Letterbox {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::Solid => f.write_str("solid"),
+            Self::Dots => f.write_str("dots"),
+            Self::Char(c) => write!(f, "{c}"),
+        }
+    }
+}
+impl Serialize for Letterbox {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// How the linear samples of an HDR image are compressed into the displayable range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ToneMap {
+    /// `v / (1 + v)`, keeps highlights from clipping but flattens contrast
+    #[default]
+    Reinhard,
+    /// An approximation of the ACES filmic curve, contrasty with soft highlights
+    Aces,
+    /// No compression, samples above 1 are clipped to white
+    Clamp,
+}
+impl FromStr for ToneMap {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reinhard" => Ok(Self::Reinhard),
+            "aces" => Ok(Self::Aces),
+            "clamp" => Ok(Self::Clamp),
+            _ => Err(ConfigError::InvalidToneMap(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for ToneMap {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+impl fmt::Display for ToneMap {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Reinhard => "reinhard",
+            Self::Aces => "aces",
+            Self::Clamp => "clamp",
+        })
+    }
+}
+impl Serialize for ToneMap {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// The order the images of the grid are shown in
+/// ```
+/// use viuwa::SortOrder;
+/// use std::str::FromStr;
+/// assert_eq!(SortOrder::from_str("mtime").unwrap(), SortOrder::Mtime);
+/// assert_eq!(SortOrder::Size.to_string(), "size");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SortOrder {
+    /// By path, as `ls` lists them
+    #[default]
+    Name,
+    /// The most recently modified first
+    Mtime,
+    /// The largest file first
+    Size,
+    /// Shuffled, differently every time
+    Random,
+}
+impl FromStr for SortOrder {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "mtime" | "time" => Ok(Self::Mtime),
+            "size" => Ok(Self::Size),
+            "random" | "shuffle" => Ok(Self::Random),
+            _ => Err(ConfigError::InvalidSortOrder(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for SortOrder {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+impl fmt::Display for SortOrder {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Name => "name",
+            Self::Mtime => "mtime",
+            Self::Size => "size",
+            Self::Random => "random",
+        })
+    }
+}
+impl Serialize for SortOrder {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 /// The main viuwa configuration struct that is deserialized from the config file and command line
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", default)]
 pub struct Config {
     /// The image to display
+    #[serde(skip_serializing_if = "is_empty_path")]
     pub image: PathBuf,
+    /// The image to compare against the image to display
+    #[serde(skip)]
+    pub compare: Option<PathBuf>,
+    /// Flip between the compared images in place instead of showing them beside each other
+    #[serde(skip)]
+    pub flip: bool,
+    /// Print a heatmap of the differences between the compared images instead of viewing them
+    #[serde(skip)]
+    pub diff: bool,
+    /// The directory to show a grid of thumbnails of instead of an image
+    #[serde(skip)]
+    pub grid: Option<PathBuf>,
+    /// The image file to write a contact sheet of the grid to instead of displaying it
+    #[serde(skip)]
+    pub contact_sheet: Option<PathBuf>,
+    /// The order the images of the grid are shown in
+    pub sort: SortOrder,
+    /// The comma separated globs of the filenames of the images the grid shows, every image when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid_filter: Option<String>,
+    /// Whether the grid shows the images of subdirectories too, skipping the files of `.gitignore` and `.ignore` files
+    pub recursive: bool,
+    /// The page of a multi-page document (e.g. a PDF) or frame of a multi-image file to display, starting at 0
+    #[serde(skip)]
+    pub page: usize,
     /// The log level
     pub log: LogLevel,
+    /// The file to write logs to instead of stdout and stderr
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
     /// The filter to use
     pub filter: FilterType,
+    /// The custom filter preset to use instead of `filter`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_custom: Option<FilterPreset>,
+    /// The amount of unsharp masking applied after downscaling, 0.0 to disable
+    pub sharpen: f32,
     #[serde(skip)]
     pub color_space: ColorSpace,
     #[serde(skip)]
@@ -72,40 +701,175 @@ pub struct Config {
     pub inline: bool,
     /// Whether to clear the screen after displaying the image inline
     pub clear: bool,
+    /// Never take over the terminal, printing inline without waiting for input, even if stdout is a terminal
+    #[serde(skip)]
+    pub non_interactive: bool,
     /// The default number of columns to use if the terminal width is unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub default_columns: Option<u16>,
     /// The default number of rows to use if the terminal height is unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub default_rows: Option<u16>,
     /// The width of to display image
     pub width: Dimension,
     /// The height of to display image
     pub height: Dimension,
-    /// The luma correction to use
-    pub luma_correct: u8,
+    /// Empty cells kept around the image
+    pub padding: Padding,
+    /// Where the image is placed within the terminal, centered windowed and top left inline when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align: Option<Align>,
+    /// The luma correction of 256 color mode, 0.0..=100.0, higher draws more muted colors with the gray ramp
+    pub luma_correct: f32,
+    /// The curve the luma correction is mapped to a color distance threshold with
+    pub luma_curve: LumaCurve,
+    /// The color distance threshold of the luma correction given directly, instead of the luma correction and curve
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub luma_threshold: Option<u32>,
+    /// How grays are mapped to the 256 colors, the bias and whether the grays of the color cube are used
+    pub gray_ramp: GrayRamp,
+    /// The colormap single-channel images are drawn with in color
+    pub colormap: Colormap,
+    /// How the character cells of the image are drawn
+    pub renderer: Renderer,
+    /// The classes of glyphs the glyph renderer chooses between
+    pub symbols: Symbols,
+    /// How inline images are drawn, with ANSI colors or a terminal graphics protocol
+    pub protocol: Protocol,
+    /// The background of the terminal, asked of it when `auto`, which the interface is colored for
+    /// and transparent images are composited over
+    pub background: Background,
+    /// The colors of the interface overriding those picked for the background, the `[theme]` table of the config file
+    #[serde(skip_serializing_if = "ThemeColors::is_empty")]
+    pub theme: ThemeColors,
+    /// The background filling the letterbox, the padding and the cells around the image, the terminal's own when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub letterbox_background: Option<UiColor>,
+    /// What the letterbox is filled with when viewing windowed
+    pub letterbox: Letterbox,
+    /// The color of the character filling the letterbox, [`Letterbox::COLOR`] when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub letterbox_color: Option<UiColor>,
+    /// The background behind the cells of the image, seen where the foreground renderer leaves them blank,
+    /// the terminal's own when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_background: Option<UiColor>,
+    /// The operations applied to the image in order before it's displayed
+    #[serde(skip_serializing_if = "Ops::is_empty")]
+    pub ops: Ops,
+    /// The width of a character cell divided by its height, detected from the terminal when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell_aspect: Option<f32>,
+    /// How long the terminal size must be stable (in milliseconds) before the image is resized
+    pub resize_debounce_ms: u64,
+    /// Whether to reload the config file when it changes
+    pub watch_config: bool,
+    /// Whether images viewed windowed are remembered in the recent list of the `:recent` menu
+    pub recent: bool,
+    /// Whether the selected image of the grid can be moved, copied, renamed, and removed from the `:` prompt
+    pub file_actions: bool,
+    /// Where terminal events are read from and how raw mode is entered
+    #[cfg(not(target_family = "wasm"))]
+    pub backend: BackendKind,
+    /// The maximum memory (in megabytes) to use for the decoded image and its resized buffers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_memory: Option<u64>,
+    /// The exposure compensation (in stops) of RAW photos and HDR images
+    pub exposure: f32,
+    /// The tone mapping operator of HDR images
+    pub tone_map: ToneMap,
+    /// The white balance of RAW photos
+    #[cfg(feature = "raw")]
+    pub white_balance: WhiteBalance,
+    /// The ICC profile to assume for images without an embedded one, instead of sRGB
+    #[cfg(feature = "icc")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assume_profile: Option<PathBuf>,
+    /// The file to write a Chrome tracing / Perfetto JSON trace of timed spans to
+    #[cfg(feature = "trace")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_out: Option<PathBuf>,
+    /// The subcommand to run instead of displaying the image
+    #[serde(skip)]
+    pub subcommand: Option<Subcommand>,
+    /// The config file given with `--config` or found in the config directories, if any
+    #[serde(skip)]
+    pub config_file: Option<PathBuf>,
+    /// The named presets of settings, switched to with the preset command
+    #[serde(rename = "preset", skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, Preset>,
     /// The keybinds to use
-    #[cfg(not(target_os = "wasi"))]
+    #[cfg(not(target_family = "wasm"))]
     pub keybinds: BTreeMap<KeyBind, Action>,
     /// The keybinds to use
-    #[cfg(target_os = "wasi")]
+    #[cfg(target_family = "wasm")]
     pub keybinds: BTreeMap<String, Action>,
 }
 impl Default for Config {
     fn default() -> Self {
         Self {
             image: Default::default(),
+            compare: None,
+            flip: false,
+            diff: false,
+            grid: None,
+            contact_sheet: None,
+            sort: SortOrder::Name,
+            grid_filter: None,
+            recursive: false,
+            page: 0,
             log: Default::default(),
+            log_file: None,
             filter: Default::default(),
+            filter_custom: None,
+            sharpen: 0.,
             color_space: Default::default(),
             color_depth: Default::default(),
             color: Default::default(),
             inline: false,
             clear: false,
+            non_interactive: false,
             default_columns: Default::default(),
             default_rows: Default::default(),
             width: Default::default(),
             height: Default::default(),
-            luma_correct: 100,
-            #[cfg(not(target_os = "wasi"))]
+            padding: Default::default(),
+            align: None,
+            luma_correct: 100.,
+            luma_curve: LumaCurve::Cubic,
+            luma_threshold: None,
+            gray_ramp: GrayRamp::default(),
+            colormap: Colormap::Gray,
+            renderer: Renderer::Half,
+            symbols: Symbols::default(),
+            protocol: Protocol::Ansi,
+            background: Background::Auto,
+            theme: ThemeColors::default(),
+            letterbox_background: None,
+            letterbox: Letterbox::Default,
+            letterbox_color: None,
+            image_background: None,
+            ops: Ops::default(),
+            cell_aspect: None,
+            resize_debounce_ms: 100,
+            watch_config: false,
+            recent: true,
+            file_actions: false,
+            #[cfg(not(target_family = "wasm"))]
+            backend: BackendKind::Crossterm,
+            max_memory: None,
+            exposure: 0.,
+            tone_map: ToneMap::Reinhard,
+            #[cfg(feature = "raw")]
+            white_balance: WhiteBalance::Camera,
+            #[cfg(feature = "icc")]
+            assume_profile: None,
+            #[cfg(feature = "trace")]
+            trace_out: None,
+            subcommand: None,
+            config_file: None,
+            presets: BTreeMap::new(),
+            #[cfg(not(target_family = "wasm"))]
             keybinds: BTreeMap::from([
                 (KeyBind(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::empty())), Action::Quit),
                 (KeyBind(KeyEvent::new(KeyCode::Esc, KeyModifiers::empty())), Action::Quit),
@@ -115,8 +879,32 @@ impl Default for Config {
                 (KeyBind(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::empty())), Action::Cycle(Cyclic::ColorSpace)),
                 (KeyBind(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::empty())), Action::Cycle(Cyclic::ColorDepth)),
                 (KeyBind(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::empty())), Action::Cycle(Cyclic::Color)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::empty())), Action::Cycle(Cyclic::FitMode)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::empty())), Action::Swap),
+                (KeyBind(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::empty())), Action::Swap),
+                (KeyBind(KeyEvent::new(KeyCode::Char('+'), KeyModifiers::empty())), Action::Zoom(Zoom::In)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('='), KeyModifiers::empty())), Action::Zoom(Zoom::In)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('-'), KeyModifiers::empty())), Action::Zoom(Zoom::Out)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('0'), KeyModifiers::empty())), Action::Zoom(Zoom::Reset)),
+                (KeyBind(KeyEvent::new(KeyCode::Left, KeyModifiers::empty())), Action::Pan(Pan::Left)),
+                (KeyBind(KeyEvent::new(KeyCode::Right, KeyModifiers::empty())), Action::Pan(Pan::Right)),
+                (KeyBind(KeyEvent::new(KeyCode::Up, KeyModifiers::empty())), Action::Pan(Pan::Up)),
+                (KeyBind(KeyEvent::new(KeyCode::Down, KeyModifiers::empty())), Action::Pan(Pan::Down)),
+                (KeyBind(KeyEvent::new(KeyCode::Left, KeyModifiers::SHIFT)), Action::Scroll(Pan::Left)),
+                (KeyBind(KeyEvent::new(KeyCode::Right, KeyModifiers::SHIFT)), Action::Scroll(Pan::Right)),
+                (KeyBind(KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT)), Action::Scroll(Pan::Up)),
+                (KeyBind(KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT)), Action::Scroll(Pan::Down)),
+                (KeyBind(KeyEvent::new(KeyCode::Char(']'), KeyModifiers::empty())), Action::Page(Page::Next)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('['), KeyModifiers::empty())), Action::Page(Page::Prev)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::empty())), Action::Stats),
+                (KeyBind(KeyEvent::new(KeyCode::Char('.'), KeyModifiers::empty())), Action::Step(Step::Next)),
+                (KeyBind(KeyEvent::new(KeyCode::Char(','), KeyModifiers::empty())), Action::Step(Step::Prev)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::empty())), Action::Pause),
+                (KeyBind(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::empty())), Action::Recent),
+                (KeyBind(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::empty())), Action::Image(Step::Next)),
+                (KeyBind(KeyEvent::new(KeyCode::Char('b'), KeyModifiers::empty())), Action::Image(Step::Prev)),
             ]),
-            #[cfg(target_os = "wasi")]
+            #[cfg(target_family = "wasm")]
             keybinds: BTreeMap::from([
                 (String::from(""), Action::Quit),
                 (String::from("q"), Action::Quit),
@@ -126,6 +914,20 @@ impl Default for Config {
                 (String::from("s"), Action::Cycle(Cyclic::ColorSpace)),
                 (String::from("d"), Action::Cycle(Cyclic::ColorDepth)),
                 (String::from("c"), Action::Cycle(Cyclic::Color)),
+                (String::from("m"), Action::Cycle(Cyclic::FitMode)),
+                (String::from("x"), Action::Swap),
+                (String::from("+"), Action::Zoom(Zoom::In)),
+                (String::from("-"), Action::Zoom(Zoom::Out)),
+                (String::from("0"), Action::Zoom(Zoom::Reset)),
+                (String::from("]"), Action::Page(Page::Next)),
+                (String::from("["), Action::Page(Page::Prev)),
+                (String::from("i"), Action::Stats),
+                (String::from("."), Action::Step(Step::Next)),
+                (String::from(","), Action::Step(Step::Prev)),
+                (String::from("p"), Action::Pause),
+                (String::from("o"), Action::Recent),
+                (String::from("n"), Action::Image(Step::Next)),
+                (String::from("b"), Action::Image(Step::Prev)),
             ]),
         }
     }
@@ -138,13 +940,14 @@ impl Default for Config {
         author = env!("CARGO_PKG_AUTHORS"),
         about = env!("CARGO_PKG_DESCRIPTION"),
         disable_help_flag = true,
+        subcommand_negates_reqs = true,
 )]
 #[command(group(
     clap::ArgGroup::new("only_color_type")
         .args([
             "color_space",
             "color_depth",
-        ]).conflicts_with("color_type"),
+        ]).conflicts_with("color"),
 ))]
 #[command(group(
     clap::ArgGroup::new("log_level")
@@ -163,6 +966,11 @@ pub struct Args {
     #[cfg_attr(feature = "env", arg(env = "VIUWA_LOG"))]
     log: Option<LogLevel>,
 
+    /// Write logs to a file with timestamps and levels, instead of the terminal
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_LOG_FILE"))]
+    log_file: Option<PathBuf>,
+
     /// Suppress verbosity
     #[arg(short, long, action = clap::ArgAction::Count, value_parser = value_parser!(u8).range(0..=3), conflicts_with = "verbose")]
     quiet: u8,
@@ -176,15 +984,90 @@ pub struct Args {
     #[cfg_attr(feature = "env", arg(env = "VIUWA_CONFIG"))]
     config: Option<PathBuf>,
 
-    /// The image to display
-    #[arg(required = true, value_name = "IMAGE", value_hint = clap::ValueHint::FilePath, value_parser = parse_file_path_str)]
-    image: PathBuf,
+    /// The image to display, `-` to stream a farbfeld or Netpbm image from stdin
+    #[arg(required_unless_present_any = ["compare", "flip", "diff", "grid"], value_name = "IMAGE", value_hint = clap::ValueHint::FilePath, value_parser = parse_image_path_str)]
+    image: Option<PathBuf>,
+
+    /// Compare two images side by side, with a synced zoom and pan
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["A", "B"],
+        value_hint = clap::ValueHint::FilePath,
+        value_parser = parse_file_path_str,
+        conflicts_with_all = ["image", "inline"],
+    )]
+    compare: Option<Vec<PathBuf>>,
+
+    /// Compare two images by flipping between them in place with the spacebar
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["A", "B"],
+        value_hint = clap::ValueHint::FilePath,
+        value_parser = parse_file_path_str,
+        conflicts_with_all = ["image", "inline", "compare"],
+    )]
+    flip: Option<Vec<PathBuf>>,
+
+    /// Print a heatmap of the differences between two images, with the max and mean delta and how much changed
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["A", "B"],
+        value_hint = clap::ValueHint::FilePath,
+        value_parser = parse_file_path_str,
+        conflicts_with_all = ["image", "inline", "compare", "flip"],
+    )]
+    diff: Option<Vec<PathBuf>>,
+
+    /// Show a grid of thumbnails of the images in a directory, windowed to select one to open
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = clap::ValueHint::DirPath,
+        conflicts_with_all = ["image", "compare", "flip", "diff"],
+    )]
+    grid: Option<PathBuf>,
+
+    /// Write the thumbnails of the grid composited into a single image file, instead of displaying them
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath, requires = "grid", conflicts_with = "image")]
+    contact_sheet: Option<PathBuf>,
+
+    /// Show the images of the grid by name, newest first (mtime), largest first (size), or shuffled (random)
+    #[arg(long, value_name = "ORDER", value_parser = SortOrder::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_SORT"))]
+    sort: Option<SortOrder>,
+
+    /// Only show the images of the grid with filenames matching one of these comma separated globs, e.g. '*.png,IMG_*'
+    #[arg(long, value_name = "GLOBS")]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_GRID_FILTER"))]
+    grid_filter: Option<String>,
+
+    /// Show the images of subdirectories in the grid too, skipping hidden files and those of .gitignore and .ignore files
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_RECURSIVE"))]
+    recursive: bool,
+
+    /// The page of a PDF, TIFF, or ICO, or the frame of a GIF to display, starting at 1
+    #[arg(long, value_name = "N", value_parser = value_parser!(u64).range(1..))]
+    page: Option<u64>,
 
     /// Set resizing filter
     #[arg(short, long, value_parser = FilterType::from_str)]
     #[cfg_attr(feature = "env", arg(env = "VIUWA_FILTER"))]
     filter: Option<FilterType>,
 
+    /// Set a custom resizing filter preset, instead of the filter
+    #[arg(long, value_name = "PRESET", value_parser = FilterPreset::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_FILTER_CUSTOM"))]
+    filter_custom: Option<FilterPreset>,
+
+    /// Sharpen downscaled images with an unsharp mask of this amount, to bring out detail at terminal resolutions
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_sharpen_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_SHARPEN"))]
+    sharpen: Option<f32>,
+
     /// Set color space
     #[arg(long, value_parser = ColorSpace::from_str)]
     #[cfg_attr(feature = "env", arg(env = "VIUWA_COLOR_SPACE"))]
@@ -214,6 +1097,11 @@ pub struct Args {
     #[cfg_attr(feature = "env", arg(env = "VIUWA_CLEAR"))]
     clear: bool,
 
+    /// Print inline without the alternate screen or waiting for input, assumed when stdout isn't a terminal
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_NON_INTERACTIVE"))]
+    non_interactive: bool,
+
     /// Set the display width of the image
     #[arg(
         short,
@@ -234,14 +1122,247 @@ pub struct Args {
     #[cfg_attr(feature = "env", arg(env = "VIUWA_HEIGHT"))]
     height: Option<Dimension>,
 
-    /// Luma correction for 256 color mode
-    #[arg(
-        short,
-        long,
-        value_parser = value_parser!(u8).range(0..=100),
-    )]
+    /// Set the display width and height together, fit, fill, stretch (ignoring the aspect ratio), or an exact WIDTHxHEIGHT
+    #[arg(long, value_name = "MODE", value_parser = FitMode::from_str, conflicts_with_all = ["width", "height"])]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_FIT"))]
+    fit: Option<FitMode>,
+
+    /// Empty cells kept around the image, 1, 2 (vertical,horizontal), or 4 (top,right,bottom,left) comma separated numbers
+    #[arg(long, value_name = "CELLS", value_parser = Padding::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_PADDING"))]
+    padding: Option<Padding>,
+
+    /// Where to place the image within the terminal, center, top, bottom, left, right, or a corner like top-left
+    /// [default: center windowed, top-left inline]
+    #[arg(long, value_name = "ALIGN", value_parser = Align::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_ALIGN"))]
+    align: Option<Align>,
+
+    /// Luma correction for 256 color mode, 0.0 to 100.0, higher draws more muted colors in gray
+    #[arg(short, long, value_name = "CORRECT", value_parser = parse_luma_correct_str)]
     #[cfg_attr(feature = "env", arg(env = "VIUWA_CORRECT"))]
-    luma_correct: Option<u8>,
+    luma_correct: Option<f32>,
+
+    /// The curve the luma correction is mapped to a color distance threshold with, linear, quadratic, or cubic
+    #[arg(long, value_name = "CURVE", value_parser = LumaCurve::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_LUMA_CURVE"))]
+    luma_curve: Option<LumaCurve>,
+
+    /// The color distance threshold of the luma correction, 0 to 584970, instead of the luma correction and curve
+    #[arg(long, value_name = "DISTANCE", value_parser = value_parser!(u32).range(0..=MAX_COLOR_DISTANCE as i64))]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_LUMA_THRESHOLD"))]
+    luma_threshold: Option<u32>,
+
+    /// How grays are mapped to the 256 colors, a bias lightening or darkening them and whether the grays of the color
+    /// cube are used, e.g. -8,no-cube [default: 0,cube]
+    #[arg(long, value_name = "RAMP", allow_hyphen_values = true, value_parser = GrayRamp::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_GRAY_RAMP"))]
+    gray_ramp: Option<GrayRamp>,
+
+    /// Draw single-channel images (grayscale, depth maps, scientific data) with a colormap in color modes
+    #[arg(long, value_name = "COLORMAP", value_parser = Colormap::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_COLORMAP"))]
+    colormap: Option<Colormap>,
+
+    /// Draw cells with half blocks, with whichever block, wedge, or diagonal glyph fits each cell best,
+    /// with foreground colors only (foreground), keeping the terminal background visible behind dark and transparent regions,
+    /// or with one pixel per cell painted as the background of a space (full)
+    #[arg(long, value_name = "RENDERER", value_parser = Renderer::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_RENDERER"))]
+    renderer: Option<Renderer>,
+
+    /// Comma separated classes of glyphs the glyph renderer chooses between: half, quad, block, wedge, diagonal, braille, ascii, or all
+    #[arg(long, value_name = "SYMBOLS", value_parser = Symbols::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_SYMBOLS"))]
+    symbols: Option<Symbols>,
+
+    /// Draw inline images with ANSI colors, the graphics protocol detected from the terminal (auto),
+    /// or the kitty, iTerm2 (iterm), sixel, Terminology, or ReGIS protocol, at the terminal's own pixels
+    #[arg(long, value_name = "PROTOCOL", value_parser = Protocol::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_PROTOCOL"))]
+    protocol: Option<Protocol>,
+
+    /// The background of the terminal the interface is colored for and transparent images are composited over,
+    /// asked of the terminal (auto), dark, light, or a hex color (#rrggbb)
+    #[arg(long, value_name = "BACKGROUND", value_parser = Background::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_BACKGROUND"))]
+    background: Option<Background>,
+
+    /// Fill the letterbox (the padding and the cells around the image) with this background, so screenshots look the
+    /// same on any terminal theme, a color name, an ANSI color 0 to 255, or a hex color (#rrggbb)
+    #[arg(long, value_name = "COLOR", value_parser = UiColor::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_LETTERBOX_BACKGROUND"))]
+    letterbox_background: Option<UiColor>,
+
+    /// Fill the letterbox when viewing windowed with blanks (default), full blocks (solid), dots,
+    /// or any single character, drawn in the letterbox color
+    #[arg(long, value_name = "FILL", value_parser = Letterbox::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_LETTERBOX"))]
+    letterbox: Option<Letterbox>,
+
+    /// The color of the character filling the letterbox, a color name, an ANSI color 0 to 255, or a hex color (#rrggbb)
+    /// [default: bright-black]
+    #[arg(long, value_name = "COLOR", value_parser = UiColor::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_LETTERBOX_COLOR"))]
+    letterbox_color: Option<UiColor>,
+
+    /// Draw the image over this background, seen where the foreground renderer leaves cells blank,
+    /// a color name, an ANSI color 0 to 255, or a hex color (#rrggbb)
+    #[arg(long, value_name = "COLOR", value_parser = UiColor::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_IMAGE_BACKGROUND"))]
+    image_background: Option<UiColor>,
+
+    /// Operations applied to the image in order before it's displayed, separated by ';':
+    /// crop=X,Y,W,H, rotate=90|180|270, flip=h|v, gray[=rec709|rec601|average], or resize=fit|WxH|N%
+    #[arg(long, value_name = "OPS", value_parser = Ops::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_OPS"))]
+    ops: Option<Ops>,
+
+    /// The width of a character cell divided by its height (e.g. 0.5 or 1:2), detected from the terminal if not set
+    #[arg(long, value_name = "RATIO", value_parser = parse_cell_aspect_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_CELL_ASPECT"))]
+    cell_aspect: Option<f32>,
+
+    /// Milliseconds the terminal size must be stable before resizing the image
+    #[arg(long, value_name = "MS")]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_RESIZE_DEBOUNCE_MS"))]
+    resize_debounce_ms: Option<u64>,
+
+    /// Reload the config file when it changes, applying keybinds, filter, colors, and other settings in place
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_WATCH_CONFIG"))]
+    watch_config: bool,
+
+    /// Don't remember the images viewed in the recent list of the :recent menu
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_NO_RECENT"))]
+    no_recent: bool,
+
+    /// Allow moving, copying, renaming, and removing the selected image of the grid with :mv, :cp, :rename, and :rm
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_FILE_ACTIONS"))]
+    file_actions: bool,
+
+    /// Read terminal events and enter raw mode with crossterm, or termion (with the termion feature on unix)
+    #[cfg(not(target_family = "wasm"))]
+    #[arg(long, value_name = "BACKEND", value_parser = BackendKind::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_BACKEND"))]
+    backend: Option<BackendKind>,
+
+    /// Limit the memory used by the image buffers, downscaling while decoding or refusing to display if it's exceeded
+    #[arg(long, value_name = "MB", value_parser = value_parser!(u64).range(1..))]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_MAX_MEMORY"))]
+    max_memory: Option<u64>,
+
+    /// Brighten (or darken, if negative) RAW photos and HDR images by a number of stops
+    #[arg(long, value_name = "EV", allow_negative_numbers = true, value_parser = parse_exposure_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_EXPOSURE"))]
+    exposure: Option<f32>,
+
+    /// Set how HDR images (EXR, HDR) are tone mapped for display
+    #[arg(long, value_name = "OPERATOR", value_parser = ToneMap::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_TONE_MAP"))]
+    tone_map: Option<ToneMap>,
+
+    /// Set the white balance of RAW photos, as shot by the camera or automatic (gray world)
+    #[cfg(feature = "raw")]
+    #[arg(long, value_name = "WB", value_parser = WhiteBalance::from_str)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_WHITE_BALANCE"))]
+    white_balance: Option<WhiteBalance>,
+
+    /// Convert images without an embedded ICC profile to sRGB from this profile, e.g. for untagged wide-gamut photos
+    #[cfg(feature = "icc")]
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_ASSUME_PROFILE"))]
+    assume_profile: Option<PathBuf>,
+
+    /// Write a Chrome tracing / Perfetto JSON trace of timed spans to a file
+    #[cfg(feature = "trace")]
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    #[cfg_attr(feature = "env", arg(env = "VIUWA_TRACE_OUT"))]
+    trace_out: Option<PathBuf>,
+
+    #[command(subcommand)]
+    subcommand: Option<Subcommand>,
+}
+
+/// Subcommands that run instead of displaying an image
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum Subcommand {
+    /// Time decoding, resizing (every filter and resizer), and ANSI conversion of an image
+    Bench {
+        /// The image to benchmark with
+        #[arg(value_name = "IMAGE", value_hint = clap::ValueHint::FilePath, value_parser = parse_file_path_str)]
+        image: PathBuf,
+        /// How many times to run each operation
+        #[arg(short = 'n', long, value_name = "N", default_value_t = 10)]
+        iterations: usize,
+    },
+    /// Manage the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print a completion script for a shell, e.g. `viuwa completions bash > /etc/bash_completion.d/viuwa`
+    Completions {
+        /// The shell to complete in
+        #[arg(value_name = "SHELL")]
+        shell: clap_complete::Shell,
+    },
+    /// Render images to ANSI, HTML, or text files at a fixed size, without touching the terminal
+    Convert {
+        /// The images to convert
+        #[arg(required = true, value_name = "IMAGES", value_hint = clap::ValueHint::FilePath, value_parser = parse_file_path_str)]
+        images: Vec<PathBuf>,
+        /// The directory to write the files to, named after the images
+        #[arg(short, long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+        out_dir: PathBuf,
+        /// The format to write, ans (ANSI), html, or txt (the characters without color)
+        #[arg(short, long, value_name = "FORMAT", default_value_t = ConvertFormat::Ansi)]
+        format: ConvertFormat,
+        /// The columns and rows to fit the images in
+        #[arg(short, long, value_name = "COLUMNSxROWS", default_value = "80x24", value_parser = parse_size_str)]
+        size: (u16, u16),
+    },
+    /// Record an animated GIF, or a slideshow of images, to an asciicast v2 file to replay with `asciinema play`
+    Cast {
+        /// The images to record, GIF frames are shown for their own delays and anything else as a slide
+        #[arg(required = true, value_name = "IMAGES", value_hint = clap::ValueHint::FilePath, value_parser = parse_file_path_str)]
+        images: Vec<PathBuf>,
+        /// The asciicast file to write
+        #[arg(short, long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        out: PathBuf,
+        /// The columns and rows of the recorded terminal
+        #[arg(short, long, value_name = "COLUMNSxROWS", default_value = "80x24", value_parser = parse_size_str)]
+        size: (u16, u16),
+        /// How long each slide (an image, TIFF page, or ICO image) is shown, in milliseconds
+        #[arg(short, long, value_name = "MS", default_value_t = 2000)]
+        interval: u64,
+    },
+    /// Print the keybinds in effect, including those of the config file, and the commands they can be bound to
+    Cheatsheet,
+}
+
+/// Subcommands of the config subcommand
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Print the path of the config file in use, or where one would be created
+    Path,
+    /// Write a default config file with every setting commented out below its description
+    Init {
+        /// The file to write, the default config path if not given
+        #[arg(value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        path: Option<PathBuf>,
+        /// Overwrite the file if it already exists
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Validate a config file, reporting every problem with its line number
+    Check {
+        /// The file to check, the config file in use if not given
+        #[arg(value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        path: Option<PathBuf>,
+    },
 }
 
 impl Args {
@@ -267,32 +1388,148 @@ impl Config {
     pub fn new() -> Self {
         let args = Args::new();
         if let Some(p) = &args.config {
-            match ::std::fs::read_to_string(p) {
-                Ok(str) => match ::toml::from_str::<Config>(&str) {
-                    Ok(con) => {
-                        debug!("Config::new", "config.toml {} parsed: {:#?}", p.display(), con);
-                        return con.merge_args(args);
-                    }
-                    Err(e) => error!("could not parse config file: {}: {}", p.display(), e),
-                },
-                Err(e) => error!("could not read config file: {}: {}", p.display(), e),
+            match Self::from_file(p) {
+                Ok(con) => {
+                    debug!("Config::new", "config.toml {} parsed: {:#?}", p.display(), con);
+                    return con.merge_args(args);
+                }
+                Err(e) => error!("{}", e),
             }
         } else {
             debug!("Config::new", "no config file found, using default config");
         }
         Config::default().merge_args(args)
     }
+    /// Start building a config from the defaults
+    /// ```
+    /// use viuwa::{Config, Dimension};
+    /// let conf = Config::builder().width(Dimension::Limit(40)).luma_correct(80.5).build().unwrap();
+    /// let back: Config = toml::from_str(&conf.to_toml().unwrap()).unwrap();
+    /// assert_eq!((back.width, back.luma_correct), (Dimension::Limit(40), 80.5));
+    /// assert!(Config::builder().luma_correct(101.).build().is_err());
+    /// ```
+    #[inline]
+    pub fn builder() -> ConfigBuilder { ConfigBuilder(Config::default()) }
+    /// Whether viuwa may take over the terminal and wait for input, only if stdout is a terminal
+    #[inline]
+    pub fn interactive(&self) -> bool {
+        !self.non_interactive && ::is_terminal::IsTerminal::is_terminal(&::std::io::stdout())
+    }
+    /// The filter to resize with, the custom preset if one is set
+    #[inline]
+    pub fn resize_filter(&self) -> ResizeFilter { self.filter_custom.map_or(self.filter.into(), ResizeFilter::Custom) }
+    /// The color attributes of the luma correction and colormap, from the threshold if it's set
+    #[inline]
+    pub fn color_attributes(&self) -> ColorAttributes {
+        let attrs = match self.luma_threshold {
+            Some(threshold) => ColorAttributes::from_threshold(threshold),
+            None => ColorAttributes::from_curve(self.luma_correct, self.luma_curve),
+        };
+        attrs.with_colormap(self.colormap).with_gray_ramp(self.gray_ramp)
+    }
+    /// The colors of the interface, for the background of the terminal unless they're set in the `[theme]` table
+    #[inline]
+    pub fn theme(&self) -> Theme { Theme::for_background(self.background).with_colors(&self.theme) }
+    /// Serialize the config as toml, without the image and subcommand
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        let mut conf = self.clone();
+        conf.image = PathBuf::new();
+        Ok(::toml::to_string_pretty(&conf)?)
+    }
+    /// Write the config to a toml file, that can be read back with [`from_file`](Self::from_file)
+    pub fn write_file(&self, path: &Path) -> Result<(), ConfigError> {
+        ::std::fs::write(path, self.to_toml()?).map_err(|source| ConfigError::Write { path: path.to_path_buf(), source })
+    }
+    /// Read and parse a config file
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let str =
+            ::std::fs::read_to_string(path).map_err(|source| ConfigError::Read { path: path.to_path_buf(), source })?;
+        ::toml::from_str(&str).map_err(|source| ConfigError::Parse { path: path.to_path_buf(), source })
+    }
+    /// Parse and validate the toml of a config file
+    /// ```
+    /// let conf = viuwa::Config::from_toml("color = \"ansi-gray\"\nluma-correct = 50").unwrap();
+    /// assert_eq!(conf.luma_correct, 50.);
+    /// assert!(viuwa::Config::from_toml("luma-correct = 101").is_err());
+    /// ```
+    pub fn from_toml(src: &str) -> Result<Self, ConfigError> { ConfigBuilder(::toml::from_str(src)?).build() }
+    /// Read the config file again and merge the command line arguments over it, keeping what's being displayed
+    /// (the images, page, and whether it's inline) and the detected cell aspect ratio
+    pub fn reloaded(&self) -> Result<Self, ConfigError> {
+        let path = self.config_file.as_ref().ok_or(ConfigError::NoConfigFile)?;
+        let mut conf = Self::from_file(path)?;
+        // the process arguments may not be ours when viuwa is used as a library
+        if let Ok(args) = Args::try_new() {
+            conf = conf.merge_args(args);
+        }
+        conf.cell_aspect = conf.cell_aspect.or(self.cell_aspect);
+        Ok(Self {
+            image: self.image.clone(),
+            compare: self.compare.clone(),
+            flip: self.flip,
+            diff: self.diff,
+            grid: self.grid.clone(),
+            contact_sheet: self.contact_sheet.clone(),
+            page: self.page,
+            inline: self.inline,
+            subcommand: None,
+            config_file: self.config_file.clone(),
+            ..conf
+        })
+    }
     pub fn merge_args(mut self, args: Args) -> Self {
-        self.image = args.image;
+        if let Some(image) = args.image {
+            self.image = image;
+        }
+        // merge comparison, the first image is the main image
+        self.flip = args.flip.is_some();
+        self.diff = args.diff.is_some();
+        if let Some(dir) = args.grid {
+            self.grid = Some(dir);
+        }
+        if let Some(path) = args.contact_sheet {
+            self.contact_sheet = Some(path);
+        }
+        // merge grid scanning
+        if let Some(order) = args.sort {
+            self.sort = order;
+        }
+        if let Some(globs) = args.grid_filter {
+            self.grid_filter = Some(globs);
+        }
+        if args.recursive {
+            self.recursive = true;
+        }
+        if let Some(page) = args.page {
+            self.page = page as usize - 1;
+        }
+        if let Some(mut images) = args.compare.or(args.flip).or(args.diff) {
+            self.compare = images.pop();
+            if let Some(image) = images.pop() {
+                self.image = image;
+            }
+        }
         // merge log level
         if let Some(l) = args.log {
             self.log = l;
         } else {
             self.log = (self.log as u8).saturating_add(args.quiet).saturating_sub(args.verbose).into();
         }
+        // merge log file
+        if let Some(p) = args.log_file {
+            self.log_file = Some(p);
+        }
         // merge filter
         if let Some(f) = args.filter {
             self.filter = f;
+            self.filter_custom = None;
+        }
+        if let Some(p) = args.filter_custom {
+            self.filter_custom = Some(p);
+        }
+        // merge sharpening
+        if let Some(a) = args.sharpen {
+            self.sharpen = a;
         }
         // merge color type
         if let Some(t) = args.color {
@@ -318,6 +1555,7 @@ impl Config {
         }
         // merge clear
         self.clear = args.clear;
+        self.non_interactive = args.non_interactive;
         // merge dimensions
         if let Some(w) = args.width {
             self.width = w;
@@ -325,23 +1563,583 @@ impl Config {
         if let Some(h) = args.height {
             self.height = h;
         }
+        // merge fit mode
+        if let Some(m) = args.fit {
+            (self.width, self.height) = m.dimensions();
+        }
+        // merge padding
+        if let Some(p) = args.padding {
+            self.padding = p;
+        }
+        // merge alignment
+        if let Some(a) = args.align {
+            self.align = Some(a);
+        }
         // merge luma correction
         if let Some(l) = args.luma_correct {
             self.luma_correct = l;
         }
+        if let Some(c) = args.luma_curve {
+            self.luma_curve = c;
+        }
+        if let Some(t) = args.luma_threshold {
+            self.luma_threshold = Some(t);
+        }
+        // merge gray ramp
+        if let Some(g) = args.gray_ramp {
+            self.gray_ramp = g;
+        }
+        // merge colormap
+        if let Some(c) = args.colormap {
+            self.colormap = c;
+        }
+        // merge renderer
+        if let Some(r) = args.renderer {
+            self.renderer = r;
+        }
+        // merge symbols
+        if let Some(s) = args.symbols {
+            self.symbols = s;
+        }
+        // merge protocol
+        if let Some(p) = args.protocol {
+            self.protocol = p;
+        }
+        // merge background
+        if let Some(b) = args.background {
+            self.background = b;
+        }
+        // merge the backgrounds of the letterbox and image
+        if let Some(b) = args.letterbox_background {
+            self.letterbox_background = Some(b);
+        }
+        // merge letterbox fill
+        if let Some(l) = args.letterbox {
+            self.letterbox = l;
+        }
+        if let Some(c) = args.letterbox_color {
+            self.letterbox_color = Some(c);
+        }
+        if let Some(b) = args.image_background {
+            self.image_background = Some(b);
+        }
+        // merge ops
+        if let Some(o) = args.ops {
+            self.ops = o;
+        }
+        // merge cell aspect ratio
+        if let Some(a) = args.cell_aspect {
+            self.cell_aspect = Some(a);
+        }
+        // merge resize debouncing
+        if let Some(ms) = args.resize_debounce_ms {
+            self.resize_debounce_ms = ms;
+        }
+        // merge config watching
+        if args.watch_config {
+            self.watch_config = true;
+        }
+        // merge recent list
+        if args.no_recent {
+            self.recent = false;
+        }
+        // merge file actions
+        if args.file_actions {
+            self.file_actions = true;
+        }
+        // merge backend
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(b) = args.backend {
+            self.backend = b;
+        }
+        // merge memory budget
+        if let Some(mb) = args.max_memory {
+            self.max_memory = Some(mb);
+        }
+        // merge development of RAW photos and HDR images
+        if let Some(ev) = args.exposure {
+            self.exposure = ev;
+        }
+        if let Some(op) = args.tone_map {
+            self.tone_map = op;
+        }
+        #[cfg(feature = "raw")]
+        if let Some(wb) = args.white_balance {
+            self.white_balance = wb;
+        }
+        // merge color management
+        #[cfg(feature = "icc")]
+        if let Some(p) = args.assume_profile {
+            self.assume_profile = Some(p);
+        }
+        // merge trace output
+        #[cfg(feature = "trace")]
+        if let Some(p) = args.trace_out {
+            self.trace_out = Some(p);
+        }
+        self.subcommand = args.subcommand;
+        self.config_file = args.config;
         self
     }
 }
 
+/// Builder for a validated [`Config`], see [`Config::builder`]
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder(Config);
+impl ConfigBuilder {
+    /// The image to display
+    #[inline]
+    pub fn image(mut self, image: impl Into<PathBuf>) -> Self {
+        self.0.image = image.into();
+        self
+    }
+    /// The image to compare against the image to display
+    #[inline]
+    pub fn compare(mut self, image: impl Into<PathBuf>) -> Self {
+        self.0.compare = Some(image.into());
+        self
+    }
+    /// Flip between the compared images in place instead of showing them beside each other
+    #[inline]
+    pub fn flip(mut self, flip: bool) -> Self {
+        self.0.flip = flip;
+        self
+    }
+    /// Print a heatmap of the differences between the compared images instead of viewing them
+    #[inline]
+    pub fn diff(mut self, diff: bool) -> Self {
+        self.0.diff = diff;
+        self
+    }
+    /// The directory to show a grid of thumbnails of instead of an image
+    #[inline]
+    pub fn grid(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.0.grid = Some(dir.into());
+        self
+    }
+    /// The image file to write a contact sheet of the grid to instead of displaying it
+    #[inline]
+    pub fn contact_sheet(mut self, path: impl Into<PathBuf>) -> Self {
+        self.0.contact_sheet = Some(path.into());
+        self
+    }
+    /// The order the images of the grid are shown in
+    #[inline]
+    pub fn sort(mut self, order: SortOrder) -> Self {
+        self.0.sort = order;
+        self
+    }
+    /// The comma separated globs of the filenames of the images the grid shows
+    #[inline]
+    pub fn grid_filter(mut self, globs: impl Into<String>) -> Self {
+        self.0.grid_filter = Some(globs.into());
+        self
+    }
+    /// Whether the grid shows the images of subdirectories too, skipping the files of `.gitignore` and `.ignore` files
+    #[inline]
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.0.recursive = recursive;
+        self
+    }
+    /// The page of a multi-page document (e.g. a PDF) or frame of a multi-image file to display, starting at 0
+    #[inline]
+    pub fn page(mut self, page: usize) -> Self {
+        self.0.page = page;
+        self
+    }
+    /// The log level
+    #[inline]
+    pub fn log(mut self, log: LogLevel) -> Self {
+        self.0.log = log;
+        self
+    }
+    /// The file to write logs to instead of stdout and stderr
+    #[inline]
+    pub fn log_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.0.log_file = Some(path.into());
+        self
+    }
+    /// The filter to use
+    #[inline]
+    pub fn filter(mut self, filter: FilterType) -> Self {
+        self.0.filter = filter;
+        self
+    }
+    /// The custom filter preset to use instead of the filter
+    #[inline]
+    pub fn filter_custom(mut self, preset: FilterPreset) -> Self {
+        self.0.filter_custom = Some(preset);
+        self
+    }
+    /// The amount of unsharp masking applied after downscaling, 0.0..=1.0
+    #[inline]
+    pub fn sharpen(mut self, amount: f32) -> Self {
+        self.0.sharpen = amount;
+        self
+    }
+    /// The color to use, also setting the color space and depth
+    #[inline]
+    pub fn color(mut self, color: ColorType) -> Self {
+        self.0.color = color;
+        self.0.color_space = color.space();
+        self.0.color_depth = color.depth();
+        self
+    }
+    /// Whether to display the image inline
+    #[inline]
+    pub fn inline(mut self, inline: bool) -> Self {
+        self.0.inline = inline;
+        self
+    }
+    /// Whether to clear the screen after displaying the image inline
+    #[inline]
+    pub fn clear(mut self, clear: bool) -> Self {
+        self.0.clear = clear;
+        self
+    }
+    /// Never take over the terminal, printing inline without waiting for input
+    #[inline]
+    pub fn non_interactive(mut self, non_interactive: bool) -> Self {
+        self.0.non_interactive = non_interactive;
+        self
+    }
+    /// The default number of columns and rows to use if the terminal size is unknown
+    #[inline]
+    pub fn default_size(mut self, columns: u16, rows: u16) -> Self {
+        self.0.default_columns = Some(columns);
+        self.0.default_rows = Some(rows);
+        self
+    }
+    /// The width of to display image
+    #[inline]
+    pub fn width(mut self, width: Dimension) -> Self {
+        self.0.width = width;
+        self
+    }
+    /// The height of to display image
+    #[inline]
+    pub fn height(mut self, height: Dimension) -> Self {
+        self.0.height = height;
+        self
+    }
+    /// The width and height of to display image together
+    #[inline]
+    pub fn fit(mut self, mode: FitMode) -> Self {
+        (self.0.width, self.0.height) = mode.dimensions();
+        self
+    }
+    /// Empty cells kept around the image
+    #[inline]
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.0.padding = padding;
+        self
+    }
+    /// Where the image is placed within the terminal
+    #[inline]
+    pub fn align(mut self, align: Align) -> Self {
+        self.0.align = Some(align);
+        self
+    }
+    /// The luma correction to use, 0.0..=100.0
+    #[inline]
+    pub fn luma_correct(mut self, luma_correct: f32) -> Self {
+        self.0.luma_correct = luma_correct;
+        self
+    }
+    /// The curve the luma correction is mapped to a color distance threshold with
+    #[inline]
+    pub fn luma_curve(mut self, curve: LumaCurve) -> Self {
+        self.0.luma_curve = curve;
+        self
+    }
+    /// The color distance threshold of the luma correction, instead of the luma correction and curve
+    #[inline]
+    pub fn luma_threshold(mut self, threshold: u32) -> Self {
+        self.0.luma_threshold = Some(threshold);
+        self
+    }
+    /// How grays are mapped to the 256 colors
+    #[inline]
+    pub fn gray_ramp(mut self, gray_ramp: GrayRamp) -> Self {
+        self.0.gray_ramp = gray_ramp;
+        self
+    }
+    /// The colormap single-channel images are drawn with in color
+    #[inline]
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.0.colormap = colormap;
+        self
+    }
+    /// How the character cells of the image are drawn
+    #[inline]
+    pub fn renderer(mut self, renderer: Renderer) -> Self {
+        self.0.renderer = renderer;
+        self
+    }
+    /// The classes of glyphs the glyph renderer chooses between
+    #[inline]
+    pub fn symbols(mut self, symbols: Symbols) -> Self {
+        self.0.symbols = symbols;
+        self
+    }
+    /// How inline images are drawn, with ANSI colors or a terminal graphics protocol
+    #[inline]
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.0.protocol = protocol;
+        self
+    }
+    /// The background of the terminal the interface is colored for and transparent images are composited over
+    #[inline]
+    pub fn background(mut self, background: Background) -> Self {
+        self.0.background = background;
+        self
+    }
+    /// The background filling the letterbox, the padding and the cells around the image
+    #[inline]
+    pub fn letterbox_background(mut self, background: UiColor) -> Self {
+        self.0.letterbox_background = Some(background);
+        self
+    }
+    /// What the letterbox is filled with when viewing windowed
+    #[inline]
+    pub fn letterbox(mut self, letterbox: Letterbox) -> Self {
+        self.0.letterbox = letterbox;
+        self
+    }
+    /// The color of the character filling the letterbox
+    #[inline]
+    pub fn letterbox_color(mut self, color: UiColor) -> Self {
+        self.0.letterbox_color = Some(color);
+        self
+    }
+    /// The background behind the cells of the image
+    #[inline]
+    pub fn image_background(mut self, background: UiColor) -> Self {
+        self.0.image_background = Some(background);
+        self
+    }
+    /// The operations applied to the image in order before it's displayed
+    #[inline]
+    pub fn ops(mut self, ops: Ops) -> Self {
+        self.0.ops = ops;
+        self
+    }
+    /// The width of a character cell divided by its height, instead of detecting it from the terminal
+    #[inline]
+    pub fn cell_aspect(mut self, aspect: f32) -> Self {
+        self.0.cell_aspect = Some(aspect);
+        self
+    }
+    /// How long the terminal size must be stable (in milliseconds) before the image is resized
+    #[inline]
+    pub fn resize_debounce_ms(mut self, ms: u64) -> Self {
+        self.0.resize_debounce_ms = ms;
+        self
+    }
+    /// Whether to reload the config file when it changes
+    #[inline]
+    pub fn watch_config(mut self, watch: bool) -> Self {
+        self.0.watch_config = watch;
+        self
+    }
+    /// Whether images viewed windowed are remembered in the recent list of the `:recent` menu
+    #[inline]
+    pub fn recent(mut self, recent: bool) -> Self {
+        self.0.recent = recent;
+        self
+    }
+    /// Whether the selected image of the grid can be moved, copied, renamed, and removed from the `:` prompt
+    #[inline]
+    pub fn file_actions(mut self, file_actions: bool) -> Self {
+        self.0.file_actions = file_actions;
+        self
+    }
+    /// Where terminal events are read from and how raw mode is entered
+    #[cfg(not(target_family = "wasm"))]
+    #[inline]
+    pub fn backend(mut self, backend: BackendKind) -> Self {
+        self.0.backend = backend;
+        self
+    }
+    /// The maximum memory (in megabytes) to use for the decoded image and its resized buffers
+    #[inline]
+    pub fn max_memory(mut self, mb: u64) -> Self {
+        self.0.max_memory = Some(mb);
+        self
+    }
+    /// The exposure compensation (in stops) of RAW photos and HDR images, -10.0..=10.0
+    #[inline]
+    pub fn exposure(mut self, ev: f32) -> Self {
+        self.0.exposure = ev;
+        self
+    }
+    /// The tone mapping operator of HDR images
+    #[inline]
+    pub fn tone_map(mut self, op: ToneMap) -> Self {
+        self.0.tone_map = op;
+        self
+    }
+    /// The white balance of RAW photos
+    #[cfg(feature = "raw")]
+    #[inline]
+    pub fn white_balance(mut self, wb: WhiteBalance) -> Self {
+        self.0.white_balance = wb;
+        self
+    }
+    /// The ICC profile to assume for images without an embedded one, instead of sRGB
+    #[cfg(feature = "icc")]
+    #[inline]
+    pub fn assume_profile(mut self, path: impl Into<PathBuf>) -> Self {
+        self.0.assume_profile = Some(path.into());
+        self
+    }
+    /// Add a preset of settings switched to with the preset command, replacing any of the same name
+    #[inline]
+    pub fn preset(mut self, name: impl Into<String>, preset: Preset) -> Self {
+        self.0.presets.insert(name.into(), preset);
+        self
+    }
+    /// Bind a key to an action, replacing any previous binding
+    #[inline]
+    pub fn bind(
+        mut self,
+        #[cfg(not(target_family = "wasm"))] key: KeyBind,
+        #[cfg(target_family = "wasm")] key: String,
+        action: Action,
+    ) -> Self {
+        self.0.keybinds.insert(key, action);
+        self
+    }
+    /// Validate and build the config
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let conf = self.0;
+        if !(0. ..=100.).contains(&conf.luma_correct) {
+            return Err(ConfigError::Invalid { field: "luma-correct", reason: "must be in the range 0.0..=100.0" });
+        }
+        if conf.luma_threshold.is_some_and(|t| t > MAX_COLOR_DISTANCE) {
+            return Err(ConfigError::Invalid { field: "luma-threshold", reason: "must be in the range 0..=584970" });
+        }
+        if conf.default_columns == Some(0) || conf.default_rows == Some(0) {
+            return Err(ConfigError::Invalid { field: "default-columns/default-rows", reason: "must be greater than 0" });
+        }
+        if conf.max_memory == Some(0) {
+            return Err(ConfigError::Invalid { field: "max-memory", reason: "must be greater than 0" });
+        }
+        if !(-MAX_EXPOSURE..=MAX_EXPOSURE).contains(&conf.exposure) {
+            return Err(ConfigError::Invalid { field: "exposure", reason: "must be in the range -10.0..=10.0" });
+        }
+        if !(0. ..=1.).contains(&conf.sharpen) {
+            return Err(ConfigError::Invalid { field: "sharpen", reason: "must be in the range 0.0..=1.0" });
+        }
+        if conf.cell_aspect.is_some_and(|a| !CELL_ASPECT_RANGE.contains(&a)) {
+            return Err(ConfigError::Invalid { field: "cell-aspect", reason: "must be in the range 0.1..=10.0" });
+        }
+        Ok(conf)
+    }
+}
+
+/// Whether a path is empty, so it can be skipped when serializing
+#[inline]
+fn is_empty_path(path: &Path) -> bool { path.as_os_str().is_empty() }
+
 /// Parse a string as a path to a file.
 #[inline]
-pub fn parse_file_path_str(path: &str) -> Result<PathBuf, String> {
+pub fn parse_file_path_str(path: &str) -> Result<PathBuf, ConfigError> {
     let mut path = PathBuf::from(path);
     path = path.canonicalize().unwrap_or(path);
     if path.is_file() {
         Ok(path)
     } else {
-        Err(format!("File does not exist: {}", path.display()))
+        Err(ConfigError::FileNotFound(path))
+    }
+}
+
+/// The largest exposure compensation (in stops) of RAW photos and HDR images, either way
+pub const MAX_EXPOSURE: f32 = 10.;
+
+/// The width of a character cell divided by its height assumed when it's neither set nor detected,
+/// making the two pixels of a half block square
+pub const DEFAULT_CELL_ASPECT: f32 = 0.5;
+/// The columns and rows assumed when the terminal size is unknown and neither `COLUMNS`/`LINES` nor a default is set
+pub const DEFAULT_TERMINAL_SIZE: (u16, u16) = (80, 24);
+/// The cell aspect ratios that can be set, anything beyond is surely a mistake
+pub const CELL_ASPECT_RANGE: std::ops::RangeInclusive<f32> = 0.1..=10.;
+
+/// Parse a string as a size in columns and rows, `COLUMNSxROWS`
+/// ```
+/// use viuwa::parse_size_str;
+/// assert_eq!(parse_size_str("80x24").unwrap(), (80, 24));
+/// assert!(parse_size_str("80x0").is_err());
+/// ```
+#[inline]
+pub fn parse_size_str(size: &str) -> Result<(u16, u16), ConfigError> {
+    match size.split_once('x').map(|(w, h)| (w.trim().parse::<u16>(), h.trim().parse::<u16>())) {
+        Some((Ok(w), Ok(h))) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(ConfigError::Invalid { field: "size", reason: "must be COLUMNSxROWS, both greater than 0" }),
+    }
+}
+
+/// Parse a string as a cell aspect ratio, a width divided by a height either as a number or `width:height`.
+/// ```
+/// use viuwa::parse_cell_aspect_str;
+/// assert_eq!(parse_cell_aspect_str("0.5").unwrap(), 0.5);
+/// assert_eq!(parse_cell_aspect_str("9:20").unwrap(), 0.45);
+/// assert!(parse_cell_aspect_str("1:0").is_err());
+/// ```
+#[inline]
+pub fn parse_cell_aspect_str(aspect: &str) -> Result<f32, ConfigError> {
+    let aspect = match aspect.split_once(':') {
+        Some((w, h)) => w.trim().parse::<f32>().and_then(|w| Ok(w / h.trim().parse::<f32>()?)),
+        None => aspect.parse::<f32>(),
+    };
+    match aspect {
+        Ok(aspect) if CELL_ASPECT_RANGE.contains(&aspect) => Ok(aspect),
+        _ => Err(ConfigError::Invalid {
+            field: "cell-aspect",
+            reason: "must be a number or width:height in the range 0.1..=10.0",
+        }),
+    }
+}
+
+/// Parse a string as an exposure compensation in stops, within [`MAX_EXPOSURE`] either way.
+#[inline]
+pub fn parse_exposure_str(ev: &str) -> Result<f32, ConfigError> {
+    match ev.parse::<f32>() {
+        Ok(ev) if (-MAX_EXPOSURE..=MAX_EXPOSURE).contains(&ev) => Ok(ev),
+        _ => Err(ConfigError::Invalid { field: "exposure", reason: "must be a number in the range -10.0..=10.0" }),
+    }
+}
+
+/// Parse a string as a luma correction, 0.0..=100.0
+#[inline]
+pub fn parse_luma_correct_str(correct: &str) -> Result<f32, ConfigError> {
+    match correct.parse::<f32>() {
+        Ok(correct) if (0. ..=100.).contains(&correct) => Ok(correct),
+        _ => Err(ConfigError::Invalid { field: "luma-correct", reason: "must be a number in the range 0.0..=100.0" }),
+    }
+}
+
+/// Parse a string as an amount of unsharp masking, 0.0..=1.0
+#[inline]
+pub fn parse_sharpen_str(amount: &str) -> Result<f32, ConfigError> {
+    match amount.parse::<f32>() {
+        Ok(amount) if (0. ..=1.).contains(&amount) => Ok(amount),
+        _ => Err(ConfigError::Invalid { field: "sharpen", reason: "must be a number in the range 0.0..=1.0" }),
+    }
+}
+
+/// Parse a string as a path to an image, a file, a FIFO to stream from, or `-` for stdin.
+#[inline]
+pub fn parse_image_path_str(path: &str) -> Result<PathBuf, ConfigError> {
+    if path == STDIN_PATH {
+        return Ok(PathBuf::from(path));
+    }
+    let mut path = PathBuf::from(path);
+    path = path.canonicalize().unwrap_or(path);
+    if path.exists() && !path.is_dir() {
+        Ok(path)
+    } else {
+        Err(ConfigError::FileNotFound(path))
     }
 }
 
@@ -376,3 +2174,193 @@ pub fn config_path() -> Option<PathBuf> {
     }
     None
 }
+
+/// Where a new config file is created, in `XDG_CONFIG_HOME`, `APPDATA`, or `HOME/.config`, the first one set
+pub fn default_config_path() -> Option<PathBuf> {
+    use std::env::*;
+    let set = |key| var(key).ok().filter(|p| !p.is_empty()).map(PathBuf::from);
+    let dir = set("XDG_CONFIG_HOME").or_else(|| set("APPDATA")).or_else(|| set("HOME").map(|p| p.join(".config")))?;
+    Some(dir.join(PathBuf::from_iter(["viuwa", "config.toml"])))
+}
+
+/// Every key of the config file, with its description and an example value for keys that are unset by default
+pub const CONFIG_KEYS: [(&str, &str, Option<&str>); 46] = [
+    ("log", "The level of verbosity, one of silent, error, warn, info, debug, or trace", None),
+    ("log-file", "Write logs to this file with timestamps and levels, instead of the terminal", Some("\"viuwa.log\"")),
+    ("filter", "The filter to resize with, one of nearest, box, triangle, hamming, catmull, mitchell, or lanczos3", None),
+    ("filter-custom", "A custom filter preset to resize with instead of the filter", Some("\"robidoux\"")),
+    ("sharpen", "The amount of unsharp masking applied after downscaling, 0.0 to 1.0, 0.0 to disable", None),
+    ("color", "The color type, one of color (truecolor), ansi-color, gray, or ansi-gray", None),
+    ("inline", "Print the image to the command line instead of showing it windowed", None),
+    ("clear", "Clear the screen after printing the image inline", None),
+    ("default-columns", "The columns to use when the terminal width is unknown", Some("80")),
+    ("default-rows", "The rows to use when the terminal height is unknown", Some("24")),
+    ("width", "The width of the image, fit, fill, stretch, or a number of columns", None),
+    ("height", "The height of the image, fit, fill, stretch, or a number of half block rows", None),
+    ("padding", "The empty cells kept around the image, 1, 2, or 4 comma separated numbers like CSS", None),
+    (
+        "align",
+        "Where the image is placed, e.g. center or top-left, centered windowed and top left inline if unset",
+        Some("\"center\""),
+    ),
+    ("luma-correct", "The luma correction of 256 color mode, 0.0 to 100.0, higher draws more muted colors in gray", None),
+    ("luma-curve", "The curve of the luma correction's distance threshold, one of linear, quadratic, or cubic", None),
+    (
+        "luma-threshold",
+        "The color distance threshold of the luma correction, 0 to 584970, instead of the correction and curve",
+        Some("73121"),
+    ),
+    ("gray-ramp", "How grays map to 256 colors, a bias lightening or darkening them, and cube or no-cube", None),
+    ("colormap", "The colormap of single-channel images in color modes, one of gray, viridis, magma, or turbo", None),
+    ("renderer", "How the cells of the image are drawn, one of half, glyph, foreground, or full", None),
+    ("symbols", "The comma separated classes of glyphs the glyph renderer chooses between", None),
+    ("protocol", "How inline images are drawn, ansi, auto, kitty, iterm, sixel, terminology, or regis", None),
+    ("background", "The terminal's background, auto (asked of it), dark, light, or #rrggbb", None),
+    (
+        "letterbox-background",
+        "The background filling the padding and the cells around the image, a color name, 0 to 255, or #rrggbb",
+        Some("\"black\""),
+    ),
+    ("letterbox", "What the letterbox is filled with windowed, default (blank), solid, dots, or any narrow character", None),
+    ("letterbox-color", "The color of the character filling the letterbox", Some("\"bright-black\"")),
+    (
+        "image-background",
+        "The background behind the image, seen through the blank cells of the foreground renderer",
+        Some("\"black\""),
+    ),
+    (
+        "theme",
+        "Colors of the interface, error, warning, info, title, and status, selection, and help with their -bg",
+        Some("{ status = \"black\", status-bg = \"#89b4fa\", selection-bg = \"bright-black\" }"),
+    ),
+    ("ops", "Operations applied to the image in order, e.g. crop=0,0,640,480;rotate=90;gray;resize=fit", None),
+    ("cell-aspect", "The width of a character cell divided by its height, detected from the terminal if unset", Some("0.5")),
+    ("resize-debounce-ms", "How long the terminal size must be stable (in milliseconds) before the image is resized", None),
+    ("watch-config", "Reload this file when it changes, applying the new settings without restarting", None),
+    ("recent", "Remember the images viewed windowed for the :recent menu, in the data directory", None),
+    ("sort", "The order of the images of the grid, one of name, mtime, size, or random", None),
+    ("grid-filter", "Only show the images of the grid matching these comma separated globs", Some("\"*.png,*.jpg\"")),
+    ("recursive", "Show the images of subdirectories in the grid too, skipping ignored and hidden files", None),
+    ("file-actions", "Allow :mv, :cp, :rename, and :rm on the selected image of the grid", None),
+    ("backend", "Where terminal events are read from and how raw mode is entered, crossterm or termion", None),
+    ("max-memory", "The most memory (in megabytes) to use for the decoded image and its resized buffers", Some("512")),
+    ("exposure", "The exposure compensation (in stops) of RAW photos and HDR images, -10.0 to 10.0", None),
+    ("tone-map", "The tone mapping operator of HDR images, one of reinhard, aces, or clamp", None),
+    ("white-balance", "The white balance of RAW photos, camera or auto", None),
+    (
+        "assume-profile",
+        "The ICC profile to assume for images without an embedded one, instead of sRGB",
+        Some("\"AdobeRGB1998.icc\""),
+    ),
+    ("trace-out", "Write a Chrome tracing / Perfetto JSON trace of timed spans to this file", Some("\"trace.json\"")),
+    (
+        "preset",
+        "Named presets of settings switched to with :preset <name>, any settings of :set",
+        Some("{ pixel = { filter = \"Nearest\", renderer = \"glyph\", symbols = \"quad\" } }"),
+    ),
+    ("keybinds", "Keys bound to commands, any command of the : prompt", None),
+];
+
+/// The default config file, with every setting commented out below its description
+/// ```
+/// use viuwa::{default_config_toml, Config};
+/// let commented = default_config_toml().unwrap();
+/// let settings = commented.lines().filter_map(|line| line.strip_prefix("# ")).filter(|line| line.contains(" = ") || line.starts_with('['));
+/// // the example values of unset keys are valid too
+/// let conf: Config = toml::from_str(&settings.collect::<Vec<_>>().join("\n")).unwrap();
+/// assert_eq!(conf.max_memory, Some(512));
+/// ```
+pub fn default_config_toml() -> Result<String, ConfigError> {
+    let ::toml::Value::Table(mut defaults) = ::toml::Value::try_from(Config::default())? else {
+        unreachable!("the config is a table")
+    };
+    let mut out = String::from(concat!(
+        "# viuwa config file, every setting is commented out at its default value.\n",
+        "# Uncomment a setting to change it, command line arguments override this file.\n",
+    ));
+    for (key, description, example) in CONFIG_KEYS {
+        let value = match (defaults.remove(key), example) {
+            (Some(value), _) => ::toml::to_string(&::toml::Table::from_iter([(key.to_string(), value)]))?,
+            (None, Some(example)) => format!("{key} = {example}\n"),
+            // a key of a disabled feature
+            (None, None) => continue,
+        };
+        out.push_str(&format!("\n# {description}\n"));
+        for line in value.lines().filter(|line| !line.is_empty()) {
+            out.push_str(&format!("# {line}\n"));
+        }
+    }
+    Ok(out)
+}
+
+/// A problem found in a config file by [`check_config`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// The line the problem is on, starting at 1, if it could be found
+    pub line: Option<usize>,
+    /// Whether the config file can't be used, or the problem is only suspicious (e.g. an unknown key)
+    pub error: bool,
+    pub message: String,
+}
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        f.write_str(if self.error { "error: " } else { "warning: " })?;
+        f.write_str(&self.message)
+    }
+}
+
+/// Check the contents of a config file, finding syntax errors, invalid and out of range values, and unknown keys
+/// ```
+/// use viuwa::check_config;
+/// assert!(check_config("sharpen = 0.5\n").is_empty());
+/// let issues = check_config("log = \"info\"\nsharpen = 2.0\n");
+/// assert_eq!((issues[0].line, issues[0].error), (Some(2), true));
+/// let issues = check_config("log = \"info\"\n\nsharpn = 0.5\n");
+/// assert_eq!((issues[0].line, issues[0].error), (Some(3), false));
+/// assert_eq!(check_config("filter = \"sharp\"\n")[0].line, Some(1));
+/// ```
+pub fn check_config(src: &str) -> Vec<ConfigIssue> {
+    let line_of = |offset: usize| src[..offset.min(src.len())].matches('\n').count() + 1;
+    // the first line assigning one of the keys at the top level
+    let line_of_key = |keys: &str| {
+        let mut table = false;
+        src.lines().position(|line| {
+            let line = line.trim();
+            table |= line.starts_with('[');
+            !table
+                && keys.split('/').any(|key| line.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with('=')))
+        })
+    };
+    let conf = match ::toml::from_str::<Config>(src) {
+        Ok(conf) => conf,
+        Err(e) => {
+            return vec![ConfigIssue {
+                line: e.span().map(|span| line_of(span.start)),
+                error: true,
+                message: e.message().to_string(),
+            }]
+        }
+    };
+    let mut issues = Vec::new();
+    if let Ok(table) = ::toml::from_str::<::toml::Table>(src) {
+        for key in table.keys().filter(|key| *key != "image" && !CONFIG_KEYS.iter().any(|(k, ..)| k == key)) {
+            issues.push(ConfigIssue {
+                line: line_of_key(key).map(|i| i + 1),
+                error: false,
+                message: format!("unknown key {key:?} is ignored"),
+            });
+        }
+    }
+    if let Err(e) = ConfigBuilder(conf).build() {
+        let line = match &e {
+            ConfigError::Invalid { field, .. } => line_of_key(field).map(|i| i + 1),
+            _ => None,
+        };
+        issues.push(ConfigIssue { line, error: true, message: e.to_string() });
+    }
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
@@ -0,0 +1,297 @@
+//! `viuwa convert`, rendering images to ANSI, HTML, or plain text files at a fixed size, to pre-generate terminal art.
+use std::collections::HashMap;
+
+use image::RgbImage;
+use viuwa_ansi::{color::ansi_to_rgb, glyph};
+
+use super::*;
+
+/// The foreground and background colors of SGR sequences, the terminal's own when `None`
+type Colors = (Option<[u8; 3]>, Option<[u8; 3]>);
+
+/// The foreground color of the terminal, assumed when rasterizing ANSI without one
+pub const TERMINAL_FOREGROUND: [u8; 3] = [229, 229, 229];
+/// The background color of the terminal, assumed when rasterizing ANSI without one
+pub const TERMINAL_BACKGROUND: [u8; 3] = [0, 0, 0];
+
+/// The formats images can be converted to
+/// ```
+/// use viuwa::ConvertFormat;
+/// use std::str::FromStr;
+/// assert_eq!(ConvertFormat::from_str("html").unwrap(), ConvertFormat::Html);
+/// assert_eq!(ConvertFormat::Text.extension(), "txt");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConvertFormat {
+    /// ANSI escape sequences, as printed with `--inline`
+    #[default]
+    Ansi,
+    /// An HTML page with the cells as colored spans
+    Html,
+    /// The characters of the cells without any color
+    Text,
+}
+impl ConvertFormat {
+    /// The extension of the files written in this format
+    #[inline]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Ansi => "ans",
+            Self::Html => "html",
+            Self::Text => "txt",
+        }
+    }
+}
+impl FromStr for ConvertFormat {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ans" | "ansi" => Ok(Self::Ansi),
+            "html" => Ok(Self::Html),
+            "txt" | "text" => Ok(Self::Text),
+            _ => Err(ConfigError::InvalidConvertFormat(s.to_string())),
+        }
+    }
+}
+impl fmt::Display for ConvertFormat {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.extension()) }
+}
+
+/// Remove the escape sequences from ANSI, leaving only the characters
+/// ```
+/// assert_eq!(viuwa::strip_ansi("\x1b[38;2;255;000;000;48;2;000;000;255m▀▄\x1b[0m\n \x1b[0m"), "▀▄\n ");
+/// ```
+pub fn strip_ansi(ansi: &str) -> String {
+    let mut text = String::with_capacity(ansi.len() / 8);
+    let mut chars = ansi.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            csi(&mut chars);
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+/// ANSI as an HTML `<pre>`, the characters in spans styled with the colors of their SGR sequences
+/// ```
+/// let html = viuwa::ansi_to_html("\x1b[38;2;255;000;000;48;5;21m▀\x1b[0m<");
+/// assert_eq!(html, "<pre><span style=\"color:#ff0000;background:#0000ff\">▀</span>&lt;</pre>");
+/// ```
+pub fn ansi_to_html(ansi: &str) -> String {
+    let mut html = String::from("<pre>");
+    // the colors of the characters that follow, and of the span that's open, if any
+    let (mut fg, mut bg) = (None, None);
+    let mut open: Option<Colors> = None;
+    let mut chars = ansi.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if let Some(params) = csi(&mut chars).and_then(|(params, end)| (end == 'm').then_some(params)) {
+                sgr(&params, &mut fg, &mut bg);
+            }
+            continue;
+        }
+        if open != Some((fg, bg)) {
+            if open.is_some_and(|(fg, bg)| fg.is_some() || bg.is_some()) {
+                html.push_str("</span>");
+            }
+            if fg.is_some() || bg.is_some() {
+                let css = [("color", fg), ("background", bg)]
+                    .iter()
+                    .filter_map(|(prop, rgb)| rgb.map(|[r, g, b]| format!("{prop}:#{r:02x}{g:02x}{b:02x}")))
+                    .collect::<Vec<_>>();
+                html.push_str(&format!("<span style=\"{}\">", css.join(";")));
+            }
+            open = Some((fg, bg));
+        }
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            c => html.push(c),
+        }
+    }
+    if open.is_some_and(|(fg, bg)| fg.is_some() || bg.is_some()) {
+        html.push_str("</span>");
+    }
+    html.push_str("</pre>");
+    html
+}
+
+/// ANSI as drawn on a terminal of `size` columns and rows, each cell rasterized as `cell` pixels.
+///
+/// The glyphs viuwa draws with cover the pixels of their [`glyph::mask_of`], by the majority of each pixel's share of
+/// a glyph cell, and any other character (e.g. text) is left as its background.
+/// ```
+/// let png = viuwa::ansi_to_image("\x1b[2;1H\x1b[38;2;255;000;000;48;2;000;000;255m▀\x1b[0m", (2, 2), (1, 2));
+/// assert_eq!(png.dimensions(), (2, 4));
+/// assert_eq!((png[(0, 2)].0, png[(0, 3)].0, png[(1, 2)].0), ([255, 0, 0], [0, 0, 255], viuwa::TERMINAL_BACKGROUND));
+/// ```
+pub fn ansi_to_image(ansi: &str, size: (u16, u16), cell: (usize, usize)) -> RgbImage {
+    let (columns, rows) = (size.0 as usize, size.1 as usize);
+    let mut image = RgbImage::from_pixel((columns * cell.0) as u32, (rows * cell.1) as u32, image::Rgb(TERMINAL_BACKGROUND));
+    let (mut fg, mut bg) = (None, None);
+    let (mut x, mut y) = (0, 0);
+    let mut chars = ansi.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                let Some((params, end)) = csi(&mut chars) else { continue };
+                // missing and zero positions are both the first row or column
+                let mut pos = params.split(';').map(|p| p.parse::<usize>().unwrap_or(0).saturating_sub(1));
+                match end {
+                    'm' => sgr(&params, &mut fg, &mut bg),
+                    'H' => (y, x) = (pos.next().unwrap_or(0), pos.next().unwrap_or(0)),
+                    'G' => x = pos.next().unwrap_or(0),
+                    _ => (),
+                }
+            }
+            '\r' => x = 0,
+            '\n' => (x, y) = (0, y + 1),
+            c => {
+                if x < columns && y < rows {
+                    let mask = glyph::mask_of(c).unwrap_or(0);
+                    let (fg, bg) = (fg.unwrap_or(TERMINAL_FOREGROUND), bg.unwrap_or(TERMINAL_BACKGROUND));
+                    // the glyph pixels each pixel of the cell covers
+                    let (gw, gh) = ((glyph::CELL.0 / cell.0).max(1), (glyph::CELL.1 / cell.1).max(1));
+                    for py in 0..cell.1 {
+                        for px in 0..cell.0 {
+                            let (gx, gy) = (px * glyph::CELL.0 / cell.0, py * glyph::CELL.1 / cell.1);
+                            let covered = (gy..gy + gh)
+                                .flat_map(|gy| (gx..gx + gw).map(move |gx| gy * glyph::CELL.0 + gx))
+                                .filter(|bit| mask >> bit & 1 == 1)
+                                .count();
+                            let rgb = if 2 * covered >= gw * gh { fg } else { bg };
+                            image.put_pixel((x * cell.0 + px) as u32, (y * cell.1 + py) as u32, image::Rgb(rgb));
+                        }
+                    }
+                }
+                x += 1;
+            }
+        }
+    }
+    image
+}
+
+/// Consume a CSI sequence after its escape, returning its parameters and final character
+fn csi(chars: &mut std::str::Chars) -> Option<(String, char)> {
+    if chars.next() != Some('[') {
+        return None;
+    }
+    let mut params = String::new();
+    for c in chars.by_ref() {
+        if ('\x40'..='\x7e').contains(&c) {
+            return Some((params, c));
+        }
+        params.push(c);
+    }
+    None
+}
+
+/// Apply the parameters of an SGR sequence to the current colors
+fn sgr(params: &str, fg: &mut Option<[u8; 3]>, bg: &mut Option<[u8; 3]>) {
+    let mut params = params.split(';').map(|p| p.parse::<u16>().unwrap_or(0));
+    while let Some(p) = params.next() {
+        match p {
+            0 => (*fg, *bg) = (None, None),
+            30..=37 => *fg = Some(ansi_to_rgb(p as u8 - 30)),
+            90..=97 => *fg = Some(ansi_to_rgb(p as u8 - 82)),
+            40..=47 => *bg = Some(ansi_to_rgb(p as u8 - 40)),
+            100..=107 => *bg = Some(ansi_to_rgb(p as u8 - 92)),
+            39 => *fg = None,
+            49 => *bg = None,
+            38 | 48 => {
+                let rgb = match params.next() {
+                    Some(5) => params.next().map(|n| ansi_to_rgb(n as u8)),
+                    Some(2) => match (params.next(), params.next(), params.next()) {
+                        (Some(r), Some(g), Some(b)) => Some([r as u8, g as u8, b as u8]),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if p == 38 {
+                    *fg = rgb;
+                } else {
+                    *bg = rgb;
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// The file `image` is converted to in `out_dir`, named after it with the extension of `format`
+fn out_path(image: &Path, out_dir: &Path, format: ConvertFormat) -> PathBuf {
+    let mut name = image.file_stem().unwrap_or(image.as_os_str()).to_os_string();
+    name.push(".");
+    name.push(format.extension());
+    out_dir.join(name)
+}
+
+/// Render each of `images` to a file in `out_dir` named after it, in `format` at `size` columns and rows,
+/// in parallel with the `rayon` feature
+pub fn converted(images: &[PathBuf], out_dir: &Path, format: ConvertFormat, size: (u16, u16), conf: &Config) -> Result<()> {
+    trace!("converted");
+    let mut outs = HashMap::new();
+    for image in images {
+        let out = out_path(image, out_dir, format);
+        // images with the same name in different directories (or with different extensions) would overwrite each other
+        if let Some(other) = outs.insert(out.clone(), image) {
+            return Err(anyhow!("{} and {} would both be written to {}", other.display(), image.display(), out.display()));
+        }
+    }
+    std::fs::create_dir_all(out_dir).with_context(|| format!("Failed to create {}", out_dir.display()))?;
+    let convert = |image: &PathBuf| {
+        let out = out_path(image, out_dir, format);
+        let res = convert(image, &out, format, size, conf);
+        (out, res)
+    };
+    #[cfg(feature = "rayon")]
+    let results: Vec<_> = {
+        use rayon::prelude::*;
+        images.par_iter().map(convert).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let results: Vec<_> = images.iter().map(convert).collect();
+    let mut failed = 0;
+    for (image, (out, res)) in images.iter().zip(results) {
+        match res {
+            Ok(()) => info!("converted {} to {}", image.display(), out.display()),
+            Err(e) => {
+                error!("failed to convert {}: {:#}", image.display(), e);
+                failed += 1;
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        failed => Err(anyhow!("{failed} of {} images failed to convert", images.len())),
+    }
+}
+
+/// Render `image` to the file `out` in `format`
+fn convert(image: &Path, out: &Path, format: ConvertFormat, size: (u16, u16), conf: &Config) -> Result<()> {
+    let conf = Config { image: image.to_path_buf(), ..conf.clone() };
+    let opts = RenderOptions::from_config(&conf, size);
+    let orig = tone_mapped(decode_for(&conf, |img_sz| Some(opts.dimensions(img_sz)))?, &conf);
+    let mut ansi = Vec::new();
+    render(&orig, &opts, &mut ansi)?;
+    ansi.push(b'\n');
+    let ansi = String::from_utf8_lossy(&ansi);
+    let contents = match format {
+        ConvertFormat::Ansi => ansi.into_owned(),
+        ConvertFormat::Html => format!(
+            concat!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n",
+                "<style>pre {{ font-family: monospace; line-height: 1; }}</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+            ),
+            image.file_stem().unwrap_or_default().to_string_lossy().replace('&', "&amp;").replace('<', "&lt;"),
+            ansi_to_html(&ansi)
+        ),
+        ConvertFormat::Text => strip_ansi(&ansi),
+    };
+    std::fs::write(out, contents).with_context(|| format!("Failed to write {}", out.display()))
+}
@@ -291,54 +291,58 @@ impl AsciiPrompt {
     fn idx(&self) -> usize { self.cur as usize }
     /// Update the terminal cursor position.
     #[inline(always)]
-    fn reposition(&mut self, term: &mut impl Terminal) {
-        _execute!(term, cursor_to_col(self.cur as u16));
-    }
+    fn reposition(&mut self, term: &mut impl Terminal) -> io::Result<()> { execute!(term, cursor_to_col(self.cur as u16)) }
     #[inline]
-    pub fn right(&mut self, term: &mut impl Terminal) {
+    pub fn right(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_end() {
             self.cur += 1;
             _execute!(term, write_as(CURSOR_RIGHT));
         }
+        Ok(())
     }
     #[inline]
-    pub fn left(&mut self, term: &mut impl Terminal) {
+    pub fn left(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur -= 1;
             _execute!(term, write_as(CURSOR_LEFT));
         }
+        Ok(())
     }
     #[inline]
-    pub fn to_start(&mut self, term: &mut impl Terminal) {
+    pub fn to_start(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur = self.start;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     #[inline]
-    pub fn to_end(&mut self, term: &mut impl Terminal) {
+    pub fn to_end(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_end() {
             self.cur = self.len() as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     #[inline]
-    pub fn to_last(&mut self, term: &mut impl Terminal) {
+    pub fn to_last(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_last() {
             self.cur = (self.len() - 1) as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     #[inline]
-    pub fn to(&mut self, term: &mut impl Terminal, idx: usize) {
+    pub fn to(&mut self, term: &mut impl Terminal, idx: usize) -> io::Result<()> {
         if idx != self.idx() && (self.start as usize..=self.len()).contains(&idx) {
             self.cur = idx as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     /// Delete the character at the cursor position.
     #[inline]
-    pub fn delete(&mut self, term: &mut impl Terminal) {
+    pub fn delete(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur -= 1;
             if self.at_last() {
@@ -355,10 +359,11 @@ impl AsciiPrompt {
                 );
             }
         }
+        Ok(())
     }
     /// Insert a character at the cursor position.
     #[inline]
-    pub fn insert(&mut self, term: &mut impl Terminal, c: char) {
+    pub fn insert(&mut self, term: &mut impl Terminal, c: char) -> io::Result<()> {
         if c.is_ascii() {
             if self.at_end() {
                 self.buf.push(c);
@@ -369,6 +374,7 @@ impl AsciiPrompt {
             self.cur += 1;
             _execute!(term, cursor_to_col(self.cur));
         }
+        Ok(())
     }
     /// Returns the end index of the current word segment (exclusive), including end of string.
     /// Note that this considers whitespace to be a word segment.
@@ -386,25 +392,27 @@ impl AsciiPrompt {
     /// Move the cursor to the start of the word that the cursor is currently on or the previous word if the cursor is already at the start of a word.
     /// This corresponds to the `Ctrl + Left` keybinding on most terminals and text editors.
     #[inline]
-    pub fn left_word(&mut self, term: &mut impl Terminal) {
+    pub fn left_word(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur -= 1;
             self.cur = self.word_start() as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     /// Move the cursor to the end of the word that the cursor is currently on or the next word if the cursor is already at the end of a word.
     /// This corresponds to the `Ctrl + Right` keybinding on most terminals and text editors.
     #[inline]
-    pub fn right_word(&mut self, term: &mut impl Terminal) {
+    pub fn right_word(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_end() {
             self.cur = self.word_end() as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     /// Delete the word that the cursor is currently on, from the cursor to the start of the word.
     #[inline]
-    pub fn delete_word(&mut self, term: &mut impl Terminal) {
+    pub fn delete_word(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             let orig = self.cur;
             self.cur -= 1;
@@ -422,6 +430,7 @@ impl AsciiPrompt {
                 _execute!(term, cursor_to_col(self.cur), clear_line_to_end());
             }
         }
+        Ok(())
     }
     /// Get the word that the cursor is currently on.
     /// Returns None if there is no word.
@@ -499,54 +508,58 @@ impl AsciiCursor {
     pub fn idx(&self) -> usize { self.cur as usize }
     /// Update the terminal cursor position.
     #[inline(always)]
-    fn reposition(&mut self, term: &mut impl Terminal) {
-        _execute!(term, cursor_to_col(self.cur as u16));
-    }
+    fn reposition(&mut self, term: &mut impl Terminal) -> io::Result<()> { execute!(term, cursor_to_col(self.cur as u16)) }
     #[inline]
-    pub fn right(&mut self, term: &mut impl Terminal) {
+    pub fn right(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_end() {
             self.cur += 1;
             _execute!(term, write_as(CURSOR_RIGHT));
         }
+        Ok(())
     }
     #[inline]
-    pub fn left(&mut self, term: &mut impl Terminal) {
+    pub fn left(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur -= 1;
             _execute!(term, write_as(CURSOR_LEFT));
         }
+        Ok(())
     }
     #[inline]
-    pub fn to_start(&mut self, term: &mut impl Terminal) {
+    pub fn to_start(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur = 0;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     #[inline]
-    pub fn to_end(&mut self, term: &mut impl Terminal) {
+    pub fn to_end(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_end() {
             self.cur = self.len() as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     #[inline]
-    pub fn to_last(&mut self, term: &mut impl Terminal) {
+    pub fn to_last(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_last() {
             self.cur = (self.len() - 1) as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     #[inline]
-    pub fn to(&mut self, term: &mut impl Terminal, idx: usize) {
+    pub fn to(&mut self, term: &mut impl Terminal, idx: usize) -> io::Result<()> {
         if idx != self.idx() && idx <= self.len() {
             self.cur = idx as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     /// Delete the character at the cursor position.
     #[inline]
-    pub fn delete(&mut self, term: &mut impl Terminal) {
+    pub fn delete(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur -= 1;
             if self.at_last() {
@@ -563,10 +576,11 @@ impl AsciiCursor {
                 );
             }
         }
+        Ok(())
     }
     /// Insert a character at the cursor position.
     #[inline]
-    pub fn insert(&mut self, term: &mut impl Terminal, c: char) {
+    pub fn insert(&mut self, term: &mut impl Terminal, c: char) -> io::Result<()> {
         if c.is_ascii() {
             if self.at_end() {
                 self.buf.push(c);
@@ -577,6 +591,7 @@ impl AsciiCursor {
             self.cur += 1;
             _execute!(term, cursor_to_col(self.cur));
         }
+        Ok(())
     }
     /// Returns the end index of the current word segment (exclusive), including end of string.
     /// Note that this considers whitespace to be a word segment.
@@ -594,25 +609,27 @@ impl AsciiCursor {
     /// Move the cursor to the start of the word that the cursor is currently on or the previous word if the cursor is already at the start of a word.
     /// This corresponds to the `Ctrl + Left` keybinding on most terminals and text editors.
     #[inline]
-    pub fn left_word(&mut self, term: &mut impl Terminal) {
+    pub fn left_word(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             self.cur -= 1;
             self.cur = self.word_start() as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     /// Move the cursor to the end of the word that the cursor is currently on or the next word if the cursor is already at the end of a word.
     /// This corresponds to the `Ctrl + Right` keybinding on most terminals and text editors.
     #[inline]
-    pub fn right_word(&mut self, term: &mut impl Terminal) {
+    pub fn right_word(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_end() {
             self.cur = self.word_end() as u16;
-            self.reposition(term);
+            self.reposition(term)?;
         }
+        Ok(())
     }
     /// Delete the word that the cursor is currently on, from the cursor to the start of the word.
     #[inline]
-    pub fn delete_word(&mut self, term: &mut impl Terminal) {
+    pub fn delete_word(&mut self, term: &mut impl Terminal) -> io::Result<()> {
         if !self.at_start() {
             let orig = self.cur;
             self.cur -= 1;
@@ -630,6 +647,7 @@ impl AsciiCursor {
                 _execute!(term, cursor_to_col(self.cur), clear_line_to_end());
             }
         }
+        Ok(())
     }
     /// Get the word that the cursor is currently on.
     /// Returns None if there is no word.
@@ -0,0 +1,96 @@
+//! `viuwa --diff <a> <b>`, a heatmap of the perceptual differences between two images, with summary statistics.
+use image::Rgb;
+
+use super::*;
+
+/// The largest [`delta`] before normalizing, between black and white
+const MAX_DELTA: f64 = 255. * 2.999349;
+/// The colors of the heatmap from no difference to the largest difference
+const HEAT: [[f64; 3]; 5] = [[0., 0., 0.], [64., 0., 160.], [220., 40., 40.], [255., 200., 0.], [255., 255., 255.]];
+
+/// Summary statistics of the differences between two images, with deltas in `0.0..=1.0`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DiffStats {
+    /// The largest difference of any pixel
+    pub max: f64,
+    /// The mean difference of all pixels
+    pub mean: f64,
+    /// The fraction of pixels that differ at all
+    pub changed: f64,
+    /// The common dimensions both images were resized to
+    pub dimensions: (usize, usize),
+}
+impl fmt::Display for DiffStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "max delta {:.1}%, mean delta {:.2}%, {:.1}% of {}x{} pixels changed",
+            self.max * 100.,
+            self.mean * 100.,
+            self.changed * 100.,
+            self.dimensions.0,
+            self.dimensions.1
+        )
+    }
+}
+
+/// The perceptual difference between two colors in `0.0..=1.0`, a red-weighted euclidean distance ("redmean")
+/// ```
+/// assert_eq!(viuwa::delta([12, 34, 56], [12, 34, 56]), 0.);
+/// assert!((viuwa::delta([0, 0, 0], [255, 255, 255]) - 1.).abs() < 1e-4);
+/// ```
+#[inline]
+pub fn delta(a: [u8; 3], b: [u8; 3]) -> f64 {
+    let rmean = (a[0] as f64 + b[0] as f64) / 2.;
+    let [dr, dg, db] = [0, 1, 2].map(|i| a[i] as f64 - b[i] as f64);
+    let d = ((2. + rmean / 256.) * dr * dr + 4. * dg * dg + (2. + (255. - rmean) / 256.) * db * db).sqrt();
+    (d / MAX_DELTA).min(1.)
+}
+
+/// The color of a difference in `0.0..=1.0` on the heatmap
+#[inline]
+pub fn heat(delta: f64) -> [u8; 3] {
+    let t = delta.clamp(0., 1.) * (HEAT.len() - 1) as f64;
+    let i = (t as usize).min(HEAT.len() - 2);
+    let t = t - i as f64;
+    [0, 1, 2].map(|c| (HEAT[i][c] + (HEAT[i + 1][c] - HEAT[i][c]) * t).round() as u8)
+}
+
+/// Resize both images to their common dimensions and compute the heatmap and statistics of their differences
+pub fn diff(a: &ImageView<Rgb<u8>>, b: &ImageView<Rgb<u8>>, filter: ResizeFilter) -> (Image<Rgb<u8>>, DiffStats) {
+    trace!("diff");
+    let dims = (a.width().min(b.width()), a.height().min(b.height()));
+    let (a, b) = (resize_view(a, dims, filter), resize_view(b, dims, filter));
+    let mut heatmap = Image::<Rgb<u8>>::new_with(dims.0, dims.1, [0; 3]);
+    let mut stats = DiffStats { dimensions: dims, ..Default::default() };
+    let mut changed = 0usize;
+    for ((&a, &b), px) in a.pixels().iter().zip(b.pixels()).zip(heatmap.pixels_mut()) {
+        let d = delta(a, b);
+        stats.max = stats.max.max(d);
+        stats.mean += d;
+        changed += (d > 0.) as usize;
+        *px = heat(d);
+    }
+    let len = (dims.0 * dims.1).max(1) as f64;
+    stats.mean /= len;
+    stats.changed = changed as f64 / len;
+    (heatmap, stats)
+}
+
+/// Display the heatmap of the differences between two images inlined, with the statistics on the line below
+pub fn diffed(a: DynamicImage, b: DynamicImage, conf: Config) -> Result<()> {
+    trace!("diffed");
+    let (a, b) = (tone_mapped(a, &conf), tone_mapped(b, &conf));
+    let sz = terminal_size(&mut stdout(), &conf);
+    let (a, b) = (processed(a, &conf, sz), processed(b, &conf, sz));
+    let (a, b) = (Image::from(a.into_rgb8()), Image::from(b.into_rgb8()));
+    let (heatmap, stats) = diff(&a.view(), &b.view(), conf.resize_filter());
+    // leave a row for the statistics
+    let opts = RenderOptions::from_config(&conf, (sz.0, sz.1.saturating_sub(1).max(1)));
+    let mut lock = BufWriter::new(stdout().lock());
+    render_image(&heatmap, &opts, &mut lock)?;
+    writeln!(lock)?;
+    writeln!(lock, "{stats}")?;
+    lock.flush()?;
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Error types for parsing commands and loading the configuration.
+use std::io;
+
+use thiserror::Error;
+
+use super::*;
+
+/// An error parsing a [`Command`] or any of its arguments
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CommandError {
+    /// Nothing to parse
+    #[error("empty command")]
+    Empty,
+    /// Not a known command
+    #[error("{0:?} is not a valid command")]
+    Unknown(String),
+    /// A command was given without its arguments
+    #[error("missing arguments to {0}")]
+    MissingArguments(&'static str),
+    /// A setting was given without its value
+    #[error("missing value for {0}")]
+    MissingValue(&'static str),
+    /// Not a mode that can be cycled
+    #[error("{0:?} is not a valid cycle mode")]
+    InvalidCycle(String),
+    /// Not a valid zoom direction
+    #[error("{0:?} is not a valid zoom, must be 'in', 'out', or 'reset'")]
+    InvalidZoom(String),
+    /// Not a valid pan direction
+    #[error("{0:?} is not a valid pan direction, must be 'left', 'right', 'up', or 'down'")]
+    InvalidPan(String),
+    /// Not a valid axis to fit to
+    #[error("{0:?} is not a valid fit, must be 'width' or 'height'")]
+    InvalidFit(String),
+    /// Not a valid page
+    #[error("{0:?} is not a valid page, must be 'next', 'prev', or a page number")]
+    InvalidPage(String),
+    /// Not a valid playback speed
+    #[error("{0:?} is not a valid speed, must be a multiplier above 0 and at most 16, e.g. '2' or '0.5x'")]
+    InvalidSpeed(String),
+    /// Not a valid frame or time to seek to
+    #[error("{0:?} is not a valid seek, must be a frame number or a time, e.g. '12', '1.5s', or '250ms'")]
+    InvalidSeek(String),
+    /// Not a valid direction to step
+    #[error("{0:?} is not a valid step, must be 'next' or 'prev'")]
+    InvalidStep(String),
+    /// Not a valid expression of image operations, with why
+    #[error("{0}")]
+    InvalidOps(String),
+    /// Not a known setting
+    #[error("{0:?} is not a valid setting")]
+    InvalidSetting(String),
+    /// The value given to a setting couldn't be parsed
+    #[error("invalid value for {setting}: {reason}")]
+    InvalidValue { setting: &'static str, reason: String },
+    /// Not a valid key combination
+    #[error("{0:?} is not a valid keybind")]
+    InvalidKeyBind(String),
+    /// A valid command that can't be bound to a key
+    #[error("{0:?} cannot be bound to a key")]
+    Unbindable(String),
+}
+impl CommandError {
+    /// Whether the command itself was not recognized, rather than its arguments
+    #[inline]
+    pub fn is_unknown(&self) -> bool { matches!(self, Self::Empty | Self::Unknown(_)) }
+}
+
+/// An error parsing a configuration value or loading the config file
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// Not a valid [`Dimension`]
+    #[error("invalid dimension {0:?}, must be 'fit', 'fill', 'stretch', or an integer limit")]
+    InvalidDimension(String),
+    /// Not a valid [`FitMode`]
+    #[error("invalid fit mode {0:?}, must be 'fit', 'fill', 'stretch', or an exact WIDTHxHEIGHT greater than 0")]
+    InvalidFitMode(String),
+    /// Not a valid [`Padding`]
+    #[error(
+        "invalid padding {0:?}, must be 1, 2 (vertical,horizontal), or 4 (top,right,bottom,left) comma separated cells"
+    )]
+    InvalidPadding(String),
+    /// Not a valid [`Align`]
+    #[error("invalid alignment {0:?}, must be 'center', 'top', 'bottom', 'left', 'right', or a corner like 'top-left'")]
+    InvalidAlign(String),
+    /// Not a valid [`LogLevel`]
+    #[error("invalid log level {0:?}")]
+    InvalidLogLevel(String),
+    /// Not a valid [`WhiteBalance`]
+    #[error("invalid white balance {0:?}, must be 'camera' or 'auto'")]
+    InvalidWhiteBalance(String),
+    /// Not a valid [`ToneMap`]
+    #[error("invalid tone map {0:?}, must be 'reinhard', 'aces', or 'clamp'")]
+    InvalidToneMap(String),
+    /// Not a valid [`ConvertFormat`]
+    #[error("invalid convert format {0:?}, must be 'ans', 'html', or 'txt'")]
+    InvalidConvertFormat(String),
+    /// Not a valid [`Renderer`]
+    #[error("invalid renderer {0:?}, must be 'half', 'glyph', 'foreground', or 'full'")]
+    InvalidRenderer(String),
+    /// Not a valid [`Letterbox`]
+    #[error("invalid letterbox {0:?}, must be 'default', 'solid', 'dots', or a single character one column wide")]
+    InvalidLetterbox(String),
+    /// Not a valid [`SortOrder`]
+    #[error("invalid sort order {0:?}, must be 'name', 'mtime', 'size', or 'random'")]
+    InvalidSortOrder(String),
+    /// Not a valid [`BackendKind`], or one viuwa was built without
+    #[error("invalid backend {0:?}, must be 'crossterm', or 'termion' with the termion feature on unix")]
+    InvalidBackend(String),
+    /// Not a valid [`Protocol`], or one viuwa was built without
+    #[error(
+        "invalid protocol {0:?}, must be 'ansi', 'auto', or one viuwa was built with, \
+         'kitty', 'iterm', 'sixel', 'terminology', or 'regis'"
+    )]
+    InvalidProtocol(String),
+    /// Not a valid [`Background`]
+    #[error("invalid background {0:?}, must be 'auto', 'dark', 'light', or a hex color like '#1e1e2e'")]
+    InvalidBackground(String),
+    /// Not a valid [`UiColor`]
+    #[error(
+        "invalid color {0:?}, must be 'default', a color name like 'bright-blue', \
+         an ANSI color 0 to 255, or a hex color like '#1e1e2e'"
+    )]
+    InvalidColor(String),
+    /// A path given as a file doesn't exist
+    #[error("file does not exist: {}", .0.display())]
+    FileNotFound(PathBuf),
+    /// The config file couldn't be read
+    #[error("could not read config file: {}: {source}", path.display())]
+    Read { path: PathBuf, source: io::Error },
+    /// The config file isn't valid toml or has invalid values
+    #[error("could not parse config file: {}: {source}", path.display())]
+    Parse { path: PathBuf, source: toml::de::Error },
+    /// A config given as toml isn't valid toml or has invalid values
+    #[error("could not parse config: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// The config couldn't be serialized
+    #[error("could not serialize config: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    /// The config file couldn't be written
+    #[error("could not write config file: {}: {source}", path.display())]
+    Write { path: PathBuf, source: io::Error },
+    /// There's no config file to reload
+    #[error("no config file to reload, give one with --config")]
+    NoConfigFile,
+    /// A config value is out of its valid range
+    #[error("invalid {field}: {reason}")]
+    Invalid { field: &'static str, reason: &'static str },
+}
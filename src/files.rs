@@ -0,0 +1,124 @@
+//! The file actions of the grid with `--file-actions`, `:mv`, `:cp`, `:rename`, and `:rm`, to cull a directory of images.
+//!
+//! Files are never overwritten, and `:rm` moves them to the trash with the `trash` feature instead of deleting them.
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Where `to` puts the file at `from`, relative to `dir`, and into it (keeping the filename) if it's a directory
+/// ```
+/// use std::path::{Path, PathBuf};
+/// use viuwa::destination;
+/// let dir = std::env::temp_dir();
+/// let from = dir.join("a.png");
+/// assert_eq!(destination(&dir, &from, Path::new("b.png")), dir.join("b.png"));
+/// assert_eq!(destination(&dir, &from, Path::new(".")), dir.join(".").join("a.png"));
+/// ```
+pub fn destination(dir: &Path, from: &Path, to: &Path) -> PathBuf {
+    let to = dir.join(to);
+    match from.file_name() {
+        Some(name) if to.is_dir() => to.join(name),
+        _ => to,
+    }
+}
+
+/// The error of an action that would overwrite `to`
+#[inline]
+fn exists(to: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", to.display()))
+}
+
+/// Copy the file at `from` to `to`, never overwriting a file, removing what was written of `to` if the copy fails
+pub fn copy_file(from: &Path, to: &Path) -> io::Result<()> {
+    let mut src = File::open(from)?;
+    // created with the copy, so a file made at `to` after checking for one can't be overwritten
+    let mut dst = match OpenOptions::new().write(true).create_new(true).open(to) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(exists(to)),
+        dst => dst?,
+    };
+    let copied = io::copy(&mut src, &mut dst).and_then(|_| dst.set_permissions(src.metadata()?.permissions()));
+    if copied.is_err() {
+        drop(dst);
+        let _ = std::fs::remove_file(to);
+    }
+    copied
+}
+
+/// Move (or rename) the file at `from` to `to`, never overwriting a file,
+/// copying it and removing the original if it's moved across filesystems
+///
+/// The file is linked at `to` before it's unlinked from `from`, as linking fails rather than overwrite a file.
+/// On filesystems without links (e.g. FAT and exFAT, which deny them), it's renamed if there's no file at `to`,
+/// and a name is changed to another case of it by renaming, as a case-insensitive filesystem finds it at `to` already.
+/// ```
+/// use viuwa::move_file;
+/// let dir = std::env::temp_dir().join("viuwa-move-file");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let (a, b) = (dir.join("a.png"), dir.join("b.png"));
+/// std::fs::write(&a, b"a").unwrap();
+/// std::fs::write(&b, b"b").unwrap();
+/// assert!(move_file(&a, &b).is_err());
+/// assert_eq!(std::fs::read(&b).unwrap(), b"b");
+/// std::fs::remove_file(&b).unwrap();
+/// move_file(&a, &b).unwrap();
+/// assert!(!a.exists() && std::fs::read(&b).unwrap() == b"a");
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if same_file(from, to) {
+        return std::fs::rename(from, to);
+    }
+    match std::fs::hard_link(from, to) {
+        Ok(()) => std::fs::remove_file(from),
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            copy_file(from, to)?;
+            std::fs::remove_file(from)
+        }
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(exists(to)),
+        Err(e) if matches!(e.kind(), io::ErrorKind::Unsupported | io::ErrorKind::PermissionDenied) => {
+            match to.try_exists()? {
+                true => Err(exists(to)),
+                false => std::fs::rename(from, to),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether `to` is the file at `from` by another case of its name, on a case-insensitive filesystem
+fn same_file(from: &Path, to: &Path) -> bool {
+    let folded = |path: &Path| path.to_string_lossy().to_lowercase();
+    if from == to || folded(from) != folded(to) {
+        return false;
+    }
+    // a case-sensitive filesystem may hold both names as different files
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (from.metadata(), to.metadata()) {
+            (Ok(a), Ok(b)) => (a.dev(), a.ino()) == (b.dev(), b.ino()),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        to.exists()
+    }
+}
+
+/// Whether `:rm` moves files to the trash rather than deleting them
+pub const TRASH: bool = cfg!(all(feature = "trash", not(target_family = "wasm")));
+
+/// Remove the file at `path`, to the trash with the `trash` feature
+pub fn remove_file(path: &Path) -> io::Result<()> {
+    #[cfg(all(feature = "trash", not(target_family = "wasm")))]
+    {
+        trash::delete(path).map_err(io::Error::other)
+    }
+    #[cfg(not(all(feature = "trash", not(target_family = "wasm"))))]
+    {
+        std::fs::remove_file(path)
+    }
+}
@@ -0,0 +1,144 @@
+//! Files holding more than one image, TIFF pages, ICO sizes, and GIF frames, navigated a frame at a time.
+use std::io::{Cursor, Read, Seek};
+
+use image::{
+    codecs::{gif::GifDecoder, ico::IcoDecoder},
+    AnimationDecoder, GrayAlphaImage, GrayImage, ImageBuffer, ImageFormat, RgbImage, RgbaImage,
+};
+use tiff::{
+    decoder::{Decoder as TiffDecoder, DecodingResult},
+    ColorType as TiffColor,
+};
+
+use super::*;
+
+/// The formats that can hold more than one image
+#[inline]
+pub fn is_multi_frame(format: ImageFormat) -> bool {
+    matches!(format, ImageFormat::Tiff | ImageFormat::Ico | ImageFormat::Gif)
+}
+
+/// The format of the file at `path` if it can hold more than one image
+pub fn multi_frame_format(path: &Path) -> Option<ImageFormat> {
+    if is_stream(path) {
+        return None;
+    }
+    image::io::Reader::open(path).ok()?.with_guessed_format().ok()?.format().filter(|&f| is_multi_frame(f))
+}
+
+/// What the images of a multi-image format are called, TIFF pages, ICO images, or GIF frames
+#[inline]
+pub fn frame_label(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Gif => "frame",
+        ImageFormat::Ico => "image",
+        _ => "page",
+    }
+}
+
+/// The number of images in the file at `path`
+pub fn frame_count(path: &Path, format: ImageFormat) -> Result<usize> {
+    trace!("frame_count");
+    let reader = BufReader::new(File::open(path)?);
+    Ok(match format {
+        ImageFormat::Tiff => {
+            let mut decoder = TiffDecoder::new(reader)?;
+            let mut count = 1;
+            while decoder.more_images() {
+                decoder.next_image()?;
+                count += 1;
+            }
+            count
+        }
+        ImageFormat::Ico => ico_entries(reader)?.len(),
+        ImageFormat::Gif => GifDecoder::new(reader)?.into_frames().count(),
+        _ => 1,
+    })
+}
+
+/// Decode the image at `index` (starting at 0) of the file at `path`,
+/// ICO images are ordered largest first, and GIF frames are composited onto the previous frames
+pub fn decode_frame(path: &Path, format: ImageFormat, index: usize) -> Result<DynamicImage> {
+    trace!("decode_frame");
+    let reader = BufReader::new(File::open(path)?);
+    match format {
+        ImageFormat::Tiff => {
+            // pages of many strips or tiles are decoded a few of them per thread
+            #[cfg(feature = "rayon")]
+            if let Some(image) = chunked::decode(path, index)? {
+                return Ok(image);
+            }
+            let mut decoder = TiffDecoder::new(reader)?;
+            decoder.seek_to_image(index)?;
+            let (w, h) = decoder.dimensions()?;
+            let color = decoder.colortype()?;
+            tiff_image((w, h), color, decoder.read_image()?, index)
+        }
+        ImageFormat::Ico => {
+            let mut reader = reader;
+            let entry =
+                *ico_entries(&mut reader)?.get(index).ok_or_else(|| anyhow!("ICO image {} is out of range", index + 1))?;
+            // a copy of the file with only this entry, since the decoder only decodes the largest
+            let (size, offset) = (u32::from_le_bytes([entry[8], entry[9], entry[10], entry[11]]), entry_offset(&entry));
+            let mut ico = Vec::with_capacity(ICO_HEADER + ICO_ENTRY + size as usize);
+            ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+            ico.extend_from_slice(&entry[..12]);
+            ico.extend_from_slice(&((ICO_HEADER + ICO_ENTRY) as u32).to_le_bytes());
+            reader.seek(io::SeekFrom::Start(offset as u64))?;
+            (&mut reader).take(size as u64).read_to_end(&mut ico)?;
+            Ok(DynamicImage::from_decoder(IcoDecoder::new(Cursor::new(ico))?)?)
+        }
+        ImageFormat::Gif => Ok(DynamicImage::ImageRgba8(
+            GifDecoder::new(reader)?
+                .into_frames()
+                .nth(index)
+                .ok_or_else(|| anyhow!("GIF frame {} is out of range", index + 1))??
+                .into_buffer(),
+        )),
+        _ => Ok(image::io::Reader::with_format(reader, format).decode()?),
+    }
+}
+
+/// The decoded pixels of the TIFF page at `index` (starting at 0) as an image, failing if its color type isn't supported
+pub fn tiff_image((w, h): (u32, u32), color: TiffColor, data: DecodingResult, index: usize) -> Result<DynamicImage> {
+    let image = match (data, color) {
+        (DecodingResult::U8(data), TiffColor::Gray(8)) => GrayImage::from_raw(w, h, data).map(DynamicImage::ImageLuma8),
+        (DecodingResult::U8(data), TiffColor::GrayA(8)) => {
+            GrayAlphaImage::from_raw(w, h, data).map(DynamicImage::ImageLumaA8)
+        }
+        (DecodingResult::U8(data), TiffColor::RGB(8)) => RgbImage::from_raw(w, h, data).map(DynamicImage::ImageRgb8),
+        (DecodingResult::U8(data), TiffColor::RGBA(8)) => RgbaImage::from_raw(w, h, data).map(DynamicImage::ImageRgba8),
+        (DecodingResult::U16(data), TiffColor::Gray(16)) => ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageLuma16),
+        (DecodingResult::U16(data), TiffColor::GrayA(16)) => {
+            ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageLumaA16)
+        }
+        (DecodingResult::U16(data), TiffColor::RGB(16)) => ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageRgb16),
+        (DecodingResult::U16(data), TiffColor::RGBA(16)) => ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageRgba16),
+        _ => return Err(anyhow!("TIFF page {} has the unsupported color type {:?}", index + 1, color)),
+    };
+    image.ok_or_else(|| anyhow!("TIFF page {} is truncated", index + 1))
+}
+
+/// The bytes of the ICO header
+const ICO_HEADER: usize = 6;
+/// The bytes of an entry of the ICO directory
+const ICO_ENTRY: usize = 16;
+
+/// The directory entries of an ICO file, largest first
+fn ico_entries(mut reader: impl Read) -> Result<Vec<[u8; ICO_ENTRY]>> {
+    let mut header = [0; ICO_HEADER];
+    reader.read_exact(&mut header)?;
+    let count = u16::from_le_bytes([header[4], header[5]]) as usize;
+    let mut entries = vec![[0; ICO_ENTRY]; count];
+    for entry in &mut entries {
+        reader.read_exact(entry)?;
+    }
+    // a width or height of 0 is 256
+    let dim = |d: u8| if d == 0 { 256 } else { d as u32 };
+    entries.sort_by_key(|e| std::cmp::Reverse(dim(e[0]) * dim(e[1])));
+    Ok(entries)
+}
+
+/// The offset of the image of an ICO directory entry
+#[inline]
+fn entry_offset(entry: &[u8; ICO_ENTRY]) -> u32 { u32::from_le_bytes([entry[12], entry[13], entry[14], entry[15]]) }
@@ -0,0 +1,155 @@
+//! Stepping through the images of the grid from the viewer with `:image next` and `:image prev` (`n` and `b`),
+//! with the next image decoded ahead of time and the previous one kept, so stepping doesn't wait on the decoder.
+//!
+//! The viewer of an image opened from the grid quits with the step it was asked for, and the grid opens the image
+//! the step selects. A [`Prefetcher`] decodes the images around the selection on a thread of its own and downscales
+//! them to the size they're viewed at, within the memory budget of `--max-memory` (or [`PREFETCH_MEMORY`] without one).
+use std::{
+    collections::BTreeSet,
+    sync::{atomic::AtomicBool, mpsc, Arc},
+};
+
+use super::*;
+
+/// The most memory (in megabytes) the decoded images around the selection take without `--max-memory`
+pub const PREFETCH_MEMORY: u64 = 512;
+
+/// Whether the image being viewed was opened from the grid, so it can be stepped away from
+static GALLERY: AtomicBool = AtomicBool::new(false);
+/// The step the viewer of an image opened from the grid was quit with
+static GALLERY_STEP: Mutex<Option<Step>> = Mutex::new(None);
+
+/// Mark the images viewed as opened from the grid (or not), returning whether they were before
+#[inline]
+pub fn set_gallery(active: bool) -> bool { GALLERY.swap(active, Ordering::SeqCst) }
+
+/// Whether the image being viewed was opened from the grid
+#[inline]
+pub fn in_gallery() -> bool { GALLERY.load(Ordering::SeqCst) }
+
+/// Ask the grid to open the image `step` away from the one being viewed, once its viewer quits
+#[inline]
+pub fn request_gallery_step(step: Step) {
+    if let Ok(mut pending) = GALLERY_STEP.lock() {
+        *pending = Some(step);
+    }
+}
+
+/// The step the last viewer was quit with, if any
+#[inline]
+pub fn take_gallery_step() -> Option<Step> { GALLERY_STEP.lock().ok()?.take() }
+
+/// Decoded images by their path, shared with the prefetching thread
+type Decoded = Arc<Mutex<BTreeMap<PathBuf, DynamicImage>>>;
+
+/// Decodes the images around the selection of the grid on a thread of its own, evicting the ones that are no longer near it
+pub struct Prefetcher {
+    decoded: Decoded,
+    conf: Config,
+    /// The images to keep decoded, and the size of the terminal they're decoded for
+    tx: mpsc::Sender<(Vec<PathBuf>, (u16, u16))>,
+    /// The most bytes of decoded images kept
+    limit: u64,
+}
+impl Prefetcher {
+    /// Start the prefetching thread, decoding with the settings of `conf`
+    pub fn new(conf: &Config) -> Self {
+        let (tx, rx) = mpsc::channel::<(Vec<PathBuf>, (u16, u16))>();
+        let decoded = Decoded::default();
+        let limit = Budget::new(conf).map_or(PREFETCH_MEMORY * 1024 * 1024, |b| b.0);
+        let (thread_conf, shared) = (conf.clone(), Arc::clone(&decoded));
+        // without threads (e.g. on wasm), nothing is ever prefetched and every image is decoded when it's opened
+        let spawned = std::thread::Builder::new().name(String::from("prefetch")).spawn(move || {
+            while let Ok(mut want) = rx.recv() {
+                // the selection moved on from all but the latest images wanted
+                while let Ok(latest) = rx.try_recv() {
+                    want = latest;
+                }
+                let (paths, sz) = want;
+                Self::evict(&shared, &paths);
+                for path in paths {
+                    if shared.lock().map_or(true, |decoded| decoded.contains_key(&path)) {
+                        continue;
+                    }
+                    let conf = Config { image: path.clone(), page: 0, ..thread_conf.clone() };
+                    match decode_for(&conf, |img_sz| Some(dimensions(sz, &conf, img_sz))) {
+                        Ok(image) => Self::keep(&shared, limit, path, fitted(&image, sz, &conf).unwrap_or(image)),
+                        Err(e) => debug!("Prefetcher", "could not prefetch {}: {:#}", path.display(), e),
+                    }
+                }
+            }
+        });
+        if let Err(e) = spawned {
+            debug!("Prefetcher", "could not start prefetching: {}", e);
+        }
+        Self { decoded, conf: conf.clone(), tx, limit }
+    }
+    /// Drop the decoded images other than `paths`
+    fn evict(decoded: &Decoded, paths: &[PathBuf]) {
+        let keep = paths.iter().collect::<BTreeSet<_>>();
+        if let Ok(mut decoded) = decoded.lock() {
+            decoded.retain(|path, _| keep.contains(path));
+        }
+    }
+    /// Keep the decoded `image` of `path` if it fits in `limit` bytes with the others
+    fn keep(decoded: &Decoded, limit: u64, path: PathBuf, image: DynamicImage) {
+        let Ok(mut decoded) = decoded.lock() else { return };
+        let used = decoded.values().map(|image| image.as_bytes().len() as u64).sum::<u64>();
+        if used + image.as_bytes().len() as u64 <= limit {
+            decoded.insert(path, image);
+        } else {
+            debug!("Prefetcher", "not keeping {}, the memory budget is spent", path.display());
+        }
+    }
+    /// Keep `paths` decoded for a terminal of `sz`, the images nearest the selection first, evicting every other image
+    #[inline]
+    pub fn want(&self, paths: Vec<PathBuf>, sz: (u16, u16)) { let _ = self.tx.send((paths, sz)); }
+    /// Keep a copy of an image decoded on another thread, e.g. the one being viewed, to step back to, downscaled to
+    /// the size it's viewed at in a terminal of `sz`
+    pub fn insert(&self, path: PathBuf, image: &DynamicImage, sz: (u16, u16)) {
+        let conf = Config { image: path.clone(), page: 0, ..self.conf.clone() };
+        let image = fitted(image, sz, &conf).unwrap_or_else(|| image.clone());
+        Self::keep(&self.decoded, self.limit, path, image)
+    }
+    /// Take the decoded image of `path` out of the prefetched ones, if it's been prefetched
+    #[inline]
+    pub fn take(&self, path: &Path) -> Option<DynamicImage> { self.decoded.lock().ok()?.remove(path) }
+}
+
+/// A decoded image downscaled to the size it's viewed at in a terminal of `sz` with `conf`, or `None` if it's viewed
+/// at full size
+///
+/// HDR images are kept in floating point and images with operations (e.g. `crop`) or more frames than the first are
+/// kept at full size, as they're viewed from more than the pixels of the image fitted to the terminal.
+/// ```
+/// use viuwa::{fitted, Config};
+/// let image = image::DynamicImage::ImageRgb8(image::RgbImage::new(4000, 3000));
+/// let conf = Config::default();
+/// let small = fitted(&image, (80, 24), &conf).unwrap();
+/// assert!(small.width() <= 80 && small.height() <= 48);
+/// assert!(fitted(&small, (80, 24), &conf).is_none());
+/// ```
+pub fn fitted(image: &DynamicImage, sz: (u16, u16), conf: &Config) -> Option<DynamicImage> {
+    let img_sz = (image.width() as usize, image.height() as usize);
+    let dims = dimensions(sz, conf, img_sz);
+    if (dims.0 >= img_sz.0 && dims.1 >= img_sz.1) || is_hdr(image) || !conf.ops.is_empty() {
+        return None;
+    }
+    if multi_frame_format(&conf.image).is_some() {
+        return None;
+    }
+    let filter = conf.resize_filter();
+    // in the colors the viewer converts the image to
+    Some(match image {
+        DynamicImage::ImageRgb8(buf) => DynamicImage::ImageRgb8(resize_view(&buf.into(), dims, filter).into()),
+        DynamicImage::ImageLuma8(buf) => DynamicImage::ImageLuma8(resize_view(&buf.into(), dims, filter).into()),
+        DynamicImage::ImageRgb16(buf) => DynamicImage::ImageRgb16(resize_view(&buf.into(), dims, filter).into()),
+        DynamicImage::ImageLuma16(buf) => DynamicImage::ImageLuma16(resize_view(&buf.into(), dims, filter).into()),
+        _ => match (image.color().has_color(), is_16bit(image)) {
+            (true, false) => DynamicImage::ImageRgb8(resize(&image.to_rgb8().into(), dims, filter).into()),
+            (false, false) => DynamicImage::ImageLuma8(resize(&image.to_luma8().into(), dims, filter).into()),
+            (true, true) => DynamicImage::ImageRgb16(resize(&image.to_rgb16().into(), dims, filter).into()),
+            (false, true) => DynamicImage::ImageLuma16(resize(&image.to_luma16().into(), dims, filter).into()),
+        },
+    })
+}
@@ -0,0 +1,765 @@
+//! `viuwa --grid <dir>`, a grid of thumbnails with their filenames beneath, to browse a directory of images.
+//!
+//! Inlined, the grid is printed a page (as many rows of thumbnails as fit the terminal) at a time.
+//! Windowed, a thumbnail can be selected and opened in the viewer, returning to the grid when it is closed,
+//! and marked with a letter to jump back to, the marks and selection of each directory kept to resume from.
+//! The images are found by [`images_in`], filtered and sorted with `--grid-filter` and `--sort` (or `:filter` and `:sort`).
+//! With `--file-actions`, the selected image can be moved, copied, renamed, or removed from the `:` prompt.
+//! With `--contact-sheet <path>`, the thumbnails and their filenames are composited into a single image file instead.
+use std::path::{Path, PathBuf};
+
+use image::Rgb;
+use viuwa_ansi::consts::SGR_DEFAULT;
+
+use super::*;
+
+/// The columns and rows of a cell, the thumbnail above a row for the filename
+pub const CELL: (u16, u16) = (20, 9);
+/// The pixel dimensions of a thumbnail in a cell
+pub const THUMB: (usize, usize) = (CELL.0 as usize, (CELL.1 as usize - 1) * 2);
+/// The pixel dimensions of a thumbnail on a contact sheet
+pub const SHEET_THUMB: (usize, usize) = (256, 256);
+/// The pixels between and around the thumbnails of a contact sheet
+pub const SHEET_GAP: usize = 8;
+/// The background color of a contact sheet
+pub const SHEET_BACKGROUND: [u8; 3] = [24, 24, 24];
+/// The color of the filenames on a contact sheet
+pub const SHEET_LABEL: [u8; 3] = [200, 200, 200];
+/// The scale of the filenames on a contact sheet, a glyph is 5x7 pixels unscaled
+pub const SHEET_LABEL_SCALE: usize = 2;
+
+/// Whether a path has the extension of a supported image format
+#[inline]
+pub fn is_image(path: &Path) -> bool {
+    #[cfg(feature = "qoi")]
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("qoi")) {
+        return true;
+    }
+    #[cfg(feature = "raw")]
+    if is_raw(path) {
+        return true;
+    }
+    ImageFormat::from_path(path).is_ok_and(|f| f.can_read())
+}
+
+/// A decoded image, downscaled to fit a cell
+pub struct Thumbnail {
+    pub path: PathBuf,
+    pub image: Image<Rgb<u8>>,
+}
+impl Thumbnail {
+    /// Decode an image and fit it into `size` pixels
+    pub fn load(path: &Path, conf: &Config, size: (usize, usize)) -> Result<Self> {
+        let conf = Config { image: path.to_path_buf(), ..conf.clone() };
+        let orig = decode_for(&conf, |img_sz| Some(viuwa_image::fit_dimensions(img_sz, size)))?;
+        let orig = Image::from(tone_mapped(orig, &conf).into_rgb8());
+        let image = resize(&orig, viuwa_image::fit_dimensions(orig.dimensions(), size), conf.resize_filter());
+        Ok(Self { path: path.to_path_buf(), image })
+    }
+    /// The filename, as the image is marked and selected by in the [`Session`] of its directory
+    #[inline]
+    pub fn name(&self) -> String { self.path.file_name().unwrap_or_default().to_string_lossy().into_owned() }
+    /// The filename, truncated to fit a cell
+    pub fn label(&self) -> String {
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let max = CELL.0 as usize;
+        if name.chars().count() > max {
+            name.chars().take(max - 1).chain(Some('…')).collect()
+        } else {
+            name.into_owned()
+        }
+    }
+}
+
+/// The marks and selection of the grid of a directory, kept in the data directory to resume from
+/// ```
+/// use viuwa::Session;
+/// let mut session = Session::default();
+/// session.marks.insert(String::from("a"), String::from("IMG_0001.jpg"));
+/// let toml = toml::to_string(&session).unwrap();
+/// assert_eq!(toml::from_str::<Session>(&toml).unwrap(), session);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Session {
+    /// The filename of the selected image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected: Option<String>,
+    /// The filenames of the marked images, by their letter
+    pub marks: BTreeMap<String, String>,
+}
+impl Session {
+    /// The file the sessions of every directory are kept in, if there's a data directory
+    #[inline]
+    pub fn path() -> Option<PathBuf> { data_dir().map(|dir| dir.join("sessions.toml")) }
+    /// The sessions of every directory, by their absolute path
+    fn read_all() -> BTreeMap<String, Session> {
+        let Some(contents) = Self::path().and_then(|path| std::fs::read_to_string(path).ok()) else {
+            return BTreeMap::new();
+        };
+        ::toml::from_str(&contents).unwrap_or_default()
+    }
+    /// The session of `dir`, empty if there's none
+    pub fn read(dir: &Path) -> Self {
+        let Some(key) = dir.canonicalize().ok().and_then(|dir| dir.to_str().map(String::from)) else {
+            return Self::default();
+        };
+        Self::read_all().remove(&key).unwrap_or_default()
+    }
+    /// Keep the session of `dir` to resume from, replacing the last
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        let (Some(file), Some(key)) = (Self::path(), dir.canonicalize()?.to_str().map(String::from)) else { return Ok(()) };
+        let mut sessions = Self::read_all();
+        sessions.insert(key, self.clone());
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let contents = ::toml::to_string(&sessions).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::fs::write(file, contents)
+    }
+}
+
+/// The thumbnails of a directory, laid out in cells to fit the terminal
+pub struct Grid {
+    /// The directory of the images
+    pub dir: PathBuf,
+    pub thumbs: Vec<Thumbnail>,
+    /// The index of the selected thumbnail
+    pub selected: usize,
+}
+impl Grid {
+    /// Load `size` pixel thumbnails of the images of `dir` found with the filter, order, and recursion of `conf`,
+    /// skipping (and warning about) the ones that fail to decode
+    pub fn load(dir: &Path, conf: &Config, size: (usize, usize)) -> Result<Self> {
+        trace!("Grid::load");
+        let paths = images_in(dir, conf).with_context(|| format!("Failed to read directory {}", dir.display()))?;
+        info!("loading {} thumbnails...", paths.len());
+        let thumbs = Self::thumbnails(&paths, conf, size, BTreeMap::new());
+        Ok(Self { dir: dir.to_path_buf(), thumbs, selected: 0 })
+    }
+    /// The thumbnails of `paths`, taken from `loaded` or loaded in `size` pixels
+    fn thumbnails(
+        paths: &[PathBuf],
+        conf: &Config,
+        size: (usize, usize),
+        mut loaded: BTreeMap<PathBuf, Thumbnail>,
+    ) -> Vec<Thumbnail> {
+        paths
+            .iter()
+            .filter_map(|path| match loaded.remove(path).map_or_else(|| Thumbnail::load(path, conf, size), Ok) {
+                Ok(thumb) => Some(thumb),
+                Err(e) => {
+                    warn!("Skipping {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect()
+    }
+    /// Find the images of the directory again with the filter, order, and recursion of `conf`,
+    /// loading only the thumbnails of images that weren't shown, and keeping the selection on the same image if it's found
+    pub fn rescan(&mut self, conf: &Config, size: (usize, usize)) -> Result<()> {
+        trace!("Grid::rescan");
+        let dir = &self.dir;
+        let paths = images_in(dir, conf).with_context(|| format!("Failed to read directory {}", dir.display()))?;
+        let selected = self.thumbs.get(self.selected).map(|thumb| thumb.path.clone());
+        let loaded = std::mem::take(&mut self.thumbs).into_iter().map(|thumb| (thumb.path.clone(), thumb)).collect();
+        self.thumbs = Self::thumbnails(&paths, conf, size, loaded);
+        self.selected = selected.and_then(|path| self.thumbs.iter().position(|thumb| thumb.path == path)).unwrap_or(0);
+        Ok(())
+    }
+    /// Put the thumbnails in `order`, keeping the selection on the same one
+    pub fn sort(&mut self, order: SortOrder) {
+        let selected = self.thumbs.get(self.selected).map(|thumb| thumb.path.clone());
+        sort_by_order(&mut self.thumbs, order, |thumb| &thumb.path);
+        if let Some(i) = selected.and_then(|path| self.thumbs.iter().position(|thumb| thumb.path == path)) {
+            self.selected = i;
+        }
+    }
+    /// Whether `path` is an image the grid would show with the filter and recursion of `conf`,
+    /// directly in its directory, or in a subdirectory of it with `--recursive`
+    fn shows(&self, path: &Path, conf: &Config) -> bool {
+        let (Some(parent), Ok(dir)) = (path.parent().and_then(|p| p.canonicalize().ok()), self.dir.canonicalize()) else {
+            return false;
+        };
+        (parent == dir || (conf.recursive && parent.starts_with(&dir)))
+            && is_image(path)
+            && conf.grid_filter.as_deref().is_none_or(|globs| matches_globs(globs, path))
+    }
+    /// The index of the thumbnail of the image named `name`
+    #[inline]
+    pub fn position(&self, name: &str) -> Option<usize> { self.thumbs.iter().position(|thumb| thumb.name() == name) }
+    /// The number of cells across an area of `sz`, with a column between cells
+    #[inline]
+    pub fn columns(sz: (u16, u16)) -> usize { ((sz.0 as usize + 1) / (CELL.0 as usize + 1)).max(1) }
+    /// The number of thumbnails on a page filling an area of `sz`
+    #[inline]
+    pub fn per_page(sz: (u16, u16)) -> usize { Self::columns(sz) * (sz.1 / CELL.1).max(1) as usize }
+    /// The number of pages filling an area of `sz`
+    #[inline]
+    pub fn pages(&self, sz: (u16, u16)) -> usize { self.thumbs.len().div_ceil(Self::per_page(sz)).max(1) }
+    /// Write the cells of a row of thumbnails, calling `line` before each line of the row,
+    /// with the label of the `selected` one in the SGR sequence beside it
+    fn write_row<W: Terminal>(
+        out: &mut W,
+        thumbs: &[Thumbnail],
+        selected: Option<(usize, &str)>,
+        color: ColorType,
+        attrs: ColorAttributes,
+        mut line: impl FnMut(&mut W, u16) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let ansis: Vec<_> = thumbs.iter().map(|t| DynamicAnsiImage::new(t.image.view(), color)).collect();
+        let height = CELL.1 - 1;
+        for y in 0..height {
+            line(out, y)?;
+            for ansi in &ansis {
+                let (w, h) = (ansi.width() as u16, ansi.height() as u16);
+                let (offx, offy) = ((CELL.0 - w) / 2, (height - h) / 2);
+                if (offy..offy + h).contains(&y) {
+                    write!(out, "{:offx$}", "", offx = offx as usize)?;
+                    ansi.write_row_to(out, (y - offy) as usize, attrs, None)?;
+                    _execute!(out, attr_reset());
+                    write!(out, "{:rest$}", "", rest = (CELL.0 - offx - w + 1) as usize)?;
+                } else {
+                    write!(out, "{:cell$}", "", cell = CELL.0 as usize + 1)?;
+                }
+            }
+        }
+        line(out, height)?;
+        for (i, thumb) in thumbs.iter().enumerate() {
+            let label = format!("{:cell$}", thumb.label(), cell = CELL.0 as usize);
+            if let Some((_, selection)) = selected.filter(|&(s, _)| s == i) {
+                write!(out, "{selection}{label}{SGR_DEFAULT} ")?;
+            } else {
+                write!(out, "{label} ")?;
+            }
+        }
+        Ok(())
+    }
+    /// Write the rows of the `page`th page, calling `line` with the row of the area before each line
+    fn write_page<W: Terminal>(
+        &self,
+        out: &mut W,
+        sz: (u16, u16),
+        page: usize,
+        selected: Option<(usize, &str)>,
+        (color, attrs): (ColorType, ColorAttributes),
+        mut line: impl FnMut(&mut W, u16) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let per_page = Self::per_page(sz);
+        let start = (page * per_page).min(self.thumbs.len());
+        let thumbs = &self.thumbs[start..(start + per_page).min(self.thumbs.len())];
+        for (row, thumbs) in thumbs.chunks(Self::columns(sz)).enumerate() {
+            let first = start + row * Self::columns(sz);
+            let selected = selected.and_then(|(s, selection)| Some((s.checked_sub(first)?, selection)));
+            let top = row as u16 * CELL.1;
+            Self::write_row(out, thumbs, selected, color, attrs, |out, y| line(out, top + y))?;
+        }
+        Ok(())
+    }
+    /// Composite the thumbnails into a single image, centered in `cell` pixel cells in a roughly square grid,
+    /// with their filenames beneath
+    pub fn contact_sheet(&self, cell: (usize, usize)) -> Image<Rgb<u8>> {
+        trace!("Grid::contact_sheet");
+        let columns = (self.thumbs.len() as f64).sqrt().ceil().max(1.) as usize;
+        let rows = self.thumbs.len().div_ceil(columns).max(1);
+        let label = viuwa_image::ADVANCE.1 * SHEET_LABEL_SCALE;
+        let (cw, ch) = (cell.0 + SHEET_GAP, cell.1 + label + SHEET_GAP);
+        let mut sheet = Image::new_with(SHEET_GAP + columns * cw, SHEET_GAP + rows * ch, SHEET_BACKGROUND);
+        for (i, thumb) in self.thumbs.iter().enumerate() {
+            let (left, top) = (SHEET_GAP + (i % columns) * cw, SHEET_GAP + (i / columns) * ch);
+            let (w, h) = thumb.image.dimensions();
+            sheet.paste(thumb.image.view(), left + cell.0.saturating_sub(w) / 2, top + cell.1.saturating_sub(h) / 2);
+            let text = thumb.label();
+            let (tw, _) = viuwa_image::text_dimensions(&text, SHEET_LABEL_SCALE);
+            let y = top + cell.1 + SHEET_GAP / 2;
+            sheet.draw_text(&text, left + cell.0.saturating_sub(tw) / 2, y, SHEET_LABEL_SCALE, SHEET_LABEL);
+        }
+        sheet
+    }
+    /// Print every page of the grid, waiting for input between pages if stdout is a terminal
+    pub fn print(&self, out: &mut impl Terminal, sz: (u16, u16), conf: &Config) -> io::Result<()> {
+        trace!("Grid::print");
+        let attrs = conf.color_attributes();
+        // leave a row for the page prompt
+        let sz = (sz.0, sz.1.saturating_sub(1));
+        let pages = self.pages(sz);
+        let interactive = conf.interactive();
+        for page in 0..pages {
+            self.write_page(out, sz, page, None, (conf.color, attrs), |out, y| {
+                if y > 0 {
+                    out.write_all(b"\n")?;
+                }
+                Ok(())
+            })?;
+            out.write_all(b"\n")?;
+            if interactive && page + 1 < pages {
+                write!(out, "-- page {}/{}, press any key --", page + 1, pages)?;
+                _execute!(out, flush());
+                wait_for_input(conf)?;
+                _execute!(out, clear_line(), cursor_to_col(0));
+            }
+        }
+        _execute!(out, flush());
+        Ok(())
+    }
+}
+
+/// Browse a grid of thumbnails in the alternate screen, opening the selected one in the viewer
+pub struct GridViewer<W: Terminal = BufWriter<StdoutLock<'static>>> {
+    pub grid: Grid,
+    pub conf: Config,
+    /// The size of the terminal
+    sz: (u16, u16),
+    lock: W,
+    attrs: ColorAttributes,
+    /// A message shown in the status line until the next draw
+    status: Option<String>,
+    /// The marks and selection of the directory, resumed from the last time it was browsed
+    pub session: Session,
+    /// `m` or `'`, waiting for the letter of the mark to set or jump to
+    #[cfg(not(target_family = "wasm"))]
+    pending: Option<char>,
+    /// Decodes the images beside the one opened, to step to them from the viewer without waiting
+    prefetcher: Prefetcher,
+    /// Where terminal events are read from and how raw mode is entered
+    #[cfg(not(target_family = "wasm"))]
+    backend: Box<dyn Backend>,
+}
+impl GridViewer {
+    /// Browse `grid` on stdout, from the image selected the last time its directory was browsed
+    pub fn new(mut grid: Grid, conf: Config) -> Result<Self> {
+        let mut lock = BufWriter::new(stdout().lock());
+        let sz = terminal_size(&mut lock, &conf);
+        let attrs = conf.color_attributes();
+        #[cfg(not(target_family = "wasm"))]
+        let backend = conf.backend.backend();
+        let prefetcher = Prefetcher::new(&conf);
+        let session = Session::read(&grid.dir);
+        if let Some(i) = session.selected.as_ref().and_then(|name| grid.position(name)) {
+            grid.selected = i;
+        }
+        Ok(Self {
+            grid,
+            conf,
+            sz,
+            lock,
+            attrs,
+            status: None,
+            session,
+            #[cfg(not(target_family = "wasm"))]
+            pending: None,
+            prefetcher,
+            #[cfg(not(target_family = "wasm"))]
+            backend,
+        })
+    }
+}
+impl<W: Terminal> GridViewer<W> {
+    /// The area of the terminal for the grid, leaving the last row for the status line
+    #[inline]
+    fn area(&self) -> (u16, u16) { (self.sz.0, self.sz.1.saturating_sub(1)) }
+    /// Enter the alternate screen
+    fn enter(&mut self) -> io::Result<()> {
+        set_terminal_active(true);
+        #[cfg(not(target_family = "wasm"))]
+        self.backend.enable_raw_mode()?;
+        execute!(self.lock, enter_alt_screen(), cursor_hide(), disable_line_wrap(), flush())
+    }
+    /// Leave the alternate screen
+    fn leave(&mut self) -> io::Result<()> {
+        set_terminal_active(false);
+        execute!(self.lock, enable_line_wrap(), cursor_show(), exit_alt_screen(), flush())?;
+        #[cfg(not(target_family = "wasm"))]
+        self.backend.disable_raw_mode()?;
+        execute!(self.lock, soft_reset(), flush())
+    }
+    /// Start browsing, the terminal is always cleaned up even if the loop fails
+    pub fn spawn(mut self) -> io::Result<()> {
+        trace!("GridViewer::spawn");
+        install_restore_hooks();
+        let res = self.enter().and_then(|_| self.run());
+        self.save();
+        res.and(self.leave())
+    }
+    /// Keep the marks and selection to resume from
+    fn save(&mut self) {
+        self.session.selected = self.grid.thumbs.get(self.grid.selected).map(Thumbnail::name);
+        if let Err(e) = self.session.write(&self.grid.dir) {
+            debug!("GridViewer::save", "could not keep the session of {}: {}", self.grid.dir.display(), e);
+        }
+    }
+    /// Mark the selected image with `letter`, replacing the image it marked before
+    fn mark(&mut self, letter: char) {
+        let Some(thumb) = self.grid.thumbs.get(self.grid.selected) else { return };
+        self.session.marks.insert(letter.to_string(), thumb.name());
+        self.status = Some(format!("marked '{letter}"));
+        self.save();
+    }
+    /// Select the image marked with `letter`
+    fn jump(&mut self, letter: char) {
+        match self.session.marks.get(&letter.to_string()).and_then(|name| self.grid.position(name)) {
+            Some(i) => self.grid.selected = i,
+            None => self.status = Some(format!("no mark '{letter}")),
+        }
+    }
+    /// Set or jump to the mark of `letter` after `m` or `'`
+    fn marked(&mut self, prefix: char, letter: char) {
+        match prefix {
+            'm' => self.mark(letter),
+            _ => self.jump(letter),
+        }
+    }
+    /// Read a command from the `:` prompt on the status line, `None` if it's cancelled or invalid
+    #[cfg(not(target_family = "wasm"))]
+    fn prompt(&mut self) -> io::Result<Option<GridCommand>> {
+        let row = self.sz.1.saturating_sub(1);
+        _execute!(self.lock, cursor_to(0, row), clear_line(), cursor_show(), write_all(b":"), flush());
+        let line = {
+            let mut cur = unsafe { AsciiPrompt::new_unchecked(String::from(":"), 1, 1) };
+            loop {
+                if let Event::Key(KeyEvent { code, kind: KeyEventKind::Press, modifiers, .. }) = self.backend.read()? {
+                    match code {
+                        KeyCode::Char(c) => cur.insert(&mut self.lock, c)?,
+                        KeyCode::Backspace if modifiers.contains(KeyModifiers::CONTROL) => {
+                            cur.delete_word(&mut self.lock)?
+                        }
+                        KeyCode::Backspace => cur.delete(&mut self.lock)?,
+                        KeyCode::Left => cur.left(&mut self.lock)?,
+                        KeyCode::Right => cur.right(&mut self.lock)?,
+                        KeyCode::Enter => break cur.buf()[1..].to_string(),
+                        KeyCode::Esc | KeyCode::Null => {
+                            _execute!(self.lock, cursor_hide());
+                            return Ok(None);
+                        }
+                        _ => (),
+                    }
+                }
+                _execute!(self.lock, flush());
+            }
+        };
+        _execute!(self.lock, cursor_hide());
+        match GridCommand::from_str(&line) {
+            Ok(cmd) => Ok(Some(cmd)),
+            Err(CommandError::Empty) => Ok(None),
+            Err(e) => {
+                self.status = Some(format!("{}error: {e}{SGR_DEFAULT}", self.conf.theme().error));
+                Ok(None)
+            }
+        }
+    }
+    /// Ask a yes or no `question` on the status line, anything but `y` is no
+    fn confirm(&mut self, question: &str) -> io::Result<bool> {
+        let row = self.sz.1.saturating_sub(1);
+        _execute!(self.lock, cursor_to(0, row), clear_line(), write_all(question.as_bytes()), write_all(b" (y/n)"), flush());
+        #[cfg(not(target_family = "wasm"))]
+        loop {
+            if let Event::Key(e) = self.backend.read()? {
+                if e.kind == KeyEventKind::Press {
+                    return Ok(matches!(e.code, KeyCode::Char('y' | 'Y')));
+                }
+            }
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            use std::io::BufRead;
+            let mut buf = String::new();
+            std::io::stdin().lock().read_line(&mut buf)?;
+            Ok(matches!(buf.trim(), "y" | "Y" | "yes"))
+        }
+    }
+    /// Run a command of the `:` prompt, returning whether to leave the grid
+    fn command(&mut self, cmd: GridCommand) -> io::Result<bool> {
+        match cmd {
+            GridCommand::Quit => return Ok(true),
+            GridCommand::Sort(order) => {
+                self.conf.sort = order;
+                self.grid.sort(order);
+                self.status = Some(format!("sorted by {order}"));
+            }
+            GridCommand::Filter(globs) => {
+                self.conf.grid_filter = globs;
+                if let Err(e) = self.grid.rescan(&self.conf, THUMB) {
+                    self.status = Some(format!("{}error: {e:#}{SGR_DEFAULT}", self.conf.theme().error));
+                }
+            }
+            GridCommand::Move(_) | GridCommand::Copy(_) | GridCommand::Rename(_) | GridCommand::Remove => {
+                if self.conf.file_actions {
+                    self.file_action(cmd)?;
+                } else {
+                    self.status = Some(String::from("file actions are off, see --file-actions"));
+                }
+            }
+        }
+        Ok(false)
+    }
+    /// Move, copy, rename, or remove the selected image
+    fn file_action(&mut self, cmd: GridCommand) -> io::Result<()> {
+        let Some(from) = self.grid.thumbs.get(self.grid.selected).map(|thumb| thumb.path.clone()) else { return Ok(()) };
+        let name = from.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let res = match cmd {
+            GridCommand::Move(to) => {
+                let to = destination(&self.grid.dir, &from, Path::new(&to));
+                move_file(&from, &to).map(|_| {
+                    self.moved(&from, &to);
+                    format!("moved {name} to {}", to.display())
+                })
+            }
+            // a new name stays in the directory of the image
+            GridCommand::Rename(to) => {
+                let to = from.with_file_name(Path::new(&to).file_name().unwrap_or_default());
+                move_file(&from, &to).map(|_| {
+                    self.moved(&from, &to);
+                    format!("renamed {name} to {}", to.display())
+                })
+            }
+            GridCommand::Copy(to) => {
+                let to = destination(&self.grid.dir, &from, Path::new(&to));
+                copy_file(&from, &to).map(|_| {
+                    if self.grid.shows(&to, &self.conf) {
+                        match Thumbnail::load(&to, &self.conf, THUMB) {
+                            Ok(thumb) => {
+                                self.grid.thumbs.push(thumb);
+                                self.resort();
+                            }
+                            Err(e) => warn!("Skipping {}: {}", to.display(), e),
+                        }
+                    }
+                    format!("copied {name} to {}", to.display())
+                })
+            }
+            GridCommand::Remove => {
+                let verb = if TRASH { "trash" } else { "delete" };
+                if !self.confirm(&format!("{verb} {name}?"))? {
+                    return Ok(());
+                }
+                remove_file(&from).map(|_| {
+                    self.moved(&from, &from);
+                    format!("{verb}d {name}")
+                })
+            }
+            _ => return Ok(()),
+        };
+        self.status = Some(match res {
+            Ok(msg) => msg,
+            Err(e) => format!("{}error: {e}{SGR_DEFAULT}", self.conf.theme().error),
+        });
+        self.save();
+        Ok(())
+    }
+    /// Keep the thumbnails in the order of `--sort` after one is added or renamed, unless they're shuffled
+    fn resort(&mut self) {
+        if self.conf.sort != SortOrder::Random {
+            self.grid.sort(self.conf.sort);
+        }
+    }
+    /// Follow the image at `from` to `to` after it's moved, renamed, or removed (when `to` no longer exists),
+    /// dropping its thumbnail and marks if the grid no longer shows it
+    fn moved(&mut self, from: &Path, to: &Path) {
+        let Some(i) = self.grid.thumbs.iter().position(|thumb| thumb.path == from) else { return };
+        let name = self.grid.thumbs[i].name();
+        if to.is_file() && self.grid.shows(to, &self.conf) {
+            self.grid.thumbs[i].path = to.to_path_buf();
+            let new = self.grid.thumbs[i].name();
+            for marked in self.session.marks.values_mut().filter(|marked| **marked == name) {
+                marked.clone_from(&new);
+            }
+            self.resort();
+        } else {
+            self.session.marks.retain(|_, marked| *marked != name);
+            self.grid.thumbs.remove(i);
+            self.grid.selected = self.grid.selected.min(self.grid.thumbs.len().saturating_sub(1));
+        }
+    }
+    /// Draw the page of the selected thumbnail and the status line
+    fn draw(&mut self) -> io::Result<()> {
+        let area = self.area();
+        let per_page = Grid::per_page(area);
+        let page = self.grid.selected / per_page;
+        let theme = self.conf.theme();
+        _execute!(self.lock, clear());
+        self.grid.write_page(
+            &mut self.lock,
+            area,
+            page,
+            Some((self.grid.selected, &theme.selection)),
+            (self.conf.color, self.attrs),
+            |out, y| out.cursor_to(0, y),
+        )?;
+        let status = match self.status.take() {
+            Some(status) => status,
+            None => match self.grid.thumbs.get(self.grid.selected) {
+                Some(thumb) => {
+                    let name = thumb.name();
+                    let marks =
+                        self.session.marks.iter().filter(|(_, marked)| **marked == name).map(|(c, _)| format!(" '{c}"));
+                    format!("{}/{} {}{}", page + 1, self.grid.pages(area), thumb.path.display(), marks.collect::<String>())
+                }
+                None => String::from("no images"),
+            },
+        };
+        _execute!(
+            self.lock,
+            cursor_to(0, self.sz.1.saturating_sub(1)),
+            write_all(theme.status.as_bytes()),
+            write_all(status.as_bytes()),
+            attr_reset()
+        );
+        execute!(self.lock, flush())
+    }
+    /// Move the selection by `by` thumbnails, staying within the grid
+    fn select(&mut self, by: isize) {
+        let last = self.grid.thumbs.len().saturating_sub(1);
+        self.grid.selected = self.grid.selected.saturating_add_signed(by).min(last);
+    }
+    /// Open the selected thumbnail in the viewer, coming back to the grid when it is closed
+    ///
+    /// Stepped from with `n` or `b` (`:image next` or `:image prev`) in the viewer, the next or previous image is opened
+    /// in its place, decoded ahead of time by the prefetcher
+    fn open(&mut self) -> io::Result<()> {
+        if self.grid.thumbs.is_empty() {
+            return Ok(());
+        }
+        self.leave()?;
+        let gallery = set_gallery(true);
+        loop {
+            let path = self.grid.thumbs[self.grid.selected].path.clone();
+            let conf = Config { image: path.clone(), ..self.conf.clone() };
+            let orig = match self.prefetcher.take(&path) {
+                Some(orig) => Ok(orig),
+                None => decode(&conf),
+            };
+            let res = orig.and_then(|orig| {
+                // kept to step back to, with the next image decoded while this one is viewed
+                self.prefetcher.insert(path, &orig, self.sz);
+                self.prefetch();
+                windowed(orig, conf)
+            });
+            if let Err(e) = res {
+                self.status = Some(format!("{}error: {e}{SGR_DEFAULT}", self.conf.theme().error));
+                break;
+            }
+            match take_gallery_step() {
+                Some(Step::Next) => self.select(1),
+                Some(Step::Prev) => self.select(-1),
+                None => break,
+            }
+        }
+        set_gallery(gallery);
+        self.enter()?;
+        self.sz = terminal_size(&mut self.lock, &self.conf);
+        Ok(())
+    }
+    /// Keep the selected image decoded, decode the next one, and keep the previous one, as they're likely stepped to
+    fn prefetch(&mut self) {
+        let (i, thumbs) = (self.grid.selected, &self.grid.thumbs);
+        let near = [Some(i), i.checked_add(1), i.checked_sub(1)];
+        let paths = near.into_iter().flatten().filter_map(|i| thumbs.get(i)).map(|thumb| thumb.path.clone()).collect();
+        self.prefetcher.want(paths, self.sz);
+    }
+    /// The main grid loop
+    fn run(&mut self) -> io::Result<()> {
+        self.draw()?;
+        loop {
+            let columns = Grid::columns(self.area()) as isize;
+            let per_page = Grid::per_page(self.area()) as isize;
+            #[cfg(not(target_family = "wasm"))]
+            match self.backend.read()? {
+                // any key but a letter cancels setting or jumping to a mark
+                Event::Key(e) if e.kind == KeyEventKind::Press && self.pending.is_some() => {
+                    let prefix = self.pending.take().unwrap_or('m');
+                    if let KeyCode::Char(c @ ('a'..='z' | 'A'..='Z')) = e.code {
+                        self.marked(prefix, c);
+                    }
+                }
+                Event::Key(e) if e.kind == KeyEventKind::Press => match e.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char(c @ ('m' | '\'')) => {
+                        self.pending = Some(c);
+                        self.status = Some(c.to_string());
+                    }
+                    KeyCode::Left | KeyCode::Char('h') => self.select(-1),
+                    KeyCode::Right | KeyCode::Char('l') => self.select(1),
+                    KeyCode::Up | KeyCode::Char('k') => self.select(-columns),
+                    KeyCode::Down | KeyCode::Char('j') => self.select(columns),
+                    KeyCode::PageUp | KeyCode::Char('p') => self.select(-per_page),
+                    KeyCode::PageDown | KeyCode::Char('n') => self.select(per_page),
+                    KeyCode::Enter | KeyCode::Char('o') => self.open()?,
+                    KeyCode::Char(':') => {
+                        if let Some(cmd) = self.prompt()? {
+                            if self.command(cmd)? {
+                                return Ok(());
+                            }
+                        }
+                    }
+                    _ => continue,
+                },
+                Event::Resize(w, h) => self.sz = (w, h),
+                _ => continue,
+            }
+            // without raw mode, read whole lines: a direction, a page, or the number of a thumbnail on the page to open
+            #[cfg(target_family = "wasm")]
+            {
+                use std::io::BufRead;
+                let mut buf = String::new();
+                if std::io::stdin().lock().read_line(&mut buf)? == 0 {
+                    return Ok(());
+                }
+                match buf.trim() {
+                    "" | "q" => return Ok(()),
+                    "h" => self.select(-1),
+                    "l" => self.select(1),
+                    "k" => self.select(-columns),
+                    "j" => self.select(columns),
+                    "p" => self.select(-per_page),
+                    "n" => self.select(per_page),
+                    "o" => self.open()?,
+                    s if s.starts_with(':') => match GridCommand::from_str(&s[1..]) {
+                        Ok(cmd) => {
+                            if self.command(cmd)? {
+                                return Ok(());
+                            }
+                        }
+                        Err(e) => self.status = Some(format!("{}error: {e}{SGR_DEFAULT}", self.conf.theme().error)),
+                    },
+                    s => match s.as_bytes() {
+                        &[prefix @ (b'm' | b'\''), letter] if letter.is_ascii_alphabetic() => {
+                            self.marked(prefix as char, letter as char)
+                        }
+                        _ => match s.parse::<usize>() {
+                            Ok(n) if n > 0 => {
+                                let first = self.grid.selected / per_page as usize * per_page as usize;
+                                self.grid.selected = first;
+                                self.select(n as isize - 1);
+                                self.open()?
+                            }
+                            _ => continue,
+                        },
+                    },
+                }
+            }
+            self.draw()?;
+        }
+    }
+}
+
+/// Display a grid of the thumbnails of the images in a directory, inlined or windowed, or export it as a contact sheet
+pub fn gridded(dir: &Path, conf: Config) -> Result<()> {
+    trace!("gridded");
+    if let Some(path) = &conf.contact_sheet {
+        let sheet = Grid::load(dir, &conf, SHEET_THUMB)?.contact_sheet(SHEET_THUMB);
+        image::RgbImage::from(sheet)
+            .save(path)
+            .with_context(|| format!("Failed to write contact sheet to {}", path.display()))?;
+        info!("wrote contact sheet to {}", path.display());
+        return Ok(());
+    }
+    let grid = Grid::load(dir, &conf, THUMB)?;
+    if conf.inline {
+        let mut lock = BufWriter::new(stdout().lock());
+        let sz = terminal_size(&mut lock, &conf);
+        grid.print(&mut lock, sz, &conf)?;
+    } else {
+        GridViewer::new(grid, conf)?.spawn()?;
+    }
+    Ok(())
+}
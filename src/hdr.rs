@@ -0,0 +1,105 @@
+//! High dynamic range images (OpenEXR and Radiance HDR), kept as linear f32 and tone mapped to 8-bit for display,
+//! so the exposure and tone mapping operator can be changed while viewing.
+use image::{codecs::hdr::HdrDecoder, Rgb, RgbImage};
+
+use super::*;
+
+/// Tone maps the HDR original to a displayable buffer with an operator and an exposure in stops
+pub type ToneMapper<P> = Box<dyn FnMut(ToneMap, f32) -> ImageBuffer<P, Vec<<P as viuwa_image::Pixel>::Scalar>>>;
+
+/// The entries of the sRGB encoding table, fine enough that dark gradients don't band
+const ENCODE_STEPS: usize = 4096;
+
+/// Whether a decoded image holds linear floating point samples, e.g. from an EXR or HDR file
+#[inline]
+pub fn is_hdr(image: &DynamicImage) -> bool { matches!(image, DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)) }
+
+/// Decode a Radiance HDR image to linear f32, since the image crate's decoder tone maps it to 8-bit itself
+pub fn decode_radiance(path: &Path) -> Result<DynamicImage> {
+    let decoder = HdrDecoder::new(BufReader::new(File::open(path)?))?;
+    let (w, h) = (decoder.metadata().width, decoder.metadata().height);
+    let data = decoder.read_image_hdr()?.into_iter().flat_map(|p| p.0).collect();
+    ImageBuffer::from_raw(w, h, data)
+        .map(DynamicImage::ImageRgb32F)
+        .ok_or_else(|| anyhow!("Radiance HDR image is truncated"))
+}
+
+/// Tone map an HDR image to 8-bit with the operator and exposure of `conf`, leaving other images as they are
+#[inline]
+pub fn tone_mapped(image: DynamicImage, conf: &Config) -> DynamicImage {
+    if is_hdr(&image) {
+        DynamicImage::ImageRgb8(tone_map(&Image::from(image.into_rgb32f()), conf.tone_map, conf.exposure))
+    } else {
+        image
+    }
+}
+
+/// Scale the linear samples of an HDR image by `exposure` stops, compress them into 0..=1 with `op`,
+/// then encode them as 8-bit sRGB
+/// ```
+/// use viuwa::{hdr::tone_map, ToneMap};
+/// let hdr = viuwa_image::Image::from(image::ImageBuffer::from_pixel(1, 1, image::Rgb([4f32, 1., 0.])));
+/// assert_eq!(tone_map(&hdr, ToneMap::Clamp, 0.)[(0, 0)].0, [255, 255, 0]);
+/// let reinhard = tone_map(&hdr, ToneMap::Reinhard, 0.)[(0, 0)].0;
+/// assert!(reinhard[0] < 255 && reinhard[0] > reinhard[1]);
+/// assert!(tone_map(&hdr, ToneMap::Aces, -2.)[(0, 0)][1] < tone_map(&hdr, ToneMap::Aces, 0.)[(0, 0)][1]);
+/// ```
+pub fn tone_map(hdr: &Image<Rgb<f32>>, op: ToneMap, exposure: f32) -> RgbImage {
+    trace!("tone_map");
+    let gain = 2f32.powf(exposure);
+    let encode: Vec<u8> =
+        (0..ENCODE_STEPS).map(|i| (from_linear(i as f32 / (ENCODE_STEPS - 1) as f32) * 255.).round() as u8).collect();
+    let map = |v: f32| {
+        // NaN and negative samples are black
+        let v = if v > 0. { op.apply(v * gain) } else { 0. };
+        encode[(v.clamp(0., 1.) * (ENCODE_STEPS - 1) as f32).round() as usize]
+    };
+    let data = hdr.pixels().iter().flat_map(|p| p.map(map)).collect();
+    RgbImage::from_raw(hdr.width() as u32, hdr.height() as u32, data).expect("tone mapped every sample")
+}
+
+impl ToneMap {
+    /// Compress a linear sample into 0..=1
+    #[inline]
+    pub fn apply(self, v: f32) -> f32 {
+        match self {
+            Self::Reinhard => v / (1. + v),
+            // Narkowicz's fit of the ACES filmic curve
+            Self::Aces => (v * (2.51 * v + 0.03)) / (v * (2.43 * v + 0.59) + 0.14),
+            Self::Clamp => v,
+        }
+        .clamp(0., 1.)
+    }
+}
+
+/// Create a new viuwa instance displaying a tone mapped HDR image,
+/// tone mapped again when the exposure or operator is set, and spawn it
+pub fn windowed(orig: DynamicImage, config: Config) -> Result<()> {
+    trace!("hdr::windowed");
+    let hdr = Image::from(orig.into_rgb32f());
+    let image = tone_map(&hdr, config.tone_map, config.exposure);
+    let mapper: ToneMapper<Rgb<u8>> = Box::new(move |op, exposure| tone_map(&hdr, op, exposure));
+    Viuwa::builder().image(image).tone_mapper(mapper).config(config).build()?.spawn()?;
+    Ok(())
+}
+
+/// Decode an sRGB value in 0..=1 to linear light
+#[inline]
+pub fn to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode linear light to an sRGB value, clipped to 0..=1
+#[inline]
+pub fn from_linear(v: f32) -> f32 {
+    let v = v.clamp(0., 1.);
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1. / 2.4) - 0.055
+    }
+}
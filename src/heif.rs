@@ -0,0 +1,36 @@
+//! HEIC/HEIF decoding with libheif (feature `heif`), the default format of iPhone photos.
+use image::{RgbImage, RgbaImage};
+use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+use super::*;
+
+/// Decode the primary image of a HEIF container, the one shown by default when it also holds
+/// bursts, thumbnails, or depth maps, with the rotation, mirroring, and cropping of the file applied
+pub fn decode(conf: &Config, display: impl FnOnce((usize, usize)) -> Option<(usize, usize)>) -> Result<DynamicImage> {
+    trace!("heif::decode");
+    let path = conf.image.to_str().ok_or_else(|| anyhow!("libheif needs a UTF-8 path, {} is not", conf.image.display()))?;
+    let ctx = HeifContext::read_from_file(path)?;
+    let handle = ctx.primary_image_handle()?;
+    if ctx.number_of_top_level_images() > 1 {
+        debug!("decode", "showing the primary of {} images", ctx.number_of_top_level_images());
+    }
+    let img_sz = (handle.width() as usize, handle.height() as usize);
+    // libheif can't downscale while decoding
+    if let Some(b) = Budget::new(conf).map(|b| b.for_decode(display(img_sz))).transpose()?.filter(|b| !b.fits(img_sz)) {
+        return Err(b.exceeded(img_sz));
+    }
+    let alpha = handle.has_alpha_channel();
+    let image =
+        LibHeif::new().decode(&handle, ColorSpace::Rgb(if alpha { RgbChroma::Rgba } else { RgbChroma::Rgb }), None)?;
+    let plane = image.planes().interleaved.ok_or_else(|| anyhow!("libheif decoded no interleaved plane"))?;
+    let (w, h) = (plane.width, plane.height);
+    let row = w as usize * if alpha { 4 } else { 3 };
+    // rows may be padded to the stride
+    let data = plane.data.chunks(plane.stride).take(h as usize).flat_map(|r| &r[..row]).copied().collect();
+    if alpha {
+        RgbaImage::from_raw(w, h, data).map(DynamicImage::ImageRgba8)
+    } else {
+        RgbImage::from_raw(w, h, data).map(DynamicImage::ImageRgb8)
+    }
+    .ok_or_else(|| anyhow!("libheif decoded a truncated {}x{} image", w, h))
+}
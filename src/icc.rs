@@ -0,0 +1,87 @@
+//! Color management (feature `icc`), converting images with an embedded or assumed ICC profile to sRGB for display,
+//! so wide-gamut photos (e.g. Display P3 or Adobe RGB) aren't washed out or oversaturated.
+use image::{
+    codecs::{png::PngDecoder, tiff::TiffDecoder, webp::WebPDecoder},
+    ImageDecoder,
+};
+use qcms::{DataType, Intent, Profile, Transform};
+
+use super::*;
+
+/// Convert a decoded image to sRGB from the ICC profile embedded in the file at `conf.image`,
+/// or the profile of `conf.assume_profile` if it has none, leaving untagged images as they are
+pub fn manage(image: DynamicImage, conf: &Config) -> Result<DynamicImage> {
+    trace!("icc::manage");
+    // streams have no profile, the previews of RAW photos are sRGB, HDR images are linear,
+    // and only RGB profiles are supported
+    if is_stream(&conf.image) || is_raw(&conf.image) || is_hdr(&image) || !image.color().has_color() {
+        return Ok(image);
+    }
+    let format = image::io::Reader::open(&conf.image)?.with_guessed_format()?.format();
+    if let Some(profile) = format.and_then(|f| embedded_profile(&conf.image, f)) {
+        return Ok(match to_srgb(image, &profile) {
+            Ok(image) => image,
+            Err((image, e)) => {
+                warn!("ignoring the embedded ICC profile of {}: {}", conf.image.display(), e);
+                image
+            }
+        });
+    }
+    match &conf.assume_profile {
+        Some(path) => {
+            let profile = std::fs::read(path).with_context(|| format!("Failed to read ICC profile {}", path.display()))?;
+            to_srgb(image, &profile).map_err(|(_, e)| e.context(format!("Failed to assume ICC profile {}", path.display())))
+        }
+        None => Ok(image),
+    }
+}
+
+/// The ICC profile embedded in a PNG, JPEG, TIFF, or WebP file, if any
+pub fn embedded_profile(path: &Path, format: ImageFormat) -> Option<Vec<u8>> {
+    let reader = || File::open(path).map(BufReader::new).ok();
+    match format {
+        ImageFormat::Png => PngDecoder::new(reader()?).ok()?.icc_profile(),
+        ImageFormat::Jpeg => JpegDecoder::new(reader()?).ok()?.icc_profile(),
+        ImageFormat::Tiff => TiffDecoder::new(reader()?).ok()?.icc_profile(),
+        ImageFormat::WebP => WebPDecoder::new(reader()?).ok()?.icc_profile(),
+        _ => None,
+    }
+    .filter(|p| !p.is_empty())
+}
+
+/// Convert an RGB(A) image from an RGB ICC profile to 8-bit sRGB, giving the image back with the error if it can't be.
+///
+/// Images already in sRGB are left as they are, including 16-bit ones.
+pub fn to_srgb(image: DynamicImage, profile: &[u8]) -> Result<DynamicImage, (DynamicImage, anyhow::Error)> {
+    trace!("icc::to_srgb");
+    // the color space signature of the profile header, transforming any other kind is a bug in qcms
+    if profile.get(16..20) != Some(b"RGB ") {
+        return Err((image, anyhow!("only RGB profiles are supported")));
+    }
+    if !image.color().has_color() {
+        return Err((image, anyhow!("the image is grayscale, but the profile is RGB")));
+    }
+    let Some(input) = Profile::new_from_slice(profile, false) else {
+        return Err((image, anyhow!("the profile is corrupt or unsupported")));
+    };
+    if input.is_sRGB() {
+        return Ok(image);
+    }
+    let mut output = Profile::new_sRGB();
+    output.precache_output_transform();
+    let has_alpha = image.color().has_alpha();
+    let ty = if has_alpha { DataType::RGBA8 } else { DataType::RGB8 };
+    let Some(transform) = Transform::new(&input, &output, ty, Intent::default()) else {
+        return Err((image, anyhow!("the profile can't be converted to sRGB")));
+    };
+    debug!("to_srgb", "converting {}x{} image to sRGB", image.width(), image.height());
+    Ok(if has_alpha {
+        let mut rgba = image.into_rgba8();
+        transform.apply(&mut rgba);
+        DynamicImage::ImageRgba8(rgba)
+    } else {
+        let mut rgb = image.into_rgb8();
+        transform.apply(&mut rgb);
+        DynamicImage::ImageRgb8(rgb)
+    })
+}
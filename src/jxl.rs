@@ -0,0 +1,47 @@
+//! JPEG XL decoding with jxl-oxide (feature `jxl`), converted to sRGB by jxl-oxide's own color management.
+use image::{GrayAlphaImage, GrayImage, ImageBuffer, Luma, LumaA, Rgb, RgbImage, Rgba, RgbaImage};
+use jxl_oxide::{EnumColourEncoding, JxlImage, PixelFormat, RenderingIntent};
+
+use super::*;
+
+/// Decode the first frame of a JPEG XL image (the only one of a still image) in sRGB,
+/// with the orientation of the file applied, in 16 bits per sample for images of more than 8
+pub fn decode(conf: &Config, display: impl FnOnce((usize, usize)) -> Option<(usize, usize)>) -> Result<DynamicImage> {
+    trace!("jxl::decode");
+    let reader = BufReader::new(Counted::new(File::open(&conf.image)?));
+    let mut image = JxlImage::builder().read(reader).map_err(|e| anyhow!(e))?;
+    let img_sz = (image.width() as usize, image.height() as usize);
+    // jxl-oxide can't downscale while decoding
+    if let Some(b) = Budget::new(conf).map(|b| b.for_decode(display(img_sz))).transpose()?.filter(|b| !b.fits(img_sz)) {
+        return Err(b.exceeded(img_sz));
+    }
+    image.request_color_encoding(EnumColourEncoding::srgb(RenderingIntent::Relative));
+    let format = image.pixel_format();
+    let wide = image.image_header().metadata.bit_depth.bits_per_sample() > 8;
+    let render = image.render_frame(0).map_err(|e| anyhow!(e))?;
+    let mut stream = render.stream();
+    let (w, h) = (stream.width(), stream.height());
+    let len = w as usize * h as usize * stream.channels() as usize;
+    let image = if wide {
+        let mut data = vec![0u16; len];
+        stream.write_to_buffer(&mut data);
+        match format {
+            PixelFormat::Gray => ImageBuffer::<Luma<u16>, _>::from_raw(w, h, data).map(DynamicImage::ImageLuma16),
+            PixelFormat::Graya => ImageBuffer::<LumaA<u16>, _>::from_raw(w, h, data).map(DynamicImage::ImageLumaA16),
+            PixelFormat::Rgb => ImageBuffer::<Rgb<u16>, _>::from_raw(w, h, data).map(DynamicImage::ImageRgb16),
+            PixelFormat::Rgba => ImageBuffer::<Rgba<u16>, _>::from_raw(w, h, data).map(DynamicImage::ImageRgba16),
+            PixelFormat::Cmyk | PixelFormat::Cmyka => return Err(anyhow!("CMYK JPEG XL images can't be displayed")),
+        }
+    } else {
+        let mut data = vec![0u8; len];
+        stream.write_to_buffer(&mut data);
+        match format {
+            PixelFormat::Gray => GrayImage::from_raw(w, h, data).map(DynamicImage::ImageLuma8),
+            PixelFormat::Graya => GrayAlphaImage::from_raw(w, h, data).map(DynamicImage::ImageLumaA8),
+            PixelFormat::Rgb => RgbImage::from_raw(w, h, data).map(DynamicImage::ImageRgb8),
+            PixelFormat::Rgba => RgbaImage::from_raw(w, h, data).map(DynamicImage::ImageRgba8),
+            PixelFormat::Cmyk | PixelFormat::Cmyka => return Err(anyhow!("CMYK JPEG XL images can't be displayed")),
+        }
+    };
+    image.ok_or_else(|| anyhow!("jxl-oxide rendered a truncated {}x{} image", w, h))
+}
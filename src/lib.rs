@@ -1,34 +1,144 @@
 use std::{
-    cell::Cell,
     collections::BTreeMap,
-    io::{self, stdout, BufWriter, StdoutLock, Write},
-    path::PathBuf,
+    fmt,
+    fs::File,
+    io::{self, stdout, BufReader, BufWriter, StdoutLock, Write},
+    ops::Range,
+    path::{Path, PathBuf},
     str::FromStr,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc, Mutex,
+    },
 };
 
-#[cfg(not(target_os = "wasi"))]
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use image::{DynamicImage, GenericImageView, ImageBuffer};
-use serde::{de, Deserialize};
+#[cfg(not(target_family = "wasm"))]
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind};
+use image::{codecs::jpeg::JpegDecoder, DynamicImage, ImageBuffer, ImageFormat};
+use serde::{de, Deserialize, Serialize};
 use viuwa_ansi::{
-    execute, fg, image::AnsiRow, AnsiImage, ColorAttributes, ColorDepth, ColorSpace, ColorType, Converter, DynamicAnsiImage,
-    Terminal,
+    color::MAX_COLOR_DISTANCE,
+    consts::SGR_REVERSE,
+    execute, fg,
+    glyph::{self, Glyph, Symbols},
+    image::AnsiRow,
+    AnsiImage, ColorAttributes, ColorDepth, ColorSpace, ColorType, Colormap, Converter, DynamicAnsiImage, GrayRamp,
+    LumaCurve, Terminal,
 };
-use viuwa_image::{CompatPixelRepr, CompatScalar, FilterType, Image, ImageView, PixelRepr};
+use viuwa_image::{CompatPixelRepr, CompatScalar, FilterPreset, FilterType, Image, ImageView, Ops, PixelRepr};
 
 #[macro_use]
 mod macros;
 mod config;
 pub use config::*;
+mod error;
+pub use error::*;
 mod commands;
 use anyhow::{anyhow, Context, Result};
 pub use commands::*;
 pub mod cursor;
 use cursor::*;
+mod resizer;
+pub use resizer::*;
+mod tiled;
+pub use tiled::*;
+mod budget;
+pub use budget::*;
+mod bench;
+pub use bench::*;
+mod restore;
+pub use restore::*;
+mod render;
+pub use render::*;
+mod compare;
+pub use compare::*;
+mod viewport;
+pub use viewport::*;
+mod diff;
+pub use diff::*;
+mod convert;
+pub use convert::*;
+mod cast;
+pub use cast::*;
+mod grid;
+pub use grid::*;
+mod gallery;
+pub use gallery::*;
+mod scan;
+pub use scan::*;
+mod ansiart;
+pub use ansiart::*;
+mod stream;
+pub use stream::*;
+mod pages;
+pub use pages::*;
+mod frames;
+pub use frames::*;
+mod stats;
+pub use stats::*;
+mod anim;
+pub use anim::*;
+mod protocol;
+pub use protocol::*;
+mod theme;
+pub use theme::*;
+mod recent;
+pub use recent::*;
+mod progress;
+pub use progress::*;
+mod files;
+pub use files::*;
+#[cfg(not(target_family = "wasm"))]
+mod backend;
+#[cfg(not(target_family = "wasm"))]
+pub use backend::*;
+#[cfg(feature = "wasm-web")]
+mod web;
+#[cfg(feature = "wasm-web")]
+pub use web::*;
+#[cfg(feature = "capi")]
+mod capi;
+#[cfg(feature = "capi")]
+pub use capi::*;
+#[cfg(feature = "avif")]
+pub mod avif;
+#[cfg(feature = "rayon")]
+pub mod chunked;
+pub mod hdr;
+#[cfg(feature = "heif")]
+pub mod heif;
+#[cfg(feature = "icc")]
+pub mod icc;
+#[cfg(feature = "jxl")]
+pub mod jxl;
+#[cfg(feature = "pyo3")]
+mod python;
+use hdr::{is_hdr, tone_mapped, ToneMapper};
+#[cfg(feature = "pdf")]
+pub mod pdf;
+#[cfg(feature = "qoi")]
+pub mod qoi;
+#[cfg(feature = "raw")]
+pub mod raw;
 
-#[cfg(feature = "trace")]
 mod tracing {
     use core::mem::ManuallyDrop;
+    use std::time::Instant;
+    #[cfg(feature = "trace")]
+    use std::{
+        cell::Cell,
+        fs::File,
+        io::{self, BufWriter, Write},
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Mutex, OnceLock,
+        },
+        time::Duration,
+    };
+
+    #[cfg(feature = "trace")]
+    use viuwa_ansi::fg;
     pub struct DropFn<F: FnOnce()>(ManuallyDrop<F>);
     impl<F: FnOnce()> DropFn<F> {
         #[inline]
@@ -42,35 +152,109 @@ mod tracing {
         #[inline]
         fn drop(&mut self) { (unsafe { ManuallyDrop::take(&mut self.0) })(); }
     }
+
+    /// A completed span, in microseconds since the first span started
+    #[cfg(feature = "trace")]
+    struct TraceEvent {
+        name: &'static str,
+        tid: u64,
+        ts: u128,
+        dur: u128,
+    }
+    #[cfg(feature = "trace")]
+    static TRACE_EVENTS: Mutex<Vec<TraceEvent>> = Mutex::new(Vec::new());
+    #[cfg(feature = "trace")]
+    static TRACE_EPOCH: OnceLock<Instant> = OnceLock::new();
+    #[cfg(feature = "trace")]
+    static NEXT_TID: AtomicU64 = AtomicU64::new(1);
+    #[cfg(feature = "trace")]
+    thread_local! {
+        static TID: Cell<u64> = Cell::new(NEXT_TID.fetch_add(1, Ordering::Relaxed));
+    }
+    /// Start a span, returning its start time
+    #[inline]
+    pub fn trace_start() -> Instant {
+        #[cfg(feature = "trace")]
+        TRACE_EPOCH.get_or_init(Instant::now);
+        Instant::now()
+    }
+    /// Record a completed span for the chrome trace
+    #[cfg(feature = "trace")]
+    pub fn trace_event(name: &'static str, start: Instant, dur: Duration) {
+        let ts = start.saturating_duration_since(*TRACE_EPOCH.get_or_init(Instant::now)).as_micros();
+        let tid = TID.with(Cell::get);
+        if let Ok(mut events) = TRACE_EVENTS.lock() {
+            events.push(TraceEvent { name, tid, ts, dur: dur.as_micros() });
+        }
+    }
+    /// Writes every recorded span to a Chrome tracing / Perfetto JSON file when dropped
+    #[cfg(feature = "trace")]
+    pub struct ChromeTrace(pub PathBuf);
+    #[cfg(feature = "trace")]
+    impl ChromeTrace {
+        fn write(&self) -> io::Result<()> {
+            let events = TRACE_EVENTS.lock().map_err(|_| io::Error::other("trace events poisoned"))?;
+            let mut w = BufWriter::new(File::create(&self.0)?);
+            w.write_all(b"{\"displayTimeUnit\":\"ms\",\"traceEvents\":[")?;
+            for (i, e) in events.iter().enumerate() {
+                if i != 0 {
+                    w.write_all(b",")?;
+                }
+                write!(
+                    w,
+                    "\n{{\"name\":{:?},\"cat\":\"viuwa\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":{}}}",
+                    e.name, e.ts, e.dur, e.tid
+                )?;
+            }
+            w.write_all(b"\n]}\n")?;
+            w.flush()
+        }
+    }
+    #[cfg(feature = "trace")]
+    impl Drop for ChromeTrace {
+        fn drop(&mut self) {
+            if let Err(e) = self.write() {
+                error!("could not write trace to {}: {}", self.0.display(), e);
+            }
+        }
+    }
 }
-#[cfg(not(target_os = "wasi"))]
+#[cfg(not(target_family = "wasm"))]
 use commands::KeyBind;
-#[cfg(feature = "trace")]
 pub use tracing::*;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u8)]
 pub enum LogLevel {
+    /// Traces, timings, and above
+    Trace = 0,
+    /// Debug messages and above
+    Debug = 1,
     /// Info and above
-    #[default]
-    Info = 0,
+    Info = 2,
     /// Warnings and above
-    Warn = 1,
+    Warn = 3,
     /// Errors and above
-    Error = 2,
+    Error = 4,
     /// No logging
-    Silent = 3,
+    Silent = 5,
+}
+impl Default for LogLevel {
+    #[inline]
+    fn default() -> Self { Self::DEFAULT }
 }
 impl FromStr for LogLevel {
-    type Err = String;
+    type Err = ConfigError;
     #[inline]
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
+            "trace" => Ok(Self::Trace),
+            "debug" => Ok(Self::Debug),
             "info" => Ok(Self::Info),
             "warn" => Ok(Self::Warn),
             "error" => Ok(Self::Error),
             "silent" => Ok(Self::Silent),
-            _ => Err(format!("Invalid log level: {}", s)),
+            _ => Err(ConfigError::InvalidLogLevel(s.to_string())),
         }
     }
 }
@@ -83,17 +267,93 @@ impl<'de> Deserialize<'de> for LogLevel {
         String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
     }
 }
+impl fmt::Display for LogLevel {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+            Self::Silent => "silent",
+        })
+    }
+}
+impl Serialize for LogLevel {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
 impl LogLevel {
+    /// Info, or the most verbose level enabled by the `debug` and `trace` features
+    pub const DEFAULT: Self = if cfg!(feature = "trace") {
+        Self::Trace
+    } else if cfg!(feature = "debug") {
+        Self::Debug
+    } else {
+        Self::Info
+    };
+    /// Whether messages of this level are logged
+    #[inline]
+    pub fn enabled(self) -> bool { LOG_LEVEL.load(Ordering::Relaxed) <= self as u8 }
+    /// The current log level
     #[inline]
-    pub fn enabled(self) -> bool { LOG_LEVEL.with(|cell| cell.get() <= self) }
+    pub fn current() -> Self { LOG_LEVEL.load(Ordering::Relaxed).into() }
+    /// Set the log level for every thread
+    #[inline]
+    pub fn set(self) { LOG_LEVEL.store(self as u8, Ordering::Relaxed) }
 }
 impl From<u8> for LogLevel {
     #[inline]
-    fn from(v: u8) -> Self { unsafe { core::mem::transmute(v.min(3)) } }
+    fn from(v: u8) -> Self { unsafe { core::mem::transmute(v.min(5)) } }
+}
+
+/// The current log level, shared by every thread
+pub static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::DEFAULT as u8);
+
+/// The file logs are written to instead of stdout and stderr, set with `--log-file`
+pub static LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+/// Append all further logs to a file, with timestamps and levels
+pub fn set_log_file(path: &Path) -> io::Result<()> {
+    let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+    Ok(())
 }
 
-thread_local! {
-    pub static LOG_LEVEL: Cell<LogLevel> = Cell::new(LogLevel::Info);
+/// Write a log line to the log file, returns false if there is no log file
+pub fn log_to_file(level: &str, args: std::fmt::Arguments) -> bool {
+    let mut file = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    match file.as_mut() {
+        Some(file) => {
+            let _ = writeln!(file, "{} {:<7} {}", timestamp(), level, args);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The current UTC time formatted as RFC 3339, e.g. `2023-01-31T12:34:56.789Z`
+fn timestamp() -> String {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    let (secs, millis) = (now.as_secs(), now.subsec_millis());
+    let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+    // civil from days, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + (m <= 2) as i64;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", y, m, d, rem / 3600, rem / 60 % 60, rem % 60, millis)
 }
 
 pub trait Pixel:
@@ -101,6 +361,7 @@ pub trait Pixel:
     + viuwa_ansi::AnsiPixel
     + viuwa_image::CompatPixel
     + viuwa_image::Pixel
+    + 'static
 where
     Self::Scalar: CompatScalar,
     Self::Repr: CompatPixelRepr,
@@ -110,7 +371,8 @@ impl<
         P: image::Pixel<Subpixel = <Self as viuwa_image::Pixel>::Scalar>
             + viuwa_ansi::AnsiPixel
             + viuwa_image::CompatPixel
-            + viuwa_image::Pixel,
+            + viuwa_image::Pixel
+            + 'static,
     > Pixel for P
 where
     P::Scalar: CompatScalar,
@@ -118,25 +380,61 @@ where
 {
 }
 
-#[inline(always)]
-fn div_ceil2(n: usize) -> usize { (n >> 1) + (n & 1) }
+/// How often to check for a finished background buffer while waiting for events
+#[cfg(not(target_family = "wasm"))]
+const BUF_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
+/// How often the config file is checked for changes with `--watch-config`
+const CONFIG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+/// How often the terminal size is measured again on wasm, where there is no signal when it's resized
+#[cfg(target_family = "wasm")]
+const RESIZE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
 
-pub struct Viuwa<'a, P: Pixel>
+pub struct Viuwa<P: Pixel, W: Write + Terminal = BufWriter<StdoutLock<'static>>>
 where
     P::Scalar: CompatScalar,
     P::Repr: CompatPixelRepr,
 {
     pub conf: Config,
-    /// The image to display
-    pub orig: Image<P>,
-    /// The resized image
-    pub buf: Image<P>,
+    /// The image to display, resized in the background
+    pub resizer: Resizer<P>,
+    /// The resized image, shared with the cache of the resizer
+    pub buf: Arc<Image<P>>,
     /// The terminal size in columns and rows
     pub sz: (u16, u16),
-    /// Lock to stdout
-    pub lock: BufWriter<StdoutLock<'a>>,
+    /// The terminal to draw to, usually a lock to stdout
+    pub lock: W,
     /// The current attributes
     pub attrs: ColorAttributes,
+    /// The image compared against the main image, drawn instead of the main buffer if set
+    pub compare: Option<Comparison<P>>,
+    /// The visible window of the main image, drawn as a crop when the image is larger than the terminal
+    pub viewport: Viewport,
+    /// The pages of the document the image is a page of, if any
+    pub pages: Option<Pages<P>>,
+    /// Tone maps the original again when the image is HDR and its exposure or operator is set
+    pub tone_mapper: Option<ToneMapper<P>>,
+    /// The looping animation the image is a frame of, if any
+    pub anim: Option<Animation<P>>,
+    /// Decodes the regions zoomed into again, when the image is the overview of a larger one decoded tiled
+    pub regions: Option<Regions<P>>,
+    /// The image before its operations were applied, kept to apply others to when they're set
+    unprocessed: Option<Image<P>>,
+    /// The rows of the terminal covered by overlays since the image was last drawn
+    overlaid: Range<u16>,
+    /// The buffers the rows are converted into, reused across redraws
+    row_bufs: RowBuffers,
+    /// The time spent in each stage of the last frame drawn, and the frame rate
+    pub timings: FrameTimings,
+    /// Whether the frame timings are drawn over the top left of the image
+    pub show_stats: bool,
+    /// When the config file was last modified, if it's watched for changes
+    config_modified: Option<std::time::SystemTime>,
+    /// Where terminal events are read from and how raw mode is entered
+    #[cfg(not(target_family = "wasm"))]
+    backend: Box<dyn Backend>,
+    /// Whether the terminal was asked for its size and hasn't replied yet
+    #[cfg(target_family = "wasm")]
+    size_queried: bool,
 }
 /// Poll results consumable by the main Viuwa loop
 pub enum Pol {
@@ -144,209 +442,856 @@ pub enum Pol {
     Cmd(Command),
     /// The terminal was resized
     Rsz,
+    /// A high quality buffer finished resizing in the background
+    Buf,
+    /// The next frame of the animation is due
+    Frame,
     /// The user requested to quit or the program was interrupted
     None,
 }
 
-impl<'a, P: Pixel> Viuwa<'a, P>
+/// Builder for a [`Viuwa`] instance, drawing to stdout unless another writer is given
+/// ```
+/// use viuwa::{Config, Viuwa};
+/// let image = image::RgbImage::new(8, 8);
+/// let mut viuwa = Viuwa::builder().image(image).config(Config::default()).writer(Vec::new()).size((8, 4)).build().unwrap();
+/// viuwa.refresh().unwrap();
+/// assert!(!viuwa.term().is_empty());
+/// ```
+pub struct ViuwaBuilder<P: Pixel, W: Write + Terminal>
 where
     P::Scalar: CompatScalar,
     P::Repr: CompatPixelRepr,
 {
-    /// Create a new viuwa instance
-    pub fn new(orig: ImageBuffer<P, Vec<P::Scalar>>, conf: Config) -> Result<Self> {
+    orig: Option<ImageBuffer<P, Vec<P::Scalar>>>,
+    other: Option<ImageBuffer<P, Vec<P::Scalar>>>,
+    pages: Option<Pages<P>>,
+    tone_mapper: Option<ToneMapper<P>>,
+    anim: Option<Animation<P>>,
+    regions: Option<Regions<P>>,
+    conf: Config,
+    writer: Option<W>,
+    /// Locks stdout for the writer when it's built, unless another writer was given
+    lock_stdout: Option<fn() -> W>,
+    size: Option<(u16, u16)>,
+}
+impl<P: Pixel, W: Write + Terminal> ViuwaBuilder<P, W>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// The image to display
+    #[inline]
+    pub fn image(mut self, orig: ImageBuffer<P, Vec<P::Scalar>>) -> Self {
+        self.orig = Some(orig);
+        self
+    }
+    /// An image to compare against the main image, drawn beside it with a synced zoom and pan
+    #[inline]
+    pub fn compare(mut self, other: ImageBuffer<P, Vec<P::Scalar>>) -> Self {
+        self.other = Some(other);
+        self
+    }
+    /// The pages of the document the image is a page of, navigated with the page command
+    #[inline]
+    pub fn pages(mut self, pages: Pages<P>) -> Self {
+        self.pages = Some(pages);
+        self
+    }
+    /// Tone maps the HDR original the image was tone mapped from, again when the exposure or operator is set
+    #[inline]
+    pub fn tone_mapper(mut self, tone_mapper: ToneMapper<P>) -> Self {
+        self.tone_mapper = Some(tone_mapper);
+        self
+    }
+    /// The looping animation the image is the first frame of, played until the viewer is closed
+    #[inline]
+    pub fn animation(mut self, anim: Animation<P>) -> Self {
+        self.anim = Some(anim);
+        self
+    }
+    /// Decodes the regions zoomed into again, when the image is the overview of a larger one decoded tiled
+    #[inline]
+    pub fn regions(mut self, regions: Regions<P>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+    /// The configuration to use, defaults to [`Config::default`]
+    #[inline]
+    pub fn config(mut self, conf: Config) -> Self {
+        self.conf = conf;
+        self
+    }
+    /// The terminal to draw to
+    #[inline]
+    pub fn writer<T: Write + Terminal>(self, writer: T) -> ViuwaBuilder<P, T> {
+        ViuwaBuilder {
+            orig: self.orig,
+            other: self.other,
+            pages: self.pages,
+            tone_mapper: self.tone_mapper,
+            anim: self.anim,
+            regions: self.regions,
+            conf: self.conf,
+            writer: Some(writer),
+            lock_stdout: None,
+            size: self.size,
+        }
+    }
+    /// The size of the terminal in columns and rows, instead of querying the terminal
+    #[inline]
+    pub fn size(mut self, size: (u16, u16)) -> Self {
+        self.size = Some(size);
+        self
+    }
+    /// Build the viuwa instance, resizing the image for the first draw
+    pub fn build(self) -> Result<Viuwa<P, W>> {
         trace!("Viuwa::new");
-        let attrs = ColorAttributes::new(conf.luma_correct as u32);
-        let mut lock = stdout().lock();
-        let sz = terminal_size(&mut lock, &conf)?;
-        let orig = Image::from(orig);
-        let dims = dimensions(sz, &conf, orig.dimensions());
-        let buf = {
-            #[cfg(feature = "fir")]
-            {
-                orig.fir_supersize(dims.0, dims.1, &conf.filter, 3)
-            }
-            #[cfg(not(feature = "fir"))]
-            {
-                orig.supersize(dims.0, dims.1, &conf.filter, 3.)
-            }
+        let Self { orig, other, pages, tone_mapper, anim, regions, conf, writer, lock_stdout, size } = self;
+        let orig = orig.ok_or_else(|| anyhow!("No image given to the Viuwa builder"))?;
+        let mut writer = writer
+            .or_else(|| lock_stdout.map(|lock| lock()))
+            .ok_or_else(|| anyhow!("No writer given to the Viuwa builder"))?;
+        let attrs = conf.color_attributes();
+        let sz = match size {
+            Some(sz) => sz,
+            None => terminal_size(&mut writer, &conf),
         };
-        Ok(Self { conf, orig, buf, sz, lock: BufWriter::new(lock), attrs })
+        let compare = other.map(|other| {
+            let fit = display_dimensions(conf.padding.area(sz).1, &conf, (other.width() as usize, other.height() as usize));
+            Comparison::new(conf.ops.apply(Image::from(other), fit, conf.resize_filter().into()), conf.flip)
+        });
+        let viewport = Viewport::default();
+        let config_modified = if conf.watch_config { config_modified(&conf) } else { None };
+        #[cfg(not(target_family = "wasm"))]
+        let backend = conf.backend.backend();
+        let mut viuwa = Viuwa {
+            conf,
+            resizer: Resizer::new(Image::default()),
+            buf: Arc::default(),
+            sz,
+            lock: writer,
+            attrs,
+            compare,
+            viewport,
+            pages,
+            tone_mapper,
+            anim,
+            regions,
+            unprocessed: None,
+            overlaid: 0..0,
+            row_bufs: RowBuffers::default(),
+            timings: FrameTimings::default(),
+            show_stats: false,
+            config_modified,
+            #[cfg(not(target_family = "wasm"))]
+            backend,
+            #[cfg(target_family = "wasm")]
+            size_queried: false,
+        };
+        let orig = viuwa.processed(Image::from(orig));
+        viuwa.resizer = Resizer::new(orig).with_budget(Budget::new(&viuwa.conf));
+        viuwa.rebuffer();
+        Ok(viuwa)
+    }
+}
+
+impl<P: Pixel> Viuwa<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// Create a new viuwa instance drawing to stdout
+    #[inline]
+    pub fn new(orig: ImageBuffer<P, Vec<P::Scalar>>, conf: Config) -> Result<Self> {
+        Self::builder().image(orig).config(conf).build()
     }
-    /// Get a mutable reference to the terminal lock
+    /// Start building a new viuwa instance
+    #[inline]
+    pub fn builder() -> ViuwaBuilder<P, BufWriter<StdoutLock<'static>>> {
+        ViuwaBuilder {
+            orig: None,
+            other: None,
+            pages: None,
+            tone_mapper: None,
+            anim: None,
+            regions: None,
+            conf: Config::default(),
+            writer: None,
+            lock_stdout: Some(|| BufWriter::new(stdout().lock())),
+            size: None,
+        }
+    }
+}
+
+impl<P: Pixel, W: Write + Terminal> Viuwa<P, W>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// Get a mutable reference to the terminal
     #[inline]
-    pub fn term(&mut self) -> &mut BufWriter<StdoutLock<'a>> { &mut self.lock }
-    /// Start viuwa app
-    pub fn spawn(mut self) {
+    pub fn term(&mut self) -> &mut W { &mut self.lock }
+    /// Start viuwa app, the terminal is always cleaned up even if the loop fails
+    pub fn spawn(mut self) -> io::Result<()> {
         trace!("Viuwa::spawn");
-        execute!(self.lock, enable_raw_mode(), enter_alt_screen(), cursor_hide(), disable_line_wrap(), flush())
-            .expect("Failed to setup Viuwa loop");
-        self._draw();
+        install_restore_hooks();
+        set_terminal_active(true);
+        // there's no raw mode on wasm
+        #[cfg(not(target_family = "wasm"))]
+        let raw = self.backend.enable_raw_mode();
+        #[cfg(target_family = "wasm")]
+        let raw = Ok(());
+        let res = raw
+            .and_then(|_| execute!(self.lock, enter_alt_screen(), cursor_hide(), disable_line_wrap(), flush()))
+            .and_then(|_| self.run());
+        set_terminal_active(false);
+        let cleanup = execute!(self.lock, disable_mouse(), enable_line_wrap(), cursor_show(), exit_alt_screen(), flush());
+        #[cfg(not(target_family = "wasm"))]
+        let cleanup = cleanup.and(self.backend.disable_raw_mode());
+        res.and(cleanup).and(execute!(self.lock, soft_reset(), flush()))
+    }
+    /// The main viuwa loop
+    fn run(&mut self) -> io::Result<()> {
+        // for scrolling with the mouse wheel, wasi only reads stdin a line at a time
+        #[cfg(not(target_family = "wasm"))]
+        _execute!(self.lock, enable_mouse());
+        self._draw()?;
         loop {
-            match self.poll() {
-                Pol::Cmd(Command::Quit) | Pol::None => break,
-                Pol::Cmd(cmd) => self.command(cmd),
-                Pol::Rsz => self.reload(),
+            match self.poll()? {
+                Pol::Cmd(Command::Quit) | Pol::None => return Ok(()),
+                // the grid the image was opened from opens the next or previous image once this viewer quits
+                Pol::Cmd(Command::Image(step)) if in_gallery() => {
+                    request_gallery_step(step);
+                    return Ok(());
+                }
+                Pol::Cmd(cmd) => self.command(cmd)?,
+                Pol::Rsz => self.reload()?,
+                Pol::Buf => self.refresh()?,
+                Pol::Frame => self.next_frame()?,
             }
         }
-        execute!(self.lock, enable_line_wrap(), cursor_show(), exit_alt_screen(), disable_raw_mode(), soft_reset(), flush())
-            .expect("Failed to cleanup Viuwa loop");
     }
+    /// The top left corner and the columns and rows of the area the image is drawn in, within the padding
+    #[inline]
+    fn area(&self) -> ((u16, u16), (u16, u16)) { self.conf.padding.area(self.sz) }
     /// Write the buffer to the terminal, and move the cursor to the bottom left
-    fn _draw(&mut self) {
-        fn write_ansi<P: Pixel, C: Converter>(viuwa: &mut Viuwa<P>, mut ansi: AnsiImage<P, C>, (offx, offy): (u16, u16))
-        where
-            <P as viuwa_image::Pixel>::Scalar: CompatScalar,
-            <P as viuwa_image::Pixel>::Repr: CompatPixelRepr,
-        {
-            for (y, row) in ansi.rows_upper(viuwa.attrs, None).enumerate() {
-                _execute!(viuwa.lock, cursor_to(offx, offy + y as u16));
-                match row {
-                    AnsiRow::Full(row) => {
-                        for p in row {
-                            _execute!(viuwa.lock, write_all(p.as_bytes()));
-                        }
-                    }
-                    AnsiRow::Half(row) => {
-                        for p in row {
-                            _execute!(viuwa.lock, write_all(p.as_bytes()));
-                        }
-                    }
-                }
-                _execute!(viuwa.lock, attr_reset());
+    fn _draw(&mut self) -> io::Result<()> {
+        trace!("Viuwa::draw");
+        _execute!(self.lock, clear());
+        self.overlaid = 0..0;
+        // the frames cached were drawn at another size or with other settings
+        if let Some(anim) = &mut self.anim {
+            anim.invalidate();
+            anim.restart();
+        }
+        self.draw_image(0..self.sz.1)?;
+        self.timings.frame();
+        self.draw_stats()
+    }
+    /// Write the rows of the buffer within `rows` of the terminal, and the stats over them if shown
+    fn draw_rows(&mut self, rows: Range<u16>) -> io::Result<()> {
+        self.draw_image(rows)?;
+        self.draw_stats()
+    }
+    /// Draw the frame timings over the top left of the image if they're shown
+    fn draw_stats(&mut self) -> io::Result<()> {
+        if !self.show_stats {
+            return Ok(());
+        }
+        let mut summary = self.timings.summary();
+        if let Some(anim) = &self.anim {
+            summary += &format!(" {} dropped", anim.dropped);
+        }
+        _execute!(self.lock, attr_reset());
+        self.draw_text((0, 0), &summary)?;
+        #[cfg(target_family = "wasm")]
+        _execute!(self.lock, cursor_to(0, self.sz.1 - 1));
+        execute!(self.lock, flush())
+    }
+    /// Write the rows of the buffer within `rows` of the terminal, and move the cursor to the bottom left
+    fn draw_image(&mut self, rows: Range<u16>) -> io::Result<()> {
+        let (origin, area) = self.area();
+        self.draw_letterbox(rows.clone())?;
+        if let Some(compare) = self.compare.as_mut().filter(|compare| compare.layout == Layout::Flip) {
+            // the rest of the pre-rendered image is the same as what's already drawn
+            compare.prerender(origin, area, self.attrs, &self.conf)?;
+            self.lock.write_all(compare.shown())?;
+        } else if let Some(compare) = &self.compare {
+            let pane = Comparison::<P>::pane(compare.layout, area);
+            for (i, buf) in compare.drawn().into_iter().enumerate() {
+                let (x, y) = compare.pane_origin(area, i as u16);
+                let origin = (origin.0 + x, origin.1 + y);
+                draw_aligned(&mut self.lock, buf, origin, pane, self.attrs, &self.conf, rows.clone(), &mut self.row_bufs)?;
             }
+        } else {
+            draw_aligned(&mut self.lock, &self.buf, origin, area, self.attrs, &self.conf, rows.clone(), &mut self.row_bufs)?;
         }
-        _execute!(self.lock, clear());
-        let offx = (self.sz.0.saturating_sub(self.buf.width() as u16)) / 2;
-        let offy = (self.sz.1.saturating_sub(div_ceil2(self.buf.height()) as u16)) / 2;
-        let ansi = DynamicAnsiImage::new(unsafe { &*((&self.buf) as *const Image<P>) }.view(), self.conf.color);
-        match ansi {
-            DynamicAnsiImage::Color(a) => write_ansi(self, a, (offx, offy)),
-            DynamicAnsiImage::Gray(a) => write_ansi(self, a, (offx, offy)),
-            DynamicAnsiImage::AnsiColor(a) => write_ansi(self, a, (offx, offy)),
-            DynamicAnsiImage::AnsiGray(a) => write_ansi(self, a, (offx, offy)),
-        }
-        #[cfg(target_os = "wasi")]
+        self.draw_status(rows)?;
+        #[cfg(target_family = "wasm")]
+        _execute!(self.lock, cursor_to(0, self.sz.1 - 1));
+        Stage::Write.time(|| execute!(self.lock, flush()))
+    }
+    /// Fill the rows of the terminal within `rows` with the letterbox, for the image to be drawn over,
+    /// unless it's left blank in the terminal's background
+    fn draw_letterbox(&mut self, rows: Range<u16>) -> io::Result<()> {
+        let background = self.conf.letterbox_background.map(UiColor::bg);
+        let line = match self.conf.letterbox.glyph() {
+            Some(c) => {
+                let color = self.conf.letterbox_color.unwrap_or(Letterbox::COLOR).fg();
+                format!("{}{color}{}", background.unwrap_or_default(), c.to_string().repeat(self.sz.0 as usize))
+            }
+            None => match background {
+                Some(background) => format!("{background}{:w$}", "", w = self.sz.0 as usize),
+                None => return Ok(()),
+            },
+        };
+        for row in rows.start..rows.end.min(self.sz.1) {
+            _execute!(self.lock, cursor_to(0, row), write_all(line.as_bytes()));
+        }
+        execute!(self.lock, attr_reset())
+    }
+    /// Write the minimap and page indicator at the bottom right, if the bottom row is within `rows`
+    fn draw_status(&mut self, rows: Range<u16>) -> io::Result<()> {
+        let minimap = self.viewport.minimap().filter(|_| self.compare.is_none());
+        let indicator = self.pages.as_ref().filter(|pages| pages.count > 1).map(Pages::indicator);
+        let status = [minimap, indicator].into_iter().flatten().collect::<Vec<_>>().join(" ");
+        if !status.is_empty() && rows.contains(&(self.sz.1 - 1)) {
+            _execute!(
+                self.lock,
+                cursor_to(self.sz.0.saturating_sub(status.chars().count() as u16), self.sz.1 - 1),
+                attr_reset(),
+                write_all(self.conf.theme().status.as_bytes()),
+                write_all(status.as_bytes()),
+                attr_reset()
+            );
+        }
+        Ok(())
+    }
+    /// Draw the next frame of the animation due over the last
+    fn next_frame(&mut self) -> io::Result<()> {
+        let Some(anim) = &mut self.anim else { return Ok(()) };
+        anim.advance();
+        self.draw_frame()
+    }
+    /// Draw the displayed frame of the animation over the last, converted to ANSI the first time it's drawn
+    /// and written from the cache on the loops after
+    fn draw_frame(&mut self) -> io::Result<()> {
+        let Some(anim) = &mut self.anim else { return Ok(()) };
+        let ansi = match anim.take_cached() {
+            Some(ansi) => ansi,
+            None => {
+                let frame = match anim.decode_current() {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        // the displayed frame stays up
+                        self.anim = None;
+                        let msg = format!("{}error: {e:#}", self.conf.theme().error);
+                        return self.message(&msg);
+                    }
+                };
+                // frames are decoded again each loop, so they're not kept unprocessed
+                let frame = Image::from(frame);
+                let fit = display_dimensions(self.area().1, &self.conf, frame.dimensions());
+                let frame = self.conf.ops.apply(frame, fit, self.conf.resize_filter().into());
+                // resized at full quality here, as it's cached for the loops after, rather than by the viewer's
+                // resizer, which keeps the image the animation started from
+                let (origin, area) = self.area();
+                let ((x, y, w, h), dims) = self.viewport.crop(frame.dimensions(), area, &self.conf);
+                let view = frame.view().subview(x, y, w, h).expect("the crop is within the frame");
+                let mut buf = resize_view(&view, dims, self.conf.resize_filter());
+                sharpen(&mut buf, (w, h), self.conf.sharpen);
+                // drawn again for the rows under overlays, while paused on the frame
+                self.buf = Arc::new(buf);
+                let mut ansi = Vec::new();
+                draw_aligned(&mut ansi, &self.buf, origin, area, self.attrs, &self.conf, 0..self.sz.1, &mut self.row_bufs)?;
+                ansi
+            }
+        };
+        // every frame covers the same cells, so it's drawn over the last without clearing
+        let start = trace_start();
+        self.lock.write_all(&ansi)?;
+        self.draw_status(0..self.sz.1)?;
+        #[cfg(target_family = "wasm")]
         _execute!(self.lock, cursor_to(0, self.sz.1 - 1));
         _execute!(self.lock, flush());
+        let took = start.elapsed();
+        Stage::Write.record(start, took);
+        if let Some(anim) = &mut self.anim {
+            anim.wrote(took);
+            anim.cache(ansi);
+        }
+        self.timings.frame();
+        self.draw_stats()
+    }
+    /// Save the image (or the images compared) as the terminal shows it to a PNG, without overlays or the status line,
+    /// each cell as the [`glyph::CELL`] pixels it covers when drawn with glyphs and as two stacked pixels otherwise
+    pub fn screenshot(&self, path: &Path) -> Result<()> {
+        trace!("Viuwa::screenshot");
+        let (origin, area) = self.area();
+        let mut ansi = Vec::new();
+        let row_bufs = &mut RowBuffers::default();
+        match &self.compare {
+            Some(compare) if compare.layout == Layout::Flip => ansi.extend_from_slice(compare.shown()),
+            Some(compare) => {
+                let pane = Comparison::<P>::pane(compare.layout, area);
+                for (i, buf) in compare.drawn().into_iter().enumerate() {
+                    let (x, y) = compare.pane_origin(area, i as u16);
+                    let origin = (origin.0 + x, origin.1 + y);
+                    draw_aligned(&mut ansi, buf, origin, pane, self.attrs, &self.conf, 0..self.sz.1, row_bufs)?;
+                }
+            }
+            None => draw_aligned(&mut ansi, &self.buf, origin, area, self.attrs, &self.conf, 0..self.sz.1, row_bufs)?,
+        }
+        let cell = if self.conf.renderer == Renderer::Glyph { glyph::CELL } else { (1, 2) };
+        let image = ansi_to_image(&String::from_utf8_lossy(&ansi), self.sz, cell);
+        image
+            .save_with_format(path, ImageFormat::Png)
+            .with_context(|| format!("Failed to save screenshot to {}", path.display()))
+    }
+    /// Mark the rows of the region of `size` at `origin` as covered by an overlay, clipped to the terminal
+    fn cover(&mut self, (x, y): (u16, u16), (w, h): (u16, u16)) -> ((u16, u16), (u16, u16)) {
+        let size = (w.min(self.sz.0.saturating_sub(x)), h.min(self.sz.1.saturating_sub(y)));
+        if size.0 > 0 && size.1 > 0 {
+            let rows = y..y + size.1;
+            self.overlaid = if self.overlaid.is_empty() {
+                rows
+            } else {
+                self.overlaid.start.min(rows.start)..self.overlaid.end.max(rows.end)
+            };
+        }
+        ((x, y), size)
+    }
+    /// Draw `text` over the image from `origin`, cut off at the edge of the terminal,
+    /// until the overlays are cleared or the image is drawn again
+    pub fn draw_text(&mut self, origin: (u16, u16), text: &str) -> io::Result<()> {
+        let (origin, (w, h)) = self.cover(origin, (text.chars().count() as u16, 1));
+        if h > 0 {
+            self.lock.draw_text(origin, w, text)?;
+        }
+        Ok(())
     }
-    /// clear screen, print help, and quit 'q'
-    fn help(&mut self) {
-        _execute!(self.lock, clear(), cursor_home());
-        self.write_centerx(0, "Viuwa help:");
-        self.write_centerxy_align_all([
-            "quit                      exit the current screen",
-            "help                      show this help screen",
-            "refresh                   redraw the image",
-            "reload                    reload the image buffer and refresh",
-            "cycle <config>            cycle through a cyclable config",
-            "set <config> <value>      set a config value",
-            "bind <keybind> <command>  bind a keybind to a command",
-            "unbind <keybind>          unbind a keybind",
-        ]);
-        #[cfg(target_os = "wasi")]
+    /// Draw a box outline of `size` over the image at `origin`, clearing the inside,
+    /// until the overlays are cleared or the image is drawn again
+    pub fn draw_box(&mut self, origin: (u16, u16), size: (u16, u16)) -> io::Result<()> {
+        let (origin, size) = self.cover(origin, size);
+        self.lock.draw_box(origin, size)
+    }
+    /// Fill a region of `size` over the image at `origin` with the current background color,
+    /// until the overlays are cleared or the image is drawn again
+    pub fn fill_region(&mut self, origin: (u16, u16), size: (u16, u16)) -> io::Result<()> {
+        let (origin, size) = self.cover(origin, size);
+        self.lock.fill_region(origin, size)
+    }
+    /// Redraw only the rows of the image covered by overlays since it was last drawn
+    pub fn clear_overlays(&mut self) -> io::Result<()> {
+        let rows = std::mem::replace(&mut self.overlaid, 0..0);
+        if rows.is_empty() {
+            return Ok(());
+        }
+        _execute!(self.lock, attr_reset());
+        for row in rows.clone() {
+            _execute!(self.lock, cursor_to(0, row), clear_line());
+        }
+        self.draw_rows(rows)
+    }
+    /// Show the help over the image until it's closed with 'q' or the help command
+    fn help(&mut self) -> io::Result<()> {
+        let lines = COMMANDS.map(|(usage, description)| format!("{usage:<26}{description}"));
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16 + 4;
+        let size = (width.min(self.sz.0), (lines.len() as u16 + 2).min(self.sz.1));
+        let origin = ((self.sz.0 - size.0) / 2, (self.sz.1 - size.1) / 2);
+        let theme = self.conf.theme();
+        _execute!(self.lock, attr_reset(), write_all(theme.help.as_bytes()));
+        self.draw_box(origin, size)?;
+        _execute!(self.lock, write_all(theme.title.as_bytes()));
+        self.draw_text((origin.0 + 2, origin.1), " Viuwa help ")?;
+        _execute!(self.lock, attr_reset(), write_all(theme.help.as_bytes()));
+        for (i, line) in lines.iter().take(size.1.saturating_sub(2) as usize).enumerate() {
+            self.draw_text((origin.0 + 2, origin.1 + 1 + i as u16), line)?;
+        }
+        _execute!(self.lock, attr_reset());
+        #[cfg(target_family = "wasm")]
         _execute!(self.lock, cursor_to(0, self.sz.1 - 1));
         _execute!(self.lock, flush());
+        // the animation would draw over the help, and is left as paused as it was
+        let paused = self.anim.as_mut().map(|anim| std::mem::replace(&mut anim.paused, true));
+        // what's under the help is stale once the terminal is resized or the buffer finishes resizing
+        let mut stale = false;
         loop {
-            match self.poll() {
+            match self.poll()? {
                 Pol::Cmd(Command::Help | Command::Quit) => break,
-                Pol::Cmd(cmd) => self.command(cmd),
-                _ => (),
+                Pol::Cmd(cmd) => self.command(cmd)?,
+                Pol::Rsz | Pol::Buf => stale = true,
+                Pol::Frame | Pol::None => (),
             }
         }
-        self.reload();
-    }
-    /// print a string centered on the x axis
-    fn write_centerx<S: AsRef<str>>(&mut self, y: u16, s: S) {
-        _execute!(self.lock, cursor_to((self.sz.0 - s.as_ref().len() as u16) / 2, y), write_all(s.as_ref().as_bytes()));
+        if let (Some(anim), Some(paused)) = (&mut self.anim, paused) {
+            anim.paused = paused;
+            anim.restart();
+        }
+        if stale {
+            self.reload()
+        } else {
+            self.clear_overlays()
+        }
     }
-    /// print strings centered and aligned on the x axis and y axis
-    fn write_centerxy_align_all<
-        S: AsRef<str>,
-        I: Iterator<Item = S> + ExactSizeIterator + Clone,
-        C: IntoIterator<Item = S, IntoIter = I>,
-    >(
-        &mut self,
-        s: C,
-    ) {
-        let s = s.into_iter();
-        let len = s.len();
-        if let Some(max) = s.clone().map(|x| x.as_ref().len()).max() {
-            let ox = (self.sz.0 - max as u16) / 2;
-            let oy = (self.sz.1 - len as u16) / 2;
-            for (i, line) in s.enumerate() {
-                _execute!(self.lock, cursor_to(ox, oy + i as u16), write_all(line.as_ref().as_bytes()));
+    /// Choose an image of the recent list, other than this one, from a menu over the image,
+    /// moved through with the arrow keys (or `j`/`k`) and opened with enter or the number beside it
+    fn recent_menu(&mut self) -> io::Result<Option<PathBuf>> {
+        let current = self.conf.image.canonicalize().ok();
+        let mut recent = read_recent().into_iter().filter(|path| Some(path) != current.as_ref()).collect::<Vec<_>>();
+        if recent.is_empty() {
+            let msg = if self.conf.recent { "no recent images" } else { "the recent list is off, see --no-recent" };
+            return self.message(msg).map(|_| None);
+        }
+        let lines =
+            recent.iter().enumerate().map(|(i, path)| format!("{:>2} {}", i + 1, path.display())).collect::<Vec<_>>();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as u16 + 4;
+        let size = (width.clamp(12, self.sz.0), (lines.len() as u16 + 2).min(self.sz.1));
+        let origin = ((self.sz.0 - size.0) / 2, (self.sz.1 - size.1) / 2);
+        let rows = size.1.saturating_sub(2).max(1) as usize;
+        let mut selected = 0usize;
+        let theme = self.conf.theme();
+        loop {
+            // the rows of the list around the selection
+            let first = selected.saturating_sub(rows - 1);
+            _execute!(self.lock, attr_reset(), write_all(theme.help.as_bytes()));
+            self.draw_box(origin, size)?;
+            _execute!(self.lock, write_all(theme.title.as_bytes()));
+            self.draw_text((origin.0 + 2, origin.1), " Recent ")?;
+            _execute!(self.lock, attr_reset(), write_all(theme.help.as_bytes()));
+            for (i, line) in lines.iter().enumerate().skip(first).take(rows) {
+                // long paths keep their filename, cut from the start
+                let max = size.0.saturating_sub(4) as usize;
+                let count = line.chars().count();
+                let line = if count > max {
+                    format!("…{}", line.chars().skip(count - max + 1).collect::<String>())
+                } else {
+                    line.clone()
+                };
+                if i == selected {
+                    _execute!(self.lock, write_all(theme.selection.as_bytes()));
+                }
+                self.draw_text((origin.0 + 2, origin.1 + 1 + (i - first) as u16), &line)?;
+                _execute!(self.lock, attr_reset(), write_all(theme.help.as_bytes()));
+            }
+            _execute!(self.lock, attr_reset());
+            #[cfg(not(target_family = "wasm"))]
+            {
+                _execute!(self.lock, flush());
+                let chosen = match self.backend.read()? {
+                    Event::Key(KeyEvent { code, kind: KeyEventKind::Press, .. }) => match code {
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            selected = selected.saturating_sub(1);
+                            continue;
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            selected = (selected + 1).min(recent.len() - 1);
+                            continue;
+                        }
+                        KeyCode::Enter | KeyCode::Char('o') => Some(selected),
+                        KeyCode::Char(c @ '1'..='9') => Some(c as usize - '1' as usize).filter(|&i| i < recent.len()),
+                        KeyCode::Esc | KeyCode::Char('q') => None,
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                self.clear_overlays()?;
+                return Ok(chosen.map(|i| recent.swap_remove(i)));
             }
+            // without raw mode, the number of the image is read from a line
+            #[cfg(target_family = "wasm")]
+            {
+                use std::io::BufRead;
+                _execute!(self.lock, cursor_to(0, self.sz.1 - 1), flush());
+                let mut buf = String::new();
+                std::io::stdin().lock().read_line(&mut buf)?;
+                self.clear_overlays()?;
+                let chosen = buf.trim().parse::<usize>().ok().and_then(|n| n.checked_sub(1)).filter(|&i| i < recent.len());
+                return Ok(chosen.map(|i| recent.swap_remove(i)));
+            }
+        }
+    }
+    /// Open another image in a viewer of its own, coming back to this one when it's closed
+    fn open(&mut self, path: PathBuf) -> io::Result<()> {
+        let conf = Config { image: path, page: 0, ..self.conf.clone() };
+        // the other image wasn't opened from the grid, so it can't step through it
+        let gallery = set_gallery(false);
+        let res = decode(&conf).and_then(|orig| windowed(orig, conf));
+        set_gallery(gallery);
+        // the other viewer left the alternate screen and raw mode as it was closed
+        set_terminal_active(true);
+        #[cfg(not(target_family = "wasm"))]
+        self.backend.enable_raw_mode()?;
+        _execute!(self.lock, enter_alt_screen(), cursor_hide(), disable_line_wrap());
+        #[cfg(not(target_family = "wasm"))]
+        _execute!(self.lock, enable_mouse());
+        #[cfg(not(target_family = "wasm"))]
+        {
+            self.sz = terminal_size(&mut self.lock, &self.conf);
+        }
+        self.reload()?;
+        if let Err(e) = res {
+            let msg = format!("{}error: {e:#}", self.conf.theme().error);
+            self.message(&msg)?;
+        }
+        Ok(())
+    }
+    /// Apply a reloaded config, redrawing with its settings
+    fn reconfigure(&mut self, conf: Config) -> io::Result<()> {
+        conf.log.set();
+        let retone = conf.exposure != self.conf.exposure || conf.tone_map != self.conf.tone_map;
+        self.attrs = conf.color_attributes();
+        self.config_modified = if conf.watch_config { config_modified(&conf) } else { None };
+        self.conf = conf;
+        if retone && self.tone_mapper.is_some() {
+            self.retone()
+        } else {
+            self.reload()
+        }
+    }
+    /// Whether the terminal was resized, measured from `COLUMNS` and `LINES` if they're set.
+    ///
+    /// Otherwise the terminal is asked for the position of the cursor moved to its bottom right corner,
+    /// and the reply is handled when it's read from stdin, a line at a time.
+    #[cfg(target_family = "wasm")]
+    fn resized(&mut self) -> io::Result<bool> {
+        if let Ok(sz) = self.lock.size_quiet() {
+            let resized = sz != self.sz;
+            self.sz = sz;
+            return Ok(resized);
         }
+        if !self.size_queried {
+            execute!(
+                self.lock,
+                cursor_save(),
+                write_all(b"\x1b[4096;4096H"),
+                cursor_report_position(),
+                cursor_restore(),
+                flush()
+            )?;
+            self.size_queried = true;
+        }
+        Ok(false)
+    }
+    /// Whether the watched config file was modified since it was last read
+    fn config_changed(&mut self) -> bool {
+        if self.config_modified.is_none() {
+            return false;
+        }
+        let modified = config_modified(&self.conf);
+        if modified.is_some() && modified != self.config_modified {
+            self.config_modified = modified;
+            return true;
+        }
+        false
+    }
+    /// Apply the configured operations to a new image, keeping the image before them if there are any
+    fn processed(&mut self, orig: Image<P>) -> Image<P> {
+        if self.conf.ops.is_empty() {
+            self.unprocessed = None;
+            return orig;
+        }
+        self.unprocessed = Some(orig.clone());
+        let fit = display_dimensions(self.area().1, &self.conf, orig.dimensions());
+        self.conf.ops.apply(orig, fit, self.conf.resize_filter().into())
+    }
+    /// Tone map the HDR original again with the current exposure and operator, if the image is HDR
+    fn retone(&mut self) -> io::Result<()> {
+        if let Some(tone_mapper) = &mut self.tone_mapper {
+            let orig = Image::from(tone_mapper(self.conf.tone_map, self.conf.exposure));
+            let orig = self.processed(orig);
+            self.resizer = Resizer::new(orig).with_budget(Budget::new(&self.conf));
+            self.reload()?;
+        }
+        Ok(())
     }
     /// Reprint ANSI sequences to the terminal
-    pub fn refresh(&mut self) {
+    pub fn refresh(&mut self) -> io::Result<()> {
         trace!("Viuwa::refresh");
         self._draw()
     }
     /// Refresh with a rebuilt buffer
-    pub fn reload(&mut self) {
+    pub fn reload(&mut self) -> io::Result<()> {
         trace!("Viuwa::reload");
-        #[cfg(target_os = "wasi")]
+        #[cfg(target_family = "wasm")]
         {
             if let Ok(sz) = self.lock.size_quiet() {
                 self.sz = sz;
             }
         }
-        let dims = dimensions(self.sz, &self.conf, self.orig.dimensions());
-        #[cfg(feature = "fir")]
-        {
-            self.buf = self.orig.fir_supersize(dims.0, dims.1, &self.conf.filter, 3);
+        self.rebuffer();
+        self._draw()
+    }
+    /// Rebuild the buffer for the current area, or the buffers of the compared images
+    fn rebuffer(&mut self) {
+        let (_, area) = self.area();
+        if let Some(compare) = &mut self.compare {
+            compare.reload(area, &self.conf, self.resizer.orig());
+            return;
         }
-        #[cfg(not(feature = "fir"))]
-        {
-            self.buf = self.orig.supersize(dims.0, dims.1, &self.conf.filter, 3.);
+        let orig = self.resizer.orig();
+        let ((x, y, w, h), dims) = self.viewport.crop(orig.dimensions(), area, &self.conf);
+        if (w, h) == orig.dimensions() {
+            self.buf = self.resizer.request(dims, self.conf.resize_filter(), self.conf.sharpen);
+        } else {
+            // zoomed in past the resolution of the overview of a tiled image, the region is decoded again
+            let region = match &mut self.regions {
+                Some(regions) if self.conf.ops.is_empty() => regions.crop(orig.dimensions(), (x, y, w, h), dims),
+                _ => None,
+            };
+            let region = region.and_then(|region| {
+                region.map_err(|e| debug!("rebuffer", "drawing the overview, could not decode the region: {:#}", e)).ok()
+            });
+            self.buf = match region {
+                Some(region) if region.dimensions() == dims => region,
+                Some(region) => {
+                    let mut buf = resize(&region, dims, self.conf.resize_filter());
+                    sharpen(&mut buf, region.dimensions(), self.conf.sharpen);
+                    Arc::new(buf)
+                }
+                // only the visible window is resized, which is quick enough to skip the background resize
+                None => {
+                    let view = orig.view().subview(x, y, w, h).expect("the crop is within the image");
+                    let mut buf = resize_view(&view, dims, self.conf.resize_filter());
+                    sharpen(&mut buf, (w, h), self.conf.sharpen);
+                    Arc::new(buf)
+                }
+            };
+            self.resizer.cancel();
         }
-        self._draw()
     }
     /// Execute a command
-    pub fn command(&mut self, cmd: Command) {
+    pub fn command(&mut self, cmd: Command) -> io::Result<()> {
         match cmd {
-            Command::Help => self.help(),
-            Command::Refresh => self.refresh(),
-            Command::Reload => self.reload(),
+            Command::Help => self.help()?,
+            Command::Refresh => self.refresh()?,
+            Command::Reload => self.reload()?,
+            Command::ReloadConfig => {
+                let msg = match self.conf.reloaded() {
+                    Ok(conf) => {
+                        self.reconfigure(conf)?;
+                        match &self.conf.config_file {
+                            Some(path) => format!("reloaded config from {}", path.display()),
+                            None => String::from("reloaded config"),
+                        }
+                    }
+                    // toml errors point at the line over several more, there's only room for the first
+                    Err(e) => {
+                        format!("{}error: {}", self.conf.theme().error, e.to_string().lines().next().unwrap_or_default())
+                    }
+                };
+                self.message(&msg)?;
+            }
             Command::Set(inner) => match inner {
-                Setting::Log(level) => self.conf.log = level,
-                Setting::Filter(filter) => self.conf.filter = filter,
+                Setting::Log(level) => {
+                    self.conf.log = level;
+                    level.set();
+                }
+                Setting::Filter(filter) => {
+                    self.conf.filter = filter;
+                    self.conf.filter_custom = None;
+                }
+                Setting::FilterCustom(preset) => self.conf.filter_custom = Some(preset),
+                Setting::Sharpen(Sharpness(amount)) => {
+                    if self.conf.sharpen != amount {
+                        self.conf.sharpen = amount;
+                        self.reload()?;
+                    }
+                }
                 Setting::ColorSpace(space) => {
                     if self.conf.color.space() != space {
                         self.conf.color = self.conf.color.cycle_space();
-                        self.refresh();
+                        self.refresh()?;
                     }
                 }
                 Setting::ColorDepth(depth) => {
                     if self.conf.color.depth() != depth {
                         self.conf.color = self.conf.color.cycle_depth();
-                        self.refresh();
+                        self.refresh()?;
                     }
                 }
                 Setting::Color(color) => {
                     if self.conf.color != color {
                         self.conf.color = color;
-                        self.refresh();
+                        self.refresh()?;
                     }
                 }
                 Setting::Width(width) => {
                     if self.conf.width != width {
                         self.conf.width = width;
-                        self.reload();
+                        self.reload()?;
                     }
                 }
                 Setting::Height(height) => {
                     if self.conf.height != height {
                         self.conf.height = height;
-                        self.reload();
+                        self.reload()?;
+                    }
+                }
+                Setting::Padding(padding) => {
+                    if self.conf.padding != padding {
+                        self.conf.padding = padding;
+                        self.reload()?;
                     }
                 }
-                Setting::LumaCorrect(correct) => {
+                Setting::Align(align) => {
+                    if self.conf.align != Some(align) {
+                        self.conf.align = Some(align);
+                        self.refresh()?;
+                    }
+                }
+                Setting::Exposure(Stops(ev)) => {
+                    if self.conf.exposure != ev {
+                        self.conf.exposure = ev;
+                        self.retone()?;
+                    }
+                }
+                Setting::ToneMap(op) => {
+                    if self.conf.tone_map != op {
+                        self.conf.tone_map = op;
+                        self.retone()?;
+                    }
+                }
+                Setting::Renderer(renderer) => {
+                    if self.conf.renderer != renderer {
+                        self.conf.renderer = renderer;
+                        self.reload()?;
+                    }
+                }
+                Setting::Symbols(symbols) => {
+                    if self.conf.symbols != symbols {
+                        self.conf.symbols = symbols;
+                        self.refresh()?;
+                    }
+                }
+                Setting::Colormap(colormap) => {
+                    if self.conf.colormap != colormap {
+                        self.conf.colormap = colormap;
+                        self.attrs.colormap = colormap;
+                        self.refresh()?;
+                    }
+                }
+                Setting::LumaCorrect(Correction(correct)) => {
                     if self.conf.luma_correct != correct {
                         self.conf.luma_correct = correct;
-                        self.refresh();
+                        self.attrs = self.conf.color_attributes();
+                        self.refresh()?;
+                    }
+                }
+                Setting::LumaCurve(curve) => {
+                    if self.conf.luma_curve != curve {
+                        self.conf.luma_curve = curve;
+                        self.attrs = self.conf.color_attributes();
+                        self.refresh()?;
+                    }
+                }
+                Setting::GrayRamp(ramp) => {
+                    if self.conf.gray_ramp != ramp {
+                        self.conf.gray_ramp = ramp;
+                        self.attrs = self.conf.color_attributes();
+                        self.refresh()?;
                     }
                 }
             },
@@ -358,75 +1303,298 @@ where
             }
             Command::Cycle(Cyclic::Filter) => {
                 self.conf.filter = self.conf.filter.cycle();
-                self.reload()
+                self.conf.filter_custom = None;
+                self.reload()?
             }
             Command::Cycle(Cyclic::Color) => {
                 self.conf.color = self.conf.color.cycle();
-                self.refresh()
+                self.refresh()?
             }
             Command::Cycle(Cyclic::ColorDepth) => {
                 self.conf.color = self.conf.color.cycle_depth();
-                self.refresh()
+                self.refresh()?
             }
             Command::Cycle(Cyclic::ColorSpace) => {
                 self.conf.color = self.conf.color.cycle_space();
-                self.refresh()
+                self.refresh()?
             }
-            _ => (),
-        };
-    }
-    /// Parse a command from the viuwa vim-like command prompt
-    pub fn command_prompt(&mut self) -> Option<Command> {
-        #[cfg(not(target_os = "wasi"))]
-        {
-            let buf = String::from(":");
-            _execute!(
-                self.lock,
-                cursor_to(0, self.sz.1 - 1),
-                clear_line(),
-                cursor_show(),
-                write_all(buf.as_bytes()),
-                flush()
-            );
-            let mut cur = unsafe { AsciiPrompt::new_unchecked(buf, 1, 1) };
-            loop {
-                match crossterm::event::read().expect("failed to read event") {
-                    Event::Key(KeyEvent { code, kind: KeyEventKind::Press, modifiers, .. }) => match code {
-                        KeyCode::Char(c) => {
-                            cur.insert(self.term(), c);
-                            _execute!(self.lock, flush());
-                        }
-                        KeyCode::Backspace => {
-                            if modifiers.contains(KeyModifiers::CONTROL) {
-                                cur.delete_word(self.term());
-                            } else {
-                                cur.delete(self.term());
-                            }
-                            _execute!(self.lock, flush());
-                        }
-                        KeyCode::Left => {
-                            if modifiers.contains(KeyModifiers::CONTROL) {
-                                cur.left_word(self.term());
-                            } else {
-                                cur.left(self.term());
-                            }
-                            _execute!(self.lock, flush());
-                        }
-                        KeyCode::Right => {
-                            if modifiers.contains(KeyModifiers::CONTROL) {
-                                cur.right_word(self.term());
+            Command::Cycle(Cyclic::FitMode) => {
+                let mode = FitMode::of(self.conf.width, self.conf.height).map_or(FitMode::Fit, FitMode::cycle);
+                (self.conf.width, self.conf.height) = mode.dimensions();
+                self.reload()?
+            }
+            Command::Swap => {
+                if let Some(compare) = &mut self.compare {
+                    compare.swapped = !compare.swapped;
+                    if compare.layout == Layout::Flip {
+                        // both images are the same size, so the shown one is overwritten without clearing
+                        self.lock.write_all(compare.shown())?;
+                        #[cfg(target_family = "wasm")]
+                        _execute!(self.lock, cursor_to(0, self.sz.1 - 1));
+                        execute!(self.lock, flush())?
+                    } else {
+                        self.refresh()?
+                    }
+                }
+            }
+            Command::Zoom(zoom) => {
+                if let Some(compare) = &mut self.compare {
+                    compare.zoom(zoom);
+                    self.reload()?
+                } else {
+                    let viewport = self.viewport;
+                    self.viewport.zoom(zoom);
+                    if self.viewport != viewport {
+                        self.reload()?
+                    }
+                }
+            }
+            Command::Pan(pan) => {
+                if let Some(compare) = &mut self.compare {
+                    compare.pan(pan);
+                    self.reload()?
+                } else {
+                    let center = self.viewport.center;
+                    self.viewport.pan(pan);
+                    if self.viewport.center != center {
+                        self.reload()?
+                    }
+                }
+            }
+            // compared images are scrolled together by panning
+            Command::Scroll(pan) if self.compare.is_none() => {
+                let center = self.viewport.center;
+                self.viewport.scroll(pan);
+                if self.viewport.center != center {
+                    self.reload()?
+                }
+            }
+            // compared images have their own zoom
+            Command::Fit(fit) if self.compare.is_none() => {
+                self.viewport.fit(fit);
+                self.reload()?
+            }
+            // the frames of an animation are its pages
+            Command::Page(page) if self.anim.is_some() => {
+                return self.command(match page {
+                    Page::Next => Command::Step(Step::Next),
+                    Page::Prev => Command::Step(Step::Prev),
+                    Page::To(n) => Command::Seek(Seek::Frame(n)),
+                })
+            }
+            Command::Page(page) => {
+                let Some(pages) = &mut self.pages else { return Ok(()) };
+                let msg = match pages.target(page).and_then(|i| i.map(|i| pages.render(i)).transpose()) {
+                    // the indicator is redrawn with the page
+                    Ok(Some(orig)) => {
+                        let orig = self.processed(Image::from(orig));
+                        self.resizer = Resizer::new(orig).with_budget(Budget::new(&self.conf));
+                        // a new page starts from its top left
+                        self.viewport.home();
+                        return self.reload();
+                    }
+                    Ok(None) => return Ok(()),
+                    Err(e) => format!("{}error: {e:#}", self.conf.theme().error),
+                };
+                self.message(&msg)?;
+            }
+            Command::WriteConfig(path) => {
+                let path = path.map(PathBuf::from).or_else(config_path).unwrap_or_else(|| PathBuf::from("config.toml"));
+                let msg = match self.conf.write_file(&path) {
+                    Ok(()) => format!("wrote config to {}", path.display()),
+                    Err(e) => format!("{}error: {e}", self.conf.theme().error),
+                };
+                self.message(&msg)?;
+            }
+            Command::Save(path) => {
+                let msg = match save(self.resizer.orig(), Path::new(&path)) {
+                    Ok(()) => format!("saved image to {path}"),
+                    Err(e) => format!("{}error: {e:#}", self.conf.theme().error),
+                };
+                self.message(&msg)?;
+            }
+            Command::Speed(Speed(speed)) => {
+                if let Some(anim) = &mut self.anim {
+                    anim.speed = speed;
+                }
+            }
+            Command::Seek(seek) => {
+                let Some(anim) = &mut self.anim else { return Ok(()) };
+                let index = match seek {
+                    Seek::Frame(n) if n <= anim.count() => n - 1,
+                    Seek::Frame(n) => {
+                        let msg = format!(
+                            "{}error: frame {n} is out of range, there are {} frames",
+                            self.conf.theme().error,
+                            anim.count()
+                        );
+                        return self.message(&msg);
+                    }
+                    Seek::Time(time) => anim.frame_at(time),
+                };
+                anim.seek(index);
+                self.draw_frame()?
+            }
+            Command::Step(step) => {
+                let Some(anim) = &mut self.anim else { return Ok(()) };
+                anim.paused = true;
+                let index = match step {
+                    Step::Next => anim.current + 1,
+                    Step::Prev => anim.current + anim.count() - 1,
+                };
+                anim.seek(index);
+                self.draw_frame()?
+            }
+            Command::Pause => {
+                if let Some(anim) = &mut self.anim {
+                    anim.paused = !anim.paused;
+                    anim.restart();
+                }
+            }
+            Command::Ops(None) => {
+                // edit the current operations on the prompt
+                #[cfg(not(target_family = "wasm"))]
+                if let Some(cmd) = self.command_prompt_with(&format!("ops {}", self.conf.ops))? {
+                    self.command(cmd)?
+                }
+                // the prompt reads a line at a time, so the operations are shown to be typed again
+                #[cfg(target_family = "wasm")]
+                {
+                    let msg = format!("ops {}", self.conf.ops);
+                    self.message(&msg)?;
+                }
+            }
+            Command::Ops(Some(ops)) => {
+                if self.compare.is_some() {
+                    let msg =
+                        format!("{}error: the operations of compared images can't be changed", self.conf.theme().error);
+                    return self.message(&msg);
+                }
+                // the expression was normalized from valid operations
+                self.conf.ops = ops.parse().unwrap_or_default();
+                if self.anim.is_some() {
+                    // the current frame is decoded again with the new operations
+                    _execute!(self.lock, clear());
+                    if let Some(anim) = &mut self.anim {
+                        anim.invalidate();
+                    }
+                    return self.draw_frame();
+                }
+                let orig = self.unprocessed.take().unwrap_or_else(|| self.resizer.orig().clone());
+                let orig = self.processed(orig);
+                self.resizer = Resizer::new(orig).with_budget(Budget::new(&self.conf));
+                self.viewport.home();
+                self.reload()?
+            }
+            Command::Recent => {
+                if let Some(path) = self.recent_menu()? {
+                    self.open(path)?
+                }
+            }
+            Command::Image(_) => {
+                self.message("not opened from a grid, browse a directory with --grid")?;
+            }
+            Command::Preset(Some(name)) if self.conf.presets.contains_key(&name) => {
+                let mut conf = self.conf.clone();
+                self.conf.presets[&name].apply(&mut conf);
+                // redrawn once with every setting, rather than once for each
+                self.reconfigure(conf)?
+            }
+            Command::Preset(name) => {
+                let presets = self.conf.presets.keys().map(String::as_str).collect::<Vec<_>>().join(", ");
+                let msg = match name {
+                    Some(name) if presets.is_empty() => {
+                        format!(
+                            "{}error: no preset {name:?}, add a [preset.{name}] table to the config file",
+                            self.conf.theme().error
+                        )
+                    }
+                    Some(name) => format!("{}error: no preset {name:?}, the presets are {presets}", self.conf.theme().error),
+                    None if presets.is_empty() => String::from("no presets, add [preset.<name>] tables to the config file"),
+                    None => format!("presets: {presets}"),
+                };
+                self.message(&msg)?;
+            }
+            Command::Stats => {
+                self.show_stats = !self.show_stats;
+                if self.show_stats {
+                    self.draw_stats()?
+                } else {
+                    self.clear_overlays()?
+                }
+            }
+            Command::Screenshot(path) => {
+                let msg = match self.screenshot(Path::new(&path)) {
+                    Ok(()) => format!("saved screenshot to {path}"),
+                    Err(e) => format!("{}error: {e:#}", self.conf.theme().error),
+                };
+                self.message(&msg)?;
+            }
+            _ => (),
+        };
+        Ok(())
+    }
+    /// Parse a command from the viuwa vim-like command prompt
+    #[inline]
+    pub fn command_prompt(&mut self) -> io::Result<Option<Command>> { self.command_prompt_with("") }
+    /// Parse a command from the command prompt, starting with `text` to be edited (ignored on wasm, which reads a line at a time)
+    pub fn command_prompt_with(&mut self, text: &str) -> io::Result<Option<Command>> {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let buf = format!(":{text}");
+            self.cover((0, self.sz.1 - 1), (self.sz.0, 1));
+            _execute!(
+                self.lock,
+                cursor_to(0, self.sz.1 - 1),
+                clear_line(),
+                cursor_show(),
+                write_all(buf.as_bytes()),
+                flush()
+            );
+            let mut cur = match AsciiPrompt::new(buf, text.len() as u16 + 1, 1) {
+                Some(cur) => cur,
+                None => unsafe { AsciiPrompt::new_unchecked(String::from(":"), 1, 1) },
+            };
+            loop {
+                match self.backend.read()? {
+                    Event::Key(KeyEvent { code, kind: KeyEventKind::Press, modifiers, .. }) => match code {
+                        KeyCode::Char(c) => {
+                            cur.insert(self.term(), c)?;
+                            _execute!(self.lock, flush());
+                        }
+                        KeyCode::Backspace => {
+                            if modifiers.contains(KeyModifiers::CONTROL) {
+                                cur.delete_word(self.term())?;
                             } else {
-                                cur.right(self.term());
+                                cur.delete(self.term())?;
+                            }
+                            _execute!(self.lock, flush());
+                        }
+                        KeyCode::Left => {
+                            if modifiers.contains(KeyModifiers::CONTROL) {
+                                cur.left_word(self.term())?;
+                            } else {
+                                cur.left(self.term())?;
+                            }
+                            _execute!(self.lock, flush());
+                        }
+                        KeyCode::Right => {
+                            if modifiers.contains(KeyModifiers::CONTROL) {
+                                cur.right_word(self.term())?;
+                            } else {
+                                cur.right(self.term())?;
                             }
                             _execute!(self.lock, flush());
                         }
                         KeyCode::Enter => {
-                            _execute!(self.lock, clear_line(), cursor_hide(), flush());
+                            _execute!(self.lock, cursor_hide());
+                            self.clear_overlays()?;
                             break;
                         }
                         KeyCode::Esc | KeyCode::Null => {
-                            _execute!(self.lock, clear_line(), cursor_hide(), flush());
-                            return None;
+                            _execute!(self.lock, cursor_hide());
+                            self.clear_overlays()?;
+                            return Ok(None);
                         }
                         _ => (),
                     },
@@ -434,87 +1602,192 @@ where
                 }
             }
             return match Command::from_str(&cur.buf()[1..]) {
-                Ok(cmd) => Some(cmd),
+                Ok(cmd) => Ok(Some(cmd)),
                 Err(e) => {
-                    _execute!(self.lock, cursor_to_col(0), write_all(b"error: "), write_all(e.as_bytes()), flush());
-                    None
+                    self.write_command_error(&e)?;
+                    Ok(None)
                 }
             };
         }
-        #[cfg(target_os = "wasi")]
+        #[cfg(target_family = "wasm")]
         {
+            let _ = text;
             _execute!(self.lock, clear_line(), cursor_show(), write_all(b":"), flush());
             use std::io::BufRead;
-
-            use rustix::{fd::BorrowedFd, io::*};
-            let stdin_raw = unsafe { BorrowedFd::borrow_raw(0) };
             let mut stdin = std::io::stdin().lock();
             let mut buf = String::new();
-            while let Ok(0) = ioctl_fionread(stdin_raw) {
-                std::thread::sleep(std::time::Duration::from_millis(50));
-            }
-            if stdin.read_line(&mut buf).expect("failed to read stdin") == 0 {
-                return None;
+            if stdin.read_line(&mut buf)? == 0 {
+                return Ok(None);
             }
             let cmd = buf.trim_end_matches(['\r', '\n']);
             _execute!(self.lock, clear_line(), cursor_hide(), flush());
             return match Command::from_str(cmd) {
-                Ok(cmd) => Some(cmd),
+                Ok(cmd) => Ok(Some(cmd)),
                 Err(e) => {
-                    _execute!(self.lock, cursor_to_col(0), write_all(b"error: "), write_all(e.as_bytes()), flush());
-                    None
+                    self.write_command_error(&e)?;
+                    Ok(None)
                 }
             };
         }
     }
+    /// Print a message on the prompt line, in the color of messages if it doesn't set its own
+    fn message(&mut self, msg: &str) -> io::Result<()> {
+        let info = self.conf.theme().info;
+        execute!(
+            self.lock,
+            cursor_to(0, self.sz.1 - 1),
+            clear_line(),
+            write_all(info.as_bytes()),
+            write_all(msg.as_bytes()),
+            attr_reset(),
+            flush()
+        )
+    }
+    /// Print a command error on the prompt line, unknown commands in red and invalid arguments in yellow
+    fn write_command_error(&mut self, e: &CommandError) -> io::Result<()> {
+        let theme = self.conf.theme();
+        let color = if e.is_unknown() { theme.error.as_bytes() } else { theme.warning.as_bytes() };
+        self.cover((0, self.sz.1 - 1), (self.sz.0, 1));
+        execute!(
+            self.lock,
+            cursor_to(0, self.sz.1 - 1),
+            clear_line(),
+            write_all(color),
+            write_all(b"error: "),
+            write_all(e.to_string().as_bytes()),
+            attr_reset(),
+            flush()
+        )
+    }
+    /// How long until the next frame of the animation is due, if one is playing
+    #[inline]
+    fn frame_left(&self) -> Option<std::time::Duration> {
+        let anim = self.anim.as_ref().filter(|anim| !anim.paused)?;
+        Some(anim.due().saturating_duration_since(std::time::Instant::now()))
+    }
     /// Poll for the next consumable event, handling miscellaneous tasks and tertiary events
-    pub fn poll(&mut self) -> Pol {
-        #[cfg(not(target_os = "wasi"))]
+    pub fn poll(&mut self) -> io::Result<Pol> {
+        #[cfg(not(target_family = "wasm"))]
         {
+            use std::time::{Duration, Instant};
+            let debounce = Duration::from_millis(self.conf.resize_debounce_ms);
+            // the last resize event and when it was received, waiting for the size to be stable
+            let mut resized: Option<(Instant, (u16, u16))> = None;
             loop {
-                match crossterm::event::read().expect("failed to read event") {
+                let mut timeout = None;
+                if let Some((at, (w, h))) = resized {
+                    let elapsed = at.elapsed();
+                    if elapsed >= debounce {
+                        if w.saturating_sub(self.sz.0) > 1 || h.saturating_sub(self.sz.1) > 1 {
+                            self.sz = (w, h);
+                        }
+                        return Ok(Pol::Rsz);
+                    }
+                    timeout = Some(debounce - elapsed);
+                } else if self.resizer.pending() {
+                    // a stale buffer is dropped by the reload following a resize, so don't bother drawing it
+                    if let Some(buf) = self.resizer.try_recv() {
+                        self.buf = buf;
+                        return Ok(Pol::Buf);
+                    }
+                    timeout = Some(BUF_POLL_INTERVAL);
+                } else if self.config_modified.is_some() {
+                    if self.config_changed() {
+                        return Ok(Pol::Cmd(Command::ReloadConfig));
+                    }
+                    timeout = Some(CONFIG_POLL_INTERVAL);
+                }
+                // resizes are waited out before drawing another frame
+                let frame_left = self.frame_left().filter(|_| resized.is_none());
+                if let Some(left) = frame_left {
+                    timeout = Some(timeout.map_or(left, |timeout: Duration| timeout.min(left)));
+                }
+                if let Some(timeout) = timeout {
+                    // input is read before every frame, so an animation that's behind doesn't starve it
+                    if !self.backend.poll(timeout)? {
+                        if frame_left.is_some() && self.frame_left().is_some_and(|left| left.is_zero()) {
+                            return Ok(Pol::Frame);
+                        }
+                        continue;
+                    }
+                }
+                match self.backend.read()? {
                     Event::Key(e) if e.kind == KeyEventKind::Press => {
                         if e.code == KeyCode::Char(':') {
-                            if let Some(cmd) = self.command_prompt() {
-                                return Pol::Cmd(cmd);
+                            if let Some(cmd) = self.command_prompt()? {
+                                return Ok(Pol::Cmd(cmd));
                             }
                         } else if let Some(cmd) = self.conf.keybinds.get(&KeyBind(e)) {
-                            return Pol::Cmd(cmd.clone().into());
+                            return Ok(Pol::Cmd(cmd.clone().into()));
                         }
                     }
-                    Event::Resize(w, h) => {
-                        if w.saturating_sub(self.sz.0) > 1 || h.saturating_sub(self.sz.1) > 1 {
-                            self.sz = (w, h);
+                    // the wheel scrolls smoothly, and sideways with shift held
+                    Event::Mouse(MouseEvent { kind, modifiers, .. }) => {
+                        let shift = modifiers.contains(KeyModifiers::SHIFT);
+                        match (kind, shift) {
+                            (MouseEventKind::ScrollUp, false) => return Ok(Pol::Cmd(Command::Scroll(Pan::Up))),
+                            (MouseEventKind::ScrollDown, false) => return Ok(Pol::Cmd(Command::Scroll(Pan::Down))),
+                            (MouseEventKind::ScrollUp, true) => return Ok(Pol::Cmd(Command::Scroll(Pan::Left))),
+                            (MouseEventKind::ScrollDown, true) => return Ok(Pol::Cmd(Command::Scroll(Pan::Right))),
+                            _ => (),
                         }
-                        return Pol::Rsz;
                     }
+                    // coalesce the flood of resize events sent while dragging the window
+                    Event::Resize(w, h) => resized = Some((Instant::now(), (w, h))),
                     _ => (),
                 }
             }
         }
-        #[cfg(target_os = "wasi")]
+        #[cfg(target_family = "wasm")]
         {
             use std::io::BufRead;
-
-            use rustix::{fd::BorrowedFd, io::*};
-            let stdin_raw = unsafe { BorrowedFd::borrow_raw(0) };
             let mut stdin = std::io::stdin().lock();
             let mut buf = String::new();
             loop {
-                while let Ok(0) = ioctl_fionread(stdin_raw) {
-                    std::thread::sleep(std::time::Duration::from_millis(50));
+                let mut timeout = match self.config_modified {
+                    Some(_) => CONFIG_POLL_INTERVAL.min(RESIZE_POLL_INTERVAL),
+                    None => RESIZE_POLL_INTERVAL,
+                };
+                if let Some(left) = self.frame_left() {
+                    timeout = timeout.min(left);
                 }
-                buf.clear();
-                if stdin.read_line(&mut buf).expect("failed to read stdin") == 0 {
+                if !poll_stdin(Some(timeout))? {
+                    if self.frame_left().is_some_and(|left| left.is_zero()) {
+                        return Ok(Pol::Frame);
+                    }
+                    if self.config_changed() {
+                        return Ok(Pol::Cmd(Command::ReloadConfig));
+                    }
+                    if self.resized()? {
+                        return Ok(Pol::Rsz);
+                    }
                     continue;
                 }
-                let key = buf.trim_end_matches(['\r', '\n']);
+                buf.clear();
+                // stdin was closed, and would be ready to read forever
+                if stdin.read_line(&mut buf)? == 0 {
+                    return Ok(Pol::None);
+                }
+                let mut key = buf.trim_end_matches(['\r', '\n']).to_string();
+                // the terminal's reply to the size query arrives with the next line of input
+                if let Some((sz, rest)) = split_cursor_report(&key) {
+                    self.size_queried = false;
+                    if sz != self.sz {
+                        self.sz = sz;
+                        return Ok(Pol::Rsz);
+                    }
+                    if rest.is_empty() {
+                        continue;
+                    }
+                    key = rest;
+                }
+                let key = key.as_str();
                 if key == ":" {
-                    if let Some(cmd) = self.command_prompt() {
-                        return Pol::Cmd(cmd);
+                    if let Some(cmd) = self.command_prompt()? {
+                        return Ok(Pol::Cmd(cmd));
                     }
                 } else if let Some(cmd) = self.conf.keybinds.get(key) {
-                    return Pol::Cmd(cmd.clone().into());
+                    return Ok(Pol::Cmd(cmd.clone().into()));
                 }
             }
         }
@@ -524,102 +1797,25 @@ where
 /// Display an image in the terminal inlined
 pub fn inlined(orig: DynamicImage, conf: Config) -> Result<()> {
     trace!("inlined");
-    let dims = orig.dimensions();
-    let dims = (dims.0 as usize, dims.1 as usize);
-    let term_sz = terminal_size(&mut stdout(), &conf)?;
-    let dims = dimensions(term_sz, &conf, dims);
-    fn write_ansi<P: Pixel, C: Converter>(
-        lock: &mut BufWriter<StdoutLock>,
-        mut ansi: AnsiImage<P, C>,
-        config: &Config,
-    ) -> io::Result<()>
-    where
-        <P as viuwa_image::Pixel>::Scalar: CompatScalar,
-        <P as viuwa_image::Pixel>::Repr: CompatPixelRepr,
-    {
-        let sz = ansi.dimensions();
-        for (i, row) in ansi.rows_upper(ColorAttributes::new(config.luma_correct as u32), None).enumerate() {
-            match row {
-                AnsiRow::Full(row) => {
-                    for p in row {
-                        lock.write_all(p.as_bytes())?;
-                    }
-                }
-                AnsiRow::Half(row) => {
-                    for p in row {
-                        lock.write_all(p.as_bytes())?;
-                    }
-                }
-            }
-            lock.attr_reset()?;
-            if i != sz.1 - 1 {
-                lock.write_all(b"\n")?;
-            }
-        }
-        Ok(())
-    }
+    let orig = tone_mapped(orig, &conf);
+    let orig = processed(orig, &conf, terminal_size(&mut stdout(), &conf));
     let mut lock = BufWriter::new(stdout().lock());
-    if orig.color().has_color() {
-        let orig = orig.into_rgb8();
-        let orig = {
-            #[cfg(feature = "fir")]
-            {
-                ImageView::from(&orig).fir_supersize(dims.0, dims.1, &conf.filter, 3)
-            }
-            #[cfg(not(feature = "fir"))]
-            {
-                ImageView::from(&orig).supersize(dims.0, dims.1, &conf.filter, 3.)
-            }
-        };
-        let ansi = DynamicAnsiImage::new(ImageView::from(&orig), conf.color);
-        match ansi {
-            DynamicAnsiImage::Color(a) => write_ansi(&mut lock, a, &conf)?,
-            DynamicAnsiImage::Gray(a) => write_ansi(&mut lock, a, &conf)?,
-            DynamicAnsiImage::AnsiColor(a) => write_ansi(&mut lock, a, &conf)?,
-            DynamicAnsiImage::AnsiGray(a) => write_ansi(&mut lock, a, &conf)?,
-        }
-    } else {
-        let orig = orig.into_luma8();
-        let orig = {
-            #[cfg(feature = "fir")]
-            {
-                ImageView::from(&orig).fir_supersize(dims.0, dims.1, &conf.filter, 3)
+    match conf.protocol.resolve() {
+        Protocol::Ansi | Protocol::Auto => {
+            let opts = RenderOptions::from_config(&conf, terminal_size(&mut stdout(), &conf));
+            match (orig.color().has_color(), is_16bit(&orig)) {
+                (true, false) => render_image(&Image::from(orig.into_rgb8()), &opts, &mut lock)?,
+                (false, false) => render_image(&Image::from(orig.into_luma8()), &opts, &mut lock)?,
+                (true, true) => render_image(&Image::from(orig.into_rgb16()), &opts, &mut lock)?,
+                (false, true) => render_image(&Image::from(orig.into_luma16()), &opts, &mut lock)?,
             }
-            #[cfg(not(feature = "fir"))]
-            {
-                ImageView::from(&orig).supersize(dims.0, dims.1, &conf.filter, 3.)
-            }
-        };
-        let ansi = DynamicAnsiImage::new(ImageView::from(&orig), conf.color);
-        match ansi {
-            DynamicAnsiImage::Color(a) => write_ansi(&mut lock, a, &conf)?,
-            DynamicAnsiImage::Gray(a) => write_ansi(&mut lock, a, &conf)?,
-            DynamicAnsiImage::AnsiColor(a) => write_ansi(&mut lock, a, &conf)?,
-            DynamicAnsiImage::AnsiGray(a) => write_ansi(&mut lock, a, &conf)?,
         }
+        #[cfg(any(feature = "kitty", feature = "iterm", feature = "sixel", feature = "terminology", feature = "regis"))]
+        protocol => protocol_inlined(&orig, &conf, protocol, &mut lock)?,
     }
-    if conf.clear {
+    if conf.clear && conf.interactive() {
         _execute!(lock, flush());
-        // wait for keypress or any input
-        #[cfg(not(target_os = "wasi"))]
-        {
-            loop {
-                match crossterm::event::read().expect("failed to read event") {
-                    Event::Key(e) if e.kind == KeyEventKind::Press => {
-                        break;
-                    }
-                    _ => (),
-                }
-            }
-        }
-        #[cfg(target_os = "wasi")]
-        {
-            use rustix::{fd::BorrowedFd, io::*};
-            let stdin_raw = unsafe { BorrowedFd::borrow_raw(0) };
-            while let Ok(0) = ioctl_fionread(stdin_raw) {
-                std::thread::sleep(std::time::Duration::from_millis(50));
-            }
-        }
+        wait_for_input(&conf)?;
         // then clear the screen
         _execute!(lock, clear(), cursor_home(), flush());
     } else {
@@ -628,45 +1824,723 @@ pub fn inlined(orig: DynamicImage, conf: Config) -> Result<()> {
     Ok(())
 }
 
+/// Wait for a keypress or any input, read with the backend of `conf`
+pub fn wait_for_input(conf: &Config) -> io::Result<()> {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let mut backend = conf.backend.backend();
+        loop {
+            match backend.read()? {
+                Event::Key(e) if e.kind == KeyEventKind::Press => {
+                    break;
+                }
+                _ => (),
+            }
+        }
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        let _ = conf;
+        poll_stdin(None)?;
+    }
+    Ok(())
+}
+
+/// The columns and rows of a terminal from its reply to a cursor position report of its bottom right corner
+/// (`ESC [ row ; column R`) within a line of input, and the rest of the line
+/// ```
+/// assert_eq!(viuwa::split_cursor_report("\x1b[24;80R"), Some(((80, 24), String::new())));
+/// assert_eq!(viuwa::split_cursor_report("j\x1b[50;132R"), Some(((132, 50), String::from("j"))));
+/// assert_eq!(viuwa::split_cursor_report("j"), None);
+/// ```
+pub fn split_cursor_report(line: &str) -> Option<((u16, u16), String)> {
+    let start = line.find("\x1b[")?;
+    let len = line[start + 2..].find('R')?;
+    let (rows, columns) = line[start + 2..start + 2 + len].split_once(';')?;
+    let sz = (columns.parse().ok()?, rows.parse().ok()?);
+    Some((sz, [&line[..start], &line[start + 3 + len..]].concat()))
+}
+
+/// Wait until stdin has input to read, or `timeout` passes, returning whether it has input.
+///
+/// Subscribes to stdin (and a clock for the timeout) with `poll_oneoff`, so waiting doesn't sleep and poll.
+#[cfg(target_os = "wasi")]
+pub fn poll_stdin(timeout: Option<std::time::Duration>) -> io::Result<bool> {
+    use wasi::{
+        Subscription, SubscriptionClock, SubscriptionFdReadwrite, SubscriptionU, SubscriptionUU, CLOCKID_MONOTONIC,
+        EVENTTYPE_CLOCK, EVENTTYPE_FD_READ,
+    };
+    const STDIN: u64 = 0;
+    const TIMEOUT: u64 = 1;
+    let mut subscriptions = vec![Subscription {
+        userdata: STDIN,
+        u: SubscriptionU {
+            tag: EVENTTYPE_FD_READ.raw(),
+            u: SubscriptionUU { fd_read: SubscriptionFdReadwrite { file_descriptor: 0 } },
+        },
+    }];
+    if let Some(timeout) = timeout {
+        subscriptions.push(Subscription {
+            userdata: TIMEOUT,
+            u: SubscriptionU {
+                tag: EVENTTYPE_CLOCK.raw(),
+                u: SubscriptionUU {
+                    clock: SubscriptionClock {
+                        id: CLOCKID_MONOTONIC,
+                        timeout: timeout.as_nanos().min(u64::MAX as u128) as u64,
+                        precision: 0,
+                        flags: 0,
+                    },
+                },
+            },
+        });
+    }
+    // SAFETY: every event is plain data, and only the `n` written by poll_oneoff are read
+    let mut events: [wasi::Event; 2] = unsafe { std::mem::zeroed() };
+    let n = unsafe { wasi::poll_oneoff(subscriptions.as_ptr(), events.as_mut_ptr(), subscriptions.len()) }
+        .map_err(|e| io::Error::from_raw_os_error(e.raw() as i32))?;
+    // an error reading stdin is ready too, for the read to report it
+    Ok(events[..n].iter().any(|e| e.userdata == STDIN))
+}
+/// [`poll_stdin`] on wasm without wasi, where there's nothing to wait with, so stdin is always ready and reading it blocks
+#[cfg(all(target_family = "wasm", not(target_os = "wasi")))]
+pub fn poll_stdin(_timeout: Option<std::time::Duration>) -> io::Result<bool> { Ok(true) }
+
 /// Create a new viuwa instance and spawn it
 pub fn windowed<'a>(orig: DynamicImage, config: Config) -> Result<()> {
     trace!("windowed");
-    if orig.color().has_color() {
-        Viuwa::new(orig.into_rgb8(), config)?.spawn();
-    } else {
-        Viuwa::new(orig.into_luma8(), config)?.spawn();
+    if config.recent && !is_stream(&config.image) {
+        if let Err(e) = remember_recent(&config.image) {
+            debug!("windowed", "could not remember {} as recent: {}", config.image.display(), e);
+        }
+    }
+    #[cfg(feature = "pdf")]
+    if !is_stream(&config.image) && is_pdf(&read_magic(&config.image)?) {
+        return pdf::windowed(orig, config);
+    }
+    if is_hdr(&orig) {
+        return hdr::windowed(orig, config);
+    }
+    if let Some(format) = multi_frame_format(&config.image) {
+        let count = frame_count(&config.image, format)?;
+        if count > 1 && format == ImageFormat::Gif {
+            return animated(orig, config, count);
+        }
+        if count > 1 {
+            let path = config.image.clone();
+            return paged(orig, config, frame_label(format), count, move |i| decode_frame(&path, format, i));
+        }
+    }
+    let tiled = tiled_source(&config, &orig);
+    match (orig.color().has_color(), is_16bit(&orig)) {
+        (true, false) => viewed(orig.into_rgb8(), config, tiled.map(|t| Regions::new(t, DynamicImage::into_rgb8))),
+        (false, false) => viewed(orig.into_luma8(), config, tiled.map(|t| Regions::new(t, DynamicImage::into_luma8))),
+        (true, true) => viewed(orig.into_rgb16(), config, tiled.map(|t| Regions::new(t, DynamicImage::into_rgb16))),
+        (false, true) => viewed(orig.into_luma16(), config, tiled.map(|t| Regions::new(t, DynamicImage::into_luma16))),
+    }
+}
+
+/// Create a new viuwa instance displaying `orig`, decoding the regions zoomed into with `regions` if it's an overview,
+/// and spawn it
+fn viewed<P: Pixel>(orig: ImageBuffer<P, Vec<P::Scalar>>, config: Config, regions: Option<Regions<P>>) -> Result<()>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let builder = Viuwa::builder().image(orig).config(config);
+    match regions {
+        Some(regions) => builder.regions(regions).build()?.spawn()?,
+        None => builder.build()?.spawn()?,
     }
     Ok(())
 }
 
-/// Get the terminal size or use the default size if it is set
+/// The image `orig` is a tiled overview of, to decode the regions zoomed into from, if it was decoded tiled
+fn tiled_source(conf: &Config, orig: &DynamicImage) -> Option<TiledImage> {
+    // the regions are decoded from the file, before any operations
+    if is_stream(&conf.image) || !conf.ops.is_empty() {
+        return None;
+    }
+    let format = image::io::Reader::open(&conf.image).ok()?.with_guessed_format().ok()?.format()?;
+    let tiled = match TiledImage::open(&conf.image, format) {
+        Ok(tiled) => tiled?,
+        Err(e) => {
+            debug!("windowed", "can't decode the regions of {}: {:#}", conf.image.display(), e);
+            return None;
+        }
+    };
+    let (w, h) = tiled.dimensions();
+    (w > orig.width() || h > orig.height()).then_some(tiled)
+}
+
+/// When the config file was last modified, if there is one
 #[inline]
-pub fn terminal_size(term: &mut impl Terminal, conf: &Config) -> Result<(u16, u16)> {
-    term.size_quiet().or_else(|_| {
-        if conf.default_columns.is_some() || conf.default_rows.is_some() {
-            Ok((conf.default_columns.unwrap_or(1), conf.default_rows.unwrap_or(1)))
-        } else {
-            Err(anyhow!("Could not get terminal size"))
+fn config_modified(conf: &Config) -> Option<std::time::SystemTime> {
+    conf.config_file.as_ref().and_then(|path| std::fs::metadata(path).and_then(|meta| meta.modified()).ok())
+}
+
+/// Whether a decoded image has 16 bits per channel, kept through resizing and only quantized to 8 bits for display
+/// ```
+/// assert!(viuwa::is_16bit(&image::DynamicImage::new_rgb16(1, 1)));
+/// assert!(!viuwa::is_16bit(&image::DynamicImage::new_rgb8(1, 1)));
+/// ```
+#[inline]
+pub fn is_16bit(image: &DynamicImage) -> bool {
+    matches!(
+        image,
+        DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_)
+    )
+}
+
+/// Create a new viuwa instance comparing two images and spawn it
+pub fn compared(orig: DynamicImage, other: DynamicImage, config: Config) -> Result<()> {
+    trace!("compared");
+    let (orig, other) = (tone_mapped(orig, &config), tone_mapped(other, &config));
+    // widening the other image to 16 bits is lossless
+    match (orig.color().has_color() || other.color().has_color(), is_16bit(&orig) || is_16bit(&other)) {
+        (true, false) => {
+            Viuwa::builder().image(orig.into_rgb8()).compare(other.into_rgb8()).config(config).build()?.spawn()?
         }
+        (false, false) => {
+            Viuwa::builder().image(orig.into_luma8()).compare(other.into_luma8()).config(config).build()?.spawn()?
+        }
+        (true, true) => {
+            Viuwa::builder().image(orig.into_rgb16()).compare(other.into_rgb16()).config(config).build()?.spawn()?
+        }
+        (false, true) => {
+            Viuwa::builder().image(orig.into_luma16()).compare(other.into_luma16()).config(config).build()?.spawn()?
+        }
+    }
+    Ok(())
+}
+
+/// Write a buffer as ANSI aligned within the `area` of columns and rows starting at `origin`,
+/// in the color type, with the renderer, and with the alignment (centered by default) of `conf`,
+/// writing only the rows that fall within `rows` of the terminal, converted into `row_bufs`
+#[allow(clippy::too_many_arguments)]
+fn draw_aligned<P: Pixel>(
+    term: &mut impl Terminal,
+    buf: &Image<P>,
+    origin: (u16, u16),
+    area: (u16, u16),
+    attrs: ColorAttributes,
+    conf: &Config,
+    rows: Range<u16>,
+    row_bufs: &mut RowBuffers,
+) -> io::Result<()>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let ansi = DynamicAnsiImage::new(buf.view(), conf.color);
+    let (w, h) = conf.renderer.cells(buf.dimensions());
+    let (x, y) = conf.align.unwrap_or_default().offset(area, (w as u16, h as u16));
+    let (offx, offy) = (origin.0 + x, origin.1 + y);
+    // the character rows that fall within `rows`
+    let end = (rows.end.saturating_sub(offy) as usize).min(h);
+    let ys = (rows.start.saturating_sub(offy) as usize).min(end)..end;
+    let rows = Stage::Convert.time(|| row_bufs.convert(&ansi, ys.clone(), conf.renderer, conf.symbols, attrs));
+    let background = conf.image_background.map(UiColor::bg).unwrap_or_default();
+    Stage::Write.time(|| {
+        for (y, row) in ys.zip(rows) {
+            _execute!(
+                term,
+                cursor_to(offx, offy + y as u16),
+                write_all(background.as_bytes()),
+                write_all(row),
+                attr_reset()
+            );
+        }
+        Ok(())
     })
 }
 
-/// Get the dimensions of the image to be displayed in the terminal by taking into account the terminal size, the image size, and the configuration
+/// The buffers the character rows of a draw are converted into, kept between draws so that redrawing doesn't allocate
+/// once they've grown to the most rows and the longest row drawn
+#[derive(Debug, Default)]
+struct RowBuffers {
+    rows: Vec<Vec<u8>>,
+    /// The glyphs of the symbols last drawn with glyphs
+    glyphs: Option<(Symbols, Vec<Glyph>)>,
+}
+impl RowBuffers {
+    /// Convert the character rows `ys` of `ansi` drawn with `renderer`, each into its own buffer without an attribute reset,
+    /// in parallel with the `rayon` feature so that only writing them out in order is left sequential
+    fn convert<P: Pixel>(
+        &mut self,
+        ansi: &DynamicAnsiImage<P>,
+        ys: Range<usize>,
+        renderer: Renderer,
+        symbols: Symbols,
+        attrs: ColorAttributes,
+    ) -> &[Vec<u8>]
+    where
+        P::Scalar: CompatScalar,
+        P::Repr: CompatPixelRepr,
+    {
+        if renderer == Renderer::Glyph && self.glyphs.as_ref().is_none_or(|(cached, _)| *cached != symbols) {
+            self.glyphs = Some((symbols, symbols.glyphs()));
+        }
+        let glyphs = self.glyphs.as_ref().map_or(&[][..], |(_, glyphs)| glyphs);
+        if self.rows.len() < ys.len() {
+            self.rows.resize_with(ys.len(), Vec::new);
+        }
+        let rows = &mut self.rows[..ys.len()];
+        let capacity = ansi.row_capacity();
+        let convert = |(row, y): (&mut Vec<u8>, usize)| {
+            // within the capacity kept from earlier draws, resizing doesn't allocate
+            row.resize(capacity, 0);
+            let len = match renderer {
+                Renderer::Half => ansi.write_row_into(row, y, attrs, None),
+                Renderer::Glyph => ansi.write_glyph_row_into(row, y, attrs, glyphs),
+                Renderer::Foreground => ansi.write_fg_row_into(row, y, attrs, Renderer::BACKGROUND_LUMA),
+                Renderer::Full => ansi.write_full_row_into(row, y, attrs),
+            };
+            row.truncate(len.expect("a row fits in the row capacity"));
+        };
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            rows.par_iter_mut().zip(ys).for_each(convert);
+        }
+        #[cfg(not(feature = "rayon"))]
+        rows.iter_mut().zip(ys).for_each(convert);
+        rows
+    }
+}
+
+/// Get the terminal size if stdout is a terminal, falling back to `COLUMNS` and `LINES`, then the default size if it is set,
+/// then [`DEFAULT_TERMINAL_SIZE`], each axis on its own
+#[inline]
+pub fn terminal_size(term: &mut impl Terminal, conf: &Config) -> (u16, u16) {
+    // piped, the terminal queried would be the one viuwa was run from rather than where the output ends up
+    if let Some(sz) = ::is_terminal::IsTerminal::is_terminal(&stdout()).then(|| term.size_quiet().ok()).flatten() {
+        return sz;
+    }
+    let env = |var: &str| std::env::var(var).ok().and_then(|n| n.parse::<u16>().ok()).filter(|&n| n > 0);
+    (
+        env("COLUMNS").or(conf.default_columns).unwrap_or(DEFAULT_TERMINAL_SIZE.0),
+        env("LINES").or(conf.default_rows).unwrap_or(DEFAULT_TERMINAL_SIZE.1),
+    )
+}
+
+/// Get the dimensions of the image to be displayed in the terminal by taking into account the terminal size, the image size, and the configuration,
+/// in pixels of the configured [`Renderer`]
 #[inline]
 pub fn dimensions(term_sz: (u16, u16), conf: &Config, img_sz: (usize, usize)) -> (usize, usize) {
-    let fit = viuwa_image::fit_dimensions(img_sz, (term_sz.0 as usize, term_sz.1 as usize * 2));
+    let img_sz = aspect_corrected(img_sz, conf.cell_aspect.unwrap_or(DEFAULT_CELL_ASPECT));
+    conf.renderer.pixels(scale_dimensions(term_sz, (conf.width, conf.height), img_sz))
+}
+
+/// The dimensions an image is displayed at within `term_sz`, in its own pixels rather than stretched for the cell aspect,
+/// which `resize=fit` downscales it to
+#[inline]
+pub fn display_dimensions(term_sz: (u16, u16), conf: &Config, img_sz: (usize, usize)) -> (usize, usize) {
+    let (w, h) = dimensions(term_sz, conf, img_sz);
+    let cell_aspect = conf.cell_aspect.unwrap_or(DEFAULT_CELL_ASPECT);
+    (((w as f32 * cell_aspect / DEFAULT_CELL_ASPECT).round() as usize).max(1), h.max(1))
+}
+
+/// Apply the operations of `conf` to a decoded image, displayed within `term_sz`
+pub fn processed(orig: DynamicImage, conf: &Config, term_sz: (u16, u16)) -> DynamicImage {
+    // over every pixel a decoded image can have, including those with alpha that are never drawn directly
+    fn apply<P, S>(buf: ImageBuffer<P, Vec<S>>, conf: &Config, term_sz: (u16, u16)) -> ImageBuffer<P, Vec<S>>
+    where
+        P: viuwa_image::Pixel<Scalar = S> + image::Pixel<Subpixel = S>,
+        S: viuwa_image::Scalar,
+    {
+        let fit = display_dimensions(term_sz, conf, (buf.width() as usize, buf.height() as usize));
+        conf.ops.apply(Image::from(buf), fit, conf.resize_filter().into()).into()
+    }
+    if conf.ops.is_empty() {
+        return orig;
+    }
+    match orig {
+        DynamicImage::ImageLuma8(buf) => DynamicImage::ImageLuma8(apply(buf, conf, term_sz)),
+        DynamicImage::ImageLumaA8(buf) => DynamicImage::ImageLumaA8(apply(buf, conf, term_sz)),
+        DynamicImage::ImageRgb8(buf) => DynamicImage::ImageRgb8(apply(buf, conf, term_sz)),
+        DynamicImage::ImageRgba8(buf) => DynamicImage::ImageRgba8(apply(buf, conf, term_sz)),
+        DynamicImage::ImageLuma16(buf) => DynamicImage::ImageLuma16(apply(buf, conf, term_sz)),
+        DynamicImage::ImageLumaA16(buf) => DynamicImage::ImageLumaA16(apply(buf, conf, term_sz)),
+        DynamicImage::ImageRgb16(buf) => DynamicImage::ImageRgb16(apply(buf, conf, term_sz)),
+        DynamicImage::ImageRgba16(buf) => DynamicImage::ImageRgba16(apply(buf, conf, term_sz)),
+        other => DynamicImage::ImageRgba16(apply(other.into_rgba16(), conf, term_sz)),
+    }
+}
+
+/// How long to wait for the terminal to reply to a query
+pub const QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// The width of a character cell divided by its height, from the size of the terminal in pixels if it reports it,
+/// or answers a query for it
+pub fn detect_cell_aspect(term: &mut impl Terminal) -> Option<f32> {
+    let (w, h) = detect_cell_pixels(term)?;
+    let aspect = w / h;
+    debug!("detect_cell_aspect", "aspect {}", aspect);
+    Some(aspect).filter(|a| CELL_ASPECT_RANGE.contains(a))
+}
+
+/// The width and height of a character cell in the terminal's own pixels (e.g. twice as many on a HiDPI screen),
+/// from the size of the terminal in pixels if it reports it, or answers a query for it
+pub fn detect_cell_pixels(term: &mut impl Terminal) -> Option<(f32, f32)> {
+    let (cols, rows) = term.size_quiet().ok().filter(|&(c, r)| c > 0 && r > 0)?;
+    let (w, h) = term.size_pixels().or_else(|_| term.query_size_pixels(QUERY_TIMEOUT)).ok()?;
+    debug!("detect_cell_pixels", "{}x{} pixels in {}x{} cells", w, h, cols, rows);
+    Some((w as f32 / cols as f32, h as f32 / rows as f32))
+}
+
+/// Stretch the dimensions of an image horizontally, so it's drawn with its own aspect ratio in cells of `cell_aspect`
+/// rather than assuming cells twice as tall as they're wide
+/// ```
+/// use viuwa::aspect_corrected;
+/// assert_eq!(aspect_corrected((100, 100), 0.5), (100, 100));
+/// // wide cells need fewer columns
+/// assert_eq!(aspect_corrected((100, 100), 0.625), (80, 100));
+/// ```
+#[inline]
+pub fn aspect_corrected((w, h): (usize, usize), cell_aspect: f32) -> (usize, usize) {
+    if cell_aspect == DEFAULT_CELL_ASPECT {
+        return (w, h);
+    }
+    (((w as f64 * DEFAULT_CELL_ASPECT as f64 / cell_aspect as f64).round() as usize).max(1), h)
+}
+
+/// Get the dimensions of the image to be displayed in the terminal by taking into account the terminal size, the image size, and the width and height settings
+#[inline]
+pub fn scale_dimensions(
+    term_sz: (u16, u16),
+    (width, height): (Dimension, Dimension),
+    img_sz: (usize, usize),
+) -> (usize, usize) {
+    let stretch = (term_sz.0 as usize, term_sz.1 as usize * 2);
+    let fit = viuwa_image::fit_dimensions(img_sz, stretch);
     let fill = viuwa_image::fill_dimensions(img_sz, fit);
-    match (conf.width, conf.height) {
-        (Dimension::Fit, Dimension::Fit) => fit,
-        (Dimension::Fit, Dimension::Fill) => (fit.0, fill.1),
-        (Dimension::Fit, Dimension::Limit(h)) => (fit.0, h as usize),
-        (Dimension::Fill, Dimension::Fit) => (fill.0, fit.1),
-        (Dimension::Fill, Dimension::Fill) => fill,
-        (Dimension::Fill, Dimension::Limit(h)) => (fill.0, h as usize),
-        (Dimension::Limit(w), Dimension::Fit) => (w as usize, fit.1),
-        (Dimension::Limit(w), Dimension::Fill) => (w as usize, fill.1),
-        (Dimension::Limit(w), Dimension::Limit(h)) => (w as usize, h as usize),
+    // each dimension is independent of the other
+    let pick = |dim, fit, fill, stretch| match dim {
+        Dimension::Fit => fit,
+        Dimension::Fill => fill,
+        Dimension::Stretch => stretch,
+        Dimension::Limit(n) => n as usize,
+    };
+    (pick(width, fit.0, fill.0, stretch.0), pick(height, fit.1, fill.1, stretch.1))
+}
+
+/// Decode the configured image, decoding large images tiled or at a reduced scale when possible,
+/// and keeping within the memory budget if one is set
+///
+/// Only images displayed inline are decoded at a reduced scale for the terminal, as the windowed viewer zooms in and
+/// fits to the width of terminals that can be enlarged.
+pub fn decode(conf: &Config) -> Result<DynamicImage> {
+    decode_for(conf, |img_sz| conf.inline.then(|| dimensions(terminal_size(&mut stdout(), conf), conf, img_sz)))
+}
+
+/// [`decode`] an image that will be displayed at the dimensions `display` returns for the image's dimensions, if any,
+/// converted to sRGB from its ICC profile with the `icc` feature
+pub fn decode_for(conf: &Config, display: impl FnOnce((usize, usize)) -> Option<(usize, usize)>) -> Result<DynamicImage> {
+    let image = Stage::Decode.time(|| decode_unmanaged(conf, display))?;
+    #[cfg(feature = "icc")]
+    let image = {
+        LoadStep::ColorManaging.set();
+        Stage::Decode.time(|| icc::manage(image, conf))?
+    };
+    Ok(match conf.renderer {
+        // transparent regions are dark, so the foreground renderer leaves them to the terminal background
+        Renderer::Foreground => premultiplied(image),
+        // over the background when it's known, left to the renderer as before otherwise
+        _ if conf.background != Background::Auto => composited(image, conf.background.color()),
+        _ => image,
+    })
+}
+
+/// Multiply the color of an image with an alpha channel by its alpha, dropping the alpha channel,
+/// so transparent regions are black instead of whatever color they happen to store.
+///
+/// HDR images are left as they are for tone mapping.
+/// ```
+/// let rgba = image::RgbaImage::from_fn(2, 1, |x, _| image::Rgba([200, 100, 50, if x == 0 { 255 } else { 0 }]));
+/// let rgb = viuwa::premultiplied(image::DynamicImage::ImageRgba8(rgba)).into_rgb8();
+/// assert_eq!((rgb[(0, 0)].0, rgb[(1, 0)].0), ([200, 100, 50], [0; 3]));
+/// ```
+#[inline]
+pub fn premultiplied(image: DynamicImage) -> DynamicImage { composited(image, [0; 3]) }
+
+/// Composite an image with an alpha channel over `background`, dropping the alpha channel,
+/// with single-channel images over the luma of `background`.
+///
+/// HDR images are left as they are for tone mapping.
+/// ```
+/// let rgba = image::RgbaImage::from_fn(2, 1, |x, _| image::Rgba([200, 100, 50, if x == 0 { 255 } else { 0 }]));
+/// let rgb = viuwa::composited(image::DynamicImage::ImageRgba8(rgba), [250, 250, 240]).into_rgb8();
+/// assert_eq!((rgb[(0, 0)].0, rgb[(1, 0)].0), ([200, 100, 50], [250, 250, 240]));
+/// ```
+pub fn composited(image: DynamicImage, background: [u8; 3]) -> DynamicImage {
+    if !image.color().has_alpha() || is_hdr(&image) {
+        return image;
+    }
+    let over8 = |c: u8, b: u8, a: u8| ((c as u32 * a as u32 + b as u32 * (255 - a as u32) + 127) / 255) as u8;
+    let over16 = |c: u16, b: u16, a: u16| ((c as u32 * a as u32 + b as u32 * (65535 - a as u32) + 32767) / 65535) as u16;
+    let [r, g, b] = background;
+    let gray = viuwa_ansi::color::luma(background);
+    match (image.color().has_color(), is_16bit(&image)) {
+        (true, false) => {
+            let mut rgba = image.into_rgba8();
+            rgba.pixels_mut().for_each(|p| p.0 = [over8(p[0], r, p[3]), over8(p[1], g, p[3]), over8(p[2], b, p[3]), 255]);
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(rgba).into_rgb8())
+        }
+        (false, false) => {
+            let mut la = image.into_luma_alpha8();
+            la.pixels_mut().for_each(|p| p.0 = [over8(p[0], gray, p[1]), 255]);
+            DynamicImage::ImageLuma8(DynamicImage::ImageLumaA8(la).into_luma8())
+        }
+        (true, true) => {
+            let (r, g, b) = (r as u16 * 257, g as u16 * 257, b as u16 * 257);
+            let mut rgba = image.into_rgba16();
+            rgba.pixels_mut()
+                .for_each(|p| p.0 = [over16(p[0], r, p[3]), over16(p[1], g, p[3]), over16(p[2], b, p[3]), 65535]);
+            DynamicImage::ImageRgb16(DynamicImage::ImageRgba16(rgba).into_rgb16())
+        }
+        (false, true) => {
+            let mut la = image.into_luma_alpha16();
+            la.pixels_mut().for_each(|p| p.0 = [over16(p[0], gray as u16 * 257, p[1]), 65535]);
+            DynamicImage::ImageLuma16(DynamicImage::ImageLumaA16(la).into_luma16())
+        }
+    }
+}
+
+/// [`decode_for`] without color management
+fn decode_unmanaged(conf: &Config, display: impl FnOnce((usize, usize)) -> Option<(usize, usize)>) -> Result<DynamicImage> {
+    trace!("decode");
+    const ERR_CTX: &str = "Failed to load image, the file extension may be incorrect";
+    if is_stream(&conf.image) {
+        let decoder = RowDecoder::new(open_stream(&conf.image)?)
+            .context("Failed to load streamed image, only farbfeld and Netpbm images can be streamed")?;
+        let img_sz = (decoder.dimensions().0 as usize, decoder.dimensions().1 as usize);
+        let budget = Budget::new(conf).map(|b| b.for_decode(display(img_sz))).transpose()?;
+        return decoder.decode(budget.map_or(img_sz, |b| b.fit(img_sz))).context("Failed to load streamed image");
+    }
+    if is_raw(&conf.image) {
+        #[cfg(feature = "raw")]
+        return raw::decode(conf, display).context("Failed to load RAW photo");
+        #[cfg(not(feature = "raw"))]
+        return Err(anyhow!(
+            "RAW photos need viuwa built with the `raw` feature, or convert {} to a supported format (e.g. png) first",
+            conf.image.display()
+        ));
+    }
+    let magic = read_magic(&conf.image)?;
+    LoadStep::Decoding.set();
+    #[cfg(feature = "qoi")]
+    if qoi::is_qoi(&magic) {
+        return qoi::decode(Counted::new(File::open(&conf.image)?)).context("Failed to load QOI image");
+    }
+    if is_pdf(&magic) {
+        #[cfg(feature = "pdf")]
+        return pdf::decode(conf, display).context("Failed to render PDF page");
+        #[cfg(not(feature = "pdf"))]
+        return Err(anyhow!(
+            "PDF documents need viuwa built with the `pdf` feature (and the pdfium library), {} can't be displayed",
+            conf.image.display()
+        ));
+    }
+    if is_heif(&magic) {
+        #[cfg(feature = "heif")]
+        return heif::decode(conf, display).context("Failed to load HEIF image");
+        #[cfg(not(feature = "heif"))]
+        return Err(anyhow!(
+            "HEIF images need viuwa built with the `heif` feature (and libheif installed), or convert {} to a supported format (e.g. png) first",
+            conf.image.display()
+        ));
+    }
+    if is_avif(&magic) {
+        #[cfg(feature = "avif")]
+        return avif::decode(conf, display).context("Failed to load AVIF image");
+        #[cfg(not(feature = "avif"))]
+        return Err(anyhow!(
+            "AVIF images need viuwa built with the `avif` feature (`cargo install --features avif`), or convert {} to a supported format (e.g. png) first",
+            conf.image.display()
+        ));
+    }
+    if is_jxl(&magic) {
+        #[cfg(feature = "jxl")]
+        return jxl::decode(conf, display).context("Failed to load JPEG XL image");
+        #[cfg(not(feature = "jxl"))]
+        return Err(anyhow!(
+            "JPEG XL images need viuwa built with the `jxl` feature (`cargo install --features jxl`), or convert {} to a supported format (e.g. png) first",
+            conf.image.display()
+        ));
+    }
+    // the reader decoding the image counts the bytes it reads toward the progress of loading
+    let mut reader = image::io::Reader::new(BufReader::new(Counted::new(File::open(&conf.image)?))).with_guessed_format()?;
+    let (w, h) = image::io::Reader::open(&conf.image)?.with_guessed_format()?.into_dimensions().context(ERR_CTX)?;
+    let img_sz = (w as usize, h as usize);
+    let display = display(img_sz);
+    let budget = Budget::new(conf).map(|b| b.for_decode(display)).transpose()?;
+    // limit the decoded dimensions to fit the budget
+    let limit = |dims: (usize, usize)| match budget {
+        Some(b) => {
+            let fit = b.fit(img_sz);
+            (dims.0.min(fit.0), dims.1.min(fit.1))
+        }
+        None => dims,
+    };
+    if let Some(format) = reader.format().filter(|&f| conf.page > 0 && is_multi_frame(f)) {
+        return decode_frame(&conf.image, format, conf.page).context("Failed to load image");
     }
+    match reader.format() {
+        Some(ImageFormat::Jpeg) => {
+            let mut decoder = JpegDecoder::new(BufReader::new(Counted::new(File::open(&conf.image)?)))?;
+            let mut want = limit(display.map_or(img_sz, |(dw, dh)| (dw * SUPERSAMPLE, dh * SUPERSAMPLE)));
+            let mut scaled = img_sz;
+            if want != img_sz {
+                // the decoder picks the smallest DCT scale (1/8, 1/4, 1/2, or 1) that is at least as large as requested,
+                // which may still exceed the budget, so keep requesting smaller until it fits or it can't get smaller
+                loop {
+                    let (sw, sh) =
+                        decoder.scale(want.0.min(u16::MAX as usize) as u16, want.1.min(u16::MAX as usize) as u16)?;
+                    scaled = (sw as usize, sh as usize);
+                    if budget.is_none_or(|b| b.fits(scaled)) || want.0 <= 1 || want.1 <= 1 {
+                        break;
+                    }
+                    want = (want.0 / 2, want.1 / 2);
+                }
+                if scaled != img_sz {
+                    debug!("decode", "decoding {}x{} jpeg at {}x{}", w, h, scaled.0, scaled.1);
+                }
+            }
+            if let Some(b) = budget.filter(|b| !b.fits(scaled)) {
+                return Err(b.exceeded(scaled));
+            }
+            return DynamicImage::from_decoder(decoder).context("Failed to load image");
+        }
+        // decoded a few strips or tiles per thread, unless it doesn't fit the budget and has to be refused below
+        #[cfg(feature = "rayon")]
+        Some(ImageFormat::Tiff) if budget.is_none_or(|b| b.fits(img_sz)) && w as u64 * h as u64 <= TILED_THRESHOLD => {
+            if let Some(image) = chunked::decode(&conf.image, 0).context("Failed to load image")? {
+                return Ok(image);
+            }
+        }
+        Some(ImageFormat::Hdr) => {
+            if let Some(b) = budget.filter(|b| !b.fits(img_sz)) {
+                return Err(b.exceeded(img_sz));
+            }
+            return hdr::decode_radiance(&conf.image).context("Failed to load image");
+        }
+        Some(format) => {
+            if let Some(tiled) = TiledImage::open(&conf.image, format)? {
+                if tiled.is_large() || budget.is_some_and(|b| !b.fits(img_sz)) {
+                    info!("decoding {}x{} image tiled...", w, h);
+                    return tiled.overview(limit(OVERVIEW_SIZE)).context("Failed to load image tiled");
+                }
+            }
+        }
+        None => (),
+    }
+    if let Some(b) = budget {
+        if !b.fits(img_sz) {
+            return Err(b.exceeded(img_sz));
+        }
+        let mut limits = image::io::Limits::default();
+        limits.max_alloc = Some(b.0);
+        reader.limits(limits);
+    }
+    reader.decode().context(ERR_CTX)
+}
+
+/// The first 16 bytes of a file, or fewer if it is shorter, to identify its format
+pub fn read_magic(path: &Path) -> io::Result<Vec<u8>> {
+    let mut magic = Vec::with_capacity(16);
+    std::io::Read::read_to_end(&mut std::io::Read::take(File::open(path)?, 16), &mut magic)?;
+    Ok(magic)
+}
+
+/// Whether the first bytes of a file are a PDF header
+/// ```
+/// assert!(viuwa::is_pdf(b"%PDF-1.7\n"));
+/// ```
+#[inline]
+pub fn is_pdf(magic: &[u8]) -> bool { magic.starts_with(b"%PDF-") }
+
+/// The extensions of the camera RAW formats with embedded previews, all of them TIFF-based
+pub const RAW_EXTENSIONS: [&str; 10] = ["cr2", "nef", "nrw", "arw", "srf", "sr2", "dng", "orf", "rw2", "pef"];
+
+/// Whether a file is a camera RAW photo, by its extension since most RAW formats are also valid TIFFs
+/// ```
+/// assert!(viuwa::is_raw("IMG_0001.CR2".as_ref()));
+/// assert!(!viuwa::is_raw("scan.tiff".as_ref()));
+/// ```
+pub fn is_raw(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| RAW_EXTENSIONS.iter().any(|r| r.eq_ignore_ascii_case(e)))
+}
+
+/// Whether the first 16 bytes of a file are a HEIF container (e.g. HEIC photos), by its major brand
+/// ```
+/// assert!(viuwa::is_heif(b"\0\0\0\x18ftypheic\0\0\0\0"));
+/// assert!(!viuwa::is_heif(b"\0\0\0\x1cftypavif\0\0\0\0"));
+/// ```
+pub fn is_heif(magic: &[u8]) -> bool {
+    const BRANDS: [&[u8]; 8] = [b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx", b"mif1", b"msf1"];
+    matches!(magic, [_, _, _, _, b'f', b't', b'y', b'p', brand @ ..] if BRANDS.iter().any(|b| brand.starts_with(b)))
+}
+
+/// Whether the first 16 bytes of a file are an AVIF image (or image sequence), by its major brand
+/// ```
+/// assert!(viuwa::is_avif(b"\0\0\0\x1cftypavif\0\0\0\0"));
+/// assert!(!viuwa::is_avif(b"\0\0\0\x18ftypheic\0\0\0\0"));
+/// ```
+pub fn is_avif(magic: &[u8]) -> bool {
+    const BRANDS: [&[u8]; 2] = [b"avif", b"avis"];
+    matches!(magic, [_, _, _, _, b'f', b't', b'y', b'p', brand @ ..] if BRANDS.iter().any(|b| brand.starts_with(b)))
+}
+
+/// Whether the first 16 bytes of a file are a JPEG XL image, a bare codestream or in its container
+/// ```
+/// assert!(viuwa::is_jxl(b"\xff\x0a\xfa"));
+/// assert!(viuwa::is_jxl(b"\0\0\0\x0cJXL \r\n\x87\n"));
+/// assert!(!viuwa::is_jxl(b"\x89PNG\r\n\x1a\n"));
+/// ```
+pub fn is_jxl(magic: &[u8]) -> bool {
+    const JXL_CONTAINER: &[u8] = b"\0\0\0\x0cJXL \r\n\x87\n";
+    magic.starts_with(&[0xFF, 0x0A]) || magic.starts_with(JXL_CONTAINER)
+}
+
+/// Save an image to a file in the format of its extension
+pub fn save<P: Pixel>(image: &Image<P>, path: &Path) -> Result<()>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    trace!("save");
+    let (w, h) = (image.width() as u32, image.height() as u32);
+    let dynamic = if std::any::TypeId::of::<P::Scalar>() == std::any::TypeId::of::<u16>() {
+        // keep 16-bit images 16-bit, formats that can't hold them convert when saving
+        let data = image.data().iter().map(|s| viuwa_image::AsScalar::u16(*s)).collect();
+        match P::Repr::CHANNELS {
+            1 => ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageLuma16),
+            2 => ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageLumaA16),
+            3 => ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageRgb16),
+            4 => ImageBuffer::from_raw(w, h, data).map(DynamicImage::ImageRgba16),
+            _ => None,
+        }
+    } else {
+        let data = image.data().iter().map(|s| viuwa_image::AsScalar::u8(*s)).collect();
+        match P::Repr::CHANNELS {
+            1 => image::GrayImage::from_raw(w, h, data).map(DynamicImage::ImageLuma8),
+            2 => image::GrayAlphaImage::from_raw(w, h, data).map(DynamicImage::ImageLumaA8),
+            3 => image::RgbImage::from_raw(w, h, data).map(DynamicImage::ImageRgb8),
+            4 => image::RgbaImage::from_raw(w, h, data).map(DynamicImage::ImageRgba8),
+            _ => None,
+        }
+    }
+    .ok_or_else(|| anyhow!("Cannot save an image with {} channels", P::Repr::CHANNELS))?;
+    #[cfg(feature = "qoi")]
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("qoi")) {
+        return qoi::encode(&dynamic, File::create(path)?)
+            .with_context(|| format!("Failed to save image to {}", path.display()));
+    }
+    dynamic.save(path).with_context(|| format!("Failed to save image to {}", path.display()))
 }
 
 /// Very basic check to see if terminal supports ansi
@@ -700,10 +2574,6 @@ fn warnings() -> Result<(), ()> {
 
 /// Default main function for viuwa
 pub fn main() -> Result<()> {
-    // this should be compatible with almost all platforms
-    if !::is_terminal::IsTerminal::is_terminal(&std::io::stdout()) {
-        warn!("We cannot guarantee that viuwa will work as intended in a file or pipe");
-    }
     #[cfg(feature = "debug")]
     {
         debug!("start", "features:\t");
@@ -713,33 +2583,196 @@ pub fn main() -> Result<()> {
         eprint!("rayon, ");
         #[cfg(feature = "fir")]
         eprint!("fir, ");
+        #[cfg(feature = "simd")]
+        eprint!("simd, ");
         #[cfg(feature = "trace")]
         eprint!("trace, ");
         eprintln!("debug");
     }
     trace!("main");
-    let config = Config::new();
+    let mut config = Config::new();
+    if let Some(path) = &config.log_file {
+        set_log_file(path).with_context(|| format!("Failed to open log file {}", path.display()))?;
+    }
     debug!("main", "generated config: {:#?}", config);
-    LOG_LEVEL.with(|cell| cell.set(config.log));
-    if warnings().is_err() {
+    config.log.set();
+    #[cfg(feature = "trace")]
+    let _trace_out = config.trace_out.clone().map(ChromeTrace);
+    #[cfg(feature = "terminology")]
+    let _terminology_file = TerminologyFile;
+    match config.subcommand.take() {
+        Some(Subcommand::Bench { image, iterations }) => {
+            config.image = image;
+            return bench(&config, iterations);
+        }
+        Some(Subcommand::Config { action }) => return config_subcommand(action, &config),
+        Some(Subcommand::Completions { shell }) => {
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut <Args as clap::CommandFactory>::command(), "viuwa", &mut script);
+            return print_piped(&script);
+        }
+        Some(Subcommand::Convert { images, out_dir, format, size }) => {
+            return converted(&images, &out_dir, format, size, &config)
+        }
+        Some(Subcommand::Cast { images, out, size, interval }) => {
+            return write_cast(&images, &out, size, std::time::Duration::from_millis(interval), &config)
+        }
+        Some(Subcommand::Cheatsheet) => return print_piped(cheatsheet(&config).as_bytes()),
+        None => (),
+    }
+    // after the subcommands, which are meant to be piped or redirected
+    // this should be compatible with almost all platforms
+    let interactive = config.interactive();
+    if !interactive {
+        if config.compare.is_some() && !config.diff {
+            return Err(anyhow!("Comparing images needs an interactive terminal, print their difference with --diff"));
+        }
+        if !config.inline {
+            info!("not interactive, printing the image inline");
+            config.inline = true;
+        }
+    }
+    // after the subcommands, which don't need the query written to the terminal, nor can a pipe answer it
+    if interactive && config.cell_aspect.is_none() {
+        config.cell_aspect = detect_cell_aspect(&mut stdout());
+    }
+    if interactive {
+        config.background = config.background.resolve(&mut stdout());
+    }
+    if interactive && warnings().is_err() {
         return Ok(());
     }
+    if let Some(dir) = config.grid.clone() {
+        let res = gridded(&dir, config).context("Failed to display the grid of images");
+        return res.or_else(|e| if is_broken_pipe(&e) { Ok(()) } else { Err(e) });
+    }
+    if is_ansi_art(&config.image) {
+        let res = ansi_art(config).context("Failed to display ANSI art");
+        return res.or_else(|e| if is_broken_pipe(&e) { Ok(()) } else { Err(e) });
+    }
     let orig = {
         info!("loading image...");
-        image::io::Reader::open(&config.image)?
-            .with_guessed_format()?
-            .decode()
-            .context("Failed to load image, the file extension may be incorrect")?
+        match decode_with_progress(&config)? {
+            Some(orig) => orig,
+            None => {
+                info!("loading cancelled");
+                return Ok(());
+            }
+        }
+    };
+    let other = match &config.compare {
+        Some(image) => {
+            info!("loading comparison image...");
+            Some(decode(&Config { image: image.clone(), ..config.clone() })?)
+        }
+        None => None,
     };
-    // Any errors from here on out are likely to not be the users direct fault, so we can ask for a bug report
-    #[cfg(not(target_os = "wasi"))]
+    // Any panics from here on out are likely to not be the users direct fault, so we can ask for a bug report
+    #[cfg(not(target_family = "wasm"))]
     human_panic::setup_panic!();
-    // unwraps so that we can use panic to report a bug if this fails, (better than opaque errors)
-    // most likely due to std::io::stdout() write failing
-    if !config.inline {
-        windowed(orig, config).expect("Failed to display image windowed");
+    let res = if let Some(other) = other {
+        if config.diff {
+            diffed(orig, other, config).context("Failed to display the difference of images")
+        } else {
+            compared(orig, other, config).context("Failed to display images compared")
+        }
+    } else if !config.inline {
+        windowed(orig, config).context("Failed to display image windowed")
     } else {
-        inlined(orig, config).expect("Failed to display image inlined")
+        inlined(orig, config).context("Failed to display image inlined")
+    };
+    match res {
+        // the reader went away (e.g. piped through `head`), there's nothing left to display to
+        Err(e) if is_broken_pipe(&e) => Ok(()),
+        res => res,
     }
-    Ok(())
+}
+
+/// Write the output of a subcommand to stdout, quietly stopping if the reader went away (e.g. piped through `head`)
+fn print_piped(out: &[u8]) -> Result<()> {
+    match stdout().lock().write_all(out) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        res => Ok(res?),
+    }
+}
+
+/// The keybinds in effect, grouped by the command they're bound to, followed by every command of the : prompt
+fn cheatsheet(conf: &Config) -> String {
+    let mut bound = BTreeMap::<&Action, Vec<String>>::new();
+    for (key, action) in &conf.keybinds {
+        #[cfg(not(target_family = "wasm"))]
+        let key = key.to_string();
+        #[cfg(target_family = "wasm")]
+        let key = key.escape_debug().to_string();
+        bound.entry(action).or_default().push(key);
+    }
+    let commands = bound.iter().map(|(action, keys)| (action.to_string(), keys.join(", "))).collect::<Vec<_>>();
+    let width = commands.iter().map(|(command, _)| command.len()).max().unwrap_or(0) + 2;
+    let mut sheet = String::from("Keybinds:\n");
+    for (command, keys) in &commands {
+        sheet.push_str(&format!("  {command:<width$}{keys}\n"));
+    }
+    sheet.push_str("\nCommands (type : to enter one):\n");
+    for (usage, description) in COMMANDS {
+        sheet.push_str(&format!("  {usage:<26}{description}\n"));
+    }
+    sheet.push_str("\nGrid commands (--grid, type : to enter one):\n");
+    for (usage, description) in GRID_COMMANDS {
+        sheet.push_str(&format!("  {usage:<26}{description}\n"));
+    }
+    sheet
+}
+
+/// Print the path of, write, or check the config file
+fn config_subcommand(action: ConfigAction, conf: &Config) -> Result<()> {
+    match action {
+        ConfigAction::Path => match conf.config_file.clone().or_else(default_config_path) {
+            Some(path) => {
+                println!("{}", path.display());
+                if !path.is_file() {
+                    eprintln!("{} doesn't exist yet, create it with `viuwa config init`", path.display());
+                }
+                Ok(())
+            }
+            None => Err(anyhow!("No config directory found, set XDG_CONFIG_HOME, APPDATA, or HOME")),
+        },
+        ConfigAction::Init { path, force } => {
+            let path = path
+                .or_else(default_config_path)
+                .ok_or_else(|| anyhow!("No config directory found, give a path to write the config file to"))?;
+            if path.exists() && !force {
+                return Err(anyhow!("{} already exists, overwrite it with --force", path.display()));
+            }
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+            }
+            std::fs::write(&path, default_config_toml()?)
+                .with_context(|| format!("Failed to write config file {}", path.display()))?;
+            println!("wrote config to {}", path.display());
+            Ok(())
+        }
+        ConfigAction::Check { path } => {
+            let path = path
+                .or_else(|| conf.config_file.clone())
+                .ok_or_else(|| anyhow!("No config file found, give a path to check"))?;
+            let src =
+                std::fs::read_to_string(&path).with_context(|| format!("Failed to read config file {}", path.display()))?;
+            let issues = check_config(&src);
+            for issue in &issues {
+                println!("{}: {issue}", path.display());
+            }
+            match issues.iter().filter(|issue| issue.error).count() {
+                0 => {
+                    println!("{} is valid", path.display());
+                    Ok(())
+                }
+                errors => Err(anyhow!("{} has {errors} error(s)", path.display())),
+            }
+        }
+    }
+}
+
+/// Whether an error was caused by writing to a closed pipe
+fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    e.chain().any(|e| e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe))
 }
@@ -0,0 +1,91 @@
+//! Multi-page documents (e.g. PDFs) and multi-image files (e.g. TIFF pages) in the windowed viewer,
+//! rendering a page or frame at a time as they are navigated to.
+use super::*;
+
+/// Renders the page at an index (starting at 0) of a document
+pub type RenderPage<P> = Box<dyn FnMut(usize) -> Result<ImageBuffer<P, Vec<<P as viuwa_image::Pixel>::Scalar>>>>;
+
+/// The pages or frames of a document, only the current one is held in memory
+pub struct Pages<P: Pixel>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// What the pages are called in the indicator, e.g. "page" or "frame"
+    pub label: &'static str,
+    /// The index of the displayed page
+    pub current: usize,
+    /// The number of pages
+    pub count: usize,
+    render: RenderPage<P>,
+}
+impl<P: Pixel> Pages<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// The pages of a document with `count` pages called `label`, displaying the page at `current`
+    #[inline]
+    pub fn new(label: &'static str, current: usize, count: usize, render: RenderPage<P>) -> Self {
+        Self { label, current, count, render }
+    }
+    /// The index of the page to go to, `None` if it is already displayed or past the first or last page
+    /// ```
+    /// use viuwa::{Page, Pages};
+    /// let pages = Pages::<image::Rgb<u8>>::new("page", 0, 3, Box::new(|_| Ok(image::RgbImage::new(1, 1))));
+    /// assert_eq!(pages.target(Page::Next).unwrap(), Some(1));
+    /// assert_eq!(pages.target(Page::Prev).unwrap(), None);
+    /// assert_eq!(pages.target(Page::To(3)).unwrap(), Some(2));
+    /// assert!(pages.target(Page::To(4)).is_err());
+    /// ```
+    pub fn target(&self, page: Page) -> Result<Option<usize>> {
+        Ok(match page {
+            Page::Next => Some(self.current + 1).filter(|&i| i < self.count),
+            Page::Prev => self.current.checked_sub(1),
+            Page::To(n) if (1..=self.count).contains(&n) => Some(n - 1).filter(|&i| i != self.current),
+            Page::To(n) => return Err(anyhow!("{} {n} is out of range, there are {} {0}s", self.label, self.count)),
+        })
+    }
+    /// Render the page at `index` and make it the displayed page
+    pub fn render(&mut self, index: usize) -> Result<ImageBuffer<P, Vec<P::Scalar>>> {
+        let page = Stage::Decode.time(|| (self.render)(index))?;
+        self.current = index;
+        Ok(page)
+    }
+    /// The indicator of the displayed page, e.g. "page 2/3"
+    #[inline]
+    pub fn indicator(&self) -> String { format!("{} {}/{}", self.label, self.current + 1, self.count) }
+}
+
+/// Create a new viuwa instance displaying a page of a document with `count` pages called `label`,
+/// navigated with the page command, and spawn it
+pub fn paged(
+    orig: DynamicImage,
+    config: Config,
+    label: &'static str,
+    count: usize,
+    mut render: impl FnMut(usize) -> Result<DynamicImage> + 'static,
+) -> Result<()> {
+    trace!("paged");
+    let current = config.page;
+    // every page is displayed at the depth of the first
+    match (orig.color().has_color(), is_16bit(&orig)) {
+        (true, false) => {
+            let pages = Pages::new(label, current, count, Box::new(move |i| Ok(render(i)?.into_rgb8())));
+            Viuwa::builder().image(orig.into_rgb8()).pages(pages).config(config).build()?.spawn()?;
+        }
+        (false, false) => {
+            let pages = Pages::new(label, current, count, Box::new(move |i| Ok(render(i)?.into_luma8())));
+            Viuwa::builder().image(orig.into_luma8()).pages(pages).config(config).build()?.spawn()?;
+        }
+        (true, true) => {
+            let pages = Pages::new(label, current, count, Box::new(move |i| Ok(render(i)?.into_rgb16())));
+            Viuwa::builder().image(orig.into_rgb16()).pages(pages).config(config).build()?.spawn()?;
+        }
+        (false, true) => {
+            let pages = Pages::new(label, current, count, Box::new(move |i| Ok(render(i)?.into_luma16())));
+            Viuwa::builder().image(orig.into_luma16()).pages(pages).config(config).build()?.spawn()?;
+        }
+    }
+    Ok(())
+}
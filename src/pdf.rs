@@ -0,0 +1,60 @@
+//! PDF page rendering with pdfium (feature `pdf`), loaded at runtime from next to the executable or the system library path.
+use pdfium_render::prelude::*;
+
+use super::*;
+
+/// The largest a page is rendered to, more than enough for any terminal even when supersampled
+pub const PAGE_SIZE: (usize, usize) = (2048, 2048);
+
+/// Bind to the pdfium library, preferring one next to the executable
+fn pdfium() -> Result<Pdfium> {
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Pdfium::pdfium_platform_library_name_at_path))
+        .map(Pdfium::bind_to_library);
+    let bindings = match beside_exe {
+        Some(Ok(bindings)) => bindings,
+        _ => Pdfium::bind_to_system_library()
+            .context("Failed to load the pdfium library, place it next to viuwa or on the library path")?,
+    };
+    Ok(Pdfium::new(bindings))
+}
+
+/// The number of pages of the PDF at `path`
+pub fn page_count(path: &Path) -> Result<usize> {
+    let pdfium = pdfium()?;
+    let doc = pdfium.load_pdf_from_file(path, None)?;
+    Ok(doc.pages().len() as usize)
+}
+
+/// Render the page at `index` (starting at 0) of the PDF at `path`, as large as fits within `max`
+pub fn render(path: &Path, index: usize, max: (usize, usize)) -> Result<DynamicImage> {
+    trace!("pdf::render");
+    let pdfium = pdfium()?;
+    let doc = pdfium.load_pdf_from_file(path, None)?;
+    let count = doc.pages().len() as usize;
+    if index >= count {
+        return Err(anyhow!("page {} is out of range, the document has {} pages", index + 1, count));
+    }
+    let page = doc.pages().get(index as u16)?;
+    let points = (page.width().value.max(1.) as usize, page.height().value.max(1.) as usize);
+    let (w, h) = viuwa_image::fit_dimensions(points, max);
+    let config = PdfRenderConfig::new().set_target_size(w as i32, h as i32);
+    let image = page.render_with_config(&config)?.as_image();
+    Ok(image)
+}
+
+/// Render the page of the PDF chosen by `--page`, downscaled to fit the memory budget
+pub fn decode(conf: &Config, display: impl FnOnce((usize, usize)) -> Option<(usize, usize)>) -> Result<DynamicImage> {
+    let budget = Budget::new(conf).map(|b| b.for_decode(display(PAGE_SIZE))).transpose()?;
+    render(&conf.image, conf.page, budget.map_or(PAGE_SIZE, |b| b.fit(PAGE_SIZE)))
+}
+
+/// Create a new viuwa instance displaying a PDF, navigated a page at a time, and spawn it
+pub fn windowed(orig: DynamicImage, config: Config) -> Result<()> {
+    trace!("pdf::windowed");
+    let count = page_count(&config.image)?;
+    let path = config.image.clone();
+    let max = Budget::new(&config).map_or(PAGE_SIZE, |b| b.fit(PAGE_SIZE));
+    paged(orig, config, "page", count, move |i| render(&path, i, max))
+}
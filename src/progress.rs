@@ -0,0 +1,215 @@
+//! A progress line shown while a large file or a slow decode keeps the image from being displayed,
+//! with a spinner, the step of loading, and the bytes of the file read so far.
+//!
+//! The image is decoded on a loader thread while the line is drawn, so Esc (or Ctrl+C, as the terminal is in raw mode)
+//! can cancel it, failing the loader's next read through [`Counted`]. Loads shorter than [`PROGRESS_DELAY`] never
+//! show it.
+use std::{
+    cell::RefCell,
+    io::{Read, Seek, SeekFrom},
+    sync::atomic::{AtomicBool, AtomicU64},
+    time::Duration,
+};
+
+use super::*;
+
+/// How long loading takes before the progress line is shown, so quick loads don't flash it
+pub const PROGRESS_DELAY: Duration = Duration::from_millis(300);
+/// How often the progress line is redrawn
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(80);
+/// The frames of the spinner of the progress line
+pub const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// The bytes of the image read through [`Counted`] readers since loading started
+static READ: AtomicU64 = AtomicU64::new(0);
+/// The [`LoadStep`] loading is at
+static STEP: AtomicU8 = AtomicU8::new(0);
+thread_local! {
+    /// Whether the load on this thread was cancelled, passed to the [`Counted`] readers opened on it
+    static CANCEL: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// The step an image is loading at, set from whichever thread decodes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadStep {
+    /// Identifying the format and reading the header
+    Opening = 0,
+    /// Decoding the pixels
+    Decoding = 1,
+    /// Converting the pixels to sRGB from the image's ICC profile
+    ColorManaging = 2,
+}
+impl LoadStep {
+    /// The step loading is at
+    #[inline]
+    pub fn current() -> Self {
+        match STEP.load(Ordering::Relaxed) {
+            0 => Self::Opening,
+            1 => Self::Decoding,
+            _ => Self::ColorManaging,
+        }
+    }
+    /// Set the step loading is at
+    #[inline]
+    pub fn set(self) { STEP.store(self as u8, Ordering::Relaxed) }
+    /// The name of the step in the progress line
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Opening => "opening",
+            Self::Decoding => "decoding",
+            Self::ColorManaging => "color managing",
+        }
+    }
+}
+
+/// Start counting the progress of loading another image from nothing read
+#[inline]
+pub fn reset_progress() {
+    READ.store(0, Ordering::Relaxed);
+    LoadStep::Opening.set();
+}
+
+/// A reader counting the bytes read through it toward the progress of loading, which fails once the load it was
+/// opened for is cancelled
+/// ```
+/// use std::io::Read;
+/// let mut data = Vec::new();
+/// viuwa::Counted::new(&b"P5 1 1 255 \x80"[..]).read_to_end(&mut data).unwrap();
+/// assert_eq!(data.len(), 12);
+/// ```
+pub struct Counted<R> {
+    inner: R,
+    cancel: Option<Arc<AtomicBool>>,
+}
+impl<R> Counted<R> {
+    /// Count the bytes read from `inner`, cancelled with the load on the current thread if any
+    #[inline]
+    pub fn new(inner: R) -> Self { Self { inner, cancel: CANCEL.with(|c| c.borrow().clone()) } }
+}
+impl<R: Read> Read for Counted<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // not `Interrupted`, which `read_exact` and `read_to_end` would retry forever
+        if self.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) {
+            return Err(io::Error::other("loading was cancelled"));
+        }
+        let n = self.inner.read(buf)?;
+        READ.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+impl<R: Seek> Seek for Counted<R> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> { self.inner.seek(pos) }
+}
+
+/// The terminal while the progress line is shown, in raw mode with the cursor hidden until it's dropped
+#[cfg(not(target_family = "wasm"))]
+struct ProgressTerminal(Box<dyn Backend>);
+#[cfg(not(target_family = "wasm"))]
+impl ProgressTerminal {
+    fn enter(mut backend: Box<dyn Backend>) -> Result<Self> {
+        backend.enable_raw_mode()?;
+        let terminal = Self(backend);
+        let mut out = stdout();
+        _execute!(out, cursor_hide(), flush());
+        Ok(terminal)
+    }
+}
+#[cfg(not(target_family = "wasm"))]
+impl Drop for ProgressTerminal {
+    fn drop(&mut self) {
+        let mut out = stdout();
+        let _ = execute!(out, write_all(b"\r"), clear_line(), cursor_show(), flush());
+        let _ = self.0.disable_raw_mode();
+    }
+}
+
+/// The progress line of loading `name` at `step`, with `read` of `total` bytes read if any are counted, spinning at `frame`
+/// ```
+/// use viuwa::{progress_line, LoadStep};
+/// let line = progress_line("cat.png", LoadStep::Decoding, 12_500_000, 50_000_000, 0);
+/// assert_eq!(line, "⠋ loading cat.png, decoding 12.5 of 50.0 MB (25%), esc to cancel");
+/// ```
+pub fn progress_line(name: &str, step: LoadStep, read: u64, total: u64, frame: usize) -> String {
+    let spinner = SPINNER[frame % SPINNER.len()];
+    let mb = |bytes: u64| bytes as f64 / 1_000_000.;
+    // a file read more than once (e.g. for its dimensions first) is never shown past the end
+    let read = read.min(total);
+    match total {
+        0 => format!("{spinner} loading {name}, {}, esc to cancel", step.name()),
+        _ if read == 0 => format!("{spinner} loading {name}, {} {:.1} MB, esc to cancel", step.name(), mb(total)),
+        _ => format!(
+            "{spinner} loading {name}, {} {:.1} of {:.1} MB ({}%), esc to cancel",
+            step.name(),
+            mb(read),
+            mb(total),
+            read * 100 / total
+        ),
+    }
+}
+
+/// [`decode`] the configured image on a loader thread, showing its progress if it takes longer than [`PROGRESS_DELAY`],
+/// or `None` if it was cancelled with Esc, which stops the loader at its next read
+///
+/// Piped, streamed, or on wasm (without threads), the image is decoded without any progress shown.
+pub fn decode_with_progress(conf: &Config) -> Result<Option<DynamicImage>> {
+    #[cfg(target_family = "wasm")]
+    return decode(conf).map(Some);
+    #[cfg(not(target_family = "wasm"))]
+    {
+        if !conf.interactive() || is_stream(&conf.image) {
+            return decode(conf).map(Some);
+        }
+        // the terminal is queried for its size here, as the loader couldn't read its answer while it's polled for Esc
+        let sz = terminal_size(&mut stdout(), conf);
+        let total = std::fs::metadata(&conf.image).map_or(0, |meta| meta.len());
+        let name = conf.image.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        reset_progress();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let loader_conf = conf.clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let loader_cancel = cancel.clone();
+        std::thread::Builder::new().name(String::from("loader")).spawn(move || {
+            CANCEL.with(|c| *c.borrow_mut() = Some(loader_cancel));
+            let conf = loader_conf;
+            // at full scale for the windowed viewer, like `decode`
+            let _ = tx.send(decode_for(&conf, |img_sz| conf.inline.then(|| dimensions(sz, &conf, img_sz))));
+        })?;
+        // stop the loader however this returns, it's done already unless cancelled or failed
+        let _cancel = DropFn::new(|| cancel.store(true, Ordering::Relaxed));
+        let start = std::time::Instant::now();
+        let mut out = stdout().lock();
+        let mut shown: Option<ProgressTerminal> = None;
+        let mut frame = 0;
+        loop {
+            let wait = PROGRESS_DELAY.saturating_sub(start.elapsed()).max(PROGRESS_INTERVAL);
+            match rx.recv_timeout(wait) {
+                Ok(res) => return res.map(Some),
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return Err(anyhow!("The image loader panicked")),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => (),
+            }
+            if shown.is_none() {
+                shown = Some(ProgressTerminal::enter(conf.backend.backend())?);
+            }
+            let mut cancelled = false;
+            if let Some(ProgressTerminal(backend)) = &mut shown {
+                while backend.poll(Duration::ZERO)? {
+                    if let Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press, .. }) = backend.read()? {
+                        let ctrl_c = code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL);
+                        cancelled |= code == KeyCode::Esc || ctrl_c;
+                    }
+                }
+            }
+            if cancelled {
+                return Ok(None);
+            }
+            let line = progress_line(&name, LoadStep::current(), READ.load(Ordering::Relaxed), total, frame);
+            // cut to the width of the terminal, so it's redrawn over the same row
+            let line = line.chars().take(sz.0 as usize).collect::<String>();
+            _execute!(out, write_all(b"\r"), clear_line(), write_all(line.as_bytes()), flush());
+            frame += 1;
+        }
+    }
+}
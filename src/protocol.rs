@@ -0,0 +1,551 @@
+//! Drawing inline images with terminal graphics protocols, in the terminal's own pixels rather than character cells.
+//!
+//! Images are resized to the pixels of the cells they cover, from the size of a cell the terminal reports,
+//! so they're as sharp as the screen on HiDPI terminals instead of scaled up by the terminal.
+//! Terminals that don't report their size in pixels are assumed to have cells [`DEFAULT_CELL_HEIGHT`] pixels tall,
+//! and told how many cells to scale the image to when their protocol allows it.
+#[cfg(any(feature = "kitty", feature = "iterm", feature = "sixel", feature = "terminology", feature = "regis"))]
+use image::Rgb;
+use is_terminal::IsTerminal;
+#[cfg(feature = "sixel")]
+use viuwa_ansi::fmt_u32;
+#[cfg(any(feature = "sixel", feature = "regis"))]
+use viuwa_ansi::{color::rgb_to_ansi_slice, ANSI_PALETTE};
+
+use super::*;
+
+/// The height in pixels of a cell when the terminal doesn't report it, its width is from the cell aspect ratio
+pub const DEFAULT_CELL_HEIGHT: f32 = 20.;
+/// The color registers ReGIS draws with, those of a VT340 but the background's
+#[cfg(feature = "regis")]
+pub const REGIS_COLORS: usize = 15;
+/// The most bytes of base64 in each escape sequence of the kitty graphics protocol
+#[cfg(feature = "kitty")]
+const KITTY_CHUNK: usize = 4096;
+
+/// How inline images are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Protocol {
+    /// Character cells of ANSI colors, with the configured [`Renderer`]
+    #[default]
+    Ansi,
+    /// The first protocol of the terminal's environment variables viuwa was built with, see [`Protocol::detect`]
+    Auto,
+    /// The kitty graphics protocol, also supported by WezTerm, Ghostty, and Konsole, with the `kitty` feature
+    #[cfg(feature = "kitty")]
+    Kitty,
+    /// iTerm2's inline images protocol, also supported by WezTerm and mintty, with the `iterm` feature
+    #[cfg(feature = "iterm")]
+    Iterm,
+    /// Sixels in the colors of the ANSI 256 palette, for xterm, foot, mlterm, and others, with the `sixel` feature
+    #[cfg(feature = "sixel")]
+    Sixel,
+    /// Terminology's inline media, loaded by the terminal from a file, with the `terminology` feature
+    #[cfg(feature = "terminology")]
+    Terminology,
+    /// ReGIS vector graphics posterized to [`REGIS_COLORS`] colors, for DEC terminals and their emulators,
+    /// with the `regis` feature
+    #[cfg(feature = "regis")]
+    Regis,
+}
+impl Protocol {
+    /// The protocol of the terminal viuwa runs in, from the environment variables it sets,
+    /// and [`Ansi`](Self::Ansi) when it's unknown or within tmux or screen, which don't pass the protocols through
+    pub fn detect() -> Self {
+        use std::env::var;
+        #[cfg(any(feature = "kitty", feature = "iterm", feature = "sixel"))]
+        let is = |key: &str, value: &str| var(key).is_ok_and(|v| v == value);
+        if var("TMUX").is_ok() || var("TERM").is_ok_and(|term| term.starts_with("screen")) {
+            return Self::Ansi;
+        }
+        #[cfg(feature = "kitty")]
+        if var("KITTY_WINDOW_ID").is_ok()
+            || is("TERM", "xterm-kitty")
+            || is("TERM_PROGRAM", "WezTerm")
+            || is("TERM_PROGRAM", "ghostty")
+        {
+            return Self::Kitty;
+        }
+        #[cfg(feature = "iterm")]
+        if is("TERM_PROGRAM", "iTerm.app") || is("LC_TERMINAL", "iTerm2") {
+            return Self::Iterm;
+        }
+        #[cfg(feature = "sixel")]
+        if var("TERM").is_ok_and(|term| term.starts_with("foot") || term.starts_with("mlterm"))
+            || is("TERM_PROGRAM", "mlterm")
+        {
+            return Self::Sixel;
+        }
+        #[cfg(feature = "terminology")]
+        if var("TERMINOLOGY").is_ok() {
+            return Self::Terminology;
+        }
+        Self::Ansi
+    }
+    /// The protocol to draw with, [detected](Self::detect) for [`Auto`](Self::Auto) when stdout is a terminal
+    #[inline]
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto if stdout().is_terminal() => Self::detect(),
+            Self::Auto => Self::Ansi,
+            protocol => protocol,
+        }
+    }
+}
+impl FromStr for Protocol {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Self::Ansi),
+            "auto" => Ok(Self::Auto),
+            #[cfg(feature = "kitty")]
+            "kitty" => Ok(Self::Kitty),
+            #[cfg(feature = "iterm")]
+            "iterm" | "iterm2" => Ok(Self::Iterm),
+            #[cfg(feature = "sixel")]
+            "sixel" => Ok(Self::Sixel),
+            #[cfg(feature = "terminology")]
+            "terminology" => Ok(Self::Terminology),
+            #[cfg(feature = "regis")]
+            "regis" => Ok(Self::Regis),
+            _ => Err(ConfigError::InvalidProtocol(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Protocol {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+impl fmt::Display for Protocol {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ansi => "ansi",
+            Self::Auto => "auto",
+            #[cfg(feature = "kitty")]
+            Self::Kitty => "kitty",
+            #[cfg(feature = "iterm")]
+            Self::Iterm => "iterm",
+            #[cfg(feature = "sixel")]
+            Self::Sixel => "sixel",
+            #[cfg(feature = "terminology")]
+            Self::Terminology => "terminology",
+            #[cfg(feature = "regis")]
+            Self::Regis => "regis",
+        })
+    }
+}
+impl Serialize for Protocol {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// The dimensions in pixels to draw an image of `img_sz` pixels at within `area` cells of `cell` pixels each,
+/// sized like [`scale_dimensions`] with a column as wide as a cell and a half block row half as tall
+/// ```
+/// use viuwa::protocol_dimensions;
+/// let (area, dims) = ((80, 24), Default::default());
+/// assert_eq!(protocol_dimensions(area, dims, (1600, 900), (10., 20.)), (800, 450));
+/// // twice the pixels in the same cells on a HiDPI screen
+/// assert_eq!(protocol_dimensions(area, dims, (1600, 900), (20., 40.)), (1600, 900));
+/// ```
+pub fn protocol_dimensions(
+    area: (u16, u16),
+    (width, height): (Dimension, Dimension),
+    img_sz: (usize, usize),
+    (cw, ch): (f32, f32),
+) -> (usize, usize) {
+    let px = |cells: u16, size: f32| ((cells as f32 * size) as usize).max(1);
+    let stretch = (px(area.0, cw), px(area.1, ch));
+    let fit = viuwa_image::fit_dimensions(img_sz, stretch);
+    let fill = viuwa_image::fill_dimensions(img_sz, fit);
+    let w = match width {
+        Dimension::Fit => fit.0,
+        Dimension::Fill => fill.0,
+        Dimension::Stretch => stretch.0,
+        Dimension::Limit(n) => px(n, cw),
+    };
+    let h = match height {
+        Dimension::Fit => fit.1,
+        Dimension::Fill => fill.1,
+        Dimension::Stretch => stretch.1,
+        Dimension::Limit(n) => px(n, ch / 2.),
+    };
+    (w, h)
+}
+
+/// The columns and rows of `cell` pixels each an image of `(w, h)` pixels covers
+#[inline]
+pub fn covered_cells((w, h): (usize, usize), (cw, ch): (f32, f32)) -> (u16, u16) {
+    ((w as f32 / cw).ceil().clamp(1., u16::MAX as f32) as u16, (h as f32 / ch).ceil().clamp(1., u16::MAX as f32) as u16)
+}
+
+/// Display an image inline with `protocol`, which must not be [`Ansi`](Protocol::Ansi) or [`Auto`](Protocol::Auto),
+/// placed within the terminal like the ANSI image would be and leaving the cursor at the end of its last row
+#[cfg(any(feature = "kitty", feature = "iterm", feature = "sixel", feature = "terminology", feature = "regis"))]
+pub fn protocol_inlined(orig: &DynamicImage, conf: &Config, protocol: Protocol, out: &mut impl Terminal) -> Result<()> {
+    trace!("protocol_inlined");
+    let cell_pixels = detect_cell_pixels(out);
+    let cell = cell_pixels
+        .unwrap_or_else(|| (DEFAULT_CELL_HEIGHT * conf.cell_aspect.unwrap_or(DEFAULT_CELL_ASPECT), DEFAULT_CELL_HEIGHT));
+    let term = terminal_size(out, conf);
+    let ((left, top), area) = conf.padding.area(term);
+    let dims = protocol_dimensions(area, (conf.width, conf.height), (orig.width() as usize, orig.height() as usize), cell);
+    let (cols, rows) = covered_cells(dims, cell);
+    let (cols, rows) = (cols.min(area.0), rows.min(area.1));
+    debug!("protocol_inlined", "{} at {}x{} pixels in {}x{} cells of {:?}", protocol, dims.0, dims.1, cols, rows, cell);
+    // gray in gray color types, and for single-channel images without a colormap, as the ANSI image would be
+    let gray = conf.color.is_gray() || (!orig.color().has_color() && conf.colormap == Colormap::Gray);
+    let rgb: Vec<u8> = if gray || !orig.color().has_color() {
+        let colormap = if conf.color.is_gray() { Colormap::Gray } else { conf.colormap };
+        let buf = protocol_resized(&Image::from(orig.to_luma8()), dims, conf);
+        buf.data().iter().flat_map(|&v| colormap.map(v)).collect()
+    } else {
+        protocol_resized(&Image::<Rgb<u8>>::from(orig.to_rgb8()), dims, conf).into_raw().into_vec()
+    };
+    let (x, y) = conf.align.unwrap_or(Align::TopLeft).offset(area, (cols, rows));
+    let (left, top) = (left + x, top + y);
+    // ReGIS draws on a graphics plane addressed by the screen rather than at the cursor, so over a cleared screen
+    #[cfg(feature = "regis")]
+    if protocol == Protocol::Regis {
+        let px = |cells: u16, size: f32| (cells as f32 * size) as usize;
+        let mut indices = quantized(&rgb, gray, conf.color_attributes());
+        let palette = posterized(&mut indices, REGIS_COLORS);
+        out.clear()?;
+        out.cursor_home()?;
+        let (origin, screen) = ((px(left, cell.0), px(top, cell.1)), (px(term.0, cell.0), px(term.1, cell.1)));
+        write_regis(out, &indices, &palette, dims, origin, screen)?;
+        out.cursor_to(0, (top + rows).min(term.1.saturating_sub(1)))?;
+        return Ok(());
+    }
+    for _ in 0..top {
+        out.write_all(b"\n")?;
+    }
+    // scroll the rows covered into view first, so the image doesn't scroll the terminal itself
+    for _ in 1..rows {
+        out.write_all(b"\n")?;
+    }
+    if rows > 1 {
+        out.cursor_up(rows - 1)?;
+    }
+    if left > 0 {
+        out.cursor_forward(left)?;
+    }
+    out.cursor_save()?;
+    match protocol {
+        #[cfg(feature = "kitty")]
+        Protocol::Kitty => write_kitty(out, &encode_png(&rgb, dims)?, cell_pixels.is_none().then_some((cols, rows)))?,
+        #[cfg(feature = "iterm")]
+        Protocol::Iterm => write_iterm(out, &encode_png(&rgb, dims)?, (cols, rows))?,
+        #[cfg(feature = "sixel")]
+        Protocol::Sixel => {
+            let attrs = conf.color_attributes();
+            write_sixel(out, &quantized(&rgb, gray, attrs), dims)?
+        }
+        #[cfg(feature = "terminology")]
+        Protocol::Terminology => write_terminology(out, &terminology_file(&encode_png(&rgb, dims)?)?, (cols, rows))?,
+        #[cfg(feature = "regis")]
+        Protocol::Regis => unreachable!("drawn over the screen"),
+        Protocol::Ansi | Protocol::Auto => unreachable!("drawn with ANSI"),
+    }
+    out.cursor_restore()?;
+    if rows > 1 {
+        out.cursor_down(rows - 1)?;
+    }
+    for _ in 0..conf.padding.bottom {
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Resize and sharpen an image to the pixels it's drawn at with a protocol
+#[cfg(any(feature = "kitty", feature = "iterm", feature = "sixel", feature = "terminology", feature = "regis"))]
+fn protocol_resized<P: viuwa_image::CompatPixel>(orig: &Image<P>, dims: (usize, usize), conf: &Config) -> Image<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let mut buf = resize_view(&orig.view(), dims, conf.resize_filter());
+    sharpen(&mut buf, orig.dimensions(), conf.sharpen);
+    buf
+}
+
+/// Encode RGB pixels as a PNG, quickly rather than small since it's sent once
+#[cfg(any(feature = "kitty", feature = "iterm", feature = "terminology"))]
+fn encode_png(rgb: &[u8], (w, h): (usize, usize)) -> Result<Vec<u8>> {
+    use image::{
+        codecs::png::{CompressionType, FilterType, PngEncoder},
+        ImageEncoder,
+    };
+    let mut png = Vec::new();
+    PngEncoder::new_with_quality(&mut png, CompressionType::Fast, FilterType::Adaptive)
+        .write_image(rgb, w as u32, h as u32, image::ColorType::Rgb8)
+        .context("Failed to encode the image as a PNG")?;
+    Ok(png)
+}
+
+/// Write a PNG with the kitty graphics protocol, in `cells` when the terminal doesn't report its cell size,
+/// without moving the cursor or the terminal replying
+#[cfg(feature = "kitty")]
+fn write_kitty(out: &mut impl Write, png: &[u8], cells: Option<(u16, u16)>) -> io::Result<()> {
+    let data = base64::encode(png);
+    let mut chunks = data.as_bytes().chunks(KITTY_CHUNK).peekable();
+    let mut first = true;
+    while let Some(chunk) = chunks.next() {
+        let more = chunks.peek().is_some() as u8;
+        if first {
+            out.write_all(b"\x1b_Ga=T,f=100,C=1,q=2")?;
+            if let Some((c, r)) = cells {
+                write!(out, ",c={c},r={r}")?;
+            }
+            write!(out, ",m={more};")?;
+            first = false;
+        } else {
+            write!(out, "\x1b_Gm={more};")?;
+        }
+        out.write_all(chunk)?;
+        out.write_all(b"\x1b\\")?;
+    }
+    Ok(())
+}
+
+/// Write a PNG with iTerm2's inline images protocol, scaled by the terminal to fit within `cells`
+#[cfg(feature = "iterm")]
+fn write_iterm(out: &mut impl Write, png: &[u8], (c, r): (u16, u16)) -> io::Result<()> {
+    write!(out, "\x1b]1337;File=inline=1;size={};width={c};height={r};preserveAspectRatio=1:", png.len())?;
+    out.write_all(base64::encode(png).as_bytes())?;
+    out.write_all(b"\x07")
+}
+
+/// The file of the last image drawn with Terminology, removed when the next is drawn or by [`TerminologyFile`]
+#[cfg(feature = "terminology")]
+static TERMINOLOGY_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+/// How long Terminology is given to load the last image drawn before its file is removed at exit
+#[cfg(feature = "terminology")]
+const TERMINOLOGY_LOAD: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Write a PNG to a file of its own in the temporary directory for Terminology to load, which it does after the
+/// escape sequence is written, so the file is kept until the next image is drawn or viuwa exits
+#[cfg(feature = "terminology")]
+fn terminology_file(png: &[u8]) -> io::Result<PathBuf> {
+    static WRITTEN: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let n = WRITTEN.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("viuwa-{}-{n}.png", std::process::id()));
+    std::fs::write(&path, png)?;
+    if let Some(last) = TERMINOLOGY_FILE.lock().ok().and_then(|mut last| last.replace(path.clone())) {
+        let _ = std::fs::remove_file(last);
+    }
+    Ok(path)
+}
+
+/// Removes the file of the last image drawn with Terminology when dropped, once the terminal had time to load it
+#[cfg(feature = "terminology")]
+pub struct TerminologyFile;
+#[cfg(feature = "terminology")]
+impl Drop for TerminologyFile {
+    fn drop(&mut self) {
+        if let Some(last) = TERMINOLOGY_FILE.lock().ok().and_then(|mut last| last.take()) {
+            std::thread::sleep(TERMINOLOGY_LOAD);
+            if let Err(e) = std::fs::remove_file(&last) {
+                debug!("TerminologyFile", "could not remove {}: {}", last.display(), e);
+            }
+        }
+    }
+}
+
+/// Write Terminology's escape sequences showing the image at `path` centered in `cells`,
+/// and the rows of placeholder characters it's drawn over
+#[cfg(feature = "terminology")]
+fn write_terminology(out: &mut impl Terminal, path: &Path, (c, r): (u16, u16)) -> io::Result<()> {
+    write!(out, "\x1b}}ic#{c};{r};{}\0", path.display())?;
+    let placeholders = vec![b'#'; c as usize];
+    for row in 0..r {
+        if row > 0 {
+            out.cursor_backward(c)?;
+            out.cursor_down(1)?;
+        }
+        out.write_all(b"\x1b}ib\0")?;
+        out.write_all(&placeholders)?;
+        out.write_all(b"\x1b}ie\0")?;
+    }
+    Ok(())
+}
+
+/// The ANSI 256 colors of RGB pixels, from the gray ramp when `gray`, as the 256 color modes would draw them
+/// ```
+/// use viuwa::quantized;
+/// use viuwa_ansi::ColorAttributes;
+/// assert_eq!(quantized(&[255, 0, 0, 0, 0, 0], false, ColorAttributes::new(100.)), [196, 16]);
+/// ```
+#[cfg(any(feature = "sixel", feature = "regis"))]
+pub fn quantized(rgb: &[u8], gray: bool, attrs: ColorAttributes) -> Vec<u8> {
+    let (rgb, _) = rgb.as_chunks::<3>();
+    if gray {
+        rgb.iter().map(|&[v, ..]| attrs.gray_ramp.ansi(v)).collect()
+    } else {
+        let mut indices = vec![0; rgb.len()];
+        rgb_to_ansi_slice(rgb, &mut indices, attrs);
+        indices
+    }
+}
+
+/// Write pixels of ANSI 256 color `indices` as sixels, with the palette registers of the colors used
+#[cfg(feature = "sixel")]
+fn write_sixel(out: &mut impl Write, indices: &[u8], (w, h): (usize, usize)) -> io::Result<()> {
+    let mut num = [0; 10];
+    let mut write_num = |out: &mut dyn Write, n: usize| out.write_all(fmt_u32(n as u32, &mut num));
+    out.write_all(b"\x1bP0;1;0q\"1;1;")?;
+    write_num(out, w)?;
+    out.write_all(b";")?;
+    write_num(out, h)?;
+    let mut used = [false; 256];
+    indices.iter().for_each(|&i| used[i as usize] = true);
+    let percent = |c: u8| (c as u32 * 100 + 127) / 255;
+    for (i, &[r, g, b]) in ANSI_PALETTE.iter().enumerate().filter(|&(i, _)| used[i]) {
+        write!(out, "#{i};2;{};{};{}", percent(r), percent(g), percent(b))?;
+    }
+    // the sixels of each color in the band, one byte of 6 bits per column
+    let mut slots = [usize::MAX; 256];
+    let mut bands: Vec<(u8, Vec<u8>)> = Vec::new();
+    for top in (0..h).step_by(6) {
+        for dy in 0..(h - top).min(6) {
+            for (x, &i) in indices[(top + dy) * w..][..w].iter().enumerate() {
+                if slots[i as usize] == usize::MAX {
+                    slots[i as usize] = bands.len();
+                    bands.push((i, vec![0; w]));
+                }
+                bands[slots[i as usize]].1[x] |= 1 << dy;
+            }
+        }
+        for (n, (i, sixels)) in bands.iter().enumerate() {
+            // back to the start of the band for every color after the first
+            out.write_all(if n == 0 { b"#" } else { b"$#" })?;
+            write_num(out, *i as usize)?;
+            // run-length encoded, leaving out the empty columns at the end
+            let end = sixels.iter().rposition(|&s| s != 0).map_or(0, |x| x + 1);
+            let mut x = 0;
+            while x < end {
+                let run = sixels[x..end].iter().take_while(|&&s| s == sixels[x]).count();
+                if run > 3 {
+                    out.write_all(b"!")?;
+                    write_num(out, run)?;
+                    out.write_all(&[63 + sixels[x]])?;
+                } else {
+                    out.write_all(&[63 + sixels[x]; 3][..run])?;
+                }
+                x += run;
+            }
+        }
+        bands.iter().for_each(|&(i, _)| slots[i as usize] = usize::MAX);
+        bands.clear();
+        out.write_all(b"-")?;
+    }
+    out.write_all(b"\x1b\\")
+}
+
+/// Posterize ANSI 256 color `indices` to the `colors` of them used most, replacing each with the position in the
+/// returned palette of the nearest of them
+/// ```
+/// use viuwa::posterized;
+/// let mut indices = [196, 196, 16, 21];
+/// assert_eq!(posterized(&mut indices, 2), [196, 16]);
+/// // blue is nearer black than red
+/// assert_eq!(indices, [0, 0, 1, 1]);
+/// ```
+#[cfg(feature = "regis")]
+pub fn posterized(indices: &mut [u8], colors: usize) -> Vec<u8> {
+    let mut counts = [0usize; 256];
+    indices.iter().for_each(|&i| counts[i as usize] += 1);
+    let mut palette = (0..=255).filter(|&i| counts[i as usize] > 0).collect::<Vec<u8>>();
+    palette.sort_by_key(|&i| std::cmp::Reverse(counts[i as usize]));
+    palette.truncate(colors);
+    let dist = |a: [u8; 3], b: [u8; 3]| a.iter().zip(b).map(|(&a, b)| (a as i32 - b as i32).pow(2)).sum::<i32>();
+    let mut nearest = [0; 256];
+    for i in (0..256).filter(|&i| counts[i] > 0) {
+        let closest = palette.iter().enumerate().min_by_key(|&(_, &p)| dist(ANSI_PALETTE[i], ANSI_PALETTE[p as usize]));
+        nearest[i] = closest.map_or(0, |(n, _)| n as u8);
+    }
+    indices.iter_mut().for_each(|i| *i = nearest[*i as usize]);
+    palette
+}
+
+/// The DEC hue (blue at 0°, red at 120°, and green at 240°), lightness, and saturation of an RGB color,
+/// in degrees and percent, as ReGIS sets the color of its registers
+/// ```
+/// use viuwa::dec_hls;
+/// assert_eq!(dec_hls([255, 0, 0]), (120, 50, 100));
+/// assert_eq!(dec_hls([0, 0, 255]), (0, 50, 100));
+/// assert_eq!(dec_hls([128, 128, 128]), (0, 50, 0));
+/// ```
+#[cfg(feature = "regis")]
+pub fn dec_hls([r, g, b]: [u8; 3]) -> (u16, u8, u8) {
+    let (r, g, b) = (r as f32 / 255., g as f32 / 255., b as f32 / 255.);
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let l = (max + min) / 2.;
+    if max == min {
+        return (0, (l * 100.).round() as u8, 0);
+    }
+    let d = max - min;
+    let s = if l > 0.5 { d / (2. - max - min) } else { d / (max + min) };
+    let h = 60.
+        * if max == r {
+            (g - b) / d + if g < b { 6. } else { 0. }
+        } else if max == g {
+            (b - r) / d + 2.
+        } else {
+            (r - g) / d + 4.
+        };
+    // DEC hues are turned a third of the way around from the usual red at 0°
+    (((h.round() as u16) + 120) % 360, (l * 100.).round() as u8, (s * 100.).round() as u8)
+}
+
+/// Write pixels of `palette` positions `indices` as ReGIS at `origin` of a screen addressed in `screen` pixels,
+/// with the ANSI 256 colors of `palette` in the registers from 1, as 0 is the background
+#[cfg(feature = "regis")]
+fn write_regis(
+    out: &mut impl Write,
+    indices: &[u8],
+    palette: &[u8],
+    (w, h): (usize, usize),
+    (x0, y0): (usize, usize),
+    (sw, sh): (usize, usize),
+) -> io::Result<()> {
+    write!(out, "\x1bPpS(E)S(A[0,0][{},{}])", sw.max(1) - 1, sh.max(1) - 1)?;
+    for (n, &i) in palette.iter().enumerate() {
+        let (h, l, s) = dec_hls(ANSI_PALETTE[i as usize]);
+        write!(out, "S(M{}(AH{h}L{l}S{s}))", n + 1)?;
+    }
+    let mut register = usize::MAX;
+    for (y, row) in indices.chunks_exact(w).take(h).enumerate() {
+        write!(out, "P[{x0},{}]", y0 + y)?;
+        // run-length encoded as a vector from the first pixel of each run to its last
+        let mut x = 0;
+        while x < w {
+            let run = row[x..].iter().take_while(|&&i| i == row[x]).count();
+            if row[x] as usize + 1 != register {
+                register = row[x] as usize + 1;
+                write!(out, "W(I{register})")?;
+            }
+            if x > 0 {
+                out.write_all(b"P[+1]")?;
+            }
+            match run {
+                1 => out.write_all(b"V[]")?,
+                _ => write!(out, "V[+{}]", run - 1)?,
+            }
+            x += run;
+        }
+    }
+    out.write_all(b"\x1b\\")
+}
@@ -0,0 +1,86 @@
+//! A Python extension module rendering images to ANSI with pyo3, for notebooks and Python tools printing to terminals.
+//!
+//! Build it with `cargo rustc --lib --crate-type cdylib --release --features pyo3` and rename the library to `viuwa.so`
+//! (`viuwa.pyd` on Windows), or with maturin.
+//! ```python
+//! import viuwa
+//! print(viuwa.render("img.png", width=60))
+//! print(viuwa.render(numpy.zeros((32, 32, 3), dtype=numpy.uint8), color="ansi-color"))
+//! ```
+use std::borrow::Cow;
+
+use numpy::{PyReadonlyArrayDyn, PyUntypedArrayMethods};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+};
+
+use super::*;
+
+#[pymodule]
+#[pyo3(name = "viuwa")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> { m.add_function(wrap_pyfunction!(python_render, m)?) }
+
+/// Render an image to ANSI, from the path of an image file or a uint8 NumPy array of shape
+/// (height, width), (height, width, 1), (height, width, 3), or (height, width, 4).
+///
+/// The image is `width` columns wide, or fits the terminal as `viuwa --inline` would if None.
+/// `color` is "truecolor" (or "color"), "gray", "ansi-color", or "ansi-gray".
+/// Contiguous arrays are read in place without copying them.
+#[pyfunction]
+#[pyo3(name = "render", signature = (image, width = None, color = "truecolor"))]
+fn python_render(image: &Bound<'_, PyAny>, width: Option<u16>, color: &str) -> PyResult<String> {
+    let color = match color {
+        "truecolor" => ColorType::Color,
+        color => color.parse().map_err(PyValueError::new_err)?,
+    };
+    let conf = Config::builder().color(color).build().map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let opts = RenderOptions::from_config(
+        &conf,
+        match width {
+            Some(0) => return Err(PyValueError::new_err("width must be greater than 0")),
+            // as many rows as the image needs at that width
+            Some(width) => (width, u16::MAX),
+            None => terminal_size(&mut stdout(), &conf),
+        },
+    );
+    let mut out = Vec::new();
+    // arrays only once numpy imports, checking for one without numpy installed panics
+    if let Ok(path) = image.extract::<PathBuf>() {
+        let conf = Config { image: path, ..conf };
+        let image = decode_for(&conf, |img_sz| Some(opts.dimensions(img_sz)))
+            .map_err(|e| PyValueError::new_err(format!("{e:#}")))?;
+        render(&tone_mapped(image, &conf), &opts, &mut out)?;
+    } else if let Some(array) =
+        image.py().import("numpy").ok().and_then(|_| image.extract::<PyReadonlyArrayDyn<'_, u8>>().ok())
+    {
+        render_array(&array, &opts, &mut out)?;
+    } else {
+        return Err(PyTypeError::new_err("expected a path or a uint8 NumPy array"));
+    }
+    Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Render a NumPy array of gray, RGB, or RGBA pixels
+fn render_array(array: &PyReadonlyArrayDyn<'_, u8>, opts: &RenderOptions, out: &mut Vec<u8>) -> PyResult<()> {
+    const SHAPES: &str =
+        "expected an array of shape (height, width), (height, width, 1), (height, width, 3), or (height, width, 4)";
+    let (height, width, channels) = match *array.shape() {
+        [h, w] => (h, w, 1),
+        [h, w, c @ (1 | 3 | 4)] => (h, w, c),
+        _ => return Err(PyValueError::new_err(SHAPES)),
+    };
+    // strided or otherwise non-contiguous arrays are copied into row-major order
+    let data = match array.as_slice() {
+        Ok(data) => Cow::Borrowed(data),
+        Err(_) => Cow::Owned(array.as_array().iter().copied().collect()),
+    };
+    let format = match channels {
+        1 => PixelFormat::Gray8,
+        3 => PixelFormat::Rgb8,
+        _ => PixelFormat::Rgba8,
+    };
+    let source = RenderSource::from_raw_parts(&data, width, height, format)
+        .ok_or_else(|| PyValueError::new_err("the array is empty"))?;
+    Ok(render_source(&source, opts, out)?)
+}
@@ -0,0 +1,171 @@
+//! The [QOI](https://qoiformat.org) image format, decoded and encoded without the `image` crate (feature `qoi`).
+//!
+//! ```
+//! use image::{DynamicImage, RgbImage};
+//! let image = DynamicImage::ImageRgb8(RgbImage::from_fn(7, 5, |x, y| image::Rgb([x as u8 * 30, y as u8 * 50, 7])));
+//! let mut qoi = Vec::new();
+//! viuwa::qoi::encode(&image, &mut qoi).unwrap();
+//! assert!(viuwa::qoi::is_qoi(&qoi));
+//! assert_eq!(viuwa::qoi::decode(&qoi[..]).unwrap(), image);
+//! ```
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+use image::{DynamicImage, RgbImage, RgbaImage};
+
+/// The magic bytes a QOI image starts with
+pub const MAGIC: &[u8; 4] = b"qoif";
+/// The bytes a QOI image ends with
+const END: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+/// The most pixels to decode, as in the reference implementation
+const MAX_PIXELS: u64 = 400_000_000;
+
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RUN: u8 = 0xC0;
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const MASK: u8 = 0xC0;
+
+/// The index of a pixel in the array of recently seen pixels
+#[inline(always)]
+fn hash([r, g, b, a]: [u8; 4]) -> usize { (r as usize * 3 + g as usize * 5 + b as usize * 7 + a as usize * 11) % 64 }
+
+#[inline]
+fn invalid(msg: &'static str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg) }
+
+/// Whether `bytes` start with the QOI magic bytes
+#[inline]
+pub fn is_qoi(bytes: &[u8]) -> bool { bytes.starts_with(MAGIC) }
+
+/// Decode a QOI image to rgb8 or rgba8, depending on its channels
+pub fn decode(reader: impl Read) -> io::Result<DynamicImage> {
+    let mut reader = BufReader::new(reader);
+    let mut header = [0u8; 14];
+    reader.read_exact(&mut header)?;
+    if !is_qoi(&header) {
+        return Err(invalid("not a QOI image"));
+    }
+    let width = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let height = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+    let channels = header[12] as usize;
+    if width == 0 || height == 0 || width as u64 * height as u64 > MAX_PIXELS {
+        return Err(invalid("QOI image dimensions are out of range"));
+    }
+    if !(3..=4).contains(&channels) {
+        return Err(invalid("QOI image must have 3 or 4 channels"));
+    }
+    let mut byte = || -> io::Result<u8> {
+        let mut b = [0];
+        reader.read_exact(&mut b)?;
+        Ok(b[0])
+    };
+    let len = width as usize * height as usize;
+    let mut data = Vec::with_capacity(len * channels);
+    let mut px = [0, 0, 0, 255];
+    let mut index = [[0u8; 4]; 64];
+    let mut run = 0u8;
+    for _ in 0..len {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let op = byte()?;
+            match op {
+                OP_RGB => {
+                    px[0] = byte()?;
+                    px[1] = byte()?;
+                    px[2] = byte()?;
+                }
+                OP_RGBA => {
+                    px[0] = byte()?;
+                    px[1] = byte()?;
+                    px[2] = byte()?;
+                    px[3] = byte()?;
+                }
+                _ => match op & MASK {
+                    OP_INDEX => px = index[op as usize],
+                    OP_DIFF => {
+                        px[0] = px[0].wrapping_add((op >> 4 & 3).wrapping_sub(2));
+                        px[1] = px[1].wrapping_add((op >> 2 & 3).wrapping_sub(2));
+                        px[2] = px[2].wrapping_add((op & 3).wrapping_sub(2));
+                    }
+                    OP_LUMA => {
+                        let rb = byte()?;
+                        let dg = (op & 0x3F).wrapping_sub(32);
+                        px[0] = px[0].wrapping_add(dg.wrapping_sub(8).wrapping_add(rb >> 4));
+                        px[1] = px[1].wrapping_add(dg);
+                        px[2] = px[2].wrapping_add(dg.wrapping_sub(8).wrapping_add(rb & 0x0F));
+                    }
+                    // OP_RUN, this pixel and `run` more
+                    _ => run = op & 0x3F,
+                },
+            }
+            index[hash(px)] = px;
+        }
+        data.extend_from_slice(&px[..channels]);
+    }
+    if channels == 4 {
+        RgbaImage::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+    } else {
+        RgbImage::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+    }
+    .ok_or_else(|| invalid("QOI image data is truncated"))
+}
+
+/// Encode an image as QOI, with an alpha channel only if the image has one
+pub fn encode(image: &DynamicImage, writer: impl Write) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    let (width, height) = (image.width(), image.height());
+    let (data, channels) =
+        if image.color().has_alpha() { (image.to_rgba8().into_raw(), 4) } else { (image.to_rgb8().into_raw(), 3) };
+    writer.write_all(MAGIC)?;
+    writer.write_all(&width.to_be_bytes())?;
+    writer.write_all(&height.to_be_bytes())?;
+    // sRGB with linear alpha
+    writer.write_all(&[channels as u8, 0])?;
+    let mut prev = [0, 0, 0, 255];
+    let mut index = [[0u8; 4]; 64];
+    let mut run = 0u8;
+    for p in data.chunks_exact(channels) {
+        let px = [p[0], p[1], p[2], if channels == 4 { p[3] } else { 255 }];
+        if px == prev {
+            run += 1;
+            if run == 62 {
+                writer.write_all(&[OP_RUN | (run - 1)])?;
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            writer.write_all(&[OP_RUN | (run - 1)])?;
+            run = 0;
+        }
+        let h = hash(px);
+        if index[h] == px {
+            writer.write_all(&[OP_INDEX | h as u8])?;
+        } else {
+            index[h] = px;
+            if px[3] == prev[3] {
+                let dr = px[0].wrapping_sub(prev[0]) as i8;
+                let dg = px[1].wrapping_sub(prev[1]) as i8;
+                let db = px[2].wrapping_sub(prev[2]) as i8;
+                let (dr_dg, db_dg) = (dr.wrapping_sub(dg), db.wrapping_sub(dg));
+                if (-2..2).contains(&dr) && (-2..2).contains(&dg) && (-2..2).contains(&db) {
+                    writer.write_all(&[OP_DIFF | ((dr + 2) as u8) << 4 | ((dg + 2) as u8) << 2 | (db + 2) as u8])?;
+                } else if (-32..32).contains(&dg) && (-8..8).contains(&dr_dg) && (-8..8).contains(&db_dg) {
+                    writer.write_all(&[OP_LUMA | (dg + 32) as u8, ((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8])?;
+                } else {
+                    writer.write_all(&[OP_RGB, px[0], px[1], px[2]])?;
+                }
+            } else {
+                writer.write_all(&[OP_RGBA, px[0], px[1], px[2], px[3]])?;
+            }
+        }
+        prev = px;
+    }
+    if run > 0 {
+        writer.write_all(&[OP_RUN | (run - 1)])?;
+    }
+    writer.write_all(&END)?;
+    writer.flush()
+}
@@ -0,0 +1,334 @@
+//! Camera RAW photos (feature `raw`), developed from their sensor data with rawloader with exposure compensation and
+//! white balance, or displayed from the largest JPEG preview the camera embeds in the file.
+use std::{
+    collections::HashSet,
+    io::{Cursor, Read, Seek, SeekFrom},
+};
+
+use image::{codecs::jpeg::JpegDecoder, RgbImage};
+use rawloader::{RawImage, RawImageData};
+
+use super::{hdr::from_linear, *};
+
+/// The TIFF tag of the compression of an IFD
+const COMPRESSION: u16 = 0x103;
+/// The TIFF tag of the offsets of the strips of an IFD
+const STRIP_OFFSETS: u16 = 0x111;
+/// The TIFF tag of the orientation of the image
+const ORIENTATION: u16 = 0x112;
+/// The TIFF tag of the byte counts of the strips of an IFD
+const STRIP_BYTE_COUNTS: u16 = 0x117;
+/// The TIFF tag of the offsets of child IFDs, holding the previews of DNG, NEF, and ARW files
+const SUB_IFDS: u16 = 0x14A;
+/// The TIFF tag of the offset of an embedded JPEG
+const JPEG_OFFSET: u16 = 0x201;
+/// The TIFF tag of the length of an embedded JPEG
+const JPEG_LENGTH: u16 = 0x202;
+/// The TIFF tag of the offset of the EXIF IFD
+const EXIF_IFD: u16 = 0x8769;
+/// The most IFDs to read, so a corrupt file with cyclic offsets can't loop forever
+const MAX_IFDS: usize = 64;
+/// The most values to read of a single tag
+const MAX_VALUES: u32 = 64;
+/// The matrix converting linear sRGB to CIE XYZ (D65)
+const SRGB_TO_XYZ: [[f32; 3]; 3] =
+    [[0.412453, 0.357580, 0.180423], [0.212671, 0.715160, 0.072169], [0.019334, 0.119193, 0.950227]];
+
+/// Develop the sensor data of the RAW file at `path` with the exposure compensation and white balance of `conf`,
+/// rotated upright, or show the largest embedded preview as shot when rawloader can't read the camera's data or
+/// the developed photo would exceed the memory budget
+pub fn decode(conf: &Config, display: impl FnOnce((usize, usize)) -> Option<(usize, usize)>) -> Result<DynamicImage> {
+    trace!("raw::decode");
+    match rawloader::decode(&mut BufReader::new(Counted::new(File::open(&conf.image)?))) {
+        Ok(raw) => {
+            let img_sz = developed_dimensions(&raw)?;
+            match Budget::new(conf).map(|b| b.for_decode(display(img_sz))).transpose()?.filter(|b| !b.fits(img_sz)) {
+                Some(b) => debug!("decode", "showing the preview instead: {}", b.exceeded(img_sz)),
+                None => {
+                    let image = develop(&raw, conf.exposure, conf.white_balance)?;
+                    return Ok(orient(image, raw.orientation.to_u16() as u32));
+                }
+            }
+        }
+        Err(e) => debug!("decode", "showing the preview instead: {}", e),
+    }
+    preview(conf)
+}
+
+/// Decode the largest embedded preview of the RAW file at `path`, rotated upright
+fn preview(conf: &Config) -> Result<DynamicImage> {
+    let mut reader = BufReader::new(File::open(&conf.image)?);
+    let len = reader.get_ref().metadata()?.len();
+    let (mut previews, orientation) = previews(&mut reader)?;
+    previews.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+    for (offset, size) in previews.into_iter().filter(|&(offset, size)| offset.saturating_add(size) <= len) {
+        let mut jpeg = vec![0; size as usize];
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(&mut jpeg)?;
+        // lossless JPEG holds the sensor data, which the baseline decoder can't read
+        match JpegDecoder::new(Cursor::new(jpeg)).and_then(DynamicImage::from_decoder) {
+            Ok(image) => {
+                debug!("decode", "showing the {}x{} preview at {}", image.width(), image.height(), offset);
+                return Ok(orient(image, orientation));
+            }
+            Err(e) => debug!("decode", "skipping the preview at {}: {}", offset, e),
+        }
+    }
+    Err(anyhow!("{} has no sensor data or embedded preview that can be decoded", conf.image.display()))
+}
+
+/// The dimensions of the sensor data of a RAW photo once cropped and developed, which demosaics each square of the
+/// color filter array holding every color into a single pixel
+fn developed_dimensions(raw: &RawImage) -> Result<(usize, usize)> {
+    let n = block(raw)?;
+    let [top, right, bottom, left] = raw.crops;
+    let (w, h) = (raw.width.saturating_sub(left + right), raw.height.saturating_sub(top + bottom));
+    match (w / n, h / n) {
+        (0, _) | (_, 0) => Err(anyhow!("the sensor data of {} {} is empty", raw.make, raw.model)),
+        dims => Ok(dims),
+    }
+}
+
+/// The side of the smallest square of the color filter array of a RAW photo holding every color, 2 for Bayer and 3
+/// for X-Trans sensors, or 1 for monochrome and RGB sensor data
+fn block(raw: &RawImage) -> Result<usize> {
+    if raw.cpp == 3 || raw.is_monochrome() {
+        return Ok(1);
+    }
+    let cfa = &raw.cropped_cfa();
+    // the pattern repeats every 48 rows and columns
+    let colors = |y: usize, x: usize, n: usize| {
+        (y..y + n).flat_map(|y| (x..x + n).map(move |x| 1u8 << cfa.color_at(y, x))).fold(0, |a, c| a | c)
+    };
+    let all = colors(0, 0, 48);
+    [1, 2, 3, 4, 6]
+        .into_iter()
+        .find(|&n| (0..48).step_by(n).all(|y| (0..48).step_by(n).all(|x| colors(y, x, n) == all)))
+        .ok_or_else(|| anyhow!("the {} color filter array of {} {} isn't supported", cfa.name, raw.make, raw.model))
+}
+
+/// Develop the sensor data of a RAW photo to sRGB, demosaicing each square of the color filter array holding every
+/// color into a single pixel, then balancing its white and brightening (or darkening) it by `exposure` stops in the
+/// linear light of the camera, before converting it to sRGB with the camera's color matrix
+/// ```
+/// use rawloader::{Orientation, RawImage, RawImageData, CFA};
+/// use viuwa::{raw::develop, WhiteBalance};
+/// // a 2x2 RGGB sensor that saw a neutral gray under a light twice as red as it is blue
+/// let raw = RawImage {
+///     make: "Test".into(),
+///     model: "Camera".into(),
+///     clean_make: "Test".into(),
+///     clean_model: "Camera".into(),
+///     width: 2,
+///     height: 2,
+///     cpp: 1,
+///     wb_coeffs: [0.5, 1., 1., f32::NAN],
+///     whitelevels: [4095; 4],
+///     blacklevels: [0; 4],
+///     xyz_to_cam: [[0.; 3]; 4],
+///     cfa: CFA::new("RGGB"),
+///     crops: [0; 4],
+///     blackareas: Vec::new(),
+///     orientation: Orientation::Normal,
+///     data: RawImageData::Integer(vec![800, 400, 400, 200]),
+/// };
+/// let auto = develop(&raw, 0., WhiteBalance::Auto).unwrap().into_rgb8();
+/// assert_eq!(auto[(0, 0)][0], auto[(0, 0)][1]);
+/// assert_eq!(auto[(0, 0)][2], auto[(0, 0)][1]);
+/// let shot = develop(&raw, 0., WhiteBalance::Camera).unwrap().into_rgb8();
+/// assert!(shot[(0, 0)][2] < shot[(0, 0)][1]);
+/// let brighter = develop(&raw, 1., WhiteBalance::Auto).unwrap().into_rgb8();
+/// assert!(brighter[(0, 0)][1] > auto[(0, 0)][1]);
+/// ```
+pub fn develop(raw: &RawImage, exposure: f32, white_balance: WhiteBalance) -> Result<DynamicImage> {
+    let n = block(raw)?;
+    let (w, h) = developed_dimensions(raw)?;
+    let (top, left) = (raw.crops[0], raw.crops[3]);
+    let cfa = raw.cropped_cfa();
+    let mono = raw.cpp == 1 && n == 1;
+    let levels: [(f32, f32); 4] = std::array::from_fn(|c| {
+        let (black, white) = (raw.blacklevels[c] as f32, raw.whitelevels[c] as f32);
+        (black, 1. / (white - black).max(1.))
+    });
+    let sample = |i: usize| match &raw.data {
+        RawImageData::Integer(data) => data[i] as f32,
+        RawImageData::Float(data) => data[i],
+    };
+    // the mean of each color of a square of sensor data, in linear light from 0 at the black level to 1 at the white
+    let cell = |x: usize, y: usize| {
+        let (mut sums, mut counts) = ([0f32; 4], [0u32; 4]);
+        for row in y * n..y * n + n {
+            for col in x * n..x * n + n {
+                let i = (top + row) * raw.width + left + col;
+                if raw.cpp == 3 {
+                    for (c, sum) in sums.iter_mut().take(3).enumerate() {
+                        *sum += (sample(i * 3 + c) - levels[c].0) * levels[c].1;
+                    }
+                    counts[..3].iter_mut().for_each(|c| *c += 1);
+                } else {
+                    let c = if mono { 1 } else { cfa.color_at(row, col) };
+                    sums[c] += (sample(i) - levels[c].0) * levels[c].1;
+                    counts[c] += 1;
+                }
+            }
+        }
+        let mut cam: [f32; 4] = std::array::from_fn(|c| if counts[c] > 0 { sums[c].max(0.) / counts[c] as f32 } else { 0. });
+        if mono {
+            (cam[0], cam[2]) = (cam[1], cam[1]);
+        }
+        cam
+    };
+    let valid = |wb: [f32; 4]| wb[..3].iter().all(|c| c.is_finite() && *c > 0.);
+    let mut gains = match white_balance {
+        _ if mono => [1.; 4],
+        WhiteBalance::Camera if valid(raw.wb_coeffs) => raw.wb_coeffs,
+        WhiteBalance::Camera if valid(raw.neutralwb()) => raw.neutralwb(),
+        WhiteBalance::Camera => [1.; 4],
+        WhiteBalance::Auto => {
+            // gray world, scale each color so its average matches green
+            let mut sums = [0f64; 4];
+            for y in 0..h {
+                for x in 0..w {
+                    for (sum, c) in sums.iter_mut().zip(cell(x, y)) {
+                        *sum += c as f64;
+                    }
+                }
+            }
+            std::array::from_fn(|c| if sums[c] > 0. { (sums[1] / sums[c]) as f32 } else { 1. })
+        }
+    };
+    if !(gains[3].is_finite() && gains[3] > 0.) {
+        gains[3] = gains[1];
+    }
+    let gains: [f32; 4] = std::array::from_fn(|c| gains[c] / gains[1]);
+    let cam_to_rgb = match raw.xyz_to_cam {
+        m if mono || m.iter().flatten().all(|&v| v == 0.) => [[1., 0., 0., 0.], [0., 1., 0., 0.], [0., 0., 1., 0.]],
+        xyz_to_cam => {
+            let rgb_to_cam: [[f32; 3]; 4] = std::array::from_fn(|i| {
+                std::array::from_fn(|j| (0..3).map(|k| xyz_to_cam[i][k] * SRGB_TO_XYZ[k][j]).sum())
+            });
+            RawImage::normalized_pseudoinverse(rgb_to_cam)
+        }
+    };
+    let exposure = 2f32.powf(exposure);
+    let mut rgb = RgbImage::new(w as u32, h as u32);
+    for (x, y, p) in rgb.enumerate_pixels_mut() {
+        // clip the balanced colors at the white level, so blown highlights stay white
+        let cam: [f32; 4] = std::array::from_fn(|c| (cell(x as usize, y as usize)[c] * gains[c]).min(1.) * exposure);
+        for (v, row) in p.0.iter_mut().zip(&cam_to_rgb) {
+            *v = (from_linear(row.iter().zip(&cam).map(|(m, c)| m * c).sum()) * 255.).round() as u8;
+        }
+    }
+    Ok(DynamicImage::ImageRgb8(rgb))
+}
+
+/// Rotate and mirror an image upright by its TIFF orientation
+fn orient(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+/// The byte ranges (offset and size) of the JPEGs embedded in a TIFF-based RAW file, and the orientation of the photo
+fn previews(reader: &mut (impl Read + Seek)) -> Result<(Vec<(u64, u64)>, u32)> {
+    let mut header = [0; 8];
+    reader.read_exact(&mut header)?;
+    let mut ifd = IfdReader {
+        reader,
+        big_endian: match &header[..2] {
+            b"II" => false,
+            b"MM" => true,
+            _ => return Err(anyhow!("not a TIFF-based RAW file, only CR2, NEF, ARW, DNG, and similar are supported")),
+        },
+    };
+    let mut previews = Vec::new();
+    let mut orientation = 1;
+    let mut queue = vec![ifd.u32_at(&header[4..])];
+    let mut seen = HashSet::new();
+    while let Some(offset) = queue.pop() {
+        if offset == 0 || seen.len() >= MAX_IFDS || !seen.insert(offset) {
+            continue;
+        }
+        let (tags, next) = ifd.read(offset as u64)?;
+        let tag = |t: u16| tags.iter().find(|(tag, _)| *tag == t).map(|(_, v)| v.as_slice());
+        if let (Some([offset, ..]), Some([size, ..])) = (tag(JPEG_OFFSET), tag(JPEG_LENGTH)) {
+            previews.push((*offset as u64, *size as u64));
+        }
+        // a JPEG compressed IFD stored in a single strip
+        if let (Some([6 | 7]), Some([offset]), Some([size])) = (tag(COMPRESSION), tag(STRIP_OFFSETS), tag(STRIP_BYTE_COUNTS))
+        {
+            previews.push((*offset as u64, *size as u64));
+        }
+        if let (true, Some([o, ..])) = (seen.len() == 1, tag(ORIENTATION)) {
+            orientation = *o;
+        }
+        queue.extend(tag(SUB_IFDS).into_iter().chain(tag(EXIF_IFD)).flatten().copied());
+        queue.push(next);
+    }
+    Ok((previews, orientation))
+}
+
+/// Reads the IFDs of a TIFF file in its byte order
+struct IfdReader<'a, R> {
+    reader: &'a mut R,
+    big_endian: bool,
+}
+impl<R: Read + Seek> IfdReader<'_, R> {
+    /// The tags of the IFD at `offset` with their SHORT or LONG values, and the offset of the next IFD
+    #[allow(clippy::type_complexity)]
+    fn read(&mut self, offset: u64) -> Result<(Vec<(u16, Vec<u32>)>, u32)> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let mut count = [0; 2];
+        self.reader.read_exact(&mut count)?;
+        let mut entries = vec![0; self.u16_at(&count) as usize * 12 + 4];
+        self.reader.read_exact(&mut entries)?;
+        let next = self.u32_at(&entries[entries.len() - 4..]);
+        let mut tags = Vec::new();
+        for entry in entries.chunks_exact(12) {
+            let (tag, kind, count) = (self.u16_at(&entry[..2]), self.u16_at(&entry[2..4]), self.u32_at(&entry[4..8]));
+            // SHORT, LONG, or IFD
+            let size = match kind {
+                3 => 2,
+                4 | 13 => 4,
+                _ => continue,
+            };
+            let count = count.min(MAX_VALUES) as usize;
+            let data = if count * size <= 4 {
+                entry[8..8 + count * size].to_vec()
+            } else {
+                let mut data = vec![0; count * size];
+                self.reader.seek(SeekFrom::Start(self.u32_at(&entry[8..]) as u64))?;
+                self.reader.read_exact(&mut data)?;
+                data
+            };
+            let values =
+                data.chunks_exact(size).map(|v| if size == 2 { self.u16_at(v) as u32 } else { self.u32_at(v) }).collect();
+            tags.push((tag, values));
+        }
+        Ok((tags, next))
+    }
+    #[inline]
+    fn u16_at(&self, b: &[u8]) -> u16 {
+        if self.big_endian {
+            u16::from_be_bytes([b[0], b[1]])
+        } else {
+            u16::from_le_bytes([b[0], b[1]])
+        }
+    }
+    #[inline]
+    fn u32_at(&self, b: &[u8]) -> u32 {
+        let b = [b[0], b[1], b[2], b[3]];
+        if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        }
+    }
+}
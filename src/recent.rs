@@ -0,0 +1,64 @@
+//! The images most recently viewed windowed, kept one path per line in the `recent` file of the data directory
+//! and reopened from the `:recent` menu.
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The most images kept in the recent list
+pub const RECENT_LIMIT: usize = 20;
+
+/// The directory viuwa keeps its data in, `viuwa` in `XDG_DATA_HOME`, `LOCALAPPDATA`, or `HOME/.local/share`, the first one set
+pub fn data_dir() -> Option<PathBuf> {
+    use std::env::*;
+    let set = |key| var(key).ok().filter(|p| !p.is_empty()).map(PathBuf::from);
+    let dir = set("XDG_DATA_HOME")
+        .or_else(|| set("LOCALAPPDATA"))
+        .or_else(|| set("HOME").map(|p| p.join(PathBuf::from_iter([".local", "share"]))))?;
+    Some(dir.join("viuwa"))
+}
+
+/// The file the recent list is kept in, if there's a data directory
+#[inline]
+pub fn recent_path() -> Option<PathBuf> { data_dir().map(|dir| dir.join("recent")) }
+
+/// The recent list with `path` moved (or added) to the front, keeping at most `limit` paths
+/// ```
+/// use std::path::PathBuf;
+/// use viuwa::pushed_recent;
+/// let list = vec![PathBuf::from("/a.png"), PathBuf::from("/b.png")];
+/// let list = pushed_recent(list, PathBuf::from("/b.png"), 2);
+/// assert_eq!(list, [PathBuf::from("/b.png"), PathBuf::from("/a.png")]);
+/// let list = pushed_recent(list, PathBuf::from("/c.png"), 2);
+/// assert_eq!(list, [PathBuf::from("/c.png"), PathBuf::from("/b.png")]);
+/// ```
+pub fn pushed_recent(mut list: Vec<PathBuf>, path: PathBuf, limit: usize) -> Vec<PathBuf> {
+    list.retain(|p| *p != path);
+    list.insert(0, path);
+    list.truncate(limit);
+    list
+}
+
+/// The recent list, most recent first, leaving out images that no longer exist
+pub fn read_recent() -> Vec<PathBuf> {
+    let Some(contents) = recent_path().and_then(|path| std::fs::read_to_string(path).ok()) else { return Vec::new() };
+    contents.lines().filter(|line| !line.is_empty()).map(PathBuf::from).filter(|path| path.is_file()).collect()
+}
+
+/// Move (or add) an image to the front of the recent list, by its absolute path
+pub fn remember_recent(image: &Path) -> io::Result<()> {
+    let Some(file) = recent_path() else { return Ok(()) };
+    let list = pushed_recent(read_recent(), image.canonicalize()?, RECENT_LIMIT);
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut contents = String::new();
+    for path in &list {
+        // a path that isn't unicode couldn't be read back
+        if let Some(path) = path.to_str().filter(|p| !p.contains('\n')) {
+            contents.push_str(path);
+            contents.push('\n');
+        }
+    }
+    std::fs::write(file, contents)
+}
@@ -0,0 +1,418 @@
+//! Rendering images to ANSI for embedding viuwa in other tools, without touching stdout or any global state.
+use image::{Luma, Rgb, Rgba};
+use viuwa_ansi::consts::SGR_DEFAULT;
+use viuwa_image::sample::RowSampler;
+
+use super::*;
+
+/// Options for [`render`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    /// The columns and rows of the area to render into
+    pub size: (u16, u16),
+    /// How to size the width within the area
+    pub width: Dimension,
+    /// How to size the height within the area
+    pub height: Dimension,
+    /// Empty cells kept around the image within the area
+    pub padding: Padding,
+    /// Where the image is placed within the area (within the padding)
+    pub align: Align,
+    /// The filter to resize with
+    pub filter: ResizeFilter,
+    /// The amount of unsharp masking applied after downscaling, 0.0..=1.0
+    pub sharpen: Sharpness,
+    /// The color type of the output
+    pub color: ColorType,
+    /// Luma correction level, 0.0..=100.0
+    pub luma_correct: f32,
+    /// The curve the luma correction is mapped to a color distance threshold with
+    pub luma_curve: LumaCurve,
+    /// The color distance threshold of the luma correction, instead of the luma correction and curve
+    pub luma_threshold: Option<u32>,
+    /// How grays are mapped to the 256 colors
+    pub gray_ramp: GrayRamp,
+    /// The colormap single-channel images are drawn with in color
+    pub colormap: Colormap,
+    /// How the character cells are drawn
+    pub renderer: Renderer,
+    /// The classes of glyphs the glyph renderer chooses between
+    pub symbols: Symbols,
+    /// The width of a character cell divided by its height
+    pub cell_aspect: f32,
+    /// The background filling the padding and alignment to the width of the area, left empty when `None`
+    pub letterbox_background: Option<UiColor>,
+    /// The background behind the cells of the image, the terminal's own when `None`
+    pub image_background: Option<UiColor>,
+}
+impl Default for RenderOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            size: (80, 24),
+            width: Default::default(),
+            height: Default::default(),
+            padding: Default::default(),
+            align: Align::TopLeft,
+            filter: FilterType::default().into(),
+            sharpen: Sharpness(0.),
+            color: Default::default(),
+            luma_correct: 100.,
+            luma_curve: LumaCurve::Cubic,
+            luma_threshold: None,
+            gray_ramp: GrayRamp::default(),
+            colormap: Colormap::Gray,
+            renderer: Renderer::Half,
+            symbols: Symbols::default(),
+            cell_aspect: DEFAULT_CELL_ASPECT,
+            letterbox_background: None,
+            image_background: None,
+        }
+    }
+}
+impl RenderOptions {
+    /// The options of a config, rendering into an area of `size`
+    #[inline]
+    pub fn from_config(conf: &Config, size: (u16, u16)) -> Self {
+        Self {
+            size,
+            width: conf.width,
+            height: conf.height,
+            padding: conf.padding,
+            align: conf.align.unwrap_or(Align::TopLeft),
+            filter: conf.resize_filter(),
+            sharpen: Sharpness(conf.sharpen),
+            color: conf.color,
+            luma_correct: conf.luma_correct,
+            luma_curve: conf.luma_curve,
+            luma_threshold: conf.luma_threshold,
+            gray_ramp: conf.gray_ramp,
+            colormap: conf.colormap,
+            renderer: conf.renderer,
+            symbols: conf.symbols,
+            cell_aspect: conf.cell_aspect.unwrap_or(DEFAULT_CELL_ASPECT),
+            letterbox_background: conf.letterbox_background,
+            image_background: conf.image_background,
+        }
+    }
+    /// The color attributes of the luma correction and colormap, from the threshold if it's set
+    #[inline]
+    pub fn color_attributes(&self) -> ColorAttributes {
+        let attrs = match self.luma_threshold {
+            Some(threshold) => ColorAttributes::from_threshold(threshold),
+            None => ColorAttributes::from_curve(self.luma_correct, self.luma_curve),
+        };
+        attrs.with_colormap(self.colormap).with_gray_ramp(self.gray_ramp)
+    }
+    /// The dimensions to resize an image of `img_sz` to, in pixels of the renderer
+    #[inline]
+    pub fn dimensions(&self, img_sz: (usize, usize)) -> (usize, usize) {
+        let img_sz = aspect_corrected(img_sz, self.cell_aspect);
+        let (_, size) = self.padding.area(self.size);
+        self.renderer.pixels(scale_dimensions(size, (self.width, self.height), img_sz))
+    }
+}
+
+/// Fit, resize, and convert `image` to ANSI, writing the rows separated by newlines to `out`.
+///
+/// Every row ends with an attribute reset and is indented by the left padding and alignment,
+/// the top padding and alignment and the bottom padding are empty lines,
+/// and no trailing newline is written without bottom padding.
+/// With a [`letterbox_background`](RenderOptions::letterbox_background), the padding and alignment are filled with it
+/// to the width of the area instead, every line a full row.
+/// ```no_run
+/// use viuwa::{render, RenderOptions};
+/// let image = image::open("img.png").unwrap();
+/// let mut out = Vec::new();
+/// render(&image, &RenderOptions { size: (40, 20), ..Default::default() }, &mut out).unwrap();
+/// ```
+pub fn render(image: &DynamicImage, opts: &RenderOptions, out: &mut impl Write) -> io::Result<()> {
+    trace!("render");
+    if image.color().has_color() {
+        render_image(&Image::from(image.to_rgb8()), opts, out)
+    } else {
+        render_image(&Image::from(image.to_luma8()), opts, out)
+    }
+}
+
+/// [`render`] an image that has already been converted
+#[inline]
+pub fn render_image<P: Pixel>(orig: &Image<P>, opts: &RenderOptions, out: &mut impl Write) -> io::Result<()>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    render_view(&orig.view(), opts, out)
+}
+
+/// [`render`] a view of pixels that have already been converted, e.g. borrowed from another library's buffer
+pub fn render_view<P: Pixel>(orig: &ImageView<P>, opts: &RenderOptions, out: &mut impl Write) -> io::Result<()>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let mut buf = resize_view(orig, opts.dimensions(orig.dimensions()), opts.filter);
+    sharpen(&mut buf, orig.dimensions(), opts.sharpen.0);
+    render_resized(&buf, opts, out)
+}
+
+/// The layout of the pixels of a [`RenderSource`], 8 bits per channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+    Gray8,
+    Rgb8,
+    /// RGB with alpha, drawn over black
+    Rgba8,
+    /// Blue, green, and red with alpha, as many screen captures are, drawn over black
+    Bgra8,
+}
+impl PixelFormat {
+    /// The number of bytes of each pixel
+    #[inline]
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Gray8 => 1,
+            Self::Rgb8 => 3,
+            Self::Rgba8 | Self::Bgra8 => 4,
+        }
+    }
+}
+
+/// Pixels borrowed from another library's buffer, e.g. a screen capture or a GPU readback,
+/// to [`render_source`] without copying them into an image first
+/// ```
+/// use viuwa::{render_source, PixelFormat, RenderOptions, RenderSource};
+/// // 2x2 BGRA pixels, each row padded to 12 bytes
+/// let data = [255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 0, 0];
+/// let source = RenderSource::from_raw_parts_strided(&data, 2, 2, 12, PixelFormat::Bgra8).unwrap();
+/// let mut out = Vec::new();
+/// render_source(&source, &RenderOptions { size: (2, 1), ..Default::default() }, &mut out).unwrap();
+/// assert!(String::from_utf8(out).unwrap().starts_with("\x1b[38;2;000;000;255;48;2;255;000;000m"));
+/// assert!(RenderSource::from_raw_parts(&data[..15], 2, 2, PixelFormat::Rgba8).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderSource<'a> {
+    data: &'a [u8],
+    width: usize,
+    height: usize,
+    /// The number of pixels from the start of one row to the start of the next
+    stride: usize,
+    format: PixelFormat,
+}
+impl<'a> RenderSource<'a> {
+    /// Borrow `width` x `height` pixels of `format` packed without padding between rows,
+    /// or `None` if there are none or `data` is too short to hold them
+    #[inline]
+    pub fn from_raw_parts(data: &'a [u8], width: usize, height: usize, format: PixelFormat) -> Option<Self> {
+        Self::from_raw_parts_strided(data, width, height, width.checked_mul(format.bytes_per_pixel())?, format)
+    }
+    /// Borrow `width` x `height` pixels of `format` where each row starts `stride` bytes after the previous one,
+    /// or `None` if there are none, `data` is too short to hold them, or `stride` isn't a whole number of pixels at least `width` long
+    pub fn from_raw_parts_strided(
+        data: &'a [u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+        format: PixelFormat,
+    ) -> Option<Self> {
+        let bpp = format.bytes_per_pixel();
+        if !stride.is_multiple_of(bpp) || width == 0 || height == 0 {
+            return None;
+        }
+        let source = Self { data, width, height, stride: stride / bpp, format };
+        // the views check the length against the width and stride
+        match format {
+            PixelFormat::Gray8 => source.view::<Luma<u8>>().map(|_| source),
+            PixelFormat::Rgb8 => source.view::<Rgb<u8>>().map(|_| source),
+            PixelFormat::Rgba8 | PixelFormat::Bgra8 => source.view::<Rgba<u8>>().map(|_| source),
+        }
+    }
+    /// The width and height in pixels
+    #[inline]
+    pub fn dimensions(&self) -> (usize, usize) { (self.width, self.height) }
+    /// The layout of the pixels
+    #[inline]
+    pub fn format(&self) -> PixelFormat { self.format }
+    #[inline]
+    fn view<P: viuwa_image::Pixel<Scalar = u8>>(&self) -> Option<ImageView<'a, P>> {
+        ImageView::from_raw_strided(self.data, self.width, self.height, self.stride)
+    }
+}
+
+/// [`render`] pixels borrowed from another library's buffer,
+/// only the resized image is allocated (and composited over black if the pixels have alpha)
+pub fn render_source(source: &RenderSource, opts: &RenderOptions, out: &mut impl Write) -> io::Result<()> {
+    trace!("render_source");
+    const CHECKED: &str = "the length is checked when the source is made";
+    match source.format {
+        PixelFormat::Gray8 => render_view::<Luma<u8>>(&source.view().expect(CHECKED), opts, out),
+        PixelFormat::Rgb8 => render_view::<Rgb<u8>>(&source.view().expect(CHECKED), opts, out),
+        PixelFormat::Rgba8 | PixelFormat::Bgra8 => {
+            let orig = source.view::<Rgba<u8>>().expect(CHECKED);
+            let mut buf = resize_view(&orig, opts.dimensions(orig.dimensions()), opts.filter);
+            sharpen(&mut buf, orig.dimensions(), opts.sharpen.0);
+            let mut rgb = Image::<Rgb<u8>>::new(buf.width(), buf.height());
+            rgb.paste_over(buf.view(), 0, 0);
+            if source.format == PixelFormat::Bgra8 {
+                rgb.pixels_mut().iter_mut().for_each(|p| p.swap(0, 2));
+            }
+            render_resized(&rgb, opts, out)
+        }
+    }
+}
+
+/// Resizes and converts an image to ANSI one character row at a time, for outputs too large to hold resized,
+/// only the source rows under a character row and that one row of resized pixels are held at once.
+///
+/// The rows are those of [`render`] without the padding, alignment, or sharpening (which needs the neighbouring rows),
+/// resized with the filter alone rather than supersampled, each ending with an attribute reset and no newline.
+/// ```
+/// use viuwa::{render_image, RenderOptions, RowStream};
+/// let image = viuwa_image::Image::from(image::RgbImage::from_fn(40, 40, |x, y| image::Rgb([x as u8 * 6, y as u8 * 6, 0])));
+/// let opts = RenderOptions { size: (10, 5), ..Default::default() };
+/// let rows: Vec<_> = RowStream::new(image.view(), &opts).collect();
+/// assert_eq!(rows.len(), 5);
+/// let mut out = Vec::new();
+/// render_image(&image, &opts, &mut out).unwrap();
+/// assert_eq!(out.split(|&b| b == b'\n').count(), rows.len());
+/// ```
+pub struct RowStream<'a, P: viuwa_image::Pixel> {
+    sampler: RowSampler<'a, P>,
+    /// The resized pixels of the character row being converted
+    buf: Image<P>,
+    /// The resized width and height in pixels
+    dims: (usize, usize),
+    /// The next character row
+    y: usize,
+    color: ColorType,
+    attrs: ColorAttributes,
+    renderer: Renderer,
+    glyphs: Vec<Glyph>,
+    /// The SGR sequence of the background behind the image, if it has one
+    background: String,
+}
+impl<'a, P: Pixel> RowStream<'a, P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// Stream the rows of `orig` fit to `opts`
+    pub fn new(orig: ImageView<'a, P>, opts: &RenderOptions) -> Self {
+        let (w, h) = opts.dimensions(orig.dimensions());
+        let dims = (w.max(1), h.max(1));
+        let filter: viuwa_image::Filter = opts.filter.into();
+        let rows = match opts.renderer {
+            Renderer::Half | Renderer::Foreground => 2,
+            Renderer::Glyph => glyph::CELL.1,
+            Renderer::Full => 1,
+        };
+        Self {
+            sampler: RowSampler::new(orig, dims, filter),
+            buf: Image::new(dims.0, rows),
+            dims,
+            y: 0,
+            color: opts.color,
+            attrs: opts.color_attributes(),
+            renderer: opts.renderer,
+            glyphs: opts.renderer.glyphs(opts.symbols).unwrap_or_default(),
+            background: opts.image_background.map(UiColor::bg).unwrap_or_default(),
+        }
+    }
+    /// The columns and rows of the cells of the streamed rows
+    #[inline]
+    pub fn cells(&self) -> (usize, usize) { self.renderer.cells(self.dims) }
+    /// Write the next character row to `out`, ending with an attribute reset and no newline,
+    /// returning `false` without writing anything once every row has been written
+    pub fn write_next_row(&mut self, out: &mut impl Write) -> io::Result<bool> {
+        if self.y >= self.cells().1 {
+            return Ok(false);
+        }
+        let top = self.y * self.buf.height();
+        let rows = self.buf.height().min(self.dims.1 - top);
+        for (dst, outy) in self.buf.rows_mut().zip(top..top + rows) {
+            let Some(row) = self.sampler.get_row(outy) else {
+                break;
+            };
+            for (dst_px, px) in dst.iter_mut().zip(row) {
+                *dst_px = px;
+            }
+        }
+        self.y += 1;
+        let view = self.buf.view().subview(0, 0, self.dims.0, rows).expect("rows are within the buffer");
+        let ansi = DynamicAnsiImage::new(view, self.color);
+        out.write_all(self.background.as_bytes())?;
+        match self.renderer {
+            Renderer::Half => ansi.write_row_to(out, 0, self.attrs, None),
+            Renderer::Glyph => ansi.write_glyph_row_to(out, 0, self.attrs, &self.glyphs),
+            Renderer::Foreground => ansi.write_fg_row_to(out, 0, self.attrs, Renderer::BACKGROUND_LUMA),
+            Renderer::Full => ansi.write_full_row_to(out, 0, self.attrs),
+        }?;
+        out.write_all(SGR_DEFAULT.as_bytes())?;
+        Ok(true)
+    }
+}
+impl<P: Pixel> Iterator for RowStream<'_, P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    type Item = Vec<u8>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut row = Vec::new();
+        // writing to a Vec never fails
+        self.write_next_row(&mut row).ok()?.then_some(row)
+    }
+}
+
+/// Convert an image already resized for `opts` to ANSI, aligned and padded within the area
+fn render_resized<P: Pixel>(buf: &Image<P>, opts: &RenderOptions, out: &mut impl Write) -> io::Result<()>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let ansi = DynamicAnsiImage::new(ImageView::from(buf), opts.color);
+    let attrs = opts.color_attributes();
+    let ((left, top), area) = opts.padding.area(opts.size);
+    let (w, h) = opts.renderer.cells(buf.dimensions());
+    let (x, y) = opts.align.offset(area, (w as u16, h as u16));
+    let (left, top) = (left + x, top + y);
+    let letterbox = opts.letterbox_background.map(UiColor::bg);
+    let background = opts.image_background.map(UiColor::bg).unwrap_or_default();
+    let width = opts.size.0 as usize;
+    for _ in 0..top {
+        if letterbox.is_some() {
+            fill(out, letterbox.as_deref(), width)?;
+        }
+        out.write_all(b"\n")?;
+    }
+    let mut row_bufs = RowBuffers::default();
+    for (y, row) in row_bufs.convert(&ansi, 0..h, opts.renderer, opts.symbols, attrs).iter().enumerate() {
+        if y != 0 {
+            out.write_all(b"\n")?;
+        }
+        fill(out, letterbox.as_deref(), left as usize)?;
+        out.write_all(background.as_bytes())?;
+        out.write_all(row)?;
+        out.write_all(SGR_DEFAULT.as_bytes())?;
+        if letterbox.is_some() {
+            fill(out, letterbox.as_deref(), width.saturating_sub(left as usize + w))?;
+        }
+    }
+    for _ in 0..opts.padding.bottom {
+        out.write_all(b"\n")?;
+        if letterbox.is_some() {
+            fill(out, letterbox.as_deref(), width)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write `n` blank cells of the letterbox, in its `background` if it has one
+#[inline]
+fn fill(out: &mut impl Write, background: Option<&str>, n: usize) -> io::Result<()> {
+    match background {
+        Some(background) => write!(out, "{background}{:n$}{SGR_DEFAULT}", ""),
+        None => write!(out, "{:n$}", ""),
+    }
+}
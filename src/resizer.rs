@@ -0,0 +1,371 @@
+//! Background image resizing, so that supersizing large images doesn't block the event loop.
+#[cfg(not(target_family = "wasm"))]
+use std::{
+    sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError},
+    time::{Duration, Instant},
+};
+use std::{
+    collections::VecDeque,
+    sync::{atomic::AtomicU64, Arc},
+};
+
+use super::*;
+
+/// How long to wait for the high quality buffer before falling back to a nearest neighbor preview
+#[cfg(not(target_family = "wasm"))]
+const PREVIEW_DELAY: Duration = Duration::from_millis(50);
+
+/// How many resized buffers to keep around for reuse
+pub const CACHE_CAPACITY: usize = 8;
+
+/// The settings a buffer was resized with
+type CacheKey = ((usize, usize), ResizeFilter, Sharpness);
+
+/// The requests sent to the worker of a [`Resizer`] and the buffers it resized for them, by generation
+#[cfg(not(target_family = "wasm"))]
+type Worker<P> = (Sender<(u64, CacheKey)>, Receiver<(u64, Image<P>)>);
+
+/// How many samples per axis to take when supersizing
+pub const SUPERSAMPLE: usize = 3;
+
+/// The filter to resize with, one of the built-in [`FilterType`]s or a custom [`FilterPreset`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResizeFilter {
+    Builtin(FilterType),
+    Custom(FilterPreset),
+}
+impl From<FilterType> for ResizeFilter {
+    #[inline]
+    fn from(filter: FilterType) -> Self { Self::Builtin(filter) }
+}
+impl From<&FilterType> for ResizeFilter {
+    #[inline]
+    fn from(filter: &FilterType) -> Self { Self::Builtin(*filter) }
+}
+impl From<FilterPreset> for ResizeFilter {
+    #[inline]
+    fn from(preset: FilterPreset) -> Self { Self::Custom(preset) }
+}
+impl From<ResizeFilter> for viuwa_image::Filter {
+    #[inline]
+    fn from(filter: ResizeFilter) -> Self {
+        match filter {
+            ResizeFilter::Builtin(filter) => filter.into(),
+            ResizeFilter::Custom(preset) => preset.into(),
+        }
+    }
+}
+
+/// Resize `orig` to `dims` with `filter`, using the fastest available implementation
+#[inline]
+pub fn resize<P: Pixel>(orig: &Image<P>, dims: (usize, usize), filter: impl Into<ResizeFilter>) -> Image<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    trace!("resize");
+    resize_view(&orig.view(), dims, filter)
+}
+
+/// [`resize`] a view of an image, such as a [`subview`](ImageView::subview) crop.
+///
+/// Custom filters are always resized without SIMD, since fir only has the built-in filters.
+#[inline]
+pub fn resize_view<P: viuwa_image::CompatPixel>(
+    view: &ImageView<P>,
+    dims: (usize, usize),
+    filter: impl Into<ResizeFilter>,
+) -> Image<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let filter = filter.into();
+    Stage::Resize.time(|| match filter {
+        #[cfg(feature = "fir")]
+        ResizeFilter::Builtin(filter) => view.fir_supersize(dims.0, dims.1, &filter, SUPERSAMPLE as u8),
+        #[cfg(not(feature = "fir"))]
+        ResizeFilter::Builtin(filter) => view.supersize(dims.0, dims.1, filter, SUPERSAMPLE as f32),
+        #[cfg(feature = "rayon")]
+        ResizeFilter::Custom(preset) => view.par_supersize(dims.0, dims.1, preset, SUPERSAMPLE as f32),
+        #[cfg(not(feature = "rayon"))]
+        ResizeFilter::Custom(preset) => view.supersize(dims.0, dims.1, preset, SUPERSAMPLE as f32),
+    })
+}
+
+/// Sharpen a buffer resized from an image of `orig` dimensions with an unsharp mask of `amount`,
+/// if it was downscaled, to bring back some of the detail lost at terminal resolutions
+#[inline]
+pub fn sharpen<P: viuwa_image::CompatPixel>(buf: &mut Image<P>, orig: (usize, usize), amount: f32)
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    if amount > 0. && (buf.width() < orig.0 || buf.height() < orig.1) {
+        #[cfg(feature = "rayon")]
+        Stage::Resize.time(|| buf.par_sharpen(amount as _));
+        #[cfg(not(feature = "rayon"))]
+        Stage::Resize.time(|| buf.sharpen(amount as _));
+    }
+}
+
+/// [`resize`] and [`sharpen`] `orig`
+#[inline]
+fn resize_sharpened<P: Pixel>(orig: &Image<P>, dims: (usize, usize), filter: ResizeFilter, amount: f32) -> Image<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    let mut buf = resize(orig, dims, filter);
+    sharpen(&mut buf, orig.dimensions(), amount);
+    buf
+}
+
+/// The bytes of the pixels of an image
+#[inline]
+pub fn image_bytes<P: viuwa_image::Pixel>(image: &Image<P>) -> u64 { std::mem::size_of_val(image.data()) as u64 }
+
+/// Owns the original image and resizes it on a worker thread, started with the first request it's needed for,
+/// sending completed buffers back through a channel.
+///
+/// Only the most recent request is resized: the worker skips the requests queued behind a newer one,
+/// and drops the buffer of a request made stale while it was being resized.
+/// Finished buffers are kept in a small LRU cache, so returning to previous settings doesn't resize again,
+/// shared with the buffer drawn rather than copied, and within what's left of the memory budget after the original.
+/// On wasi, where threads are unavailable, every request is resized synchronously.
+pub struct Resizer<P: Pixel>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// The original image
+    orig: Arc<Image<P>>,
+    /// Generation of the most recent request, shared with the worker to skip stale requests
+    gen: Arc<AtomicU64>,
+    /// Whether the most recent request is still being resized
+    pending: bool,
+    /// The settings of the most recent request
+    key: CacheKey,
+    /// Recently resized buffers, most recently used first
+    cache: VecDeque<(CacheKey, Arc<Image<P>>)>,
+    /// The most bytes of cached buffers
+    limit: u64,
+    /// Requests to the worker by generation, and the buffers it resized for them, disconnected if it died
+    #[cfg(not(target_family = "wasm"))]
+    worker: Option<Worker<P>>,
+}
+impl<P: Pixel> Resizer<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// Create a new resizer for the original image
+    pub fn new(orig: Image<P>) -> Self {
+        Self {
+            orig: Arc::new(orig),
+            gen: Arc::default(),
+            pending: false,
+            key: ((0, 0), ResizeFilter::Builtin(FilterType::Nearest), Sharpness(0.)),
+            cache: VecDeque::with_capacity(CACHE_CAPACITY),
+            limit: u64::MAX,
+            #[cfg(not(target_family = "wasm"))]
+            worker: None,
+        }
+    }
+    /// Keep the cached buffers within what's left of `budget` after the original, if there's a budget
+    #[inline]
+    pub fn with_budget(mut self, budget: Option<Budget>) -> Self {
+        self.limit = budget.map_or(u64::MAX, |b| b.0.saturating_sub(image_bytes(&self.orig)));
+        self
+    }
+    /// The generation of the most recent request
+    #[cfg(not(target_family = "wasm"))]
+    #[inline(always)]
+    fn gen(&self) -> u64 { self.gen.load(Ordering::SeqCst) }
+    /// Make every request so far stale, returning the generation of the next
+    #[inline]
+    fn next_gen(&mut self) -> u64 { self.gen.fetch_add(1, Ordering::SeqCst).wrapping_add(1) }
+    /// Start the worker, resizing the latest of the requests it's sent until the resizer is dropped
+    #[cfg(not(target_family = "wasm"))]
+    fn spawn_worker(&self) -> io::Result<Worker<P>> {
+        let (req_tx, req_rx) = channel::<(u64, CacheKey)>();
+        // only the worker holds the sender, so a worker that panicked (e.g. out of memory) disconnects the receiver
+        let (tx, rx) = channel();
+        let (orig, gen) = (self.orig.clone(), self.gen.clone());
+        std::thread::Builder::new().name(String::from("resizer")).spawn(move || {
+            while let Ok(mut req) = req_rx.recv() {
+                while let Ok(newer) = req_rx.try_recv() {
+                    req = newer;
+                }
+                let (req_gen, (dims, filter, Sharpness(amount))) = req;
+                if req_gen != gen.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let buf = resize_sharpened(&orig, dims, filter, amount);
+                // requested again or cancelled while it was resized
+                if req_gen == gen.load(Ordering::SeqCst) && tx.send((req_gen, buf)).is_err() {
+                    return;
+                }
+            }
+        })?;
+        Ok((req_tx, rx))
+    }
+    /// The original image
+    #[inline(always)]
+    pub fn orig(&self) -> &Image<P> { &self.orig }
+    /// Whether a high quality buffer is still being resized
+    #[inline(always)]
+    pub fn pending(&self) -> bool { self.pending }
+    /// Drop the high quality buffer being resized, for when the buffer is no longer drawn from a request
+    #[inline]
+    pub fn cancel(&mut self) {
+        self.next_gen();
+        self.pending = false;
+    }
+    /// Take a cached buffer for the settings, moving it to the front of the cache
+    fn cached(&mut self, key: CacheKey) -> Option<Arc<Image<P>>> {
+        let i = self.cache.iter().position(|(k, _)| *k == key)?;
+        let entry = self.cache.remove(i)?;
+        let buf = entry.1.clone();
+        self.cache.push_front(entry);
+        Some(buf)
+    }
+    /// Cache a finished buffer, evicting the least recently used buffers if full or over the budget,
+    /// returning it to be drawn
+    fn cache(&mut self, key: CacheKey, buf: Image<P>) -> Arc<Image<P>> {
+        let buf = Arc::new(buf);
+        let bytes = image_bytes(&buf);
+        if bytes > self.limit {
+            self.cache.clear();
+            return buf;
+        }
+        let mut used = self.cache.iter().map(|(_, b)| image_bytes(b)).sum::<u64>();
+        while self.cache.len() >= CACHE_CAPACITY || used + bytes > self.limit {
+            let Some((_, evicted)) = self.cache.pop_back() else { break };
+            used -= image_bytes(&evicted);
+        }
+        self.cache.push_front((key, buf.clone()));
+        buf
+    }
+    /// Request a new buffer, returning a buffer that can be drawn immediately.
+    ///
+    /// If the high quality buffer takes too long, a nearest neighbor preview (without sharpening) is returned instead,
+    /// and the high quality buffer can be received later with [`try_recv`](Self::try_recv).
+    #[cfg(not(target_family = "wasm"))]
+    pub fn request(&mut self, dims: (usize, usize), filter: ResizeFilter, sharpen: f32) -> Arc<Image<P>> {
+        trace!("Resizer::request");
+        let gen = self.next_gen();
+        self.key = (dims, filter, Sharpness(sharpen));
+        self.pending = false;
+        if let Some(buf) = self.cached(self.key) {
+            return buf;
+        }
+        if self.worker.is_none() && filter != ResizeFilter::Builtin(FilterType::Nearest) {
+            match self.spawn_worker() {
+                Ok(worker) => self.worker = Some(worker),
+                Err(e) => debug!("Resizer", "could not start resizing in the background: {}", e),
+            }
+        }
+        let sent = match &self.worker {
+            Some((worker, _)) if filter != ResizeFilter::Builtin(FilterType::Nearest) => {
+                worker.send((gen, self.key)).is_ok()
+            }
+            _ => false,
+        };
+        if !sent {
+            return self.cache(self.key, resize_sharpened(&self.orig, dims, filter, sharpen));
+        }
+        self.pending = true;
+        let deadline = Instant::now() + PREVIEW_DELAY;
+        while let Some((_, rx)) = &self.worker {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok((gen, buf)) if gen == self.gen() => {
+                    self.pending = false;
+                    return self.cache(self.key, buf);
+                }
+                Ok(_) => (),
+                Err(RecvTimeoutError::Timeout) => break,
+                // started again with the next request
+                Err(RecvTimeoutError::Disconnected) => {
+                    debug!("Resizer", "the worker stopped, resizing synchronously");
+                    self.worker = None;
+                    self.pending = false;
+                    return self.cache(self.key, resize_sharpened(&self.orig, dims, filter, sharpen));
+                }
+            }
+        }
+        Arc::new(resize(&self.orig, dims, FilterType::Nearest))
+    }
+    /// Request a new buffer, returning a buffer that can be drawn immediately.
+    #[cfg(target_family = "wasm")]
+    pub fn request(&mut self, dims: (usize, usize), filter: ResizeFilter, sharpen: f32) -> Arc<Image<P>> {
+        trace!("Resizer::request");
+        self.next_gen();
+        self.key = (dims, filter, Sharpness(sharpen));
+        if let Some(buf) = self.cached(self.key) {
+            return buf;
+        }
+        self.cache(self.key, resize_sharpened(&self.orig, dims, filter, sharpen))
+    }
+    /// Wait for the high quality buffer of the most recent request, if it's still being resized,
+    /// failing if the worker resizing it stopped (e.g. it ran out of memory)
+    /// ```
+    /// use viuwa::Resizer;
+    /// use viuwa_image::{FilterPreset, Image};
+    /// let mut resizer = Resizer::new(Image::from(image::RgbImage::new(1024, 1024)));
+    /// // a newer request makes the one before it stale, so it's never drawn
+    /// resizer.request((512, 512), FilterPreset::Lanczos2.into(), 0.);
+    /// let preview = resizer.request((64, 64), FilterPreset::Lanczos2.into(), 0.);
+    /// let buf = resizer.wait().unwrap().unwrap_or(preview);
+    /// assert_eq!(buf.dimensions(), (64, 64));
+    /// assert!(!resizer.pending());
+    /// ```
+    #[cfg(not(target_family = "wasm"))]
+    pub fn wait(&mut self) -> Result<Option<Arc<Image<P>>>> {
+        while self.pending {
+            let received = self.worker.as_ref().and_then(|(_, rx)| rx.recv().ok());
+            let Some((gen, buf)) = received else {
+                self.worker = None;
+                self.pending = false;
+                return Err(anyhow!("The resizer stopped before the image was resized"));
+            };
+            if gen == self.gen() {
+                self.pending = false;
+                return Ok(Some(self.cache(self.key, buf)));
+            }
+        }
+        Ok(None)
+    }
+    /// Wait for the high quality buffer of the most recent request, never pending without threads
+    #[cfg(target_family = "wasm")]
+    #[inline]
+    pub fn wait(&mut self) -> Result<Option<Arc<Image<P>>>> { Ok(None) }
+    /// Receive the high quality buffer of the most recent request, if it's ready
+    ///
+    /// If the worker stopped (e.g. it ran out of memory), the request is no longer pending, and the worker is started
+    /// again with the next request.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn try_recv(&mut self) -> Option<Arc<Image<P>>> {
+        let (_, rx) = self.worker.as_ref()?;
+        loop {
+            match rx.try_recv() {
+                Ok((gen, buf)) if gen == self.gen() => {
+                    self.pending = false;
+                    return Some(self.cache(self.key, buf));
+                }
+                Ok(_) => (),
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    debug!("Resizer", "the worker stopped before the image was resized");
+                    self.worker = None;
+                    self.pending = false;
+                    return None;
+                }
+            }
+        }
+    }
+    /// Receive the high quality buffer of the most recent request, never pending without threads
+    #[cfg(target_family = "wasm")]
+    #[inline]
+    pub fn try_recv(&mut self) -> Option<Arc<Image<P>>> { None }
+}
@@ -0,0 +1,78 @@
+//! Restoring the terminal when viuwa exits unexpectedly, from a panic or a signal,
+//! so that the user's shell isn't left in the alternate screen or raw mode.
+#[cfg(unix)]
+use std::fs::File;
+use std::{
+    mem::ManuallyDrop,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+};
+
+use super::*;
+
+/// Whether the terminal is set up for the TUI and should be restored on exit
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Mark the terminal as set up (or no longer set up) for the TUI
+#[inline]
+pub fn set_terminal_active(active: bool) { ACTIVE.store(active, Ordering::SeqCst) }
+
+/// Leave the alternate screen, re-enable line wrap, show the cursor, and disable raw mode,
+/// if the terminal is still set up for the TUI
+pub fn restore_terminal() {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    // stdout may be locked or mid-write by the panicking thread, so write to the raw file descriptor
+    #[cfg(unix)]
+    let mut out = ManuallyDrop::new(unsafe { <File as std::os::fd::FromRawFd>::from_raw_fd(1) });
+    #[cfg(not(unix))]
+    let mut out = ManuallyDrop::new(stdout());
+    let _ = execute!(
+        *out,
+        disable_mouse(),
+        enable_line_wrap(),
+        cursor_show(),
+        exit_alt_screen(),
+        disable_raw_mode(),
+        soft_reset(),
+        flush()
+    );
+}
+
+/// Install a panic hook, and on unix SIGINT, SIGTERM, and SIGHUP handlers, that restore the terminal before exiting
+pub fn install_restore_hooks() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let prev = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // the worker threads are safe to panic, the event loop keeps running
+            if std::thread::current().name() == Some("main") {
+                restore_terminal();
+            }
+            prev(info);
+        }));
+        #[cfg(unix)]
+        {
+            use signal_hook::{
+                consts::{SIGHUP, SIGINT, SIGTERM},
+                iterator::Signals,
+                low_level::emulate_default_handler,
+            };
+            match Signals::new([SIGINT, SIGTERM, SIGHUP]) {
+                Ok(mut signals) => {
+                    std::thread::spawn(move || {
+                        if let Some(signal) = signals.forever().next() {
+                            restore_terminal();
+                            let _ = emulate_default_handler(signal);
+                            std::process::exit(128 + signal);
+                        }
+                    });
+                }
+                Err(e) => warn!("could not install signal handlers: {}", e),
+            }
+        }
+    });
+}
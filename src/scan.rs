@@ -0,0 +1,126 @@
+//! Finding the images of a directory for the grid, filtered by the globs of `--grid-filter`, in the order of `--sort`,
+//! and with `--recursive`, in its subdirectories too.
+//!
+//! Subdirectories are walked with [ignore](https://docs.rs/ignore) with the `ignore` feature, skipping hidden files
+//! and the files of `.gitignore` and `.ignore` files like ripgrep, or skipping only hidden files without it.
+use std::{
+    hash::{BuildHasher, Hasher},
+    path::{Path, PathBuf},
+};
+
+use super::*;
+
+/// Whether `name` matches `glob`, where `*` matches any run of characters and `?` any one character, ignoring ASCII case
+/// ```
+/// use viuwa::glob_match;
+/// assert!(glob_match("*.png", "cat.PNG"));
+/// assert!(glob_match("IMG_????.*", "img_0001.jpg"));
+/// assert!(!glob_match("*.png", "cat.png.txt"));
+/// ```
+pub fn glob_match(glob: &str, name: &str) -> bool {
+    let (glob, name) = (glob.chars().collect::<Vec<_>>(), name.chars().collect::<Vec<_>>());
+    let (mut g, mut n) = (0, 0);
+    // where to retry after the last `*`, matching one more character with it
+    let mut star = None;
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                star = Some((g, n));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c.eq_ignore_ascii_case(&name[n]) => {
+                g += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((sg, sn)) => {
+                    star = Some((sg, sn + 1));
+                    (g, n) = (sg + 1, sn + 1);
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+/// Whether the filename of `path` matches any of the comma separated `globs`
+#[inline]
+pub fn matches_globs(globs: &str, path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    globs.split(',').map(str::trim).filter(|glob| !glob.is_empty()).any(|glob| glob_match(glob, &name))
+}
+
+/// Sort `items` by the files at their `path` in `order`
+pub fn sort_by_order<T>(items: &mut [T], order: SortOrder, path: impl Fn(&T) -> &Path) {
+    match order {
+        SortOrder::Name => items.sort_by(|a, b| path(a).cmp(path(b))),
+        SortOrder::Mtime => {
+            items.sort_by_cached_key(|item| std::cmp::Reverse(path(item).metadata().and_then(|m| m.modified()).ok()))
+        }
+        SortOrder::Size => items.sort_by_cached_key(|item| std::cmp::Reverse(path(item).metadata().map_or(0, |m| m.len()))),
+        SortOrder::Random => {
+            // xorshift seeded by the random keys std gives every hasher, good enough to shuffle with
+            let mut seed = std::collections::hash_map::RandomState::new().build_hasher().finish() | 1;
+            for i in (1..items.len()).rev() {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                items.swap(i, (seed % (i as u64 + 1)) as usize);
+            }
+        }
+    }
+}
+
+/// The images in `dir`, and its subdirectories with `--recursive`, matching the globs of `--grid-filter`,
+/// in the order of `--sort`
+pub fn images_in(dir: &Path, conf: &Config) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    if conf.recursive {
+        walk(dir, &mut paths)?;
+    } else {
+        for entry in std::fs::read_dir(dir)? {
+            paths.push(entry?.path());
+        }
+    }
+    paths.retain(|path| {
+        path.is_file() && is_image(path) && conf.grid_filter.as_deref().is_none_or(|globs| matches_globs(globs, path))
+    });
+    sort_by_order(&mut paths, conf.sort, PathBuf::as_path);
+    Ok(paths)
+}
+
+/// Push the files in `dir` and its subdirectories to `paths`, skipping hidden and ignored files
+#[cfg(all(feature = "ignore", not(target_family = "wasm")))]
+fn walk(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    // .gitignore files are followed outside of git repositories too
+    for entry in ignore::WalkBuilder::new(dir).require_git(false).build() {
+        match entry {
+            Ok(entry) => paths.push(entry.into_path()),
+            Err(e) => debug!("walk", "skipping {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Push the files in `dir` and its subdirectories to `paths`, skipping hidden files
+#[cfg(not(all(feature = "ignore", not(target_family = "wasm"))))]
+fn walk(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+            continue;
+        }
+        // links aren't followed into directories, like the `ignore` walk, so a link to an ancestor can't recurse forever
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if let Err(e) = walk(&path, paths) {
+                debug!("walk", "skipping {}: {}", path.display(), e);
+            }
+        } else if !(file_type.is_symlink() && path.is_dir()) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Frame timings for the stats overlay, the time spent in each stage of getting an image onto the terminal
+//! and a rolling frame rate. Every stage recorded is also a span of the `--trace-out` trace.
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// How many of the most recent frames the frame rate is averaged over
+const FPS_WINDOW: usize = 16;
+/// Frames older than this are left out of the frame rate, so it doesn't average over the time spent idle
+const FPS_MAX_AGE: Duration = Duration::from_secs(1);
+
+/// Nanoseconds spent in each stage since the last frame, recorded from any thread
+static SPENT: [AtomicU64; 4] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+/// A stage of getting an image onto the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Decoding the image, or rendering a page or frame of it
+    Decode = 0,
+    /// Resizing and sharpening the image to the cells drawn
+    Resize = 1,
+    /// Converting the resized image to ANSI
+    Convert = 2,
+    /// Writing the ANSI to the terminal
+    Write = 3,
+}
+impl Stage {
+    pub const ALL: [Self; 4] = [Self::Decode, Self::Resize, Self::Convert, Self::Write];
+    /// The name of the stage in the overlay
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Decode => "decode",
+            Self::Resize => "resize",
+            Self::Convert => "convert",
+            Self::Write => "write",
+        }
+    }
+    /// The name of the stage's spans in the trace
+    #[cfg(feature = "trace")]
+    const fn span(self) -> &'static str {
+        match self {
+            Self::Decode => "viuwa::stats::decode",
+            Self::Resize => "viuwa::stats::resize",
+            Self::Convert => "viuwa::stats::convert",
+            Self::Write => "viuwa::stats::write",
+        }
+    }
+    /// Add time spent in this stage started at `start` to the next frame
+    #[inline]
+    pub fn record(self, start: Instant, took: Duration) {
+        SPENT[self as usize].fetch_add(took.as_nanos() as u64, Ordering::Relaxed);
+        #[cfg(feature = "trace")]
+        crate::trace_event(self.span(), start, took);
+        #[cfg(not(feature = "trace"))]
+        let _ = start;
+    }
+    /// Run `f`, recording the time it takes as spent in this stage
+    #[inline]
+    pub fn time<T>(self, f: impl FnOnce() -> T) -> T {
+        let start = crate::trace_start();
+        let res = f();
+        self.record(start, start.elapsed());
+        res
+    }
+}
+
+/// The time spent in each [`Stage`] of the last frame, and the rate frames were drawn at
+/// ```
+/// use viuwa::{FrameTimings, Stage};
+/// let mut timings = FrameTimings::default();
+/// Stage::Resize.time(|| std::thread::sleep(std::time::Duration::from_millis(2)));
+/// timings.frame();
+/// assert!(timings.last(Stage::Resize).as_millis() >= 2);
+/// assert!(timings.summary().starts_with("decode "));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrameTimings {
+    last: [Duration; 4],
+    /// When the most recent frames were drawn, oldest first
+    frames: VecDeque<Instant>,
+}
+impl FrameTimings {
+    /// End a frame, taking the time spent in each stage since the last frame as this frame's
+    pub fn frame(&mut self) {
+        for stage in Stage::ALL {
+            self.last[stage as usize] = Duration::from_nanos(SPENT[stage as usize].swap(0, Ordering::Relaxed));
+        }
+        let now = Instant::now();
+        if self.frames.len() == FPS_WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(now);
+        while self.frames.front().is_some_and(|&at| now - at > FPS_MAX_AGE) {
+            self.frames.pop_front();
+        }
+    }
+    /// The time spent in `stage` for the last frame
+    #[inline]
+    pub fn last(&self, stage: Stage) -> Duration { self.last[stage as usize] }
+    /// The frames drawn per second over the most recent frames, 0 without at least two recent frames
+    pub fn fps(&self) -> f32 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some(&first), Some(&last)) if last > first => (self.frames.len() - 1) as f32 / (last - first).as_secs_f32(),
+            _ => 0.,
+        }
+    }
+    /// The milliseconds spent in each stage of the last frame and the frame rate, e.g. `decode 0.0ms ... 24.0 fps`
+    pub fn summary(&self) -> String {
+        let mut s = String::new();
+        for stage in Stage::ALL {
+            s += &format!("{} {:.1}ms ", stage.name(), self.last(stage).as_secs_f32() * 1000.);
+        }
+        s + &format!("{:.1} fps", self.fps())
+    }
+}
@@ -0,0 +1,287 @@
+//! Streaming decoding of uncompressed images from stdin (`viuwa -`) or a FIFO, a row at a time.
+//!
+//! Pipes can only be read once and in order, so the whole file is never buffered: each row is read, converted to 8-bit,
+//! and area-averaged into the output (downscaling to fit `--max-memory`) before the next is read.
+//! Supports farbfeld and every Netpbm format (PBM, PGM, PPM in plain or raw, and PAM).
+//! ```
+//! let ppm = b"P6\n# a comment\n2 1\n255\n\xff\x00\x00\x00\x00\xff";
+//! let decoder = viuwa::RowDecoder::new(&ppm[..]).unwrap();
+//! assert_eq!(decoder.dimensions(), (2, 1));
+//! let image = decoder.decode((2, 1)).unwrap().into_rgb8();
+//! assert_eq!(image.into_raw(), [255, 0, 0, 0, 0, 255]);
+//! ```
+use std::io::{self, BufRead, BufReader};
+
+use image::{GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+
+use super::*;
+
+/// The path that reads the image from stdin
+pub const STDIN_PATH: &str = "-";
+/// The most pixels to decode, a header claiming more is assumed corrupt
+const MAX_PIXELS: u64 = 1 << 32;
+
+/// How the samples of a row are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    /// Big-endian 16-bit rgba
+    Farbfeld,
+    /// Packed bits, 1 is black (PBM raw)
+    Bits,
+    /// Big-endian samples of 1 or 2 bytes, by maxval (PGM/PPM raw and PAM)
+    Binary,
+    /// Decimal samples separated by whitespace, PBM's are single digits where 1 is black (plain PBM/PGM/PPM)
+    Plain { bits: bool },
+}
+
+#[inline]
+fn invalid(msg: impl Into<String>) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.into()) }
+
+/// Whether `magic` starts like an image [`RowDecoder`] can stream, farbfeld or Netpbm
+/// ```
+/// assert!(viuwa::is_streamable(b"farbfeld"));
+/// assert!(viuwa::is_streamable(b"P7\nWIDTH"));
+/// assert!(!viuwa::is_streamable(b"\x89PNG"));
+/// ```
+#[inline]
+pub fn is_streamable(magic: &[u8]) -> bool { magic.starts_with(b"farbfeld") || matches!(magic, [b'P', b'1'..=b'7', ..]) }
+
+/// Whether the image should be streamed instead of opened, it is stdin or not a regular file (e.g. a FIFO)
+#[inline]
+pub fn is_stream(path: &Path) -> bool {
+    path.as_os_str() == STDIN_PATH || std::fs::metadata(path).is_ok_and(|m| !m.is_file())
+}
+
+/// Open the image at `path` for streaming, stdin if it is [`STDIN_PATH`]
+pub fn open_stream(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    Ok(if path.as_os_str() == STDIN_PATH {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        Box::new(BufReader::new(File::open(path)?))
+    })
+}
+
+/// A decoder of farbfeld and Netpbm images that reads a row at a time
+pub struct RowDecoder<R> {
+    reader: R,
+    encoding: Encoding,
+    dims: (u32, u32),
+    /// Channels of a pixel, gray, gray and alpha, rgb, or rgba
+    channels: usize,
+    /// The largest sample, every sample is scaled from `0..=maxval` to `0..=255`
+    maxval: u32,
+    /// Scratch space for the raw bytes of a row
+    raw: Vec<u8>,
+}
+impl<R: BufRead> RowDecoder<R> {
+    /// Read the header, leaving the reader at the first row
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 2];
+        reader.read_exact(&mut magic)?;
+        let (encoding, dims, channels, maxval) = match &magic {
+            b"fa" => {
+                let mut rest = [0; 14];
+                reader.read_exact(&mut rest)?;
+                if &rest[..6] != b"rbfeld" {
+                    return Err(invalid("not a farbfeld image"));
+                }
+                let dim = |i: usize| u32::from_be_bytes([rest[i], rest[i + 1], rest[i + 2], rest[i + 3]]);
+                (Encoding::Farbfeld, (dim(6), dim(10)), 4, u16::MAX as u32)
+            }
+            [b'P', kind @ b'1'..=b'6'] => {
+                let (w, h) = (token(&mut reader)?, token(&mut reader)?);
+                let maxval = if matches!(kind, b'1' | b'4') { 1 } else { token(&mut reader)? };
+                let channels = if matches!(kind, b'3' | b'6') { 3 } else { 1 };
+                let encoding = match kind {
+                    b'1' => Encoding::Plain { bits: true },
+                    b'2' | b'3' => Encoding::Plain { bits: false },
+                    b'4' => Encoding::Bits,
+                    _ => Encoding::Binary,
+                };
+                (encoding, (w, h), channels, maxval)
+            }
+            b"P7" => {
+                let (mut w, mut h, mut depth, mut maxval) = (None, None, None, None);
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    if reader.read_line(&mut line)? == 0 {
+                        return Err(invalid("PAM header is missing ENDHDR"));
+                    }
+                    let mut words = line.split_ascii_whitespace();
+                    let value = |words: &mut std::str::SplitAsciiWhitespace| {
+                        words
+                            .next()
+                            .and_then(|v| v.parse::<u32>().ok())
+                            .ok_or_else(|| invalid(format!("invalid PAM header line {:?}", line.trim())))
+                    };
+                    match words.next() {
+                        Some("ENDHDR") => break,
+                        Some("WIDTH") => w = Some(value(&mut words)?),
+                        Some("HEIGHT") => h = Some(value(&mut words)?),
+                        Some("DEPTH") => depth = Some(value(&mut words)?),
+                        Some("MAXVAL") => maxval = Some(value(&mut words)?),
+                        // the channels are implied by DEPTH, and comments are ignored
+                        _ => (),
+                    }
+                }
+                match (w, h, depth, maxval) {
+                    (Some(w), Some(h), Some(depth @ 1..=4), Some(maxval)) => {
+                        (Encoding::Binary, (w, h), depth as usize, maxval)
+                    }
+                    _ => return Err(invalid("PAM header needs WIDTH, HEIGHT, DEPTH of 1 to 4, and MAXVAL")),
+                }
+            }
+            _ => return Err(invalid("not a farbfeld or Netpbm image")),
+        };
+        if dims.0 == 0 || dims.1 == 0 || dims.0 as u64 * dims.1 as u64 > MAX_PIXELS {
+            return Err(invalid(format!("image dimensions {}x{} are out of range", dims.0, dims.1)));
+        }
+        if !(1..=u16::MAX as u32).contains(&maxval) {
+            return Err(invalid(format!("maxval {maxval} is out of range")));
+        }
+        let samples = dims.0 as usize * channels;
+        let raw = match encoding {
+            Encoding::Farbfeld => samples * 2,
+            Encoding::Bits => (dims.0 as usize).div_ceil(8),
+            Encoding::Binary if maxval > u8::MAX as u32 => samples * 2,
+            Encoding::Binary => samples,
+            Encoding::Plain { .. } => 0,
+        };
+        Ok(Self { reader, encoding, dims, channels, maxval, raw: vec![0; raw] })
+    }
+    /// The dimensions of the image
+    #[inline(always)]
+    pub fn dimensions(&self) -> (u32, u32) { self.dims }
+    /// The channels of a pixel, 1 to 4 for gray, gray and alpha, rgb, or rgba
+    #[inline(always)]
+    pub fn channels(&self) -> usize { self.channels }
+    /// Read the next row as 8-bit samples into `row`, which must hold `width * channels` samples
+    pub fn read_row(&mut self, row: &mut [u8]) -> io::Result<()> {
+        let scale = |v: u32| ((v.min(self.maxval) * 255 + self.maxval / 2) / self.maxval) as u8;
+        match self.encoding {
+            Encoding::Plain { bits } => {
+                for s in row.iter_mut() {
+                    *s = if bits {
+                        // plain PBM digits don't need to be separated
+                        let mut b = [0];
+                        loop {
+                            self.reader.read_exact(&mut b)?;
+                            match b[0] {
+                                b'0' | b'1' => break,
+                                b'#' => skip_line(&mut self.reader)?,
+                                c if c.is_ascii_whitespace() => (),
+                                _ => return Err(invalid("invalid PBM sample")),
+                            }
+                        }
+                        if b[0] == b'1' {
+                            0
+                        } else {
+                            255
+                        }
+                    } else {
+                        scale(token(&mut self.reader)?)
+                    };
+                }
+            }
+            Encoding::Bits => {
+                self.reader.read_exact(&mut self.raw)?;
+                for (x, s) in row.iter_mut().enumerate() {
+                    *s = if self.raw[x / 8] & (0x80 >> (x % 8)) != 0 { 0 } else { 255 };
+                }
+            }
+            Encoding::Binary if self.raw.len() == row.len() => {
+                self.reader.read_exact(&mut self.raw)?;
+                if self.maxval == u8::MAX as u32 {
+                    row.copy_from_slice(&self.raw);
+                } else {
+                    row.iter_mut().zip(&self.raw).for_each(|(s, &v)| *s = scale(v as u32));
+                }
+            }
+            Encoding::Farbfeld | Encoding::Binary => {
+                self.reader.read_exact(&mut self.raw)?;
+                for (s, v) in row.iter_mut().zip(self.raw.chunks_exact(2)) {
+                    *s = scale(u16::from_be_bytes([v[0], v[1]]) as u32);
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Decode the rest of the image, area-averaged down to `dims` (clamped to the image's dimensions)
+    pub fn decode(mut self, dims: (usize, usize)) -> io::Result<DynamicImage> {
+        trace!("RowDecoder::decode");
+        let (w, h) = (self.dims.0 as usize, self.dims.1 as usize);
+        let (dw, dh) = (dims.0.clamp(1, w), dims.1.clamp(1, h));
+        let ch = self.channels;
+        let mut row = vec![0u8; w * ch];
+        let data = if (dw, dh) == (w, h) {
+            let mut data = vec![0u8; w * h * ch];
+            for out in data.chunks_exact_mut(w * ch) {
+                self.read_row(out)?;
+            }
+            data
+        } else {
+            let mut acc = vec![0u32; dw * dh * ch];
+            let mut cnt = vec![0u32; dw * dh];
+            // the target column of every source column
+            let cols: Vec<usize> = (0..w).map(|x| x * dw / w).collect();
+            for y in 0..h {
+                self.read_row(&mut row)?;
+                let ty = y * dh / h;
+                for (p, &tx) in row.chunks_exact(ch).zip(&cols) {
+                    let i = ty * dw + tx;
+                    acc[i * ch..(i + 1) * ch].iter_mut().zip(p).for_each(|(a, &s)| *a += s as u32);
+                    cnt[i] += 1;
+                }
+            }
+            acc.chunks_exact(ch)
+                .zip(&cnt)
+                .flat_map(|(a, &n)| a.iter().map(move |&v| ((v + n / 2) / n.max(1)) as u8))
+                .collect()
+        };
+        let (dw, dh) = (dw as u32, dh as u32);
+        match ch {
+            1 => GrayImage::from_raw(dw, dh, data).map(DynamicImage::ImageLuma8),
+            2 => GrayAlphaImage::from_raw(dw, dh, data).map(DynamicImage::ImageLumaA8),
+            3 => RgbImage::from_raw(dw, dh, data).map(DynamicImage::ImageRgb8),
+            _ => RgbaImage::from_raw(dw, dh, data).map(DynamicImage::ImageRgba8),
+        }
+        .ok_or_else(|| invalid("decoded buffer too small"))
+    }
+}
+
+/// Skip the rest of a comment line
+#[inline]
+fn skip_line(reader: &mut impl BufRead) -> io::Result<()> {
+    reader.read_until(b'\n', &mut Vec::new())?;
+    Ok(())
+}
+
+/// Read a decimal number of a Netpbm header or plain image, skipping whitespace and comments before it,
+/// and consuming the single whitespace after it
+fn token(reader: &mut impl BufRead) -> io::Result<u32> {
+    let mut n: Option<u32> = None;
+    let mut b = [0];
+    loop {
+        if reader.read(&mut b)? == 0 {
+            return n.ok_or_else(|| io::ErrorKind::UnexpectedEof.into());
+        }
+        match b[0] {
+            d @ b'0'..=b'9' => {
+                n = Some(
+                    n.unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add((d - b'0') as u32))
+                        .ok_or_else(|| invalid("Netpbm number is too large"))?,
+                );
+            }
+            b'#' if n.is_none() => skip_line(reader)?,
+            c if c.is_ascii_whitespace() => {
+                if let Some(n) = n {
+                    return Ok(n);
+                }
+            }
+            c => return Err(invalid(format!("unexpected byte {:?} in Netpbm header", c as char))),
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! The colors of viuwa's own interface, picked for the background of the terminal so it reads on light themes too.
+//!
+//! With `--background auto` (the default), the terminal is asked for its background color with OSC 11 when viuwa
+//! starts, the colors of messages and titles are picked for it, and images with transparency are composited over it.
+//! Terminals that don't answer within [`QUERY_TIMEOUT`] are assumed to be dark.
+//!
+//! The `[theme]` table of the config file overrides any of the colors, e.g. to match the status line to the terminal's
+//! color scheme, see [`ThemeColors`].
+use super::*;
+
+/// The background color of the terminal, which transparent images are composited over and the interface is colored for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Background {
+    /// Asked of the terminal when viuwa starts, assumed dark if it doesn't answer
+    #[default]
+    Auto,
+    /// A dark theme, composited over black
+    Dark,
+    /// A light theme, composited over white
+    Light,
+    /// The color the terminal answered with, or as given in hex (`#rrggbb`)
+    Color([u8; 3]),
+}
+impl Background {
+    /// The background of the terminal for [`Auto`](Self::Auto), from its answer to OSC 11 if it answers
+    pub fn resolve(self, term: &mut impl Terminal) -> Self {
+        match self {
+            #[cfg(not(target_family = "wasm"))]
+            Self::Auto => match term.query_background(QUERY_TIMEOUT) {
+                Ok(rgb) => {
+                    debug!("Background::resolve", "the terminal's background is {}", Self::Color(rgb));
+                    Self::Color(rgb)
+                }
+                Err(e) => {
+                    debug!("Background::resolve", "assuming a dark background: {}", e);
+                    Self::Auto
+                }
+            },
+            #[cfg(target_family = "wasm")]
+            Self::Auto => {
+                let _ = term;
+                Self::Auto
+            }
+            background => background,
+        }
+    }
+    /// The color transparent images are composited over, black when it's unknown
+    #[inline]
+    pub const fn color(self) -> [u8; 3] {
+        match self {
+            Self::Auto | Self::Dark => [0; 3],
+            Self::Light => [255; 3],
+            Self::Color(rgb) => rgb,
+        }
+    }
+    /// Whether the background is light, so the interface is drawn in darker colors
+    /// ```
+    /// use viuwa::Background;
+    /// assert!(Background::Color([0xfd, 0xf6, 0xe3]).is_light());
+    /// assert!(!Background::Color([0x28, 0x2c, 0x34]).is_light());
+    /// ```
+    #[inline]
+    pub fn is_light(self) -> bool { viuwa_ansi::color::luma(self.color()) >= 128 }
+}
+impl FromStr for Background {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = |s: &str| u8::from_str_radix(s, 16).ok();
+        match s {
+            "auto" => Ok(Self::Auto),
+            "dark" => Ok(Self::Dark),
+            "light" => Ok(Self::Light),
+            _ => match s.strip_prefix('#').filter(|rgb| rgb.len() == 6 && rgb.is_ascii()) {
+                Some(rgb) => match (hex(&rgb[..2]), hex(&rgb[2..4]), hex(&rgb[4..])) {
+                    (Some(r), Some(g), Some(b)) => Ok(Self::Color([r, g, b])),
+                    _ => Err(ConfigError::InvalidBackground(s.to_string())),
+                },
+                None => Err(ConfigError::InvalidBackground(s.to_string())),
+            },
+        }
+    }
+}
+impl<'de> Deserialize<'de> for Background {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+impl fmt::Display for Background {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => f.write_str("auto"),
+            Self::Dark => f.write_str("dark"),
+            Self::Light => f.write_str("light"),
+            Self::Color([r, g, b]) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+        }
+    }
+}
+impl Serialize for Background {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// A color of the interface in the `[theme]` table of the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiColor {
+    /// The terminal's own foreground or background color
+    Default,
+    /// One of the 256 ANSI colors, the first 16 following the terminal's palette
+    Ansi(u8),
+    /// A truecolor color, given in hex (`#rrggbb`)
+    Rgb([u8; 3]),
+}
+impl UiColor {
+    /// The names of the first 16 ANSI colors, the bright ones prefixed with `bright-`
+    pub const NAMES: [&'static str; 16] = [
+        "black",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "magenta",
+        "cyan",
+        "white",
+        "bright-black",
+        "bright-red",
+        "bright-green",
+        "bright-yellow",
+        "bright-blue",
+        "bright-magenta",
+        "bright-cyan",
+        "bright-white",
+    ];
+    /// The SGR sequence setting the foreground to this color
+    /// ```
+    /// use viuwa::UiColor;
+    /// assert_eq!("bright-red".parse::<UiColor>().unwrap().fg(), "\x1b[91m");
+    /// assert_eq!("#1e1e2e".parse::<UiColor>().unwrap().fg(), "\x1b[38;2;30;30;46m");
+    /// ```
+    pub fn fg(self) -> String { self.sgr(30, 90, 38) }
+    /// The SGR sequence setting the background to this color
+    pub fn bg(self) -> String { self.sgr(40, 100, 48) }
+    /// The SGR sequence of this color, from the parameters of the basic, bright, and extended colors of its layer
+    fn sgr(self, basic: u8, bright: u8, extended: u8) -> String {
+        match self {
+            Self::Default => format!("\x1b[{}m", basic + 9),
+            Self::Ansi(n @ 0..=7) => format!("\x1b[{}m", basic + n),
+            Self::Ansi(n @ 8..=15) => format!("\x1b[{}m", bright + n - 8),
+            Self::Ansi(n) => format!("\x1b[{extended};5;{n}m"),
+            Self::Rgb([r, g, b]) => format!("\x1b[{extended};2;{r};{g};{b}m"),
+        }
+    }
+}
+impl FromStr for UiColor {
+    type Err = ConfigError;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "default" {
+            return Ok(Self::Default);
+        }
+        if let Some(n) = Self::NAMES.iter().position(|name| *name == s) {
+            return Ok(Self::Ansi(n as u8));
+        }
+        if let Ok(n) = s.parse::<u8>() {
+            return Ok(Self::Ansi(n));
+        }
+        match Background::from_str(s) {
+            Ok(Background::Color(rgb)) => Ok(Self::Rgb(rgb)),
+            _ => Err(ConfigError::InvalidColor(s.to_string())),
+        }
+    }
+}
+impl<'de> Deserialize<'de> for UiColor {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+impl fmt::Display for UiColor {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::Ansi(n) => match Self::NAMES.get(*n as usize) {
+                Some(name) => f.write_str(name),
+                None => write!(f, "{n}"),
+            },
+            Self::Rgb(rgb) => write!(f, "{}", Background::Color(*rgb)),
+        }
+    }
+}
+impl Serialize for UiColor {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// The colors of the `[theme]` table of the config file, overriding those picked for the background of the terminal
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct ThemeColors {
+    /// Errors, e.g. of commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<UiColor>,
+    /// Warnings, e.g. the invalid arguments of a command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<UiColor>,
+    /// Other messages, e.g. that the config was reloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<UiColor>,
+    /// The text of the status line, the minimap and page of the viewer and the selected path of the grid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<UiColor>,
+    /// The background of the status line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_bg: Option<UiColor>,
+    /// The text of the selection, the label of the selected thumbnail of the grid and image of the recent images menu
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection: Option<UiColor>,
+    /// The background of the selection, which is drawn in reverse video unless it or [`selection`](Self::selection) is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_bg: Option<UiColor>,
+    /// The text and outline of the help and recent images boxes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<UiColor>,
+    /// The background of the help and recent images boxes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help_bg: Option<UiColor>,
+    /// The titles of the help and recent images boxes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<UiColor>,
+}
+impl ThemeColors {
+    /// Whether no colors are overridden
+    #[inline]
+    pub fn is_empty(&self) -> bool { *self == Self::default() }
+}
+
+/// The SGR sequences the interface is drawn with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Errors, e.g. of commands
+    pub error: String,
+    /// Warnings, e.g. the invalid arguments of a command
+    pub warning: String,
+    /// Other messages, in the terminal's colors by default
+    pub info: String,
+    /// The titles of the help and recent images boxes
+    pub title: String,
+    /// The status line, in the terminal's colors by default
+    pub status: String,
+    /// The selection, in reverse video by default
+    pub selection: String,
+    /// The help and recent images boxes, in the terminal's colors by default
+    pub help: String,
+}
+impl Theme {
+    /// Bright colors, for dark backgrounds
+    pub fn dark() -> Self {
+        Self {
+            error: fg!(Red).to_string(),
+            warning: fg!(Yellow).to_string(),
+            title: fg!(Cyan).to_string(),
+            ..Self::plain()
+        }
+    }
+    /// The darker colors of the 8 basic ones, as the bright ones are hard to read on light backgrounds
+    pub fn light() -> Self {
+        Self {
+            error: fg!(red).to_string(),
+            warning: fg!(yellow).to_string(),
+            title: fg!(blue).to_string(),
+            ..Self::plain()
+        }
+    }
+    /// The terminal's own colors, with the selection in reverse video
+    fn plain() -> Self {
+        Self {
+            error: String::new(),
+            warning: String::new(),
+            info: String::new(),
+            title: String::new(),
+            status: String::new(),
+            selection: SGR_REVERSE.to_string(),
+            help: String::new(),
+        }
+    }
+    /// The colors of the interface on `background`
+    #[inline]
+    pub fn for_background(background: Background) -> Self {
+        if background.is_light() {
+            Self::light()
+        } else {
+            Self::dark()
+        }
+    }
+    /// The theme with the colors set in `colors` instead
+    /// ```
+    /// use viuwa::{Theme, ThemeColors, UiColor};
+    /// let colors =
+    ///     ThemeColors { status_bg: Some(UiColor::Ansi(4)), selection: Some(UiColor::Default), ..Default::default() };
+    /// let theme = Theme::dark().with_colors(&colors);
+    /// assert_eq!(theme.status, "\x1b[44m");
+    /// assert_eq!(theme.selection, "\x1b[39m");
+    /// assert_eq!(theme.error, Theme::dark().error);
+    /// ```
+    pub fn with_colors(mut self, colors: &ThemeColors) -> Self {
+        let fg = |color: Option<UiColor>| color.map(UiColor::fg).unwrap_or_default();
+        let bg = |color: Option<UiColor>| color.map(UiColor::bg).unwrap_or_default();
+        if let Some(color) = colors.error {
+            self.error = color.fg();
+        }
+        if let Some(color) = colors.warning {
+            self.warning = color.fg();
+        }
+        if let Some(color) = colors.title {
+            self.title = color.fg();
+        }
+        self.info = fg(colors.info);
+        self.status = fg(colors.status) + &bg(colors.status_bg);
+        if colors.selection.is_some() || colors.selection_bg.is_some() {
+            self.selection = fg(colors.selection) + &bg(colors.selection_bg);
+        }
+        self.help = fg(colors.help) + &bg(colors.help_bg);
+        self
+    }
+}
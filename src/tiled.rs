@@ -0,0 +1,385 @@
+//! Tiled decoding of very large images, so that gigapixel scans and maps can be viewed
+//! without ever holding the whole image in memory.
+//!
+//! Images are decoded a strip of rows at a time, and each strip is area-averaged into a much smaller buffer
+//! before the next strip is read. Formats with partial decoding (farbfeld) only read the requested region,
+//! TIFFs only decode the strips or tiles the region overlaps, and formats with streaming decoders (png, pnm)
+//! read sequentially and skip rows outside the requested region.
+//!
+//! The viewer displays an overview of the image, and when it's zoomed in past the overview's resolution,
+//! decodes the visible region of the image again with [`Regions`], at the resolution it's drawn.
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use image::{
+    codecs::{farbfeld::FarbfeldDecoder, png::PngDecoder, pnm::PnmDecoder},
+    ColorType, ImageDecoder, ImageDecoderRect, ImageFormat, Luma, Rgb,
+};
+use tiff::{
+    decoder::{ChunkType, Decoder as TiffDecoder, DecodingResult},
+    tags::Tag,
+    ColorType as TiffColor,
+};
+
+use super::*;
+
+/// How many rows to decode at a time
+pub const TILE_HEIGHT: u32 = 256;
+/// Images with more pixels than this are decoded tiled, if their format supports it
+pub const TILED_THRESHOLD: u64 = 64 * 1024 * 1024;
+/// The largest overview to decode a tiled image to, more than enough for any terminal even when supersampled
+pub const OVERVIEW_SIZE: (usize, usize) = (4096, 4096);
+
+/// A region of an image in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A source of decoded rows, read top to bottom in strips
+trait Strips {
+    /// Read `rows` rows of the region `x..x+width` starting at row `y` into `buf`
+    fn read(&mut self, x: u32, y: u32, width: u32, rows: u32, buf: &mut [u8]) -> Result<()>;
+}
+
+/// An opened decoder, with the dimensions and color type of the image
+type Opened = (Box<dyn Strips>, (u32, u32), ColorType);
+
+/// Strips from a decoder that supports partial decoding
+struct RectStrips<D>(D);
+impl<'a, D: ImageDecoderRect<'a>> Strips for RectStrips<D> {
+    #[inline]
+    fn read(&mut self, x: u32, y: u32, width: u32, rows: u32, buf: &mut [u8]) -> Result<()> {
+        Ok(self.0.read_rect(x, y, width, rows, buf)?)
+    }
+}
+
+/// Strips from a streaming decoder, rows must be read in order
+struct SeqStrips<R> {
+    reader: R,
+    /// The next row the reader will yield
+    next: u32,
+    /// Bytes per pixel
+    px_bytes: usize,
+    /// Scratch space for a single full row
+    row: Vec<u8>,
+}
+impl<R: Read> Strips for SeqStrips<R> {
+    fn read(&mut self, x: u32, y: u32, width: u32, rows: u32, buf: &mut [u8]) -> Result<()> {
+        debug_assert!(y >= self.next, "sequential strips must be read in order");
+        for _ in self.next..y {
+            self.reader.read_exact(&mut self.row)?;
+        }
+        let (start, len) = (x as usize * self.px_bytes, width as usize * self.px_bytes);
+        for out in buf.chunks_exact_mut(len).take(rows as usize) {
+            self.reader.read_exact(&mut self.row)?;
+            out.copy_from_slice(&self.row[start..start + len]);
+        }
+        self.next = y + rows;
+        Ok(())
+    }
+}
+impl<R: Read> SeqStrips<R> {
+    fn new<'a, D: ImageDecoder<'a, Reader = R>>(decoder: D) -> Result<Self> {
+        let px_bytes = decoder.color_type().bytes_per_pixel() as usize;
+        let row_bytes = decoder.dimensions().0 as usize * px_bytes;
+        // streaming strips needs the reader, which is only deprecated in favor of image 0.25's API
+        #[allow(deprecated)]
+        let reader = decoder.into_reader()?;
+        Ok(Self { reader, next: 0, px_bytes, row: vec![0; row_bytes] })
+    }
+}
+
+/// Strips from the strips or tiles of a TIFF, only decoding the chunks a strip overlaps
+struct TiffStrips {
+    decoder: TiffDecoder<BufReader<File>>,
+    /// Whether the chunks are tiles rather than strips
+    tiled: bool,
+    /// The dimensions of the image and of every chunk, but those cut off at the right and bottom edges
+    dims: (u32, u32),
+    chunk: (u32, u32),
+    /// Bytes per pixel
+    px_bytes: usize,
+    /// The row of chunks last decoded, and its decoded chunks by column
+    band: Option<(u32, Vec<Option<Vec<u8>>>)>,
+}
+impl TiffStrips {
+    /// Strips of a TIFF in 8 or 16 bit gray or RGB (with or without alpha) stored in chunks of every sample,
+    /// `None` for any other
+    fn new(mut decoder: TiffDecoder<BufReader<File>>) -> Result<Option<(Self, ColorType)>> {
+        let color = match decoder.colortype()? {
+            TiffColor::Gray(8) => ColorType::L8,
+            TiffColor::GrayA(8) => ColorType::La8,
+            TiffColor::RGB(8) => ColorType::Rgb8,
+            TiffColor::RGBA(8) => ColorType::Rgba8,
+            TiffColor::Gray(16) => ColorType::L16,
+            TiffColor::GrayA(16) => ColorType::La16,
+            TiffColor::RGB(16) => ColorType::Rgb16,
+            TiffColor::RGBA(16) => ColorType::Rgba16,
+            _ => return Ok(None),
+        };
+        // separate planes (2) are stored a chunk per plane, rather than a chunk of every sample
+        if decoder.find_tag_unsigned::<u16>(Tag::PlanarConfiguration)?.is_some_and(|planar| planar != 1) {
+            return Ok(None);
+        }
+        let (dims, chunk) = (decoder.dimensions()?, decoder.chunk_dimensions());
+        let tiled = matches!(decoder.get_chunk_type(), ChunkType::Tile);
+        let px_bytes = color.bytes_per_pixel() as usize;
+        Ok(Some((Self { decoder, tiled, dims, chunk: (chunk.0.max(1), chunk.1.max(1)), px_bytes, band: None }, color)))
+    }
+    /// Decode the chunk at `col` of the row of chunks `band` to bytes, in native endianness like image's decoders
+    fn decode(&mut self, band: u32, col: u32) -> Result<Vec<u8>> {
+        let across = if self.tiled { self.dims.0.div_ceil(self.chunk.0) } else { 1 };
+        Ok(match self.decoder.read_chunk(band * across + col)? {
+            DecodingResult::U8(data) => data,
+            DecodingResult::U16(data) => data.iter().flat_map(|s| s.to_ne_bytes()).collect(),
+            _ => return Err(anyhow!("TIFF chunk {} has a different sample format than its image", band * across + col)),
+        })
+    }
+}
+impl Strips for TiffStrips {
+    fn read(&mut self, x: u32, y: u32, width: u32, rows: u32, buf: &mut [u8]) -> Result<()> {
+        let (cw, ch) = if self.tiled { self.chunk } else { (self.dims.0, self.chunk.1) };
+        let cols = x / cw..=(x + width - 1) / cw;
+        let len = width as usize * self.px_bytes;
+        for (r, out) in buf.chunks_exact_mut(len).take(rows as usize).enumerate() {
+            let row = y + r as u32;
+            let band = row / ch;
+            if self.band.as_ref().is_none_or(|(b, _)| *b != band) {
+                self.band = Some((band, vec![None; (self.dims.0.div_ceil(cw)) as usize]));
+            }
+            for col in cols.clone() {
+                if self.band.as_ref().is_some_and(|(_, chunks)| chunks[col as usize].is_none()) {
+                    let data = self.decode(band, col)?;
+                    if let Some((_, chunks)) = &mut self.band {
+                        chunks[col as usize] = Some(data);
+                    }
+                }
+                let Some(data) = self.band.as_ref().and_then(|(_, chunks)| chunks[col as usize].as_ref()) else { continue };
+                // the chunks at the right edge are only as wide as what's left of the image
+                let dw = cw.min(self.dims.0 - col * cw);
+                let src = &data[((row - band * ch) * dw) as usize * self.px_bytes..][..dw as usize * self.px_bytes];
+                // the columns of the chunk within the strip, relative to the chunk
+                let (start, end) = (x.max(col * cw) - col * cw, (x + width).min(col * cw + dw) - col * cw);
+                let at = (col * cw + start - x) as usize * self.px_bytes;
+                let n = (end - start) as usize * self.px_bytes;
+                out[at..at + n].copy_from_slice(&src[start as usize * self.px_bytes..][..n]);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An image that is decoded a strip at a time, only ever holding a strip and the downscaled output in memory
+pub struct TiledImage {
+    path: PathBuf,
+    format: ImageFormat,
+    dims: (u32, u32),
+    color: ColorType,
+}
+impl TiledImage {
+    /// Open an image for tiled decoding, returns `None` if the format doesn't support it
+    pub fn open(path: &Path, format: ImageFormat) -> Result<Option<Self>> {
+        let (dims, color) = match Self::strips(path, format)? {
+            Some((_, dims, color)) => (dims, color),
+            None => return Ok(None),
+        };
+        Ok(Some(Self { path: path.to_path_buf(), format, dims, color }))
+    }
+    /// The dimensions of the full image
+    #[inline(always)]
+    pub fn dimensions(&self) -> (u32, u32) { self.dims }
+    /// Whether the image is large enough that it should be decoded tiled
+    #[inline]
+    pub fn is_large(&self) -> bool { self.dims.0 as u64 * self.dims.1 as u64 > TILED_THRESHOLD }
+    /// Open a new decoder, reading only the header
+    fn strips(path: &Path, format: ImageFormat) -> Result<Option<Opened>> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(Some(match format {
+            ImageFormat::Farbfeld => {
+                let d = FarbfeldDecoder::new(reader)?;
+                let (dims, color) = (d.dimensions(), d.color_type());
+                (Box::new(RectStrips(d)), dims, color)
+            }
+            ImageFormat::Png => {
+                let d = PngDecoder::new(reader)?;
+                let (dims, color) = (d.dimensions(), d.color_type());
+                (Box::new(SeqStrips::new(d)?), dims, color)
+            }
+            ImageFormat::Pnm => {
+                let d = PnmDecoder::new(reader)?;
+                let (dims, color) = (d.dimensions(), d.color_type());
+                (Box::new(SeqStrips::new(d)?), dims, color)
+            }
+            ImageFormat::Tiff => match TiffStrips::new(TiffDecoder::new(reader)?)? {
+                Some((strips, color)) => {
+                    let dims = strips.dims;
+                    (Box::new(strips), dims, color)
+                }
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        }))
+    }
+    /// Decode the overview of the whole image, fit within `max` (usually [`OVERVIEW_SIZE`])
+    #[inline]
+    pub fn overview(&self, max: (usize, usize)) -> Result<DynamicImage> {
+        let dims = viuwa_image::fit_dimensions((self.dims.0 as usize, self.dims.1 as usize), max);
+        self.region(Rect { x: 0, y: 0, width: self.dims.0, height: self.dims.1 }, dims)
+    }
+    /// Decode a region of the image, area-averaged down to `dims`
+    /// ```
+    /// use viuwa::{Rect, TiledImage};
+    /// let path = std::env::temp_dir().join("viuwa-region.tiff");
+    /// let image = image::RgbImage::from_fn(300, 200, |x, y| image::Rgb([x as u8, y as u8, (x ^ y) as u8]));
+    /// image.save(&path).unwrap();
+    /// let tiled = TiledImage::open(&path, image::ImageFormat::Tiff).unwrap().unwrap();
+    /// let region = tiled.region(Rect { x: 250, y: 30, width: 50, height: 60 }, (50, 60)).unwrap().into_rgb8();
+    /// assert_eq!(region, image::imageops::crop_imm(&image, 250, 30, 50, 60).to_image());
+    /// ```
+    pub fn region(&self, region: Rect, dims: (usize, usize)) -> Result<DynamicImage> {
+        trace!("TiledImage::region");
+        if region.width == 0
+            || region.height == 0
+            || region.x.saturating_add(region.width) > self.dims.0
+            || region.y.saturating_add(region.height) > self.dims.1
+        {
+            return Err(anyhow!("Region {:?} is out of bounds of the {}x{} image", region, self.dims.0, self.dims.1));
+        }
+        let (mut strips, ..) =
+            Self::strips(&self.path, self.format)?.ok_or_else(|| anyhow!("Tiled decoding unsupported"))?;
+        let (dw, dh) = (dims.0.clamp(1, region.width as usize), dims.1.clamp(1, region.height as usize));
+        let channels = if self.color.has_color() { 3 } else { 1 };
+        let px_bytes = self.color.bytes_per_pixel() as usize;
+        let mut acc = vec![0f32; dw * dh * channels];
+        let mut cnt = vec![0u32; dw * dh];
+        // the target column of every source column in the region
+        let cols: Vec<usize> = (0..region.width as usize).map(|x| x * dw / region.width as usize).collect();
+        let mut buf = vec![0u8; region.width as usize * TILE_HEIGHT as usize * px_bytes];
+        let mut px = [0f32; 3];
+        let mut y = 0;
+        while y < region.height {
+            let rows = TILE_HEIGHT.min(region.height - y);
+            let strip = &mut buf[..region.width as usize * rows as usize * px_bytes];
+            strips.read(region.x, region.y + y, region.width, rows, strip)?;
+            for (r, row) in strip.chunks_exact(region.width as usize * px_bytes).enumerate() {
+                let ty = (y as usize + r) * dh / region.height as usize;
+                for (p, &tx) in row.chunks_exact(px_bytes).zip(cols.iter()) {
+                    sample(self.color, p, &mut px);
+                    let i = ty * dw + tx;
+                    for c in 0..channels {
+                        acc[i * channels + c] += px[c];
+                    }
+                    cnt[i] += 1;
+                }
+            }
+            y += rows;
+        }
+        let data: Vec<u8> = acc
+            .chunks_exact(channels)
+            .zip(cnt.iter())
+            .flat_map(|(a, &n)| a.iter().map(move |v| (v / n.max(1) as f32).round() as u8))
+            .collect();
+        let (dw, dh) = (dw as u32, dh as u32);
+        if channels == 3 {
+            ImageBuffer::<Rgb<u8>, _>::from_raw(dw, dh, data).map(DynamicImage::ImageRgb8)
+        } else {
+            ImageBuffer::<Luma<u8>, _>::from_raw(dw, dh, data).map(DynamicImage::ImageLuma8)
+        }
+        .ok_or_else(|| anyhow!("Region {:?} decoded to a truncated {}x{} image", region, dw, dh))
+    }
+}
+
+/// Read a decoded pixel as gray or rgb in the range `0..=255`, dropping alpha
+#[inline]
+fn sample(color: ColorType, p: &[u8], out: &mut [f32; 3]) {
+    let bpc = color.bytes_per_pixel() as usize / color.channel_count() as usize;
+    let ch = |c: usize| -> f32 {
+        let b = &p[c * bpc..(c + 1) * bpc];
+        match bpc {
+            1 => b[0] as f32,
+            2 => u16::from_ne_bytes([b[0], b[1]]) as f32 / 257.,
+            _ => f32::from_ne_bytes([b[0], b[1], b[2], b[3]]).clamp(0., 1.) * 255.,
+        }
+    };
+    if color.has_color() {
+        *out = [ch(0), ch(1), ch(2)];
+    } else {
+        out[0] = ch(0);
+    }
+}
+
+/// A region of an image and the dimensions it's decoded to
+type RegionKey = (Rect, (usize, usize));
+
+/// Decodes the regions of a tiled image zoomed into past the resolution of its overview, at the resolution they're drawn
+pub struct Regions<P: Pixel>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    tiled: TiledImage,
+    /// Converts a decoded region to the pixels of the overview
+    convert: fn(DynamicImage) -> ImageBuffer<P, Vec<P::Scalar>>,
+    /// The last region decoded and the dimensions it was decoded to, kept for redraws
+    last: Option<(RegionKey, Arc<Image<P>>)>,
+}
+impl<P: Pixel> Regions<P>
+where
+    P::Scalar: CompatScalar,
+    P::Repr: CompatPixelRepr,
+{
+    /// Decode the regions of `tiled`, converted to the pixels of the overview with e.g. [`DynamicImage::into_rgb8`]
+    #[inline]
+    pub fn new(tiled: TiledImage, convert: fn(DynamicImage) -> ImageBuffer<P, Vec<P::Scalar>>) -> Self {
+        Self { tiled, convert, last: None }
+    }
+    /// The region of the image under the crop `(x, y, w, h)` of an overview of `overview` dimensions
+    /// ```
+    /// use viuwa::{Rect, Regions, TiledImage};
+    /// let path = std::env::temp_dir().join("viuwa-regions.pgm");
+    /// image::GrayImage::new(800, 600).save(&path).unwrap();
+    /// let tiled = TiledImage::open(&path, image::ImageFormat::Pnm).unwrap().unwrap();
+    /// let regions = Regions::new(tiled, image::DynamicImage::into_luma8);
+    /// assert_eq!(regions.rect((80, 60), (10, 20, 40, 30)), Rect { x: 100, y: 200, width: 400, height: 300 });
+    /// ```
+    pub fn rect(&self, overview: (usize, usize), (x, y, w, h): (usize, usize, usize, usize)) -> Rect {
+        let (iw, ih) = self.tiled.dimensions();
+        let span = |start: usize, len: usize, of: usize, full: u32| {
+            let scale = full as f64 / of.max(1) as f64;
+            let start = ((start as f64 * scale).floor() as u32).min(full - 1);
+            (start, ((len as f64 * scale).ceil() as u32).clamp(1, full - start))
+        };
+        let ((x, width), (y, height)) = (span(x, w, overview.0, iw), span(y, h, overview.1, ih));
+        Rect { x, y, width, height }
+    }
+    /// The crop `(x, y, w, h)` of an overview of `overview` dimensions decoded again from the image,
+    /// area-averaged down to `dims` if it's larger, or `None` if the crop of the overview has enough pixels for `dims`
+    pub fn crop(
+        &mut self,
+        overview: (usize, usize),
+        crop: (usize, usize, usize, usize),
+        dims: (usize, usize),
+    ) -> Option<Result<Arc<Image<P>>>> {
+        if crop.2 >= dims.0 && crop.3 >= dims.1 {
+            return None;
+        }
+        let key = (self.rect(overview, crop), dims);
+        if let Some((_, region)) = self.last.as_ref().filter(|(k, _)| *k == key) {
+            return Some(Ok(region.clone()));
+        }
+        debug!("Regions", "decoding {:?} of the {}x{} image", key.0, self.tiled.dims.0, self.tiled.dims.1);
+        Some(Stage::Decode.time(|| self.tiled.region(key.0, dims)).map(|region| {
+            let region = Arc::new(Image::from((self.convert)(region)));
+            self.last = Some((key, region.clone()));
+            region
+        }))
+    }
+}
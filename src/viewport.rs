@@ -0,0 +1,129 @@
+//! The visible window of the main image when it's zoomed, fit to one axis, or otherwise larger than the terminal,
+//! scrolled over like a document viewer.
+use super::*;
+
+/// How many columns the minimap takes in the status line, each a braille cell of 2x4 dots
+pub const MINIMAP_COLUMNS: usize = 8;
+
+/// The bits of the braille dots of a cell, by row and column
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// The visible window of the main image, only what's in it is resized and drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The axis spanning the terminal, if fit to one
+    pub fit: Option<Fit>,
+    /// The zoom level, 1 shows the image at the size of the configured width and height
+    pub zoom: u16,
+    /// The center of the visible crop, relative to the size of the image
+    pub center: (f64, f64),
+    /// How much of the image is visible along each axis, as of the last crop
+    shown: (f64, f64),
+    /// The size of the whole image once scaled, in columns and half block rows, as of the last crop
+    full: (usize, usize),
+}
+impl Default for Viewport {
+    #[inline]
+    fn default() -> Self { Self { fit: None, zoom: 1, center: (0.5, 0.5), shown: (1., 1.), full: (1, 1) } }
+}
+impl Viewport {
+    /// Fit the image to the width or height of the terminal, from its top or left edge
+    #[inline]
+    pub fn fit(&mut self, fit: Fit) {
+        self.fit = Some(fit);
+        self.home();
+    }
+    /// Go back to the top left of the image
+    #[inline]
+    pub fn home(&mut self) { self.center = (0., 0.) }
+    /// Whether any of the image is outside of the terminal
+    #[inline]
+    pub fn scrollable(&self) -> bool { self.shown.0 < 1. || self.shown.1 < 1. }
+    /// The x, y, width, and height of the visible crop of an image of `size` drawn in `area` columns and rows,
+    /// and the dimensions to resize it to in pixels of the configured [`Renderer`]
+    pub fn crop(
+        &mut self,
+        (w, h): (usize, usize),
+        area: (u16, u16),
+        conf: &Config,
+    ) -> ((usize, usize, usize, usize), (usize, usize)) {
+        let (cw, ch) = aspect_corrected((w, h), conf.cell_aspect.unwrap_or(DEFAULT_CELL_ASPECT));
+        let (aw, ah) = (area.0.max(1) as usize, area.1.max(1) as usize * 2);
+        let (fw, fh) = match self.fit {
+            Some(Fit::Width) => (aw, ((ch as f64 * aw as f64 / cw as f64).round() as usize).max(1)),
+            Some(Fit::Height) => (((cw as f64 * ah as f64 / ch as f64).round() as usize).max(1), ah),
+            None => scale_dimensions(area, (conf.width, conf.height), (cw, ch)),
+        };
+        let zoom = self.zoom as usize;
+        self.full = (fw.saturating_mul(zoom).max(1), fh.saturating_mul(zoom).max(1));
+        let visible = (self.full.0.min(aw), self.full.1.min(ah));
+        self.shown = (visible.0 as f64 / self.full.0 as f64, visible.1 as f64 / self.full.1 as f64);
+        self.clamp_center();
+        let window = |len: usize, shown: f64, center: f64| {
+            let shown = ((len as f64 * shown).round() as usize).clamp(1, len.max(1));
+            let start = (center * len as f64 - shown as f64 / 2.).round().clamp(0., len.saturating_sub(shown) as f64);
+            (start as usize, shown)
+        };
+        let (x, cw) = window(w, self.shown.0, self.center.0);
+        let (y, ch) = window(h, self.shown.1, self.center.1);
+        ((x, y, cw, ch), conf.renderer.pixels(visible))
+    }
+    /// Zoom the image, keeping the center of the crop
+    pub fn zoom(&mut self, zoom: Zoom) {
+        match zoom {
+            Zoom::In => self.zoom = self.zoom.saturating_mul(2).min(MAX_ZOOM),
+            Zoom::Out => self.zoom = (self.zoom / 2).max(1),
+            Zoom::Reset => *self = Self::default(),
+        }
+    }
+    /// Pan a quarter of the visible crop
+    #[inline]
+    pub fn pan(&mut self, pan: Pan) { self.step(pan, (self.shown.0 / 4., self.shown.1 / 4.)) }
+    /// Scroll a single column or row, for smooth scrolling
+    #[inline]
+    pub fn scroll(&mut self, pan: Pan) { self.step(pan, (1. / self.full.0 as f64, 2. / self.full.1 as f64)) }
+    /// Move the center of the crop by `step` in the direction of `pan`
+    fn step(&mut self, pan: Pan, step: (f64, f64)) {
+        match pan {
+            Pan::Left => self.center.0 -= step.0,
+            Pan::Right => self.center.0 += step.0,
+            Pan::Up => self.center.1 -= step.1,
+            Pan::Down => self.center.1 += step.1,
+        }
+        self.clamp_center();
+    }
+    /// Keep the crop within the image, so scrolling back from an edge responds immediately
+    fn clamp_center(&mut self) {
+        let (hw, hh) = (self.shown.0 / 2., self.shown.1 / 2.);
+        self.center = (self.center.0.clamp(hw, 1. - hw), self.center.1.clamp(hh, 1. - hh));
+    }
+    /// A map of where the crop is within the image, in [`MINIMAP_COLUMNS`] braille cells between brackets,
+    /// if any of the image is outside of the terminal
+    pub fn minimap(&self) -> Option<String> {
+        if !self.scrollable() {
+            return None;
+        }
+        let (dw, dh) = (MINIMAP_COLUMNS * 2, BRAILLE_DOTS.len());
+        // the dots covered by the crop, at least one along each axis
+        let span = |len: usize, shown: f64, center: f64| {
+            let start = (((center - shown / 2.) * len as f64).floor() as usize).min(len - 1);
+            let end = (((center + shown / 2.) * len as f64).ceil() as usize).clamp(start + 1, len);
+            start..end
+        };
+        let (xs, ys) = (span(dw, self.shown.0, self.center.0), span(dh, self.shown.1, self.center.1));
+        let mut map = String::from("[");
+        for col in 0..MINIMAP_COLUMNS {
+            let mut bits = 0;
+            for (row, dots) in BRAILLE_DOTS.iter().enumerate() {
+                for (dx, dot) in dots.iter().enumerate() {
+                    if xs.contains(&(col * 2 + dx)) && ys.contains(&row) {
+                        bits |= dot;
+                    }
+                }
+            }
+            map.push(char::from_u32(0x2800 + bits as u32).expect("braille is within char"));
+        }
+        map.push(']');
+        Some(map)
+    }
+}
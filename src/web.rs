@@ -0,0 +1,111 @@
+//! A viewer driven from JavaScript with wasm-bindgen, for web terminals like xterm.js.
+//!
+//! Nothing is read from stdin or written to stdout, [`WebViewer::render_to_string`] returns what to write to the terminal,
+//! and key presses and resizes are passed in from the terminal's events.
+use image::Rgb;
+use viuwa_ansi::consts::CLEAR_SCREEN;
+use wasm_bindgen::prelude::*;
+
+use super::*;
+
+/// An image shown in a web terminal, zoomed and panned with the keys of the terminal viewer
+#[wasm_bindgen]
+pub struct WebViewer {
+    orig: Image<Rgb<u8>>,
+    conf: Config,
+    /// The columns and rows of the terminal
+    size: (u16, u16),
+    viewport: Viewport,
+}
+#[wasm_bindgen]
+impl WebViewer {
+    /// Decode an image from its encoded `bytes`, shown in a terminal of `columns` and `rows`
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8], columns: u16, rows: u16) -> Result<WebViewer, JsError> {
+        let conf = Config::default();
+        let image = image::load_from_memory(bytes).map_err(|e| JsError::new(&e.to_string()))?;
+        let orig = Image::from(tone_mapped(image, &conf).into_rgb8());
+        Ok(Self { orig, conf, size: (columns.max(1), rows.max(1)), viewport: Viewport::default() })
+    }
+    /// The ANSI to write to the terminal to show the image, clearing it first
+    pub fn render_to_string(&mut self) -> String {
+        trace!("WebViewer::render_to_string");
+        let (origin, area) = self.conf.padding.area(self.size);
+        let ((x, y, w, h), dims) = self.viewport.crop(self.orig.dimensions(), area, &self.conf);
+        let view = self.orig.view().subview(x, y, w, h).expect("the crop is within the image");
+        let mut buf = resize_view(&view, dims, self.conf.resize_filter());
+        sharpen(&mut buf, (w, h), self.conf.sharpen);
+        let attrs = self.conf.color_attributes();
+        let mut out = CLEAR_SCREEN.as_bytes().to_vec();
+        // writing to a Vec can't fail
+        let _ = draw_aligned(&mut out, &buf, origin, area, attrs, &self.conf, 0..self.size.1, &mut RowBuffers::default());
+        String::from_utf8_lossy(&out).into_owned()
+    }
+    /// Handle the data of a key press from the terminal, returning whether the image should be rendered again.
+    ///
+    /// The arrow keys pan (or scroll with shift), and the rest of the keys are bound as in the terminal viewer on wasm.
+    pub fn handle_key(&mut self, key: &str) -> bool {
+        let Some(action) = self.action(key) else {
+            return false;
+        };
+        let viewport = self.viewport;
+        match action {
+            Action::Zoom(zoom) => self.viewport.zoom(zoom),
+            Action::Pan(pan) => self.viewport.pan(pan),
+            Action::Scroll(pan) => self.viewport.scroll(pan),
+            Action::Fit(fit) => self.viewport.fit(fit),
+            Action::Cycle(Cyclic::Filter) => {
+                self.conf.filter = self.conf.filter.cycle();
+                self.conf.filter_custom = None;
+                return true;
+            }
+            Action::Cycle(Cyclic::Color) => {
+                self.conf.color = self.conf.color.cycle();
+                return true;
+            }
+            Action::Cycle(Cyclic::ColorDepth) => {
+                self.conf.color = self.conf.color.cycle_depth();
+                return true;
+            }
+            Action::Cycle(Cyclic::ColorSpace) => {
+                self.conf.color = self.conf.color.cycle_space();
+                return true;
+            }
+            Action::Cycle(Cyclic::FitMode) => {
+                let mode = FitMode::of(self.conf.width, self.conf.height).map_or(FitMode::Fit, FitMode::cycle);
+                (self.conf.width, self.conf.height) = mode.dimensions();
+                return true;
+            }
+            Action::Refresh | Action::Reload => return true,
+            _ => return false,
+        }
+        self.viewport != viewport
+    }
+    /// Handle the terminal being resized to `columns` and `rows`, it should be rendered again
+    pub fn handle_resize(&mut self, columns: u16, rows: u16) { self.size = (columns.max(1), rows.max(1)) }
+}
+impl WebViewer {
+    /// The action of the data of a key press, arrow keys as sent by xterm.js
+    fn action(&self, key: &str) -> Option<Action> {
+        match key {
+            "\x1b[A" => Some(Action::Pan(Pan::Up)),
+            "\x1b[B" => Some(Action::Pan(Pan::Down)),
+            "\x1b[C" => Some(Action::Pan(Pan::Right)),
+            "\x1b[D" => Some(Action::Pan(Pan::Left)),
+            "\x1b[1;2A" => Some(Action::Scroll(Pan::Up)),
+            "\x1b[1;2B" => Some(Action::Scroll(Pan::Down)),
+            "\x1b[1;2C" => Some(Action::Scroll(Pan::Right)),
+            "\x1b[1;2D" => Some(Action::Scroll(Pan::Left)),
+            #[cfg(target_family = "wasm")]
+            key => self.conf.keybinds.get(key).cloned(),
+            #[cfg(not(target_family = "wasm"))]
+            _ => None,
+        }
+    }
+}
+
+/// Render an image from its encoded `bytes` once, as the ANSI to write to a terminal of `columns` and `rows`
+#[wasm_bindgen]
+pub fn render_to_string(bytes: &[u8], columns: u16, rows: u16) -> Result<String, JsError> {
+    Ok(WebViewer::new(bytes, columns, rows)?.render_to_string())
+}
@@ -2,7 +2,10 @@
 //!
 //! Use `PixelConverter` to convert pixels to ansi color sequences
 
-use std::str::FromStr;
+#[cfg(all(not(feature = "std"), feature = "parse"))]
+use alloc::format;
+#[cfg(feature = "parse")]
+use core::str::FromStr;
 
 use super::*;
 
@@ -18,7 +21,7 @@ pub enum ColorSpace {
 impl FromStr for ColorSpace {
     type Err = String;
     #[inline]
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
         match s {
             "color" | "rgb" | "truecolor" => Ok(Self::Color),
             "gray" | "grey" | "grayscale" | "greyscale" => Ok(Self::Gray),
@@ -29,13 +32,33 @@ impl FromStr for ColorSpace {
 #[cfg(feature = "serde")]
 impl<'de> ::serde::Deserialize<'de> for ColorSpace {
     #[inline]
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
     where
         D: ::serde::Deserializer<'de>,
     {
         String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
     }
 }
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for ColorSpace {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Self::Color => "color",
+            Self::Gray => "gray",
+        })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for ColorSpace {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
 impl ColorSpace {
     /// Cycle through the color spaces
     #[inline]
@@ -54,7 +77,7 @@ pub enum ColorDepth {
 impl FromStr for ColorDepth {
     type Err = String;
     #[inline]
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
         match s {
             "24" | "24bit" | "24-bit" => Ok(Self::B24),
             "8" | "8bit" | "8-bit" => Ok(Self::B8),
@@ -65,13 +88,33 @@ impl FromStr for ColorDepth {
 #[cfg(feature = "serde")]
 impl<'de> ::serde::Deserialize<'de> for ColorDepth {
     #[inline]
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
     where
         D: ::serde::Deserializer<'de>,
     {
         String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
     }
 }
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for ColorDepth {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Self::B24 => "24",
+            Self::B8 => "8",
+        })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for ColorDepth {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
 impl ColorDepth {
     /// Cycle through the color depths
     #[inline]
@@ -94,7 +137,7 @@ pub enum ColorType {
 impl FromStr for ColorType {
     type Err = String;
     #[inline]
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
         match s {
             "color" | "rgb" | "truecolor" => Ok(Self::Color),
             "ansi-color" => Ok(Self::AnsiColor),
@@ -107,7 +150,7 @@ impl FromStr for ColorType {
 #[cfg(feature = "serde")]
 impl<'de> ::serde::Deserialize<'de> for ColorType {
     #[inline]
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
     where
         D: ::serde::Deserializer<'de>,
     {
@@ -115,6 +158,29 @@ impl<'de> ::serde::Deserialize<'de> for ColorType {
     }
 }
 
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for ColorType {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Self::Color => "color",
+            Self::AnsiColor => "ansi-color",
+            Self::Gray => "gray",
+            Self::AnsiGray => "ansi-gray",
+        })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for ColorType {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 impl From<(ColorSpace, ColorDepth)> for ColorType {
     fn from((space, depth): (ColorSpace, ColorDepth)) -> Self {
         unsafe { ::core::mem::transmute(space as u8 | depth as u8) }
@@ -154,20 +220,322 @@ impl ColorType {
     pub fn is_8bit(&self) -> bool { *self as u8 & 1 != 0 }
 }
 
+/// The colormap single-channel images are drawn with in color, e.g. for scientific data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub enum Colormap {
+    #[default]
+    Gray,
+    Viridis,
+    Magma,
+    Turbo,
+}
+#[cfg(feature = "parse")]
+impl FromStr for Colormap {
+    type Err = String;
+    #[inline]
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        match s {
+            "gray" | "grey" | "grayscale" | "greyscale" => Ok(Self::Gray),
+            "viridis" => Ok(Self::Viridis),
+            "magma" => Ok(Self::Magma),
+            "turbo" => Ok(Self::Turbo),
+            _ => Err(format!("{s:?} is not a valid colormap, expected one of gray, viridis, magma, or turbo")),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Colormap {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for Colormap {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Self::Gray => "gray",
+            Self::Viridis => "viridis",
+            Self::Magma => "magma",
+            Self::Turbo => "turbo",
+        })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Colormap {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+impl Colormap {
+    /// Map a single-channel value to a color
+    /// ```
+    /// use viuwa_ansi::Colormap;
+    /// assert_eq!(Colormap::Gray.map(42), [42; 3]);
+    /// assert_eq!(Colormap::Viridis.map(0), [68, 1, 84]);
+    /// assert_eq!(Colormap::Magma.map(255), [252, 253, 191]);
+    /// ```
+    #[inline(always)]
+    pub fn map(self, v: u8) -> [u8; 3] {
+        match self {
+            Self::Gray => [v; 3],
+            Self::Viridis => VIRIDIS[v as usize],
+            Self::Magma => MAGMA[v as usize],
+            Self::Turbo => TURBO[v as usize],
+        }
+    }
+    /// Map a single-channel value to the closest 8-bit ANSI color, using the gray ramp for [`Colormap::Gray`]
+    #[inline(always)]
+    pub fn map_256(self, v: u8, a: ColorAttributes) -> u8 {
+        match self {
+            Self::Gray => a.gray_ramp.ansi(v),
+            _ => rgb_to_ansi(self.map(v), a),
+        }
+    }
+}
+
+/// The curve mapping a luma correct of `0.0..=100.0` to the color distance threshold of [`ColorAttributes`]
+///
+/// The gray ramp is chosen over the color cube when its gray is closer to the color than the cube's color by more than
+/// the threshold, so a lower threshold draws more muted colors in gray (keeping their brightness), and a threshold of 0
+/// picks whichever is closest. Steeper curves lower the threshold faster, drawing more grays at the same luma correct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
+pub enum LumaCurve {
+    /// The threshold falls off evenly, half of the maximum at 50
+    Linear,
+    /// A quarter of the maximum at 50
+    Quadratic,
+    /// An eighth of the maximum at 50
+    #[default]
+    Cubic,
+}
+impl LumaCurve {
+    /// The color distance threshold of `luma_correct`, clamped to `0.0..=100.0`, [`MAX_COLOR_DISTANCE`] at 0
+    /// ```
+    /// use viuwa_ansi::{color::MAX_COLOR_DISTANCE, LumaCurve};
+    /// assert_eq!(LumaCurve::Linear.threshold(50.), MAX_COLOR_DISTANCE / 2);
+    /// assert_eq!(LumaCurve::Cubic.threshold(50.), MAX_COLOR_DISTANCE / 8);
+    /// assert_eq!(LumaCurve::Quadratic.threshold(100.), 0);
+    /// // finer than the whole steps of the luma correct
+    /// assert!(LumaCurve::Cubic.threshold(80.5) < LumaCurve::Cubic.threshold(80.));
+    /// ```
+    #[inline]
+    pub fn threshold(self, luma_correct: f32) -> u32 {
+        let t = (100. - luma_correct.clamp(0., 100.)) / 100.;
+        let t = match self {
+            Self::Linear => t,
+            Self::Quadratic => t * t,
+            Self::Cubic => t * t * t,
+        };
+        (t * MAX_COLOR_DISTANCE as f32).round() as u32
+    }
+}
+#[cfg(feature = "parse")]
+impl FromStr for LumaCurve {
+    type Err = String;
+    #[inline]
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Self::Linear),
+            "quadratic" => Ok(Self::Quadratic),
+            "cubic" => Ok(Self::Cubic),
+            _ => Err(format!("{s:?} is not a valid luma curve, expected one of linear, quadratic, or cubic")),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for LumaCurve {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for LumaCurve {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Self::Linear => "linear",
+            Self::Quadratic => "quadratic",
+            Self::Cubic => "cubic",
+        })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for LumaCurve {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// How grays are mapped to the 256 colors, e.g. in the gray color modes and where luma correct picks the gray ramp
+///
+/// Grays are drawn with the closest of the 24 steps of the gray ramp (232 to 255) and the 6 grays of the color cube
+/// (16, 59, 102, 145, 188, and 231), black and white only in the cube. Terminal themes often change the ramp or the cube,
+/// so either can look off, and the bias shifts every gray lighter or darker before it's mapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GrayRamp {
+    /// Added to the value of every gray before it's mapped, positive is lighter
+    pub bias: i8,
+    /// Whether the grays of the color cube are used beside the gray ramp
+    pub cube: bool,
+}
+impl Default for GrayRamp {
+    #[inline]
+    fn default() -> Self { Self { bias: 0, cube: true } }
+}
+impl GrayRamp {
+    /// The closest 256 color to the gray `v`, shifted by the bias
+    /// ```
+    /// use viuwa_ansi::GrayRamp;
+    /// assert_eq!(GrayRamp::default().ansi(0), 16);
+    /// assert_eq!(GrayRamp { bias: 0, cube: false }.ansi(0), 232);
+    /// assert_eq!(GrayRamp { bias: 0, cube: false }.ansi(255), 255);
+    /// assert_eq!(GrayRamp { bias: 10, cube: false }.ansi(128), GrayRamp { bias: 0, cube: false }.ansi(138));
+    /// ```
+    #[inline(always)]
+    pub fn ansi(self, v: u8) -> u8 {
+        let v = (v as i16 + self.bias as i16).clamp(0, 255) as u8;
+        if self.cube {
+            ANSI_GRAY[v as usize]
+        } else {
+            // the steps of the ramp are 8 to 238 by 10
+            232 + ((v.saturating_sub(3)) / 10).min(23)
+        }
+    }
+}
+#[cfg(feature = "parse")]
+impl FromStr for GrayRamp {
+    type Err = String;
+    /// A bias, `cube` or `no-cube`, or both separated by a comma, e.g. `-8,no-cube`
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        let mut ramp = Self::default();
+        for part in s.split(',').map(str::trim) {
+            match part {
+                "cube" => ramp.cube = true,
+                "no-cube" => ramp.cube = false,
+                _ => match part.strip_prefix('+').unwrap_or(part).parse() {
+                    Ok(bias) => ramp.bias = bias,
+                    Err(_) => {
+                        return Err(format!(
+                            "{part:?} is not a valid gray ramp, expected a bias of -128 to 127, cube, or no-cube"
+                        ))
+                    }
+                },
+            }
+        }
+        Ok(ramp)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for GrayRamp {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for GrayRamp {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "{},{}", self.bias, if self.cube { "cube" } else { "no-cube" })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for GrayRamp {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 /// Wrapper around possibly user-controlled color attributes
 #[derive(Debug, Clone, Copy)]
 pub struct ColorAttributes {
-    /// luma correct as a color distance threshold
+    /// luma correct as a color distance threshold, `0..=`[`MAX_COLOR_DISTANCE`]
     pub luma_correct: u32,
+    /// The range of floating point samples mapped to 0..=255, samples outside it are clamped
+    pub float_range: (f32, f32),
+    /// The colormap single-channel images are drawn with
+    pub colormap: Colormap,
+    /// How grays are mapped to the 256 colors
+    pub gray_ramp: GrayRamp,
 }
 
 impl ColorAttributes {
-    /// luma correct is 0..=100, 100 is the highest luma correct
-    // for n and f(luma_correct) = ((100 - luma_correct)^n / 100^(n-1)), as n increases, the luma correct becomes less aggressive
-    // distance threshold = (MAX_COLOR_DISTANCE / 100) * ((100 - luma_correct)^3 / 100^2)
-    pub fn new(luma_correct: u32) -> Self {
-        Self { luma_correct: (((100 - luma_correct).pow(3) / 10000) as f32 * color::MAP_DIST_100) as u32 }
+    /// luma correct is 0.0..=100.0, 100 is the highest luma correct, mapped to a threshold with [`LumaCurve::Cubic`]
+    #[inline]
+    pub fn new(luma_correct: f32) -> Self { Self::from_curve(luma_correct, LumaCurve::Cubic) }
+    /// luma correct is 0.0..=100.0, mapped to a threshold with `curve`
+    #[inline]
+    pub fn from_curve(luma_correct: f32, curve: LumaCurve) -> Self { Self::from_threshold(curve.threshold(luma_correct)) }
+    /// The color distance threshold given directly, clamped to [`MAX_COLOR_DISTANCE`]
+    #[inline]
+    pub fn from_threshold(threshold: u32) -> Self {
+        Self {
+            luma_correct: threshold.min(MAX_COLOR_DISTANCE),
+            float_range: (0., 1.),
+            colormap: Colormap::Gray,
+            gray_ramp: GrayRamp::default(),
+        }
+    }
+    /// Map floating point samples from `min..=max` instead of `0.0..=1.0`, e.g. for scientific data or an exposure
+    #[inline]
+    pub fn with_float_range(mut self, min: f32, max: f32) -> Self {
+        debug_assert!(min < max, "empty float range {min}..={max}");
+        self.float_range = (min, max);
+        self
+    }
+    /// Draw single-channel images with a colormap instead of in gray
+    #[inline]
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
     }
+    /// Map grays to the 256 colors with `gray_ramp`
+    #[inline]
+    pub fn with_gray_ramp(mut self, gray_ramp: GrayRamp) -> Self {
+        self.gray_ramp = gray_ramp;
+        self
+    }
+    /// Map a floating point sample in the float range to 8 bits, clamping samples outside it, NaN is 0
+    /// ```
+    /// use viuwa_ansi::ColorAttributes;
+    /// let a = ColorAttributes::default();
+    /// assert_eq!((a.quantize_float(0.5), a.quantize_float(2.), a.quantize_float(-1.), a.quantize_float(f32::NAN)), (128, 255, 0, 0));
+    /// assert_eq!(a.with_float_range(-1., 1.).quantize_float(0.), 128);
+    /// ```
+    #[inline(always)]
+    pub fn quantize_float(&self, v: f32) -> u8 {
+        let (min, max) = self.float_range;
+        ((v - min) * (255. / (max - min))).clamp(0., 255.).round() as u8
+    }
+}
+impl Default for ColorAttributes {
+    /// The most aggressive luma correct
+    #[inline]
+    fn default() -> Self { Self::new(100.) }
 }
 
 macro_rules! color_enum {
@@ -230,7 +598,7 @@ pub const MAP_DIST_100: f32 = MAX_COLOR_DISTANCE as f32 / 100.;
 #[inline]
 pub fn rgb_to_ansi(c: [u8; 3], a: ColorAttributes) -> u8 {
     let xyz = rgb_to_ansi_direct(c);
-    let gray = gray_to_ansi(luma(c));
+    let gray = a.gray_ramp.ansi(luma(c));
     if dist(c, ansi_to_rgb(gray)) + a.luma_correct < dist(c, ansi_to_rgb(xyz)) {
         gray
     } else {
@@ -238,10 +606,46 @@ pub fn rgb_to_ansi(c: [u8; 3], a: ColorAttributes) -> u8 {
     }
 }
 
+/// [`rgb_to_ansi`] of every color of `src` into the start of `dst`, 8 at a time with the `simd` feature.
+/// ```
+/// use viuwa_ansi::{color::{rgb_to_ansi, rgb_to_ansi_slice}, ColorAttributes};
+/// let src: Vec<_> = (0..=255).map(|c| [c, 255 - c, c / 2]).collect();
+/// let mut dst = vec![0; src.len()];
+/// rgb_to_ansi_slice(&src, &mut dst, ColorAttributes::new(50.));
+/// assert!(src.iter().zip(&dst).all(|(&c, &d)| rgb_to_ansi(c, ColorAttributes::new(50.)) == d));
+/// ```
+#[inline]
+pub fn rgb_to_ansi_slice(src: &[[u8; 3]], dst: &mut [u8], a: ColorAttributes) {
+    let len = src.len().min(dst.len());
+    let (src, dst) = (&src[..len], &mut dst[..len]);
+    #[cfg(feature = "simd")]
+    let (src, dst) = {
+        let (src, rest) = src.as_chunks::<{ simd::LANES }>();
+        let (dst, dst_rest) = dst.as_chunks_mut::<{ simd::LANES }>();
+        ::core::iter::zip(src, dst).for_each(|(src, dst)| *dst = simd::rgb_to_ansi8(src, a));
+        (rest, dst_rest)
+    };
+    ::core::iter::zip(src, dst).for_each(|(&src, dst)| *dst = rgb_to_ansi(src, a));
+}
+
+/// [`luma`] of every color of `src` into the start of `dst`.
+///
+/// The compiler vectorizes this loop on its own, faster than the `simd` feature could with its division.
+#[inline]
+pub fn luma_slice(src: &[[u8; 3]], dst: &mut [u8]) { ::core::iter::zip(src, dst).for_each(|(&src, dst)| *dst = luma(src)) }
+
 /// Grayscale u8 to ANSI 256 (8-bit) color.
 #[inline(always)]
 pub fn gray_to_ansi(c: u8) -> u8 { ANSI_GRAY[c as usize] }
 
+/// Quantize a 16-bit color channel to 8 bits, rounding to the nearest.
+/// ```
+/// use viuwa_ansi::color::quantize16;
+/// assert_eq!((quantize16(0), quantize16(257), quantize16(128), quantize16(u16::MAX)), (0, 1, 0, 255));
+/// ```
+#[inline(always)]
+pub const fn quantize16(c: u16) -> u8 { ((c as u32 * 255 + 32895) >> 16) as u8 }
+
 /// ANSI 256 (8-bit) color to 24-bit RGB color.
 #[inline(always)]
 pub fn ansi_to_rgb(c: u8) -> [u8; 3] { ANSI_PALETTE[c as usize] }
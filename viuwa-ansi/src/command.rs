@@ -0,0 +1,100 @@
+//! [`crossterm::Command`] implementations, so viuwa output can be interleaved with crossterm's `queue!` and `execute!`.
+//! ```no_run
+//! use crossterm::{cursor::MoveTo, execute};
+//! use viuwa_ansi::{consts::CLEAR_SCREEN, Sequence};
+//! execute!(std::io::stdout(), Sequence(CLEAR_SCREEN), MoveTo(0, 0)).unwrap();
+//! ```
+use core::{fmt, marker::PhantomData};
+
+use crossterm::Command;
+
+use super::*;
+use crate::image::{AnsiRow, AnyOrder, DynamicAnsiRow, FullAnsiRow, HalfAnsiRow, Order};
+
+/// Write the bytes of a cell or sequence, dropping the nul padding of its [`Char`]
+#[inline]
+fn write_bytes(f: &mut impl fmt::Write, bytes: &[u8]) -> fmt::Result {
+    f.write_str(core::str::from_utf8(bytes).map_err(|_| fmt::Error)?.trim_end_matches('\0'))
+}
+
+/// Only ANSI terminals are supported, there is no WinAPI equivalent
+#[cfg(windows)]
+#[inline]
+fn unsupported() -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "viuwa-ansi commands require ANSI support"))
+}
+
+/// An escape sequence, such as any of the [`consts`](crate::consts), as a [`Command`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sequence(pub &'static str);
+impl Command for Sequence {
+    #[inline]
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result { f.write_str(self.0) }
+    #[cfg(windows)]
+    #[inline]
+    fn execute_winapi(&self) -> std::io::Result<()> { unsupported() }
+}
+
+/// A single cell yielded by an [`AnsiRow`] (a color sequence and a [`Char`]) as a [`Command`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell<T: Bytes>(pub T);
+impl<T: Bytes> Command for Cell<T> {
+    #[inline]
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result { write_bytes(f, self.0.as_slice()) }
+    #[cfg(windows)]
+    #[inline]
+    fn execute_winapi(&self) -> std::io::Result<()> { unsupported() }
+}
+
+impl<'a, P: AnsiPixel, C: Converter, O: Order<P, C>> Command for FullAnsiRow<'a, P, C, O> {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        let row =
+            FullAnsiRow::<P, C, O> { iter: self.iter.clone(), char: self.char, attrs: self.attrs, phantom: PhantomData };
+        for cell in row {
+            write_bytes(f, cell.as_slice())?;
+        }
+        Ok(())
+    }
+    #[cfg(windows)]
+    #[inline]
+    fn execute_winapi(&self) -> std::io::Result<()> { unsupported() }
+}
+impl<'a, P: AnsiPixel, C: Converter, O: Order<P, C>> Command for HalfAnsiRow<'a, P, C, O> {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        let row =
+            HalfAnsiRow::<P, C, O> { iter: self.iter.clone(), char: self.char, attrs: self.attrs, phantom: PhantomData };
+        for cell in row {
+            write_bytes(f, cell.as_slice())?;
+        }
+        Ok(())
+    }
+    #[cfg(windows)]
+    #[inline]
+    fn execute_winapi(&self) -> std::io::Result<()> { unsupported() }
+}
+impl<'a, P: AnsiPixel, C: Converter, O: Order<P, C>> Command for AnsiRow<'a, P, C, O> {
+    #[inline]
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match self {
+            AnsiRow::Full(row) => row.write_ansi(f),
+            AnsiRow::Half(row) => row.write_ansi(f),
+        }
+    }
+    #[cfg(windows)]
+    #[inline]
+    fn execute_winapi(&self) -> std::io::Result<()> { unsupported() }
+}
+impl<'a, P: AnsiPixel, O: AnyOrder<P>> Command for DynamicAnsiRow<'a, P, O> {
+    #[inline]
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match self {
+            DynamicAnsiRow::Color(row) => row.write_ansi(f),
+            DynamicAnsiRow::Gray(row) => row.write_ansi(f),
+            DynamicAnsiRow::AnsiColor(row) => row.write_ansi(f),
+            DynamicAnsiRow::AnsiGray(row) => row.write_ansi(f),
+        }
+    }
+    #[cfg(windows)]
+    #[inline]
+    fn execute_winapi(&self) -> std::io::Result<()> { unsupported() }
+}
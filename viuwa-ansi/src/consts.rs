@@ -38,6 +38,10 @@ pub const EXIT_ALT_SCREEN: &str = csi!("?1049l");
 pub const ENABLE_LINE_WRAP: &str = csi!("?7h");
 /// Disable line wrap
 pub const DISABLE_LINE_WRAP: &str = csi!("?7l");
+/// Report mouse buttons and the wheel in the SGR format, `ESC [ < <button> ; <col> ; <row> M` (sent to stdin)
+pub const ENABLE_MOUSE: &str = csi!("?1000;1006h");
+/// Stop reporting mouse buttons and the wheel
+pub const DISABLE_MOUSE: &str = csi!("?1000;1006l");
 // pub const START_SIXEL: &str = dcs!("Pq");
 
 /// Scroll up 1 line
@@ -45,6 +49,9 @@ pub const SCROLL_UP: &str = csi!("1S");
 /// Scroll down 1 line
 pub const SCROLL_DOWN: &str = csi!("1T");
 
+/// The light box drawing characters of a box outline, clockwise from the top left corner, then horizontal and vertical
+pub const BOX_LIGHT: [char; 6] = ['┌', '┐', '┘', '└', '─', '│'];
+
 /// Hide the cursor
 pub const HIDE_CURSOR: &str = csi!("?25l");
 /// Show the cursor
@@ -55,6 +62,11 @@ pub const SAVE_CURSOR: &str = esc!("7");
 pub const RESTORE_CURSOR: &str = esc!("8");
 /// Report the cursor position in the format `ESC [ <row> ; <col> R` (sent to stdin)
 pub const REPORT_CURSOR_POSITION: &str = csi!("6n");
+/// Report the size of the text area in pixels (XTWINOPS) in the format `ESC [ 4 ; <height> ; <width> t` (sent to stdin)
+pub const REPORT_TEXT_AREA_PIXELS: &str = csi!("14t");
+/// Report the default background color in the format `ESC ] 11 ; rgb:<red>/<green>/<blue>` ended by BEL or ST,
+/// with 1 to 4 hex digits per channel (sent to stdin)
+pub const REPORT_BACKGROUND: &str = osc!("11;?\x07");
 
 /// Move the cursor to the top left corner (can be overwritten by the terminal)
 pub const CURSOR_HOME: &str = csi!("H");
@@ -0,0 +1,378 @@
+//! Glyph selection, drawing each character cell with whichever block, wedge, or diagonal glyph
+//! (and pair of foreground and background colors) reproduces the pixels it covers best, like chafa.
+//!
+//! Every cell covers [`CELL`] pixels, so an image drawn with glyphs must be resized to 4 times the
+//! width and height it would be drawn with half blocks, and edges within a cell are kept instead of being averaged away.
+//!
+//! Complementary glyphs (e.g. `'▀'` and `'▄'`) are redundant because the colors can be swapped,
+//! so only one of each pair is a candidate.
+//!
+//! Which classes of glyphs are candidates is chosen with [`Symbols`], for fonts with poor coverage or alignment.
+#[cfg(all(not(feature = "std"), feature = "parse"))]
+use alloc::{format, string::ToString};
+#[cfg(feature = "parse")]
+use core::str::FromStr;
+
+use viuwa_image::{Pixel, PixelRepr, Scalar, Weight};
+
+use super::*;
+
+/// The columns and rows of pixels covered by one character cell drawn with glyphs
+pub const CELL: (usize, usize) = (4, 8);
+
+/// A character with the pixels of a [`CELL`] it covers in the foreground color,
+/// bit `y * 4 + x` of the mask being the pixel at (x, y)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Glyph {
+    pub char: Char,
+    pub mask: u32,
+}
+impl Glyph {
+    /// A glyph of a character and its mask
+    #[inline]
+    pub const fn new(char: char, mask: u32) -> Self { Self { char: Char::from_char(char), mask } }
+}
+
+/// The mask of the pixels from (x0, y0) up to (x1, y1)
+const fn rect(x0: usize, y0: usize, x1: usize, y1: usize) -> u32 {
+    let mut mask = 0;
+    let mut y = y0;
+    while y < y1 {
+        let mut x = x0;
+        while x < x1 {
+            mask |= 1 << (y * CELL.0 + x);
+            x += 1;
+        }
+        y += 1;
+    }
+    mask
+}
+
+/// The mask of the pixels whose centers are below the diagonal from the top right (or top left, if `left`) corner
+const fn wedge(left: bool) -> u32 {
+    let mut mask = 0;
+    let mut i = 0;
+    while i < CELL.0 * CELL.1 {
+        let (x, y) = (i % CELL.0, i / CELL.0);
+        let x = if left { CELL.0 - 1 - x } else { x };
+        // (2x + 1) / 8 + (2y + 1) / 16 > 1
+        if 4 * x + 2 * y + 3 > 16 {
+            mask |= 1 << i;
+        }
+        i += 1;
+    }
+    mask
+}
+
+/// The mask of the pixels a line from the bottom left (or bottom right, if `back`) to the opposite corner passes through
+const fn diagonal(back: bool) -> u32 {
+    let mut mask = 0;
+    let mut y = 0;
+    while y < CELL.1 {
+        let x = (15 - 2 * y) / 4;
+        let x = if back { CELL.0 - 1 - x } else { x };
+        mask |= 1 << (y * CELL.0 + x);
+        y += 1;
+    }
+    mask
+}
+
+/// The braille pattern of every combination of dots, each dot covering 2x2 pixels
+const fn braille() -> [Glyph; 256] {
+    // the column and row of the dot of each bit of the pattern
+    const DOTS: [(usize, usize); 8] = [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (0, 3), (1, 3)];
+    let mut glyphs = [Glyph::new(' ', 0); 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut mask = 0;
+        let mut bit = 0;
+        while bit < 8 {
+            if i >> bit & 1 == 1 {
+                let (x, y) = (DOTS[bit].0 * 2, DOTS[bit].1 * 2);
+                mask |= rect(x, y, x + 2, y + 2);
+            }
+            bit += 1;
+        }
+        glyphs[i] = match char::from_u32(0x2800 + i as u32) {
+            Some(c) => Glyph::new(c, mask),
+            None => Glyph::new(' ', 0),
+        };
+        i += 1;
+    }
+    glyphs
+}
+
+/// The upper and left half blocks, the upper half block first so it's chosen for flat cells
+pub static HALF_GLYPHS: &[Glyph] = &[Glyph::new('▀', rect(0, 0, 4, 4)), Glyph::new('▌', rect(0, 0, 2, 8))];
+/// The quadrant blocks
+pub static QUAD_GLYPHS: &[Glyph] = &[
+    Glyph::new('▘', rect(0, 0, 2, 4)),
+    Glyph::new('▝', rect(2, 0, 4, 4)),
+    Glyph::new('▖', rect(0, 4, 2, 8)),
+    Glyph::new('▗', rect(2, 4, 4, 8)),
+    Glyph::new('▚', rect(0, 0, 2, 4) | rect(2, 4, 4, 8)),
+];
+/// The lower eighth and left quarter blocks
+pub static BLOCK_GLYPHS: &[Glyph] = &[
+    Glyph::new('▁', rect(0, 7, 4, 8)),
+    Glyph::new('▂', rect(0, 6, 4, 8)),
+    Glyph::new('▃', rect(0, 5, 4, 8)),
+    Glyph::new('▅', rect(0, 3, 4, 8)),
+    Glyph::new('▆', rect(0, 2, 4, 8)),
+    Glyph::new('▇', rect(0, 1, 4, 8)),
+    Glyph::new('▎', rect(0, 0, 1, 8)),
+    Glyph::new('▊', rect(0, 0, 3, 8)),
+];
+/// The lower right and lower left triangles
+pub static WEDGE_GLYPHS: &[Glyph] = &[Glyph::new('◢', wedge(false)), Glyph::new('◣', wedge(true))];
+/// The light diagonal lines
+pub static DIAGONAL_GLYPHS: &[Glyph] = &[Glyph::new('╱', diagonal(false)), Glyph::new('╲', diagonal(true))];
+/// Every braille pattern, the blank pattern first so it's chosen for flat cells
+pub static BRAILLE_GLYPHS: &[Glyph] = &braille();
+/// Printable ASCII approximating the pixels they cover, the space first so it's chosen for flat cells
+pub static ASCII_GLYPHS: &[Glyph] = &[
+    Glyph::new(' ', 0),
+    Glyph::new('.', rect(1, 6, 3, 8)),
+    Glyph::new('\'', rect(1, 0, 3, 2)),
+    Glyph::new('-', rect(0, 3, 4, 5)),
+    Glyph::new('_', rect(0, 7, 4, 8)),
+    Glyph::new('|', rect(1, 0, 3, 8)),
+    Glyph::new('/', diagonal(false)),
+    Glyph::new('\\', diagonal(true)),
+    Glyph::new(':', rect(1, 1, 3, 3) | rect(1, 5, 3, 7)),
+    Glyph::new('=', rect(0, 2, 4, 3) | rect(0, 5, 4, 6)),
+    Glyph::new('+', rect(0, 3, 4, 5) | rect(1, 1, 3, 7)),
+];
+
+/// The mask of the pixels of a [`CELL`] a character covers in the foreground color,
+/// for any glyph (or the complementary lower half block or the full block) viuwa draws with
+/// ```
+/// use viuwa_ansi::glyph::mask_of;
+/// assert_eq!(mask_of('█'), Some(u32::MAX));
+/// assert_eq!(mask_of('▄'), mask_of('▀').map(|mask| !mask));
+/// assert_eq!(mask_of('x'), None);
+/// ```
+pub fn mask_of(c: char) -> Option<u32> {
+    match c {
+        '█' => Some(u32::MAX),
+        '▄' => Some(rect(0, 4, 4, 8)),
+        c => {
+            let char = Char::from_char(c);
+            Symbols::CLASSES.iter().flat_map(|(_, _, glyphs)| glyphs.iter()).find(|g| g.char == char).map(|g| g.mask)
+        }
+    }
+}
+
+/// The classes of glyphs to choose between, like chafa's `--symbols`,
+/// parsed from and displayed as a comma separated list (e.g. `"half,quad"`)
+/// ```
+/// use viuwa_ansi::glyph::Symbols;
+/// let symbols: Symbols = "block,half,quad".parse().unwrap();
+/// assert!(symbols.contains(Symbols::QUAD) && !symbols.contains(Symbols::BRAILLE));
+/// assert_eq!(symbols.to_string(), "half,quad,block");
+/// assert!("half,emoji".parse::<Symbols>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbols(u8);
+impl Symbols {
+    /// The upper and left half blocks, [`HALF_GLYPHS`]
+    pub const HALF: Self = Self(1);
+    /// The quadrant blocks, [`QUAD_GLYPHS`]
+    pub const QUAD: Self = Self(1 << 1);
+    /// The eighth and quarter blocks, [`BLOCK_GLYPHS`]
+    pub const BLOCK: Self = Self(1 << 2);
+    /// The triangles, [`WEDGE_GLYPHS`]
+    pub const WEDGE: Self = Self(1 << 3);
+    /// The diagonal lines, [`DIAGONAL_GLYPHS`]
+    pub const DIAGONAL: Self = Self(1 << 4);
+    /// The braille patterns, [`BRAILLE_GLYPHS`]
+    pub const BRAILLE: Self = Self(1 << 5);
+    /// Printable ASCII, [`ASCII_GLYPHS`]
+    pub const ASCII: Self = Self(1 << 6);
+    /// Every class of glyphs
+    pub const ALL: Self = Self((1 << 7) - 1);
+    /// The names and glyphs of each class, in the order their glyphs are candidates
+    const CLASSES: [(&'static str, Self, &'static [Glyph]); 7] = [
+        ("half", Self::HALF, HALF_GLYPHS),
+        ("quad", Self::QUAD, QUAD_GLYPHS),
+        ("block", Self::BLOCK, BLOCK_GLYPHS),
+        ("wedge", Self::WEDGE, WEDGE_GLYPHS),
+        ("diagonal", Self::DIAGONAL, DIAGONAL_GLYPHS),
+        ("braille", Self::BRAILLE, BRAILLE_GLYPHS),
+        ("ascii", Self::ASCII, ASCII_GLYPHS),
+    ];
+    /// Every class of glyphs in either set
+    #[inline]
+    pub const fn union(self, other: Self) -> Self { Self(self.0 | other.0) }
+    /// Whether every class of glyphs of `other` is in the set
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool { self.0 & other.0 == other.0 }
+    /// Whether the set has no classes of glyphs
+    #[inline]
+    pub const fn is_empty(self) -> bool { self.0 == 0 }
+    /// The glyphs of every class in the set, as candidates for [`select`]
+    pub fn glyphs(self) -> Vec<Glyph> {
+        Self::CLASSES
+            .iter()
+            .filter(|(_, class, _)| self.contains(*class))
+            .flat_map(|(_, _, glyphs)| glyphs.iter().copied())
+            .collect()
+    }
+}
+impl Default for Symbols {
+    /// Every class of block glyphs, without braille or ASCII
+    #[inline]
+    fn default() -> Self { Self::HALF | Self::QUAD | Self::BLOCK | Self::WEDGE | Self::DIAGONAL }
+}
+impl ::core::ops::BitOr for Symbols {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self { self.union(rhs) }
+}
+#[cfg(feature = "parse")]
+impl FromStr for Symbols {
+    type Err = String;
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        let mut symbols = Self(0);
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            symbols = symbols.union(match name {
+                "all" => Self::ALL,
+                name => match Self::CLASSES.iter().find(|(n, _, _)| *n == name) {
+                    Some((_, class, _)) => *class,
+                    None => {
+                        return Err(format!(
+                            "{name:?} is not a valid class of symbols, expected a comma separated list of half, quad, block, wedge, diagonal, braille, ascii, or all"
+                        ))
+                    }
+                },
+            });
+        }
+        if symbols.is_empty() {
+            return Err("at least one class of symbols is required".to_string());
+        }
+        Ok(symbols)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Symbols {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for Symbols {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let mut names = Self::CLASSES.iter().filter(|(_, class, _)| self.contains(*class)).map(|(name, _, _)| name);
+        if let Some(name) = names.next() {
+            f.write_str(name)?;
+        }
+        for name in names {
+            write!(f, ",{name}")?;
+        }
+        Ok(())
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Symbols {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// Choose the glyph that reproduces the pixels of a cell best, returning it with the mean foreground and background colors.
+///
+/// `pixels` are the pixels of the cell in rows of [`CELL`]`.0`, only those in the `valid` mask are considered,
+/// e.g. the cells on the bottom edge of an image may be cut off.
+/// The glyph minimizing the squared error of the pixels from the mean color of the side they're on is chosen,
+/// so the edge of the glyph lies along the strongest edge within the cell.
+/// ```
+/// use viuwa_ansi::glyph::{select, Symbols};
+/// use viuwa_ansi::ColorPixel;
+/// // the left half black, the right half white
+/// let pixels: Vec<[u8; 3]> = (0..32).map(|i| if i % 4 < 2 { [0; 3] } else { [255; 3] }).collect();
+/// let (glyph, fg, bg) = select::<ColorPixel>(&pixels, u32::MAX, &Symbols::default().glyphs());
+/// assert_eq!((glyph.char, fg, bg), (viuwa_ansi::Char::from_char('▌'), [0; 3], [255; 3]));
+/// ```
+pub fn select<P: Pixel>(pixels: &[P::Repr], valid: u32, glyphs: &[Glyph]) -> (Glyph, P::Repr, P::Repr) {
+    type Weights<P> = <<P as Pixel>::Repr as PixelRepr>::Weights;
+    let add = |sum: &mut Weights<P>, px: &P::Repr| {
+        for (s, c) in ::core::iter::zip(sum.as_slice_mut(), px.as_slice()) {
+            *s += c.weight();
+        }
+    };
+    let dot = |sum: &Weights<P>| sum.as_slice().iter().map(|s| s * s).sum::<Weight>();
+    let valid = if pixels.len() < 32 { valid & ((1 << pixels.len()) - 1) } else { valid };
+    let n = valid.count_ones();
+    let mut total = Weights::<P>::ZERO;
+    for (_, px) in pixels.iter().enumerate().filter(|(i, _)| valid >> i & 1 == 1) {
+        add(&mut total, px);
+    }
+    // the glyph, foreground and background sums, and score of the best glyph so far,
+    // where maximizing |fg|² / n_fg + |bg|² / n_bg minimizes the squared error
+    let first = glyphs.first().copied().unwrap_or(Glyph::new('▀', 0));
+    let mut best = (first, Weights::<P>::ZERO, total, Weight::NEG_INFINITY);
+    for &glyph in glyphs {
+        let mask = glyph.mask & valid;
+        let nf = mask.count_ones();
+        let mut fg = Weights::<P>::ZERO;
+        for (_, px) in pixels.iter().enumerate().filter(|(i, _)| mask >> i & 1 == 1) {
+            add(&mut fg, px);
+        }
+        let mut bg = total;
+        for (b, f) in ::core::iter::zip(bg.as_slice_mut(), fg.as_slice()) {
+            *b -= f;
+        }
+        let score = match (nf, n - nf) {
+            (0, 0) => 0.,
+            (0, nb) => dot(&bg) / nb as Weight,
+            (nf, 0) => dot(&fg) / nf as Weight,
+            (nf, nb) => dot(&fg) / nf as Weight + dot(&bg) / nb as Weight,
+        };
+        if score > best.3 {
+            best = (glyph, fg, bg, score);
+        }
+    }
+    let (glyph, fg, bg, _) = best;
+    let nf = (glyph.mask & valid).count_ones();
+    // a side without pixels takes the color of the other, so the cell is flat
+    let (fg, bg) = match (nf, n - nf) {
+        (0, nb) => (mean::<P>(&bg, nb), mean::<P>(&bg, nb)),
+        (nf, 0) => (mean::<P>(&fg, nf), mean::<P>(&fg, nf)),
+        (nf, nb) => (mean::<P>(&fg, nf), mean::<P>(&bg, nb)),
+    };
+    (glyph, fg, bg)
+}
+
+/// The rounded mean of two pixels
+#[inline]
+pub(crate) fn midpoint<P: Pixel>(a: P::Repr, b: P::Repr) -> P::Repr {
+    let mut sum = <P::Repr as PixelRepr>::Weights::ZERO;
+    for (s, (a, b)) in ::core::iter::zip(sum.as_slice_mut(), ::core::iter::zip(a.as_slice(), b.as_slice())) {
+        *s = a.weight() + b.weight();
+    }
+    mean::<P>(&sum, 2)
+}
+
+/// The rounded mean of a sum of `n` pixels
+#[inline]
+fn mean<P: Pixel>(sum: &<P::Repr as PixelRepr>::Weights, n: u32) -> P::Repr {
+    let mut px = P::Repr::ZERO;
+    if n == 0 {
+        return px;
+    }
+    // integer scalars truncate, so round them first
+    let round = <P::Scalar as Scalar>::scalar(0.5) == <P::Scalar as Scalar>::ZERO;
+    for (p, &s) in ::core::iter::zip(px.as_slice_mut(), sum.as_slice()) {
+        let v = s / n as Weight;
+        *p = Scalar::scalar(if round { v.round() } else { v });
+    }
+    px
+}
@@ -4,9 +4,13 @@
 //!
 //! NOTE: byte casting any tuple of Converters with the bytes of a Char is safe because they both are (*should be*) align 1.
 
-use std::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use ::core::{fmt, marker::PhantomData};
 
 use super::*;
+use crate::consts::SGR_DEFAULT;
 
 /// Wrapper around an [`ImageView`] with a [`Pixel`] that implements [`AnsiPixel`]
 /// to provide methods for converting the image to ANSI escape sequences by iteration.
@@ -15,6 +19,9 @@ use super::*;
 /// an image in ANSI escape sequences can be *very* large (up to a factor of 40x the original image size).
 pub struct AnsiImage<'a, P: AnsiPixel, C: Converter>(ImageView<'a, P>, PhantomData<C>);
 
+/// The pixels of a row converted at a time, so that converters can convert more than one at once
+const CHUNK: usize = 64;
+
 /// Wrapper around an [`ImageView`] with a [`Pixel`] that implements [`AnsiPixel`]
 /// to provide methods for converting the pixels to ANSI escape sequences
 pub enum DynamicAnsiImage<'a, P: AnsiPixel> {
@@ -45,6 +52,302 @@ impl<'a, P: AnsiPixel, C: Converter> AnsiImage<'a, P, C> {
     pub fn rows_lower(&mut self, attrs: ColorAttributes, char: Option<Char>) -> AnsiRows<P, C, Lower> {
         AnsiRows { iter: self.0.rows(), char: char.unwrap_or(LOWER_HALF_BLOCK), attrs, phantom: PhantomData }
     }
+    /// Character rows iterator over only the `w` columns and `h` rows of characters starting at `x`, `y`. <br>
+    /// Where char is a character that mainly fills the upper half of the cell <br><br>
+    /// `'▀'` will be used if `char` is `None`, the region is clamped to the bounds of the image
+    /// ```
+    /// use viuwa_ansi::{image::AnsiRow, AnsiImage, ColorAttributes, ColorConverter};
+    /// let image = viuwa_image::Image::from(image::RgbImage::new(4, 4));
+    /// let ansi = AnsiImage::<_, ColorConverter>::new(image.view());
+    /// let mut rows = ansi.rows_region(1, 1, 8, 8, ColorAttributes::default(), None);
+    /// assert_eq!(rows.len(), 1);
+    /// assert!(matches!(rows.next(), Some(AnsiRow::Full(row)) if row.len() == 3));
+    /// ```
+    pub fn rows_region(
+        &self,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        attrs: ColorAttributes,
+        char: Option<Char>,
+    ) -> AnsiRows<'_, P, C, Upper> {
+        self.region(x, y, w, h, attrs, char.unwrap_or(UPPER_HALF_BLOCK))
+    }
+    /// Character rows iterator over only the `w` columns and `h` rows of characters starting at `x`, `y`. <br>
+    /// Where char is a character that mainly fills the lower half of the cell <br><br>
+    /// `'▄'` will be used if `char` is `None`, the region is clamped to the bounds of the image
+    pub fn rows_region_lower(
+        &self,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        attrs: ColorAttributes,
+        char: Option<Char>,
+    ) -> AnsiRows<'_, P, C, Lower> {
+        self.region(x, y, w, h, attrs, char.unwrap_or(LOWER_HALF_BLOCK))
+    }
+    fn region<O: Order<P, C>>(
+        &self,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        attrs: ColorAttributes,
+        char: Char,
+    ) -> AnsiRows<'_, P, C, O> {
+        let (width, height) = self.0.dimensions();
+        let x = x.min(width);
+        let (top, bottom) = (y.saturating_mul(2).min(height), y.saturating_add(h).saturating_mul(2).min(height));
+        let region = self.0.subview(x, top, w.min(width - x), bottom - top).expect("region is clamped to the image");
+        AnsiRows { iter: region.into_rows(), char, attrs, phantom: PhantomData }
+    }
+    /// The most bytes any character row of the image can take when written, to size the buffers of the `_into` writers. <br>
+    /// Every cell is at most a full foreground and background sequence and a [`Char`]
+    /// ```
+    /// use viuwa_ansi::{AnsiImage, ColorAttributes, ColorConverter};
+    /// let image = viuwa_image::Image::from(image::RgbImage::new(3, 2));
+    /// let ansi = AnsiImage::<_, ColorConverter>::new(image.view());
+    /// let mut buf = vec![0; ansi.row_capacity()];
+    /// assert_eq!(ansi.write_row_into(&mut buf, 0, ColorAttributes::default(), None), Some(3 * (36 + 3)));
+    /// assert_eq!(ansi.write_row_into(&mut buf[..40], 0, ColorAttributes::default(), None), None);
+    /// ```
+    pub fn row_capacity(&self) -> usize { self.width() * ::core::mem::size_of::<<C::Sequencer as Sequencer>::FullChar>() }
+    #[cfg(feature = "std")]
+    /// Write character row `y` directly into `out`, without an attribute reset or newline. <br>
+    /// Where char is a character that mainly fills the upper half of the cell <br><br>
+    /// `'▀'` will be used if `char` is `None`, nothing is written if `y` is out of bounds
+    pub fn write_row_to(
+        &self,
+        out: &mut impl Write,
+        y: usize,
+        attrs: ColorAttributes,
+        char: Option<Char>,
+    ) -> io::Result<()> {
+        self.row(&mut Io(out), y, attrs, char)
+    }
+    /// Write character row `y` into the start of `buf` as [`write_row_to`](Self::write_row_to) would,
+    /// returning how many bytes were written, or `None` if they don't fit in `buf`
+    pub fn write_row_into(&self, buf: &mut [u8], y: usize, attrs: ColorAttributes, char: Option<Char>) -> Option<usize> {
+        Slice::write(buf, |out| self.row(out, y, attrs, char))
+    }
+    fn row<S: Sink>(&self, out: &mut S, y: usize, attrs: ColorAttributes, char: Option<Char>) -> Result<(), S::Error> {
+        let char = char.unwrap_or(UPPER_HALF_BLOCK);
+        let mut rows = self.0.rows().skip(y * 2);
+        let mut fgs = [<C::Sequencer as Sequencer>::Raw::ZERO; CHUNK];
+        let mut bgs = [<C::Sequencer as Sequencer>::Raw::ZERO; CHUNK];
+        match (rows.next(), rows.next()) {
+            (Some(fg), Some(bg)) => {
+                for (fg, bg) in ::core::iter::zip(fg.chunks(CHUNK), bg.chunks(CHUNK)) {
+                    C::convert_slice::<P>(fg, attrs, &mut fgs);
+                    C::convert_slice::<P>(bg, attrs, &mut bgs);
+                    for (fg, bg) in ::core::iter::zip(&fgs[..fg.len()], &bgs) {
+                        out.put(C::Sequencer::full(*fg, *bg).as_slice())?;
+                        out.put(char.as_bytes())?;
+                    }
+                }
+            }
+            (Some(fg), None) => {
+                for fg in fg.chunks(CHUNK) {
+                    C::convert_slice::<P>(fg, attrs, &mut fgs);
+                    for fg in &fgs[..fg.len()] {
+                        out.put(C::Sequencer::fg(*fg).as_slice())?;
+                        out.put(char.as_bytes())?;
+                    }
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+    /// Write `rows` rows with `row`, each ending with an attribute reset and separated by newlines
+    fn lines<S: Sink>(
+        out: &mut S,
+        rows: usize,
+        mut row: impl FnMut(&mut S, usize) -> Result<(), S::Error>,
+    ) -> Result<(), S::Error> {
+        for y in 0..rows {
+            if y != 0 {
+                out.put(b"\n")?;
+            }
+            row(out, y)?;
+            out.put(SGR_DEFAULT.as_bytes())?;
+        }
+        Ok(())
+    }
+    #[cfg(feature = "std")]
+    /// Write every character row directly into `out`, each row ending with an attribute reset and separated by newlines. <br>
+    /// `'▀'` will be used if `char` is `None`
+    pub fn write_to(&self, out: &mut impl Write, attrs: ColorAttributes, char: Option<Char>) -> io::Result<()> {
+        Self::lines(&mut Io(out), self.height(), |out, y| self.row(out, y, attrs, char))
+    }
+    /// The image as ANSI with the default [`ColorAttributes`] and `'▀'`, as written by `write_to`
+    pub fn to_ansi_string(&self) -> String {
+        let mut buf = Vec::new();
+        let attrs = ColorAttributes::default();
+        // writing to a Vec never fails
+        let _ = Self::lines(&mut buf, self.height(), |out, y| self.row(out, y, attrs, None));
+        String::from_utf8_lossy(&buf).into_owned()
+    }
+    #[cfg(feature = "std")]
+    /// Write character row `y` directly into `out` with one pixel per cell, painted as the background color of a space,
+    /// without an attribute reset or newline. <br>
+    /// Each character row is a single row of pixels, nothing is written if `y` is out of bounds
+    /// ```
+    /// use viuwa_ansi::{AnsiImage, ColorAttributes, ColorConverter};
+    /// let image = viuwa_image::Image::from(image::RgbImage::from_pixel(3, 2, image::Rgb([255, 0, 0])));
+    /// let mut out = Vec::new();
+    /// AnsiImage::<_, ColorConverter>::new(image.view()).write_full_row_to(&mut out, 1, ColorAttributes::default()).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap().matches("[48;2;255;000;000m ").count(), 3);
+    /// ```
+    pub fn write_full_row_to(&self, out: &mut impl Write, y: usize, attrs: ColorAttributes) -> io::Result<()> {
+        self.full_row(&mut Io(out), y, attrs)
+    }
+    /// Write row `y` with one pixel per cell into the start of `buf` as [`write_full_row_to`](Self::write_full_row_to) would,
+    /// returning how many bytes were written, or `None` if they don't fit in `buf`
+    pub fn write_full_row_into(&self, buf: &mut [u8], y: usize, attrs: ColorAttributes) -> Option<usize> {
+        Slice::write(buf, |out| self.full_row(out, y, attrs))
+    }
+    fn full_row<S: Sink>(&self, out: &mut S, y: usize, attrs: ColorAttributes) -> Result<(), S::Error> {
+        let mut bgs = [<C::Sequencer as Sequencer>::Raw::ZERO; CHUNK];
+        for row in self.0.rows().nth(y).iter().flat_map(|row| row.chunks(CHUNK)) {
+            C::convert_slice::<P>(row, attrs, &mut bgs);
+            for bg in &bgs[..row.len()] {
+                out.put(C::Sequencer::bg(*bg).as_slice())?;
+                out.put(b" ")?;
+            }
+        }
+        Ok(())
+    }
+    #[cfg(feature = "std")]
+    /// Write every row of pixels with one pixel per cell directly into `out`,
+    /// each row ending with an attribute reset and separated by newlines, see [`write_full_row_to`](Self::write_full_row_to)
+    pub fn write_full_to(&self, out: &mut impl Write, attrs: ColorAttributes) -> io::Result<()> {
+        Self::lines(&mut Io(out), self.0.height(), |out, y| self.full_row(out, y, attrs))
+    }
+    #[cfg(feature = "std")]
+    /// Write character row `y` directly into `out` with foreground colors only, without an attribute reset or newline,
+    /// leaving pixels with a luma of at most `background` to the terminal's own background. <br>
+    /// Each cell is `'▀'`, `'▄'`, `'█'` (the mean of both pixels), or a space, nothing is written if `y` is out of bounds
+    /// ```
+    /// use viuwa_ansi::{AnsiImage, ColorAttributes, ColorConverter};
+    /// // a lit pixel above a dark one, and a dark pixel above a lit one
+    /// let image = viuwa_image::Image::from(image::RgbImage::from_fn(2, 2, |x, y| image::Rgb([if x == y { 200 } else { 0 }; 3])));
+    /// let mut out = Vec::new();
+    /// AnsiImage::<_, ColorConverter>::new(image.view()).write_fg_row_to(&mut out, 0, ColorAttributes::default(), 16).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.contains('▀') && out.contains('▄') && !out.contains("[48;"));
+    /// ```
+    pub fn write_fg_row_to(&self, out: &mut impl Write, y: usize, attrs: ColorAttributes, background: u8) -> io::Result<()> {
+        self.fg_row(&mut Io(out), y, attrs, background)
+    }
+    /// Write character row `y` with foreground colors only into the start of `buf` as [`write_fg_row_to`](Self::write_fg_row_to) would,
+    /// returning how many bytes were written, or `None` if they don't fit in `buf`
+    pub fn write_fg_row_into(&self, buf: &mut [u8], y: usize, attrs: ColorAttributes, background: u8) -> Option<usize> {
+        Slice::write(buf, |out| self.fg_row(out, y, attrs, background))
+    }
+    fn fg_row<S: Sink>(&self, out: &mut S, y: usize, attrs: ColorAttributes, background: u8) -> Result<(), S::Error> {
+        let lit = |px: &&P::Repr| P::to_luma(**px, attrs) > background;
+        let mut rows = self.0.rows().skip(y * 2);
+        let (Some(upper), lower) = (rows.next(), rows.next()) else {
+            return Ok(());
+        };
+        for (x, up) in upper.iter().enumerate() {
+            let low = lower.as_ref().and_then(|row| row.get(x));
+            match (Some(up).filter(lit), low.filter(lit)) {
+                (Some(up), Some(low)) => {
+                    out.put(C::fg::<P>(glyph::midpoint::<P>(*up, *low), attrs).as_slice())?;
+                    out.put(FULL_BLOCK.as_bytes())?;
+                }
+                (Some(up), None) => {
+                    out.put(C::fg::<P>(*up, attrs).as_slice())?;
+                    out.put(UPPER_HALF_BLOCK.as_bytes())?;
+                }
+                (None, Some(low)) => {
+                    out.put(C::fg::<P>(*low, attrs).as_slice())?;
+                    out.put(LOWER_HALF_BLOCK.as_bytes())?;
+                }
+                (None, None) => out.put(b" ")?,
+            }
+        }
+        Ok(())
+    }
+    #[cfg(feature = "std")]
+    /// Write every character row with foreground colors only directly into `out`,
+    /// each row ending with an attribute reset and separated by newlines, see [`write_fg_row_to`](Self::write_fg_row_to)
+    pub fn write_fg_to(&self, out: &mut impl Write, attrs: ColorAttributes, background: u8) -> io::Result<()> {
+        Self::lines(&mut Io(out), self.height(), |out, y| self.fg_row(out, y, attrs, background))
+    }
+    /// The dimensions of the image in characters when drawn with [`glyph`]s, each character covering a [`CELL`](glyph::CELL) of pixels
+    pub fn glyph_dimensions(&self) -> (usize, usize) {
+        let (w, h) = self.0.dimensions();
+        (w.div_ceil(glyph::CELL.0), h.div_ceil(glyph::CELL.1))
+    }
+    #[cfg(feature = "std")]
+    /// Write character row `y` directly into `out` drawn with whichever of `glyphs` fits each cell best,
+    /// without an attribute reset or newline. <br>
+    /// Nothing is written if `y` is out of bounds
+    /// ```
+    /// use viuwa_ansi::{glyph::Symbols, AnsiImage, ColorAttributes, ColorConverter};
+    /// let image = viuwa_image::Image::from(image::RgbImage::from_fn(8, 8, |x, _| image::Rgb([(x % 4) as u8 * 80; 3])));
+    /// let ansi = AnsiImage::<_, ColorConverter>::new(image.view());
+    /// assert_eq!(ansi.glyph_dimensions(), (2, 1));
+    /// let mut out = Vec::new();
+    /// ansi.write_glyph_row_to(&mut out, 0, ColorAttributes::default(), &Symbols::default().glyphs()).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap().matches('▌').count(), 2);
+    /// ```
+    pub fn write_glyph_row_to(
+        &self,
+        out: &mut impl Write,
+        y: usize,
+        attrs: ColorAttributes,
+        glyphs: &[glyph::Glyph],
+    ) -> io::Result<()> {
+        self.glyph_row(&mut Io(out), y, attrs, glyphs)
+    }
+    /// Write character row `y` drawn with `glyphs` into the start of `buf` as [`write_glyph_row_to`](Self::write_glyph_row_to) would,
+    /// returning how many bytes were written, or `None` if they don't fit in `buf`
+    pub fn write_glyph_row_into(
+        &self,
+        buf: &mut [u8],
+        y: usize,
+        attrs: ColorAttributes,
+        glyphs: &[glyph::Glyph],
+    ) -> Option<usize> {
+        Slice::write(buf, |out| self.glyph_row(out, y, attrs, glyphs))
+    }
+    fn glyph_row<S: Sink>(
+        &self,
+        out: &mut S,
+        y: usize,
+        attrs: ColorAttributes,
+        glyphs: &[glyph::Glyph],
+    ) -> Result<(), S::Error> {
+        let (cw, ch) = glyph::CELL;
+        let rows: Vec<_> = self.0.rows().skip(y * ch).take(ch).collect();
+        let mut pixels = [P::Repr::ZERO; 32];
+        for x in 0..self.glyph_dimensions().0 {
+            let mut valid = 0;
+            for (cy, row) in rows.iter().enumerate() {
+                for (cx, px) in row.iter().enumerate().skip(x * cw).take(cw) {
+                    let i = cy * cw + cx - x * cw;
+                    pixels[i] = *px;
+                    valid |= 1 << i;
+                }
+            }
+            let (glyph, fg, bg) = glyph::select::<P>(&pixels, valid, glyphs);
+            out.put(C::full::<P>(fg, bg, attrs).as_slice())?;
+            out.put(glyph.char.as_bytes())?;
+        }
+        Ok(())
+    }
+    #[cfg(feature = "std")]
+    /// Write every character row drawn with `glyphs` directly into `out`,
+    /// each row ending with an attribute reset and separated by newlines.
+    pub fn write_glyphs_to(&self, out: &mut impl Write, attrs: ColorAttributes, glyphs: &[glyph::Glyph]) -> io::Result<()> {
+        Self::lines(&mut Io(out), self.glyph_dimensions().1, |out, y| self.glyph_row(out, y, attrs, glyphs))
+    }
     #[cfg(feature = "rayon")]
     /// Parallel character rows iterator with a given [`char`] and [`ColorAttributes`].
     /// Where char is a character that mainly fills the upper half of the cell, e.g. `'▀'` <br>
@@ -124,6 +427,206 @@ impl<'a, P: AnsiPixel> DynamicAnsiImage<'a, P> {
     pub fn height(&self) -> usize { dyn_map!(self, |image| image.height()) }
     /// The dimensions of the image in characters
     pub fn dimensions(&self) -> (usize, usize) { dyn_map!(self, |image| image.dimensions()) }
+    /// The most bytes any character row can take when written, see [`AnsiImage::row_capacity`]
+    #[inline]
+    pub fn row_capacity(&self) -> usize { dyn_map!(self, |image| image.row_capacity()) }
+    #[cfg(feature = "std")]
+    /// Write character row `y` directly into `out`, see [`AnsiImage::write_row_to`]
+    #[inline]
+    pub fn write_row_to(
+        &self,
+        out: &mut impl Write,
+        y: usize,
+        attrs: ColorAttributes,
+        char: Option<Char>,
+    ) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_row_to(out, y, attrs, char))
+    }
+    /// Write character row `y` into the start of `buf`, see [`AnsiImage::write_row_into`]
+    #[inline]
+    pub fn write_row_into(&self, buf: &mut [u8], y: usize, attrs: ColorAttributes, char: Option<Char>) -> Option<usize> {
+        dyn_map!(self, |image| image.write_row_into(buf, y, attrs, char))
+    }
+    #[cfg(feature = "std")]
+    /// Write every character row directly into `out`, see [`AnsiImage::write_to`]
+    #[inline]
+    pub fn write_to(&self, out: &mut impl Write, attrs: ColorAttributes, char: Option<Char>) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_to(out, attrs, char))
+    }
+    /// The image as ANSI, see [`AnsiImage::to_ansi_string`]
+    #[inline]
+    pub fn to_ansi_string(&self) -> String { dyn_map!(self, |image| image.to_ansi_string()) }
+    #[cfg(feature = "std")]
+    /// Write row `y` with one pixel per cell directly into `out`, see [`AnsiImage::write_full_row_to`]
+    #[inline]
+    pub fn write_full_row_to(&self, out: &mut impl Write, y: usize, attrs: ColorAttributes) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_full_row_to(out, y, attrs))
+    }
+    /// Write row `y` with one pixel per cell into the start of `buf`, see [`AnsiImage::write_full_row_into`]
+    #[inline]
+    pub fn write_full_row_into(&self, buf: &mut [u8], y: usize, attrs: ColorAttributes) -> Option<usize> {
+        dyn_map!(self, |image| image.write_full_row_into(buf, y, attrs))
+    }
+    #[cfg(feature = "std")]
+    /// Write every row with one pixel per cell directly into `out`, see [`AnsiImage::write_full_to`]
+    #[inline]
+    pub fn write_full_to(&self, out: &mut impl Write, attrs: ColorAttributes) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_full_to(out, attrs))
+    }
+    #[cfg(feature = "std")]
+    /// Write character row `y` with foreground colors only directly into `out`, see [`AnsiImage::write_fg_row_to`]
+    #[inline]
+    pub fn write_fg_row_to(&self, out: &mut impl Write, y: usize, attrs: ColorAttributes, background: u8) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_fg_row_to(out, y, attrs, background))
+    }
+    /// Write character row `y` with foreground colors only into the start of `buf`, see [`AnsiImage::write_fg_row_into`]
+    #[inline]
+    pub fn write_fg_row_into(&self, buf: &mut [u8], y: usize, attrs: ColorAttributes, background: u8) -> Option<usize> {
+        dyn_map!(self, |image| image.write_fg_row_into(buf, y, attrs, background))
+    }
+    #[cfg(feature = "std")]
+    /// Write every character row with foreground colors only directly into `out`, see [`AnsiImage::write_fg_to`]
+    #[inline]
+    pub fn write_fg_to(&self, out: &mut impl Write, attrs: ColorAttributes, background: u8) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_fg_to(out, attrs, background))
+    }
+    /// The dimensions of the image in characters when drawn with glyphs, see [`AnsiImage::glyph_dimensions`]
+    #[inline]
+    pub fn glyph_dimensions(&self) -> (usize, usize) { dyn_map!(self, |image| image.glyph_dimensions()) }
+    #[cfg(feature = "std")]
+    /// Write character row `y` drawn with glyphs directly into `out`, see [`AnsiImage::write_glyph_row_to`]
+    #[inline]
+    pub fn write_glyph_row_to(
+        &self,
+        out: &mut impl Write,
+        y: usize,
+        attrs: ColorAttributes,
+        glyphs: &[glyph::Glyph],
+    ) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_glyph_row_to(out, y, attrs, glyphs))
+    }
+    /// Write character row `y` drawn with glyphs into the start of `buf`, see [`AnsiImage::write_glyph_row_into`]
+    #[inline]
+    pub fn write_glyph_row_into(
+        &self,
+        buf: &mut [u8],
+        y: usize,
+        attrs: ColorAttributes,
+        glyphs: &[glyph::Glyph],
+    ) -> Option<usize> {
+        dyn_map!(self, |image| image.write_glyph_row_into(buf, y, attrs, glyphs))
+    }
+    #[cfg(feature = "std")]
+    /// Write every character row drawn with glyphs directly into `out`, see [`AnsiImage::write_glyphs_to`]
+    #[inline]
+    pub fn write_glyphs_to(&self, out: &mut impl Write, attrs: ColorAttributes, glyphs: &[glyph::Glyph]) -> io::Result<()> {
+        dyn_map!(self, |image| image.write_glyphs_to(out, attrs, glyphs))
+    }
+    /// Character rows iterator of any [`ColorType`], see [`AnsiImage::rows_upper`]
+    pub fn rows_upper(&mut self, attrs: ColorAttributes, char: Option<Char>) -> DynamicAnsiRows<'_, P, Upper> {
+        match self {
+            Self::Color(image) => DynamicAnsiRows::Color(image.rows_upper(attrs, char)),
+            Self::Gray(image) => DynamicAnsiRows::Gray(image.rows_upper(attrs, char)),
+            Self::AnsiColor(image) => DynamicAnsiRows::AnsiColor(image.rows_upper(attrs, char)),
+            Self::AnsiGray(image) => DynamicAnsiRows::AnsiGray(image.rows_upper(attrs, char)),
+        }
+    }
+    /// Character rows iterator over a region of characters of any [`ColorType`], see [`AnsiImage::rows_region`]
+    pub fn rows_region(
+        &self,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        attrs: ColorAttributes,
+        char: Option<Char>,
+    ) -> DynamicAnsiRows<'_, P, Upper> {
+        match self {
+            Self::Color(image) => DynamicAnsiRows::Color(image.rows_region(x, y, w, h, attrs, char)),
+            Self::Gray(image) => DynamicAnsiRows::Gray(image.rows_region(x, y, w, h, attrs, char)),
+            Self::AnsiColor(image) => DynamicAnsiRows::AnsiColor(image.rows_region(x, y, w, h, attrs, char)),
+            Self::AnsiGray(image) => DynamicAnsiRows::AnsiGray(image.rows_region(x, y, w, h, attrs, char)),
+        }
+    }
+    /// Character rows iterator over a region of characters of any [`ColorType`], see [`AnsiImage::rows_region_lower`]
+    pub fn rows_region_lower(
+        &self,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        attrs: ColorAttributes,
+        char: Option<Char>,
+    ) -> DynamicAnsiRows<'_, P, Lower> {
+        match self {
+            Self::Color(image) => DynamicAnsiRows::Color(image.rows_region_lower(x, y, w, h, attrs, char)),
+            Self::Gray(image) => DynamicAnsiRows::Gray(image.rows_region_lower(x, y, w, h, attrs, char)),
+            Self::AnsiColor(image) => DynamicAnsiRows::AnsiColor(image.rows_region_lower(x, y, w, h, attrs, char)),
+            Self::AnsiGray(image) => DynamicAnsiRows::AnsiGray(image.rows_region_lower(x, y, w, h, attrs, char)),
+        }
+    }
+    /// Character rows iterator of any [`ColorType`], see [`AnsiImage::rows_lower`]
+    pub fn rows_lower(&mut self, attrs: ColorAttributes, char: Option<Char>) -> DynamicAnsiRows<'_, P, Lower> {
+        match self {
+            Self::Color(image) => DynamicAnsiRows::Color(image.rows_lower(attrs, char)),
+            Self::Gray(image) => DynamicAnsiRows::Gray(image.rows_lower(attrs, char)),
+            Self::AnsiColor(image) => DynamicAnsiRows::AnsiColor(image.rows_lower(attrs, char)),
+            Self::AnsiGray(image) => DynamicAnsiRows::AnsiGray(image.rows_lower(attrs, char)),
+        }
+    }
+}
+
+/// Iterator over rows of characters in a [`DynamicAnsiImage`]
+pub enum DynamicAnsiRows<'a, P: AnsiPixel, O: AnyOrder<P>> {
+    Color(AnsiRows<'a, P, ColorConverter, O>),
+    Gray(AnsiRows<'a, P, GrayConverter, O>),
+    AnsiColor(AnsiRows<'a, P, AnsiColorConverter, O>),
+    AnsiGray(AnsiRows<'a, P, AnsiGrayConverter, O>),
+}
+/// A row of characters in a [`DynamicAnsiImage`], written with [`write_to`](Self::write_to)
+pub enum DynamicAnsiRow<'a, P: AnsiPixel, O: AnyOrder<P>> {
+    Color(AnsiRow<'a, P, ColorConverter, O>),
+    Gray(AnsiRow<'a, P, GrayConverter, O>),
+    AnsiColor(AnsiRow<'a, P, AnsiColorConverter, O>),
+    AnsiGray(AnsiRow<'a, P, AnsiGrayConverter, O>),
+}
+impl<'a, P: AnsiPixel, O: AnyOrder<P>> Iterator for DynamicAnsiRows<'a, P, O> {
+    type Item = DynamicAnsiRow<'a, P, O>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Color(rows) => rows.next().map(DynamicAnsiRow::Color),
+            Self::Gray(rows) => rows.next().map(DynamicAnsiRow::Gray),
+            Self::AnsiColor(rows) => rows.next().map(DynamicAnsiRow::AnsiColor),
+            Self::AnsiGray(rows) => rows.next().map(DynamicAnsiRow::AnsiGray),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { dyn_map!(self, |rows| rows.size_hint()) }
+}
+impl<'a, P: AnsiPixel, O: AnyOrder<P>> ExactSizeIterator for DynamicAnsiRows<'a, P, O> {}
+impl<'a, P: AnsiPixel, O: AnyOrder<P>> ::core::iter::FusedIterator for DynamicAnsiRows<'a, P, O> {}
+impl<'a, P: AnsiPixel, O: AnyOrder<P>> DynamicAnsiRow<'a, P, O> {
+    /// Write every character of the row, without a trailing attribute reset or newline
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn write_to(self, out: &mut impl Write) -> io::Result<()> { dyn_map!(self, |row| row.write_to(out)) }
+}
+
+/// Displays the image as ANSI, see [`AnsiImage::to_ansi_string`]
+/// ```
+/// use viuwa_ansi::{AnsiImage, ColorConverter};
+/// let image = viuwa_image::Image::from(image::RgbImage::new(4, 4));
+/// println!("{}", AnsiImage::<_, ColorConverter>::new(image.view()));
+/// ```
+impl<'a, P: AnsiPixel, C: Converter> fmt::Display for AnsiImage<'a, P, C> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.to_ansi_string()) }
+}
+/// Displays the image as ANSI, see [`AnsiImage::to_ansi_string`]
+impl<'a, P: AnsiPixel> fmt::Display for DynamicAnsiImage<'a, P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.to_ansi_string()) }
 }
 
 impl<'a, P: AnsiPixel> From<AnsiImage<'a, P, ColorConverter>> for DynamicAnsiImage<'a, P> {
@@ -146,6 +649,52 @@ impl<'a, P: AnsiPixel> From<AnsiImage<'a, P, AnsiGrayConverter>> for DynamicAnsi
 #[inline(always)]
 fn div_ceil2(n: usize) -> usize { (n >> 1) + (n & 1) }
 
+/// Where rows are written, so the same encoders write into an [`io::Write`], a [`Vec`], or a byte buffer
+trait Sink {
+    type Error;
+    fn put(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+/// An [`io::Write`] as a [`Sink`]
+#[cfg(feature = "std")]
+struct Io<'a, W>(&'a mut W);
+#[cfg(feature = "std")]
+impl<W: Write> Sink for Io<'_, W> {
+    type Error = io::Error;
+    #[inline(always)]
+    fn put(&mut self, bytes: &[u8]) -> io::Result<()> { self.0.write_all(bytes) }
+}
+impl Sink for Vec<u8> {
+    type Error = ::core::convert::Infallible;
+    #[inline(always)]
+    fn put(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+/// A byte buffer and how much of it is written, failing once it's full
+struct Slice<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+impl<'a> Slice<'a> {
+    /// Write into the start of `buf` with `f`, returning how many bytes were written if they all fit
+    #[inline]
+    fn write(buf: &'a mut [u8], f: impl FnOnce(&mut Self) -> Result<(), ()>) -> Option<usize> {
+        let mut out = Self { buf, len: 0 };
+        f(&mut out).ok().map(|_| out.len)
+    }
+}
+impl Sink for Slice<'_> {
+    type Error = ();
+    #[inline(always)]
+    fn put(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        let end = self.len + bytes.len();
+        self.buf.get_mut(self.len..end).ok_or(())?.copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+}
+
 mod iter {
     use ::core::{iter::*, slice::*};
 
@@ -205,9 +754,37 @@ mod iter {
         Full(FullAnsiRow<'a, P, C, O>),
         Half(HalfAnsiRow<'a, P, C, O>),
     }
+    impl<'a, P: AnsiPixel, C: Converter, O: Order<P, C>> AnsiRow<'a, P, C, O> {
+        /// Write every character of the row, without a trailing attribute reset or newline
+        #[cfg(feature = "std")]
+        pub fn write_to(self, out: &mut impl Write) -> io::Result<()> {
+            match self {
+                AnsiRow::Full(row) => {
+                    for c in row {
+                        out.write_all(c.as_slice())?;
+                    }
+                }
+                AnsiRow::Half(row) => {
+                    for c in row {
+                        out.write_all(c.as_slice())?;
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+    /// An [`Order`] for any [`Converter`], used by [`DynamicAnsiRows`]
+    pub trait AnyOrder<P: AnsiPixel>:
+        Order<P, ColorConverter> + Order<P, GrayConverter> + Order<P, AnsiColorConverter> + Order<P, AnsiGrayConverter>
+    {
+    }
+    impl<P: AnsiPixel, O> AnyOrder<P> for O where
+        O: Order<P, ColorConverter> + Order<P, GrayConverter> + Order<P, AnsiColorConverter> + Order<P, AnsiGrayConverter>
+    {
+    }
     /// Iterator over rows of characters in an image
     pub struct AnsiRows<'a, P: AnsiPixel, C: Converter, O: Order<P, C>> {
-        pub(crate) iter: ChunksExact<'a, P::Repr>,
+        pub(crate) iter: Rows<'a, P>,
         pub(crate) char: Char,
         pub(crate) attrs: ColorAttributes,
         pub(crate) phantom: PhantomData<(C, O)>,
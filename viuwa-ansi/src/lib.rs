@@ -1,8 +1,16 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
 
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+// float methods from libm, which are inherent with std
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
-use viuwa_image::{ImageView, PixelRepr};
+use viuwa_image::{ImageView, PixelRepr, Rows};
 
 mod private {
     pub trait Sealed {}
@@ -14,22 +22,34 @@ pub mod consts;
 mod statics;
 #[doc(inline)]
 pub use statics::*;
+#[cfg(feature = "std")]
 mod traits;
+#[cfg(feature = "std")]
 #[doc(inline)]
 pub use traits::*;
 pub mod color;
 use color::*;
-pub use color::{ColorAttributes, ColorDepth, ColorPresets, ColorSpace, ColorType};
+#[cfg(feature = "simd")]
+mod simd;
+pub use color::{ColorAttributes, ColorDepth, ColorPresets, ColorSpace, ColorType, Colormap, GrayRamp, LumaCurve};
 mod pixel;
 #[doc(inline)]
 pub use pixel::*;
 pub mod image;
 pub use crate::image::{AnsiImage, DynamicAnsiImage};
+#[cfg(all(feature = "std", not(target_family = "wasm")))]
+mod command;
+pub mod glyph;
+#[cfg(all(feature = "std", not(target_family = "wasm")))]
+#[doc(inline)]
+pub use command::*;
 
 /// ```'▄'``` (U+2584) in UTF-8 codepoints. A default `Lower` `Order` character.
 pub const LOWER_HALF_BLOCK: Char = Char([0xE2, 0x96, 0x84, 0]); // Char::from_char('▀');
 /// ```'▀'``` (U+2580) in UTF-8 codepoints. A default `Upper` `Order` character.
 pub const UPPER_HALF_BLOCK: Char = Char([0xE2, 0x96, 0x80, 0]); // Char::from_char('▀');
+/// ```'█'``` (U+2588) in UTF-8 codepoints.
+pub const FULL_BLOCK: Char = Char([0xE2, 0x96, 0x88, 0]); // Char::from_char('█');
 
 /// [`PixelRepr`] with `u8` [`Scalar`].
 pub trait Bytes: PixelRepr<Scalar = u8> {}
@@ -45,6 +65,12 @@ impl Char {
     /// Get the raw codepoints of this `Char`.
     #[inline(always)]
     pub const fn into_inner(self) -> [u8; 4] { self.0 }
+    /// The UTF-8 bytes of this `Char`, without the nul padding.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self.0.iter().position(|&b| b == 0).unwrap_or(4).max(1);
+        &self.0[..len]
+    }
     /// Create a new `Char` from a `char`.<br>
     /// If you know the `char` in advance, instead create a Char` directly with the respective UTF-8 codepoints as `[u8; 4]`.
     ///
@@ -111,6 +111,15 @@ pub trait Converter: Sealed {
     type Sequencer: Sequencer;
     /// Convert a pixel into raw color channels that can be used by the [`Sequencer`].
     fn convert<P: AnsiPixel>(pixel: P::Repr, attributes: ColorAttributes) -> <Self::Sequencer as Sequencer>::Raw;
+    /// Convert every pixel of `ps` into raw color channels into the start of `out`.
+    #[inline]
+    fn convert_slice<P: AnsiPixel>(
+        ps: &[P::Repr],
+        attributes: ColorAttributes,
+        out: &mut [<Self::Sequencer as Sequencer>::Raw],
+    ) {
+        ::core::iter::zip(ps, out).for_each(|(p, out)| *out = Self::convert::<P>(*p, attributes));
+    }
     /// Convert a pixel into a foreground color sequence.
     #[inline(always)]
     fn fg<P: AnsiPixel>(pixel: P::Repr, attributes: ColorAttributes) -> <Self::Sequencer as Sequencer>::Half {
@@ -142,6 +151,10 @@ impl Converter for AnsiColorConverter {
     type Sequencer = AnsiSequencer;
     #[inline(always)]
     fn convert<P: AnsiPixel>(p: P::Repr, a: ColorAttributes) -> <Self::Sequencer as Sequencer>::Raw { P::to_256(p, a) }
+    #[inline(always)]
+    fn convert_slice<P: AnsiPixel>(ps: &[P::Repr], a: ColorAttributes, out: &mut [<Self::Sequencer as Sequencer>::Raw]) {
+        P::to_256_slice(ps, a, out)
+    }
 }
 /// Converter to 24-bit (RGB) grayscale colors.
 pub struct GrayConverter;
@@ -158,7 +171,7 @@ impl Converter for AnsiGrayConverter {
     type Sequencer = AnsiSequencer;
     #[inline(always)]
     fn convert<P: AnsiPixel>(p: P::Repr, a: ColorAttributes) -> <Self::Sequencer as Sequencer>::Raw {
-        gray_to_ansi(P::to_luma(p, a))
+        a.gray_ramp.ansi(P::to_luma(p, a))
     }
 }
 
@@ -170,6 +183,12 @@ pub trait AnsiPixel: Pixel {
     fn to_luma(p: Self::Repr, a: ColorAttributes) -> u8;
     /// Convert Repr to 8-bit ANSI 256 color value.
     fn to_256(p: Self::Repr, a: ColorAttributes) -> u8;
+    /// Convert every Repr of `ps` to an 8-bit ANSI 256 color value into the start of `out`,
+    /// override to convert more than one pixel at a time.
+    #[inline]
+    fn to_256_slice(ps: &[Self::Repr], a: ColorAttributes, out: &mut [u8]) {
+        ::core::iter::zip(ps, out).for_each(|(p, out)| *out = Self::to_256(*p, a));
+    }
 }
 
 /// Predefined 24-bit RGB pixel usable with a [`Converter`]
@@ -185,6 +204,8 @@ impl AnsiPixel for ColorPixel {
     fn to_luma(p: Self::Repr, _: ColorAttributes) -> u8 { luma(p) }
     #[inline(always)]
     fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { rgb_to_ansi(p, a) }
+    #[inline(always)]
+    fn to_256_slice(ps: &[Self::Repr], a: ColorAttributes, out: &mut [u8]) { rgb_to_ansi_slice(ps, out, a) }
 }
 /// Predefined 8-bit (ANSI 256) color pixel usable with a [`Converter`]
 pub struct AnsiColorPixel;
@@ -208,11 +229,11 @@ impl Pixel for GrayPixel {
 }
 impl AnsiPixel for GrayPixel {
     #[inline(always)]
-    fn to_rgb(p: Self::Repr, _: ColorAttributes) -> [u8; 3] { [p; 3] }
+    fn to_rgb(p: Self::Repr, a: ColorAttributes) -> [u8; 3] { a.colormap.map(p) }
     #[inline(always)]
     fn to_luma(p: Self::Repr, _: ColorAttributes) -> u8 { p }
     #[inline(always)]
-    fn to_256(p: Self::Repr, _: ColorAttributes) -> u8 { gray_to_ansi(p) }
+    fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { a.colormap.map_256(p, a) }
 }
 /// Predefined 8-bit (ANSI 256) grayscale pixel usable with a [`Converter`]
 pub struct AnsiGrayPixel;
@@ -235,7 +256,8 @@ mod compat_image {
 
     use super::*;
 
-    // We can only guarantee the expected behavior of Pixel<u8>
+    // We can only guarantee the expected behavior of Pixel<u8>, Pixel<u16>, and Pixel<f32>, which are quantized to 8 bits when converted,
+    // floats from the float range of the attributes
     impl AnsiPixel for Rgb<u8> {
         #[inline(always)]
         fn to_rgb(p: Self::Repr, _: ColorAttributes) -> [u8; 3] { p }
@@ -243,13 +265,47 @@ mod compat_image {
         fn to_luma(p: Self::Repr, _: ColorAttributes) -> u8 { luma(p) }
         #[inline(always)]
         fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { rgb_to_ansi(p, a) }
+        #[inline(always)]
+        fn to_256_slice(ps: &[Self::Repr], a: ColorAttributes, out: &mut [u8]) { rgb_to_ansi_slice(ps, out, a) }
     }
     impl AnsiPixel for Luma<u8> {
         #[inline(always)]
-        fn to_rgb(p: Self::Repr, _: ColorAttributes) -> [u8; 3] { [p; 3] }
+        fn to_rgb(p: Self::Repr, a: ColorAttributes) -> [u8; 3] { a.colormap.map(p) }
         #[inline(always)]
         fn to_luma(p: Self::Repr, _: ColorAttributes) -> u8 { p }
         #[inline(always)]
-        fn to_256(p: Self::Repr, _: ColorAttributes) -> u8 { gray_to_ansi(p) }
+        fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { a.colormap.map_256(p, a) }
+    }
+    impl AnsiPixel for Rgb<u16> {
+        #[inline(always)]
+        fn to_rgb(p: Self::Repr, _: ColorAttributes) -> [u8; 3] { p.map(quantize16) }
+        #[inline(always)]
+        fn to_luma(p: Self::Repr, _: ColorAttributes) -> u8 { luma(p.map(quantize16)) }
+        #[inline(always)]
+        fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { rgb_to_ansi(p.map(quantize16), a) }
+    }
+    impl AnsiPixel for Luma<u16> {
+        #[inline(always)]
+        fn to_rgb(p: Self::Repr, a: ColorAttributes) -> [u8; 3] { a.colormap.map(quantize16(p)) }
+        #[inline(always)]
+        fn to_luma(p: Self::Repr, _: ColorAttributes) -> u8 { quantize16(p) }
+        #[inline(always)]
+        fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { a.colormap.map_256(quantize16(p), a) }
+    }
+    impl AnsiPixel for Rgb<f32> {
+        #[inline(always)]
+        fn to_rgb(p: Self::Repr, a: ColorAttributes) -> [u8; 3] { p.map(|c| a.quantize_float(c)) }
+        #[inline(always)]
+        fn to_luma(p: Self::Repr, a: ColorAttributes) -> u8 { luma(Self::to_rgb(p, a)) }
+        #[inline(always)]
+        fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { rgb_to_ansi(Self::to_rgb(p, a), a) }
+    }
+    impl AnsiPixel for Luma<f32> {
+        #[inline(always)]
+        fn to_rgb(p: Self::Repr, a: ColorAttributes) -> [u8; 3] { a.colormap.map(a.quantize_float(p)) }
+        #[inline(always)]
+        fn to_luma(p: Self::Repr, a: ColorAttributes) -> u8 { a.quantize_float(p) }
+        #[inline(always)]
+        fn to_256(p: Self::Repr, a: ColorAttributes) -> u8 { a.colormap.map_256(a.quantize_float(p), a) }
     }
 }
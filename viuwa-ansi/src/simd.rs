@@ -0,0 +1,51 @@
+//! Color conversion of 8 colors at a time with [`wide`], which is SSE2 on x86_64 and NEON on aarch64
+//! (or AVX when compiled for it), and plain arrays elsewhere.
+//!
+//! The results are exactly those of the scalar functions in [`color`](crate::color).
+use wide::{f32x8, i32x8, CmpEq, CmpLt};
+
+use super::*;
+
+/// The number of colors converted at a time
+pub const LANES: usize = 8;
+
+/// The channels of 8 colors in separate vectors
+#[inline(always)]
+fn channels(c: &[[u8; 3]; LANES]) -> [i32x8; 3] {
+    let ch = |i: usize| i32x8::from(c.map(|c| c[i] as i32));
+    [ch(0), ch(1), ch(2)]
+}
+
+/// [`luma`] of 8 colors
+#[inline(always)]
+fn lumas([r, g, b]: [i32x8; 3]) -> i32x8 {
+    // the sum is exact in f32, and no quotient is close enough below a whole number to round up to it
+    let sum = r * i32x8::splat(2126) + g * i32x8::splat(7152) + b * i32x8::splat(722);
+    (f32x8::from_i32x8(sum) / f32x8::splat(10000.)).trunc_int()
+}
+
+/// [`dist`] of 8 pairs of colors
+#[inline(always)]
+fn dists([r1, g1, b1]: [i32x8; 3], [r2, g2, b2]: [i32x8; 3]) -> i32x8 {
+    let rmean = (r1 + r2) >> 1;
+    let (r, g, b) = ((r1 - r2).abs(), (g1 - g2).abs(), (b1 - b2).abs());
+    (((i32x8::splat(512) + rmean) * r * r) >> 8) + i32x8::splat(4) * g * g + (((i32x8::splat(767) - rmean) * b * b) >> 8)
+}
+
+/// [`rgb_to_ansi`] of 8 colors
+#[inline]
+pub fn rgb_to_ansi8(c: &[[u8; 3]; LANES], a: ColorAttributes) -> [u8; LANES] {
+    let rgb = channels(c);
+    // the closest levels of the 6x6x6 cube, no channel is ever close enough to halfway between two for f32 to round it differently
+    let level = rgb.map(|c| (f32x8::from_i32x8(c) * f32x8::splat(5. / 255.)).round_int());
+    let cube = i32x8::splat(16) + i32x8::splat(36) * level[0] + i32x8::splat(6) * level[1] + level[2];
+    // levels are 0, then 95 to 255 in steps of 40
+    let cube_rgb = level.map(|l| l.cmp_eq(i32x8::ZERO).blend(i32x8::ZERO, i32x8::splat(55) + i32x8::splat(40) * l));
+    // the table lookups aren't vectorized, every gray of the table is as bright in each channel
+    let gray = lumas(rgb).to_array().map(|l| a.gray_ramp.ansi(l as u8));
+    let gray_rgb = i32x8::from(gray.map(|g| ansi_to_rgb(g)[0] as i32));
+    let luma_correct = i32x8::splat(a.luma_correct as i32);
+    let use_gray = (dists(rgb, [gray_rgb; 3]) + luma_correct).cmp_lt(dists(rgb, cube_rgb));
+    let gray = i32x8::from(gray.map(i32::from));
+    use_gray.blend(gray, cube).to_array().map(|c| c as u8)
+}
@@ -64,6 +64,26 @@ const FMT_U8_ARRAY: [[u8; 3]; 256] = unsafe {
 /// (`size_of_val([&[u8]; 256])` == 4 KB)
 pub static FMT_U8: [[u8; 3]; 256] = FMT_U8_ARRAY;
 
+/// Format a u32 in base 10 without leading zeros into the end of `buf`, returning the digits,
+/// for numbers too large for [`FMT_U8`] without going through `core::fmt`.
+/// ```
+/// let mut buf = [0; 10];
+/// assert_eq!(viuwa_ansi::fmt_u32(0, &mut buf), b"0");
+/// assert_eq!(viuwa_ansi::fmt_u32(u32::MAX, &mut buf), u32::MAX.to_string().as_bytes());
+/// ```
+#[inline]
+pub fn fmt_u32(mut n: u32, buf: &mut [u8; 10]) -> &[u8] {
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buf[i..];
+        }
+    }
+}
+
 /// 256 (8-bit) ANSI color palette as 24-bit RGB values. (0.75 KB)
 #[rustfmt::skip]
 pub static ANSI_PALETTE: [[u8; 3]; 256] = [
@@ -114,3 +134,114 @@ pub static ANSI_PALETTE: [[u8; 3]; 256] = [
 [0x80,0x80,0x80], [0x8A,0x8A,0x8A], [0x94,0x94,0x94], [0x9E,0x9E,0x9E], [0xA8,0xA8,0xA8], [0xB2,0xB2,0xB2],
 [0xBC,0xBC,0xBC], [0xC6,0xC6,0xC6], [0xD0,0xD0,0xD0], [0xDA,0xDA,0xDA], [0xE4,0xE4,0xE4], [0xEE,0xEE,0xEE],
 ];
+
+/// The viridis colormap, perceptually uniform from dark purple to yellow, interpolated from 11 stops. (0.75 KB)
+#[rustfmt::skip]
+pub static VIRIDIS: [[u8; 3]; 256] = [
+[0x44, 0x01, 0x54],[0x44, 0x02, 0x55],[0x44, 0x04, 0x57],[0x44, 0x05, 0x58],[0x45, 0x06, 0x59],[0x45, 0x08, 0x5A],[0x45, 0x09, 0x5C],[0x45, 0x0B, 0x5D],
+[0x45, 0x0C, 0x5E],[0x45, 0x0D, 0x60],[0x46, 0x0F, 0x61],[0x46, 0x10, 0x62],[0x46, 0x11, 0x64],[0x46, 0x13, 0x65],[0x46, 0x14, 0x66],[0x46, 0x16, 0x67],
+[0x47, 0x17, 0x69],[0x47, 0x18, 0x6A],[0x47, 0x1A, 0x6B],[0x47, 0x1B, 0x6D],[0x47, 0x1C, 0x6E],[0x47, 0x1E, 0x6F],[0x47, 0x1F, 0x70],[0x48, 0x21, 0x72],
+[0x48, 0x22, 0x73],[0x48, 0x23, 0x74],[0x48, 0x25, 0x75],[0x48, 0x26, 0x76],[0x47, 0x27, 0x77],[0x47, 0x28, 0x77],[0x47, 0x2A, 0x78],[0x46, 0x2B, 0x79],
+[0x46, 0x2C, 0x7A],[0x46, 0x2D, 0x7A],[0x46, 0x2F, 0x7B],[0x45, 0x30, 0x7C],[0x45, 0x31, 0x7C],[0x45, 0x32, 0x7D],[0x45, 0x34, 0x7E],[0x44, 0x35, 0x7F],
+[0x44, 0x36, 0x7F],[0x44, 0x37, 0x80],[0x43, 0x39, 0x81],[0x43, 0x3A, 0x81],[0x43, 0x3B, 0x82],[0x43, 0x3C, 0x83],[0x42, 0x3E, 0x83],[0x42, 0x3F, 0x84],
+[0x42, 0x40, 0x85],[0x42, 0x41, 0x86],[0x41, 0x43, 0x86],[0x41, 0x44, 0x87],[0x41, 0x45, 0x87],[0x40, 0x46, 0x87],[0x40, 0x47, 0x88],[0x3F, 0x48, 0x88],
+[0x3F, 0x49, 0x88],[0x3E, 0x4A, 0x88],[0x3E, 0x4B, 0x89],[0x3D, 0x4C, 0x89],[0x3D, 0x4E, 0x89],[0x3C, 0x4F, 0x89],[0x3C, 0x50, 0x8A],[0x3B, 0x51, 0x8A],
+[0x3B, 0x52, 0x8A],[0x3A, 0x53, 0x8A],[0x3A, 0x54, 0x8B],[0x39, 0x55, 0x8B],[0x39, 0x56, 0x8B],[0x39, 0x57, 0x8B],[0x38, 0x58, 0x8B],[0x38, 0x59, 0x8C],
+[0x37, 0x5A, 0x8C],[0x37, 0x5B, 0x8C],[0x36, 0x5C, 0x8C],[0x36, 0x5D, 0x8D],[0x35, 0x5E, 0x8D],[0x35, 0x5F, 0x8D],[0x34, 0x60, 0x8D],[0x34, 0x61, 0x8D],
+[0x33, 0x62, 0x8D],[0x33, 0x63, 0x8D],[0x33, 0x64, 0x8D],[0x32, 0x65, 0x8D],[0x32, 0x66, 0x8D],[0x31, 0x67, 0x8D],[0x31, 0x68, 0x8D],[0x30, 0x69, 0x8D],
+[0x30, 0x6A, 0x8D],[0x30, 0x6B, 0x8D],[0x2F, 0x6C, 0x8E],[0x2F, 0x6D, 0x8E],[0x2E, 0x6E, 0x8E],[0x2E, 0x6F, 0x8E],[0x2D, 0x70, 0x8E],[0x2D, 0x71, 0x8E],
+[0x2D, 0x72, 0x8E],[0x2C, 0x73, 0x8E],[0x2C, 0x74, 0x8E],[0x2B, 0x75, 0x8E],[0x2B, 0x76, 0x8E],[0x2A, 0x77, 0x8E],[0x2A, 0x78, 0x8E],[0x2A, 0x79, 0x8E],
+[0x29, 0x7A, 0x8E],[0x29, 0x7B, 0x8E],[0x29, 0x7C, 0x8E],[0x28, 0x7D, 0x8E],[0x28, 0x7E, 0x8E],[0x28, 0x7F, 0x8D],[0x27, 0x80, 0x8D],[0x27, 0x81, 0x8D],
+[0x26, 0x82, 0x8D],[0x26, 0x83, 0x8D],[0x26, 0x84, 0x8D],[0x25, 0x85, 0x8D],[0x25, 0x86, 0x8D],[0x25, 0x87, 0x8D],[0x24, 0x88, 0x8D],[0x24, 0x89, 0x8D],
+[0x24, 0x8A, 0x8D],[0x23, 0x8B, 0x8D],[0x23, 0x8C, 0x8C],[0x23, 0x8D, 0x8C],[0x22, 0x8E, 0x8C],[0x22, 0x8F, 0x8C],[0x22, 0x90, 0x8C],[0x21, 0x91, 0x8C],
+[0x21, 0x91, 0x8C],[0x21, 0x92, 0x8C],[0x21, 0x93, 0x8B],[0x21, 0x94, 0x8B],[0x21, 0x95, 0x8B],[0x21, 0x96, 0x8A],[0x21, 0x97, 0x8A],[0x21, 0x98, 0x8A],
+[0x21, 0x99, 0x89],[0x21, 0x9A, 0x89],[0x21, 0x9A, 0x89],[0x21, 0x9B, 0x88],[0x21, 0x9C, 0x88],[0x22, 0x9D, 0x88],[0x22, 0x9E, 0x87],[0x22, 0x9F, 0x87],
+[0x22, 0xA0, 0x87],[0x22, 0xA1, 0x87],[0x22, 0xA2, 0x86],[0x22, 0xA3, 0x86],[0x22, 0xA3, 0x86],[0x22, 0xA4, 0x85],[0x22, 0xA5, 0x85],[0x22, 0xA6, 0x85],
+[0x22, 0xA7, 0x84],[0x22, 0xA8, 0x84],[0x23, 0xA9, 0x83],[0x25, 0xAA, 0x82],[0x26, 0xAB, 0x82],[0x27, 0xAC, 0x81],[0x29, 0xAD, 0x80],[0x2A, 0xAD, 0x7F],
+[0x2B, 0xAE, 0x7F],[0x2D, 0xAF, 0x7E],[0x2E, 0xB0, 0x7D],[0x2F, 0xB1, 0x7C],[0x31, 0xB2, 0x7B],[0x32, 0xB3, 0x7B],[0x33, 0xB4, 0x7A],[0x35, 0xB5, 0x79],
+[0x36, 0xB6, 0x78],[0x37, 0xB6, 0x77],[0x39, 0xB7, 0x77],[0x3A, 0xB8, 0x76],[0x3B, 0xB9, 0x75],[0x3D, 0xBA, 0x74],[0x3E, 0xBB, 0x74],[0x3F, 0xBC, 0x73],
+[0x41, 0xBD, 0x72],[0x42, 0xBE, 0x71],[0x43, 0xBF, 0x70],[0x45, 0xBF, 0x6F],[0x47, 0xC0, 0x6E],[0x49, 0xC1, 0x6D],[0x4B, 0xC1, 0x6C],[0x4E, 0xC2, 0x6B],
+[0x50, 0xC3, 0x69],[0x52, 0xC4, 0x68],[0x54, 0xC4, 0x67],[0x56, 0xC5, 0x66],[0x58, 0xC6, 0x64],[0x5A, 0xC6, 0x63],[0x5C, 0xC7, 0x62],[0x5E, 0xC8, 0x61],
+[0x61, 0xC9, 0x60],[0x63, 0xC9, 0x5E],[0x65, 0xCA, 0x5D],[0x67, 0xCB, 0x5C],[0x69, 0xCB, 0x5B],[0x6B, 0xCC, 0x5A],[0x6D, 0xCD, 0x58],[0x6F, 0xCD, 0x57],
+[0x72, 0xCE, 0x56],[0x74, 0xCF, 0x55],[0x76, 0xD0, 0x53],[0x78, 0xD0, 0x52],[0x7A, 0xD1, 0x51],[0x7D, 0xD2, 0x4F],[0x7F, 0xD2, 0x4E],[0x82, 0xD3, 0x4C],
+[0x85, 0xD3, 0x4A],[0x87, 0xD4, 0x49],[0x8A, 0xD4, 0x47],[0x8C, 0xD5, 0x45],[0x8F, 0xD5, 0x44],[0x92, 0xD6, 0x42],[0x94, 0xD6, 0x40],[0x97, 0xD7, 0x3E],
+[0x9A, 0xD8, 0x3D],[0x9C, 0xD8, 0x3B],[0x9F, 0xD9, 0x39],[0xA1, 0xD9, 0x38],[0xA4, 0xDA, 0x36],[0xA7, 0xDA, 0x34],[0xA9, 0xDB, 0x33],[0xAC, 0xDB, 0x31],
+[0xAF, 0xDC, 0x2F],[0xB1, 0xDD, 0x2E],[0xB4, 0xDD, 0x2C],[0xB6, 0xDE, 0x2A],[0xB9, 0xDE, 0x29],[0xBC, 0xDF, 0x27],[0xBE, 0xDF, 0x26],[0xC1, 0xDF, 0x26],
+[0xC3, 0xE0, 0x26],[0xC6, 0xE0, 0x26],[0xC8, 0xE0, 0x26],[0xCB, 0xE1, 0x26],[0xCD, 0xE1, 0x26],[0xD0, 0xE1, 0x26],[0xD2, 0xE2, 0x26],[0xD5, 0xE2, 0x26],
+[0xD7, 0xE2, 0x26],[0xDA, 0xE3, 0x26],[0xDC, 0xE3, 0x26],[0xDF, 0xE3, 0x25],[0xE1, 0xE4, 0x25],[0xE4, 0xE4, 0x25],[0xE6, 0xE4, 0x25],[0xE9, 0xE4, 0x25],
+[0xEB, 0xE5, 0x25],[0xEE, 0xE5, 0x25],[0xF0, 0xE5, 0x25],[0xF3, 0xE6, 0x25],[0xF5, 0xE6, 0x25],[0xF8, 0xE6, 0x25],[0xFA, 0xE7, 0x25],[0xFD, 0xE7, 0x25],
+];
+
+/// The magma colormap, perceptually uniform from black through purple to pale yellow, interpolated from 11 stops. (0.75 KB)
+#[rustfmt::skip]
+pub static MAGMA: [[u8; 3]; 256] = [
+[0x00, 0x00, 0x04],[0x01, 0x01, 0x06],[0x02, 0x01, 0x08],[0x02, 0x02, 0x0A],[0x03, 0x02, 0x0C],[0x04, 0x03, 0x0E],[0x05, 0x03, 0x10],[0x05, 0x04, 0x12],
+[0x06, 0x04, 0x14],[0x07, 0x05, 0x16],[0x08, 0x05, 0x18],[0x09, 0x06, 0x1A],[0x09, 0x07, 0x1C],[0x0A, 0x07, 0x1D],[0x0B, 0x08, 0x1F],[0x0C, 0x08, 0x21],
+[0x0D, 0x09, 0x23],[0x0D, 0x09, 0x25],[0x0E, 0x0A, 0x27],[0x0F, 0x0A, 0x29],[0x10, 0x0B, 0x2B],[0x10, 0x0C, 0x2D],[0x11, 0x0C, 0x2F],[0x12, 0x0D, 0x31],
+[0x13, 0x0D, 0x33],[0x14, 0x0E, 0x35],[0x15, 0x0E, 0x37],[0x16, 0x0E, 0x39],[0x18, 0x0E, 0x3C],[0x19, 0x0E, 0x3E],[0x1B, 0x0E, 0x40],[0x1C, 0x0E, 0x43],
+[0x1E, 0x0E, 0x45],[0x1F, 0x0E, 0x47],[0x21, 0x0E, 0x49],[0x23, 0x0E, 0x4C],[0x24, 0x0E, 0x4E],[0x26, 0x0E, 0x50],[0x27, 0x0E, 0x52],[0x29, 0x0F, 0x55],
+[0x2A, 0x0F, 0x57],[0x2C, 0x0F, 0x59],[0x2D, 0x0F, 0x5C],[0x2F, 0x0F, 0x5E],[0x30, 0x0F, 0x60],[0x32, 0x0F, 0x62],[0x33, 0x0F, 0x65],[0x35, 0x0F, 0x67],
+[0x36, 0x0F, 0x69],[0x38, 0x0F, 0x6B],[0x39, 0x0F, 0x6E],[0x3B, 0x0F, 0x70],[0x3D, 0x0F, 0x71],[0x3E, 0x10, 0x71],[0x40, 0x10, 0x72],[0x41, 0x11, 0x73],
+[0x43, 0x11, 0x73],[0x45, 0x12, 0x74],[0x46, 0x12, 0x74],[0x48, 0x12, 0x75],[0x49, 0x13, 0x76],[0x4B, 0x13, 0x76],[0x4D, 0x14, 0x77],[0x4E, 0x14, 0x78],
+[0x50, 0x15, 0x78],[0x52, 0x15, 0x79],[0x53, 0x15, 0x79],[0x55, 0x16, 0x7A],[0x56, 0x16, 0x7B],[0x58, 0x17, 0x7B],[0x5A, 0x17, 0x7C],[0x5B, 0x18, 0x7D],
+[0x5D, 0x18, 0x7D],[0x5E, 0x18, 0x7E],[0x60, 0x19, 0x7E],[0x62, 0x19, 0x7F],[0x63, 0x1A, 0x80],[0x65, 0x1A, 0x80],[0x66, 0x1B, 0x80],[0x68, 0x1B, 0x80],
+[0x69, 0x1C, 0x80],[0x6B, 0x1D, 0x80],[0x6D, 0x1D, 0x80],[0x6E, 0x1E, 0x80],[0x70, 0x1E, 0x80],[0x71, 0x1F, 0x80],[0x73, 0x20, 0x80],[0x74, 0x20, 0x80],
+[0x76, 0x21, 0x80],[0x78, 0x21, 0x80],[0x79, 0x22, 0x81],[0x7B, 0x23, 0x81],[0x7C, 0x23, 0x81],[0x7E, 0x24, 0x81],[0x7F, 0x24, 0x81],[0x81, 0x25, 0x81],
+[0x83, 0x25, 0x81],[0x84, 0x26, 0x81],[0x86, 0x27, 0x81],[0x87, 0x27, 0x81],[0x89, 0x28, 0x81],[0x8A, 0x28, 0x81],[0x8C, 0x29, 0x81],[0x8E, 0x2A, 0x81],
+[0x8F, 0x2A, 0x80],[0x91, 0x2B, 0x80],[0x93, 0x2B, 0x80],[0x94, 0x2C, 0x7F],[0x96, 0x2C, 0x7F],[0x98, 0x2D, 0x7F],[0x99, 0x2D, 0x7E],[0x9B, 0x2E, 0x7E],
+[0x9D, 0x2E, 0x7E],[0x9F, 0x2F, 0x7E],[0xA0, 0x30, 0x7D],[0xA2, 0x30, 0x7D],[0xA4, 0x31, 0x7D],[0xA5, 0x31, 0x7C],[0xA7, 0x32, 0x7C],[0xA9, 0x32, 0x7C],
+[0xAA, 0x33, 0x7B],[0xAC, 0x33, 0x7B],[0xAE, 0x34, 0x7B],[0xAF, 0x35, 0x7A],[0xB1, 0x35, 0x7A],[0xB3, 0x36, 0x7A],[0xB4, 0x36, 0x79],[0xB6, 0x37, 0x79],
+[0xB8, 0x37, 0x79],[0xB9, 0x38, 0x78],[0xBB, 0x39, 0x77],[0xBC, 0x39, 0x77],[0xBE, 0x3A, 0x76],[0xBF, 0x3B, 0x75],[0xC1, 0x3C, 0x75],[0xC2, 0x3C, 0x74],
+[0xC4, 0x3D, 0x73],[0xC6, 0x3E, 0x73],[0xC7, 0x3E, 0x72],[0xC9, 0x3F, 0x71],[0xCA, 0x40, 0x71],[0xCC, 0x41, 0x70],[0xCD, 0x41, 0x6F],[0xCF, 0x42, 0x6F],
+[0xD0, 0x43, 0x6E],[0xD2, 0x43, 0x6D],[0xD3, 0x44, 0x6D],[0xD5, 0x45, 0x6C],[0xD6, 0x45, 0x6B],[0xD8, 0x46, 0x6B],[0xD9, 0x47, 0x6A],[0xDB, 0x48, 0x69],
+[0xDC, 0x48, 0x69],[0xDE, 0x49, 0x68],[0xDF, 0x4B, 0x68],[0xE0, 0x4C, 0x67],[0xE1, 0x4E, 0x67],[0xE2, 0x4F, 0x66],[0xE3, 0x51, 0x66],[0xE4, 0x52, 0x65],
+[0xE5, 0x54, 0x65],[0xE6, 0x55, 0x64],[0xE7, 0x57, 0x64],[0xE8, 0x58, 0x63],[0xE9, 0x5A, 0x63],[0xEA, 0x5B, 0x62],[0xEB, 0x5D, 0x62],[0xEC, 0x5E, 0x61],
+[0xED, 0x60, 0x61],[0xEE, 0x61, 0x60],[0xEF, 0x63, 0x60],[0xF0, 0x65, 0x60],[0xF1, 0x66, 0x5F],[0xF2, 0x68, 0x5F],[0xF3, 0x69, 0x5E],[0xF4, 0x6B, 0x5E],
+[0xF5, 0x6C, 0x5D],[0xF6, 0x6E, 0x5D],[0xF7, 0x6F, 0x5C],[0xF7, 0x71, 0x5C],[0xF7, 0x73, 0x5D],[0xF8, 0x75, 0x5E],[0xF8, 0x76, 0x5E],[0xF8, 0x78, 0x5F],
+[0xF9, 0x7A, 0x60],[0xF9, 0x7C, 0x60],[0xF9, 0x7E, 0x61],[0xF9, 0x80, 0x62],[0xFA, 0x82, 0x62],[0xFA, 0x83, 0x63],[0xFA, 0x85, 0x64],[0xFA, 0x87, 0x64],
+[0xFB, 0x89, 0x65],[0xFB, 0x8B, 0x66],[0xFB, 0x8D, 0x66],[0xFC, 0x8E, 0x67],[0xFC, 0x90, 0x68],[0xFC, 0x92, 0x68],[0xFC, 0x94, 0x69],[0xFD, 0x96, 0x6A],
+[0xFD, 0x98, 0x6A],[0xFD, 0x99, 0x6B],[0xFD, 0x9B, 0x6C],[0xFE, 0x9D, 0x6C],[0xFE, 0x9F, 0x6D],[0xFE, 0xA1, 0x6E],[0xFE, 0xA3, 0x70],[0xFE, 0xA5, 0x71],
+[0xFE, 0xA7, 0x73],[0xFE, 0xA8, 0x74],[0xFE, 0xAA, 0x76],[0xFE, 0xAC, 0x77],[0xFE, 0xAE, 0x79],[0xFE, 0xB0, 0x7A],[0xFE, 0xB2, 0x7C],[0xFE, 0xB4, 0x7D],
+[0xFE, 0xB6, 0x7E],[0xFE, 0xB7, 0x80],[0xFE, 0xB9, 0x81],[0xFE, 0xBB, 0x83],[0xFE, 0xBD, 0x84],[0xFE, 0xBF, 0x86],[0xFE, 0xC1, 0x87],[0xFE, 0xC3, 0x89],
+[0xFE, 0xC5, 0x8A],[0xFE, 0xC7, 0x8B],[0xFE, 0xC8, 0x8D],[0xFE, 0xCA, 0x8E],[0xFE, 0xCC, 0x90],[0xFE, 0xCE, 0x91],[0xFE, 0xD0, 0x93],[0xFE, 0xD2, 0x95],
+[0xFE, 0xD4, 0x96],[0xFE, 0xD5, 0x98],[0xFE, 0xD7, 0x9A],[0xFE, 0xD9, 0x9C],[0xFD, 0xDB, 0x9D],[0xFD, 0xDD, 0x9F],[0xFD, 0xDE, 0xA1],[0xFD, 0xE0, 0xA3],
+[0xFD, 0xE2, 0xA5],[0xFD, 0xE4, 0xA6],[0xFD, 0xE6, 0xA8],[0xFD, 0xE7, 0xAA],[0xFD, 0xE9, 0xAC],[0xFD, 0xEB, 0xAD],[0xFD, 0xED, 0xAF],[0xFD, 0xEF, 0xB1],
+[0xFD, 0xF0, 0xB3],[0xFC, 0xF2, 0xB4],[0xFC, 0xF4, 0xB6],[0xFC, 0xF6, 0xB8],[0xFC, 0xF8, 0xBA],[0xFC, 0xF9, 0xBB],[0xFC, 0xFB, 0xBD],[0xFC, 0xFD, 0xBF],
+];
+
+/// The turbo colormap, a smooth rainbow from dark blue to dark red, from its polynomial approximation. (0.75 KB)
+#[rustfmt::skip]
+pub static TURBO: [[u8; 3]; 256] = [
+[0x23, 0x17, 0x1B],[0x27, 0x1A, 0x28],[0x2B, 0x1C, 0x34],[0x2F, 0x1E, 0x3F],[0x33, 0x20, 0x4A],[0x36, 0x23, 0x55],[0x39, 0x25, 0x5F],[0x3B, 0x28, 0x69],
+[0x3E, 0x2A, 0x72],[0x40, 0x2C, 0x7B],[0x42, 0x2F, 0x84],[0x44, 0x31, 0x8C],[0x45, 0x34, 0x94],[0x47, 0x37, 0x9B],[0x48, 0x39, 0xA2],[0x49, 0x3C, 0xA9],
+[0x49, 0x3E, 0xAF],[0x4A, 0x41, 0xB5],[0x4A, 0x44, 0xBB],[0x4B, 0x46, 0xC1],[0x4B, 0x49, 0xC6],[0x4B, 0x4C, 0xCB],[0x4B, 0x4F, 0xCF],[0x4A, 0x51, 0xD3],
+[0x4A, 0x54, 0xD7],[0x4A, 0x57, 0xDB],[0x49, 0x59, 0xDF],[0x49, 0x5C, 0xE2],[0x48, 0x5F, 0xE5],[0x47, 0x62, 0xE8],[0x46, 0x65, 0xEA],[0x45, 0x67, 0xED],
+[0x44, 0x6A, 0xEF],[0x43, 0x6D, 0xF0],[0x42, 0x70, 0xF2],[0x41, 0x72, 0xF4],[0x40, 0x75, 0xF5],[0x3F, 0x78, 0xF6],[0x3E, 0x7B, 0xF7],[0x3D, 0x7D, 0xF8],
+[0x3B, 0x80, 0xF8],[0x3A, 0x83, 0xF9],[0x39, 0x86, 0xF9],[0x38, 0x88, 0xF9],[0x37, 0x8B, 0xF9],[0x35, 0x8E, 0xF9],[0x34, 0x90, 0xF8],[0x33, 0x93, 0xF8],
+[0x32, 0x96, 0xF7],[0x31, 0x98, 0xF6],[0x30, 0x9B, 0xF6],[0x2F, 0x9E, 0xF5],[0x2E, 0xA0, 0xF4],[0x2D, 0xA3, 0xF2],[0x2C, 0xA5, 0xF1],[0x2B, 0xA8, 0xF0],
+[0x2A, 0xAA, 0xEE],[0x2A, 0xAD, 0xED],[0x29, 0xAF, 0xEB],[0x28, 0xB2, 0xEA],[0x28, 0xB4, 0xE8],[0x27, 0xB6, 0xE6],[0x27, 0xB9, 0xE4],[0x26, 0xBB, 0xE2],
+[0x26, 0xBD, 0xE0],[0x25, 0xC0, 0xDE],[0x25, 0xC2, 0xDC],[0x25, 0xC4, 0xDA],[0x25, 0xC6, 0xD7],[0x25, 0xC8, 0xD5],[0x25, 0xCA, 0xD3],[0x25, 0xCD, 0xD1],
+[0x25, 0xCF, 0xCE],[0x26, 0xD1, 0xCC],[0x26, 0xD2, 0xC9],[0x26, 0xD4, 0xC7],[0x27, 0xD6, 0xC4],[0x27, 0xD8, 0xC2],[0x28, 0xDA, 0xBF],[0x29, 0xDC, 0xBD],
+[0x2A, 0xDD, 0xBA],[0x2B, 0xDF, 0xB8],[0x2C, 0xE1, 0xB5],[0x2D, 0xE2, 0xB2],[0x2E, 0xE4, 0xB0],[0x2F, 0xE5, 0xAD],[0x30, 0xE7, 0xAB],[0x31, 0xE8, 0xA8],
+[0x33, 0xEA, 0xA6],[0x34, 0xEB, 0xA3],[0x36, 0xEC, 0xA0],[0x37, 0xEE, 0x9E],[0x39, 0xEF, 0x9B],[0x3B, 0xF0, 0x99],[0x3D, 0xF1, 0x96],[0x3F, 0xF2, 0x94],
+[0x41, 0xF3, 0x91],[0x43, 0xF4, 0x8F],[0x45, 0xF5, 0x8C],[0x47, 0xF6, 0x8A],[0x49, 0xF7, 0x87],[0x4B, 0xF8, 0x85],[0x4E, 0xF9, 0x83],[0x50, 0xF9, 0x80],
+[0x52, 0xFA, 0x7E],[0x55, 0xFA, 0x7C],[0x57, 0xFB, 0x79],[0x5A, 0xFB, 0x77],[0x5D, 0xFC, 0x75],[0x5F, 0xFC, 0x73],[0x62, 0xFD, 0x71],[0x65, 0xFD, 0x6E],
+[0x68, 0xFD, 0x6C],[0x6A, 0xFD, 0x6A],[0x6D, 0xFE, 0x68],[0x70, 0xFE, 0x66],[0x73, 0xFE, 0x64],[0x76, 0xFE, 0x62],[0x79, 0xFE, 0x60],[0x7C, 0xFD, 0x5E],
+[0x7F, 0xFD, 0x5D],[0x82, 0xFD, 0x5B],[0x85, 0xFD, 0x59],[0x88, 0xFC, 0x57],[0x8B, 0xFC, 0x56],[0x8E, 0xFC, 0x54],[0x91, 0xFB, 0x52],[0x95, 0xFB, 0x51],
+[0x98, 0xFA, 0x4F],[0x9B, 0xF9, 0x4E],[0x9E, 0xF9, 0x4C],[0xA1, 0xF8, 0x4B],[0xA4, 0xF7, 0x49],[0xA7, 0xF6, 0x48],[0xAA, 0xF6, 0x46],[0xAD, 0xF5, 0x45],
+[0xB0, 0xF4, 0x44],[0xB3, 0xF3, 0x42],[0xB6, 0xF2, 0x41],[0xB9, 0xF0, 0x40],[0xBC, 0xEF, 0x3F],[0xBF, 0xEE, 0x3E],[0xC2, 0xED, 0x3C],[0xC5, 0xEB, 0x3B],
+[0xC8, 0xEA, 0x3A],[0xCB, 0xE9, 0x39],[0xCD, 0xE7, 0x38],[0xD0, 0xE6, 0x37],[0xD3, 0xE4, 0x36],[0xD5, 0xE3, 0x35],[0xD8, 0xE1, 0x34],[0xDB, 0xDF, 0x34],
+[0xDD, 0xDE, 0x33],[0xDF, 0xDC, 0x32],[0xE2, 0xDA, 0x31],[0xE4, 0xD8, 0x30],[0xE6, 0xD6, 0x30],[0xE9, 0xD4, 0x2F],[0xEB, 0xD2, 0x2E],[0xED, 0xD0, 0x2D],
+[0xEF, 0xCE, 0x2D],[0xF1, 0xCC, 0x2C],[0xF3, 0xCA, 0x2B],[0xF4, 0xC8, 0x2B],[0xF6, 0xC6, 0x2A],[0xF8, 0xC4, 0x2A],[0xF9, 0xC1, 0x29],[0xFB, 0xBF, 0x28],
+[0xFC, 0xBD, 0x28],[0xFD, 0xBA, 0x27],[0xFF, 0xB8, 0x27],[0xFF, 0xB5, 0x26],[0xFF, 0xB3, 0x26],[0xFF, 0xB1, 0x25],[0xFF, 0xAE, 0x25],[0xFF, 0xAC, 0x24],
+[0xFF, 0xA9, 0x24],[0xFF, 0xA6, 0x23],[0xFF, 0xA4, 0x23],[0xFF, 0xA1, 0x22],[0xFF, 0x9F, 0x22],[0xFF, 0x9C, 0x22],[0xFF, 0x99, 0x21],[0xFF, 0x97, 0x21],
+[0xFF, 0x94, 0x20],[0xFF, 0x91, 0x20],[0xFF, 0x8E, 0x1F],[0xFF, 0x8C, 0x1F],[0xFF, 0x89, 0x1E],[0xFF, 0x86, 0x1E],[0xFF, 0x83, 0x1E],[0xFF, 0x81, 0x1D],
+[0xFF, 0x7E, 0x1D],[0xFF, 0x7B, 0x1C],[0xFF, 0x78, 0x1C],[0xFF, 0x75, 0x1B],[0xFF, 0x73, 0x1B],[0xFF, 0x70, 0x1A],[0xFE, 0x6D, 0x1A],[0xFC, 0x6A, 0x1A],
+[0xFB, 0x68, 0x19],[0xF9, 0x65, 0x19],[0xF8, 0x62, 0x18],[0xF6, 0x5F, 0x18],[0xF4, 0x5C, 0x17],[0xF3, 0x5A, 0x17],[0xF1, 0x57, 0x16],[0xEF, 0x54, 0x16],
+[0xED, 0x52, 0x15],[0xEB, 0x4F, 0x14],[0xE9, 0x4C, 0x14],[0xE6, 0x4A, 0x13],[0xE4, 0x47, 0x13],[0xE2, 0x45, 0x12],[0xE0, 0x42, 0x12],[0xDD, 0x40, 0x11],
+[0xDB, 0x3D, 0x10],[0xD8, 0x3B, 0x10],[0xD6, 0x38, 0x0F],[0xD3, 0x36, 0x0F],[0xD1, 0x34, 0x0E],[0xCE, 0x31, 0x0D],[0xCB, 0x2F, 0x0D],[0xC9, 0x2D, 0x0C],
+[0xC6, 0x2B, 0x0B],[0xC4, 0x29, 0x0B],[0xC1, 0x27, 0x0A],[0xBE, 0x25, 0x0A],[0xBC, 0x23, 0x09],[0xB9, 0x21, 0x08],[0xB7, 0x1F, 0x08],[0xB4, 0x1D, 0x07],
+[0xB1, 0x1C, 0x06],[0xAF, 0x1A, 0x06],[0xAC, 0x18, 0x05],[0xAA, 0x17, 0x04],[0xA8, 0x16, 0x04],[0xA5, 0x14, 0x03],[0xA3, 0x13, 0x02],[0xA1, 0x12, 0x02],
+[0x9F, 0x11, 0x01],[0x9D, 0x10, 0x00],[0x9B, 0x0F, 0x00],[0x9A, 0x0E, 0x00],[0x98, 0x0E, 0x00],[0x96, 0x0D, 0x00],[0x95, 0x0C, 0x00],[0x94, 0x0C, 0x00],
+[0x93, 0x0C, 0x00],[0x92, 0x0C, 0x00],[0x91, 0x0B, 0x00],[0x91, 0x0C, 0x00],[0x90, 0x0C, 0x00],[0x90, 0x0C, 0x00],[0x90, 0x0C, 0x00],[0x90, 0x0D, 0x00],
+];
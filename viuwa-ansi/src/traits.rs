@@ -1,6 +1,28 @@
-use std::io::{self, Result, Write};
+use std::{
+    io::{self, Result, Write},
+    time::Duration,
+};
 
-use crate::consts::*;
+use crate::{consts::*, fmt_u32};
+
+/// Write a CSI sequence of up to three numeric `params` and the `end` byte, formatted without going through `core::fmt`
+fn csi(out: &mut impl Write, params: &[u32], end: u8) -> Result<()> {
+    let mut seq = [0; 2 + 3 * 11];
+    seq[..2].copy_from_slice(b"\x1b[");
+    let mut len = 2;
+    for (i, &n) in params.iter().enumerate() {
+        if i != 0 {
+            seq[len] = b';';
+            len += 1;
+        }
+        let mut buf = [0; 10];
+        let digits = fmt_u32(n, &mut buf);
+        seq[len..len + digits.len()].copy_from_slice(digits);
+        len += digits.len();
+    }
+    seq[len] = end;
+    out.write_all(&seq[..=len])
+}
 
 /// Terminal ANSI writes
 pub trait Terminal: Write + Sized {
@@ -37,6 +59,10 @@ pub trait Terminal: Write + Sized {
     #[inline]
     fn disable_line_wrap(&mut self) -> Result<()> { self.write_as(DISABLE_LINE_WRAP) }
     #[inline]
+    fn enable_mouse(&mut self) -> Result<()> { self.write_as(ENABLE_MOUSE) }
+    #[inline]
+    fn disable_mouse(&mut self) -> Result<()> { self.write_as(DISABLE_MOUSE) }
+    #[inline]
     fn enable_raw_mode(&mut self) -> Result<()> {
         #[cfg(target_family = "wasm")]
         return {
@@ -65,7 +91,7 @@ pub trait Terminal: Write + Sized {
     fn set_title<T: ::std::fmt::Display>(&mut self, title: &T) -> Result<()> { write!(self, osc!("0;", st!("{}")), title) }
     #[inline]
     /// Resize the window using ansi escape codes
-    fn resize(&mut self, width: u16, height: u16) -> Result<()> { write!(self, csi!("8;{};{}t"), height, width) }
+    fn resize(&mut self, width: u16, height: u16) -> Result<()> { csi(self, &[8, height as u32, width as u32], b't') }
     /// Attempt to read the terminal size in characters quietly (only affects wasm)
     #[inline]
     fn size_quiet(&mut self) -> Result<(u16, u16)> {
@@ -126,6 +152,81 @@ pub trait Terminal: Write + Sized {
             };
         }
     }
+    /// Attempt to read the size of the terminal's text area in pixels from `TIOCGWINSZ`,
+    /// many terminals (and every non-unix platform) don't report it
+    #[inline]
+    fn size_pixels(&mut self) -> Result<(u16, u16)> {
+        #[cfg(unix)]
+        {
+            let mut ws: ::libc::winsize = unsafe { ::core::mem::zeroed() };
+            // stdout may be redirected while the terminal is still on stdin
+            for fd in [::libc::STDOUT_FILENO, ::libc::STDIN_FILENO] {
+                if unsafe { ::libc::ioctl(fd, ::libc::TIOCGWINSZ, &mut ws) } == 0 && ws.ws_xpixel > 0 && ws.ws_ypixel > 0 {
+                    return Ok((ws.ws_xpixel, ws.ws_ypixel));
+                }
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::Unsupported, "the terminal doesn't report its size in pixels"))
+    }
+    /// Attempt to query the size of the terminal's text area in pixels with XTWINOPS (`CSI 14 t`),
+    /// for terminals that answer it but don't report it to [`size_pixels`](Self::size_pixels).
+    ///
+    /// The reply is read from stdin in raw mode, giving up after `timeout` if the terminal doesn't answer,
+    /// so it must not be called while anything else (e.g. an event loop) reads stdin.
+    fn query_size_pixels(&mut self, timeout: Duration) -> Result<(u16, u16)> {
+        #[cfg(unix)]
+        {
+            // the query would end up in a file or pipe, and the reply would never come
+            if unsafe { ::libc::isatty(::libc::STDIN_FILENO) != 1 || ::libc::isatty(::libc::STDOUT_FILENO) != 1 } {
+                return Err(io::Error::new(io::ErrorKind::Unsupported, "stdin and stdout must be a terminal to query it"));
+            }
+            let raw = ::crossterm::terminal::is_raw_mode_enabled()?;
+            if !raw {
+                self.enable_raw_mode()?;
+            }
+            let reply =
+                self.write_as(REPORT_TEXT_AREA_PIXELS).and_then(|_| self.flush()).and_then(|_| read_reply(b"t", timeout));
+            if !raw {
+                self.disable_raw_mode()?;
+            }
+            parse_text_area_reply(&reply?)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unexpected reply to the text area size query"))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = timeout;
+            Err(io::Error::new(io::ErrorKind::Unsupported, "querying the terminal is only supported on unix"))
+        }
+    }
+    /// Attempt to query the default background color of the terminal with OSC 11, to tell light themes from dark ones.
+    ///
+    /// Like [`query_size_pixels`](Self::query_size_pixels), the reply is read from stdin in raw mode,
+    /// giving up after `timeout`, so it must not be called while anything else reads stdin.
+    fn query_background(&mut self, timeout: Duration) -> Result<[u8; 3]> {
+        #[cfg(unix)]
+        {
+            if unsafe { ::libc::isatty(::libc::STDIN_FILENO) != 1 || ::libc::isatty(::libc::STDOUT_FILENO) != 1 } {
+                return Err(io::Error::new(io::ErrorKind::Unsupported, "stdin and stdout must be a terminal to query it"));
+            }
+            let raw = ::crossterm::terminal::is_raw_mode_enabled()?;
+            if !raw {
+                self.enable_raw_mode()?;
+            }
+            // answered with BEL or ST (ESC \) at the end, whichever the query ended with or the terminal prefers
+            let reply =
+                self.write_as(REPORT_BACKGROUND).and_then(|_| self.flush()).and_then(|_| read_reply(b"\x07\\", timeout));
+            if !raw {
+                self.disable_raw_mode()?;
+            }
+            parse_background_reply(&reply?)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unexpected reply to the background color query"))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = timeout;
+            Err(io::Error::new(io::ErrorKind::Unsupported, "querying the terminal is only supported on unix"))
+        }
+    }
     #[inline]
     fn cursor_hide(&mut self) -> Result<()> { self.write_as(HIDE_CURSOR) }
     #[inline]
@@ -143,21 +244,56 @@ pub trait Terminal: Write + Sized {
     #[inline]
     fn cursor_home(&mut self) -> Result<()> { self.write_as(CURSOR_HOME) }
     #[inline]
-    fn cursor_to(&mut self, x: u16, y: u16) -> Result<()> { write!(self, csi!("{};{}H"), y + 1, x + 1) }
+    fn cursor_to(&mut self, x: u16, y: u16) -> Result<()> { csi(self, &[y as u32 + 1, x as u32 + 1], b'H') }
+    /// Write `text` from column `x` of row `y`, cut off after `width` columns (one per char)
+    fn draw_text(&mut self, (x, y): (u16, u16), width: u16, text: &str) -> Result<()> {
+        self.cursor_to(x, y)?;
+        match text.char_indices().nth(width as usize) {
+            Some((end, _)) => self.write_as(&text[..end]),
+            None => self.write_as(text),
+        }
+    }
+    /// Fill the region of `size` with its top left corner at `origin` with spaces, in the current background color
+    fn fill_region(&mut self, (x, y): (u16, u16), (w, h): (u16, u16)) -> Result<()> {
+        let blank = " ".repeat(w as usize);
+        for row in y..y.saturating_add(h) {
+            self.cursor_to(x, row)?;
+            self.write_as(&blank)?;
+        }
+        Ok(())
+    }
+    /// Draw a box outline of light box drawing characters around the edge of the region of `size`
+    /// with its top left corner at `origin`, clearing the inside
+    fn draw_box(&mut self, (x, y): (u16, u16), (w, h): (u16, u16)) -> Result<()> {
+        if w < 2 || h < 2 {
+            return self.fill_region((x, y), (w, h));
+        }
+        let [tl, tr, br, bl, horizontal, vertical] = BOX_LIGHT;
+        let line = horizontal.to_string().repeat(w as usize - 2);
+        let inside = " ".repeat(w as usize - 2);
+        self.cursor_to(x, y)?;
+        write!(self, "{tl}{line}{tr}")?;
+        for row in y + 1..y + h - 1 {
+            self.cursor_to(x, row)?;
+            write!(self, "{vertical}{inside}{vertical}")?;
+        }
+        self.cursor_to(x, y + h - 1)?;
+        write!(self, "{bl}{line}{br}")
+    }
     #[inline]
-    fn cursor_to_col(&mut self, x: u16) -> Result<()> { write!(self, csi!("{}G"), x + 1) }
+    fn cursor_to_col(&mut self, x: u16) -> Result<()> { csi(self, &[x as u32 + 1], b'G') }
     #[inline]
-    fn cursor_up(&mut self, n: u16) -> Result<()> { write!(self, csi!("{}A"), n) }
+    fn cursor_up(&mut self, n: u16) -> Result<()> { csi(self, &[n as u32], b'A') }
     #[inline]
-    fn cursor_down(&mut self, n: u16) -> Result<()> { write!(self, csi!("{}B"), n) }
+    fn cursor_down(&mut self, n: u16) -> Result<()> { csi(self, &[n as u32], b'B') }
     #[inline]
-    fn cursor_forward(&mut self, n: u16) -> Result<()> { write!(self, csi!("{}C"), n) }
+    fn cursor_forward(&mut self, n: u16) -> Result<()> { csi(self, &[n as u32], b'C') }
     #[inline]
-    fn cursor_backward(&mut self, n: u16) -> Result<()> { write!(self, csi!("{}D"), n) }
+    fn cursor_backward(&mut self, n: u16) -> Result<()> { csi(self, &[n as u32], b'D') }
     #[inline]
-    fn cursor_next_lines(&mut self, n: u16) -> Result<()> { write!(self, csi!("{}E"), n) }
+    fn cursor_next_lines(&mut self, n: u16) -> Result<()> { csi(self, &[n as u32], b'E') }
     #[inline]
-    fn cursor_prev_lines(&mut self, n: u16) -> Result<()> { write!(self, csi!("{}F"), n) }
+    fn cursor_prev_lines(&mut self, n: u16) -> Result<()> { csi(self, &[n as u32], b'F') }
     #[inline]
     fn attr_reset(&mut self) -> Result<()> { self.write_as(SGR_DEFAULT) }
     #[inline]
@@ -175,3 +311,54 @@ impl<'a> Terminal for io::BufWriter<io::StdoutLock<'a>> {}
 impl Terminal for io::Stderr {}
 impl<'a> Terminal for io::StderrLock<'a> {}
 impl Terminal for io::BufWriter<io::Stderr> {}
+impl Terminal for std::fs::File {}
+impl Terminal for Vec<u8> {}
+
+/// Read a reply to a query from stdin up to and including any byte of `ends`, waiting at most `timeout` altogether
+#[cfg(unix)]
+fn read_reply(ends: &[u8], timeout: Duration) -> Result<Vec<u8>> {
+    let deadline = std::time::Instant::now() + timeout;
+    let mut reply = Vec::new();
+    // replies are short, anything longer isn't one
+    while reply.len() < 64 {
+        let left = deadline.saturating_duration_since(std::time::Instant::now());
+        let mut fd = ::libc::pollfd { fd: ::libc::STDIN_FILENO, events: ::libc::POLLIN, revents: 0 };
+        if left.is_zero() || unsafe { ::libc::poll(&mut fd, 1, left.as_millis() as _) } <= 0 {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "the terminal didn't reply"));
+        }
+        let mut b = 0u8;
+        if unsafe { ::libc::read(::libc::STDIN_FILENO, &mut b as *mut u8 as *mut _, 1) } != 1 {
+            return Err(io::Error::last_os_error());
+        }
+        reply.push(b);
+        if ends.contains(&b) {
+            return Ok(reply);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "the reply is too long"))
+}
+
+/// Parse the width and height of a reply to [`REPORT_TEXT_AREA_PIXELS`], `ESC [ 4 ; <height> ; <width> t`
+#[cfg(unix)]
+fn parse_text_area_reply(reply: &[u8]) -> Option<(u16, u16)> {
+    let reply = std::str::from_utf8(reply).ok()?;
+    let (h, w) = reply.strip_prefix("\x1b[4;")?.strip_suffix('t')?.split_once(';')?;
+    Some((w.parse().ok()?, h.parse().ok()?)).filter(|&(w, h)| w > 0 && h > 0)
+}
+
+/// Parse the color of a reply to [`REPORT_BACKGROUND`], `ESC ] 11 ; rgb:<red>/<green>/<blue>` and BEL or ST,
+/// scaling channels of 1 to 4 hex digits to 8 bits
+#[cfg(unix)]
+fn parse_background_reply(reply: &[u8]) -> Option<[u8; 3]> {
+    let reply = std::str::from_utf8(reply).ok()?;
+    let reply = reply.strip_suffix('\x07').or_else(|| reply.strip_suffix("\x1b\\"))?;
+    let mut channels = reply.strip_prefix("\x1b]11;rgb:")?.split('/').map(|c| {
+        let max = match c.len() {
+            1..=4 => 16u32.pow(c.len() as u32) - 1,
+            _ => return None,
+        };
+        Some((u32::from_str_radix(c, 16).ok()? * 255 / max) as u8)
+    });
+    let rgb = [channels.next()??, channels.next()??, channels.next()??];
+    channels.next().is_none().then_some(rgb)
+}
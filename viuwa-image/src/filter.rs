@@ -1,22 +1,29 @@
 //! Image filters and kernels
-use std::str::FromStr;
+#[cfg(all(not(feature = "std"), feature = "parse"))]
+use alloc::{format, string::String};
+#[cfg(feature = "parse")]
+use core::str::FromStr;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use crate::Weight;
 
-const PI: Weight = ::std::f64::consts::PI as Weight;
-
-// REFERENCE: Cubic
-// fn cubic_bc(b: f32, c: f32, x: f32) -> f32 {
-//     let x = x.abs();
-//     let x = if x < 1. {
-//         (12. - 9. * b - 6. * c) * x.powi(3) + (-18. + 12. * b + 6. * c) * x.powi(2) + (6. - 2. * b)
-//     } else if x < 2.0 {
-//         (-b - 6. * c) * x.powi(3) + (6. * b + 30. * c) * x.powi(2) + (-12. * b - 48. * c) * x + (8. * b + 24. * c)
-//     } else {
-//         0.0
-//     };
-//     x / 6.0
-// }
+const PI: Weight = ::core::f64::consts::PI as Weight;
+
+/// Mitchell-Netravali family of cubic kernels with parameters b and c, support 2
+#[inline]
+pub fn cubic_bc(b: Weight, c: Weight, x: Weight) -> Weight {
+    let x = x.abs();
+    let x = if x < 1. {
+        (12. - 9. * b - 6. * c) * x.powi(3) + (-18. + 12. * b + 6. * c) * x.powi(2) + (6. - 2. * b)
+    } else if x < 2.0 {
+        (-b - 6. * c) * x.powi(3) + (6. * b + 30. * c) * x.powi(2) + (-12. * b - 48. * c) * x + (8. * b + 24. * c)
+    } else {
+        0.0
+    };
+    x / 6.0
+}
 
 #[inline]
 pub fn sinc(x: Weight) -> Weight {
@@ -105,12 +112,76 @@ pub fn lanczos3_kernel(x: Weight) -> Weight {
     }
 }
 
+/// sinc windowed by a wider sinc with a number of lobes, support `lobes`
+#[inline]
+pub fn lanczos_kernel(lobes: Weight, x: Weight) -> Weight {
+    if x.abs() < lobes {
+        sinc(x) * sinc(x / lobes)
+    } else {
+        0.
+    }
+}
+
+/// cubic_bc with b=1., c=0., blurry but without ringing
+#[inline]
+pub fn bspline_kernel(x: Weight) -> Weight { cubic_bc(1., 0., x) }
+
+/// cubic_bc tuned by Nicolas Robidoux for downsampling with EWA
+#[inline]
+pub fn robidoux_kernel(x: Weight) -> Weight { cubic_bc(0.378_215_8, 0.310_892_1, x) }
+
+/// cubic_bc tuned by Nicolas Robidoux, sharper than [`robidoux_kernel`]
+#[inline]
+pub fn robidoux_sharp_kernel(x: Weight) -> Weight { cubic_bc(0.262_014_5, 0.368_992_74, x) }
+
+#[inline]
+pub fn lanczos2_kernel(x: Weight) -> Weight { lanczos_kernel(2., x) }
+
+#[inline]
+pub fn lanczos4_kernel(x: Weight) -> Weight { lanczos_kernel(4., x) }
+
+/// sinc windowed by a 3 lobe Blackman window, less ringing than lanczos3
+#[inline]
+pub fn blackman3_kernel(x: Weight) -> Weight {
+    if x.abs() < 3. {
+        let t = x * PI / 3.;
+        sinc(x) * (0.42 + 0.5 * t.cos() + 0.08 * (2. * t).cos())
+    } else {
+        0.
+    }
+}
+
 /// A specific static filter with a kernel fn and a support radius
 #[derive(Clone, Copy)]
 pub struct Filter {
     pub kernel: fn(Weight) -> Weight,
     pub support: Weight,
 }
+impl Filter {
+    /// A filter with a custom kernel, which must be 0 outside of `-support..=support`
+    /// ```
+    /// use image::Rgb;
+    /// use viuwa_image::{filter::{cubic_bc, Filter}, Image, Weight};
+    /// fn soft(x: Weight) -> Weight { cubic_bc(0.5, 0.25, x) }
+    /// let image = Image::<Rgb<u8>>::from_raw(vec![0, 0, 0, 255, 255, 255], 2, 1).unwrap();
+    /// let resized = image.resize(1, 1, Filter::custom(2., soft));
+    /// assert!(resized.pixels()[0][0] > 100 && resized.pixels()[0][0] < 155);
+    /// ```
+    #[inline]
+    pub const fn custom(support: Weight, kernel: fn(Weight) -> Weight) -> Self { Self { kernel, support } }
+}
+impl From<FilterType> for Filter {
+    #[inline]
+    fn from(filter: FilterType) -> Self { filter.filter() }
+}
+impl From<&FilterType> for Filter {
+    #[inline]
+    fn from(filter: &FilterType) -> Self { filter.filter() }
+}
+impl From<FilterPreset> for Filter {
+    #[inline]
+    fn from(preset: FilterPreset) -> Self { preset.filter() }
+}
 pub static FILTER_NEAREST: Filter = Filter { kernel: point_kernel, support: 0. };
 pub static FILTER_BOX: Filter = Filter { kernel: box_kernel, support: 0.5 };
 pub static FILTER_TRIANGLE: Filter = Filter { kernel: triangle_kernel, support: 1. };
@@ -119,6 +190,12 @@ pub static FILTER_CATMULL_ROM: Filter = Filter { kernel: catmull_rom_kernel, sup
 pub static FILTER_MITCHELL: Filter = Filter { kernel: mitchell_netravali_kernel, support: 2. };
 // pub static FILTER_GAUSSIAN: Filter = Filter { kernel: gaussian_kernel, support: 3. }; // Unused until I can find out how to implement it with fir
 pub static FILTER_LANCZOS3: Filter = Filter { kernel: lanczos3_kernel, support: 3. };
+pub static FILTER_BSPLINE: Filter = Filter::custom(2., bspline_kernel);
+pub static FILTER_ROBIDOUX: Filter = Filter::custom(2., robidoux_kernel);
+pub static FILTER_ROBIDOUX_SHARP: Filter = Filter::custom(2., robidoux_sharp_kernel);
+pub static FILTER_LANCZOS2: Filter = Filter::custom(2., lanczos2_kernel);
+pub static FILTER_LANCZOS4: Filter = Filter::custom(4., lanczos4_kernel);
+pub static FILTER_BLACKMAN3: Filter = Filter::custom(3., blackman3_kernel);
 
 /// Dynamic filter type, also implements From<u8>
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, PartialOrd, Ord, Hash)]
@@ -169,6 +246,31 @@ impl<'de> ::serde::Deserialize<'de> for FilterType {
         String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
     }
 }
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for FilterType {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Nearest => "nearest",
+            Box => "box",
+            Triangle => "triangle",
+            Hamming => "hamming",
+            Catmull => "catmull",
+            Mitchell => "mitchell",
+            Lanczos => "lanczos3",
+        })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for FilterType {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
 impl FilterType {
     /// Get the static filter for this type
     #[inline]
@@ -239,3 +341,87 @@ impl From<u8> for FilterType {
         }
     }
 }
+
+/// Named custom filters beyond [`FilterType`], e.g. Mitchell variants and windowed sincs,
+/// which are only resized by [`sample`](crate::sample::sample) and not SIMD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FilterPreset {
+    /// Cubic B-spline, blurry but without ringing
+    BSpline,
+    /// Robidoux cubic
+    Robidoux,
+    /// Sharper Robidoux cubic
+    RobidouxSharp,
+    /// Lanczos with 2 lobes
+    Lanczos2,
+    /// Lanczos with 4 lobes
+    Lanczos4,
+    /// Blackman windowed sinc with 3 lobes
+    Blackman3,
+}
+#[cfg(feature = "parse")]
+impl FromStr for FilterPreset {
+    type Err = String;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bspline" | "b-spline" => Ok(Self::BSpline),
+            "robidoux" => Ok(Self::Robidoux),
+            "robidoux-sharp" => Ok(Self::RobidouxSharp),
+            "lanczos2" => Ok(Self::Lanczos2),
+            "lanczos4" => Ok(Self::Lanczos4),
+            "blackman3" | "blackman" => Ok(Self::Blackman3),
+            _ => Err(format!(
+                "{s:?} is not a valid FilterPreset, expected one of bspline, robidoux, robidoux-sharp, lanczos2, lanczos4, or blackman3"
+            )),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for FilterPreset {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for FilterPreset {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            Self::BSpline => "bspline",
+            Self::Robidoux => "robidoux",
+            Self::RobidouxSharp => "robidoux-sharp",
+            Self::Lanczos2 => "lanczos2",
+            Self::Lanczos4 => "lanczos4",
+            Self::Blackman3 => "blackman3",
+        })
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for FilterPreset {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+impl FilterPreset {
+    /// Get the static filter for this preset
+    #[inline]
+    pub fn filter(&self) -> Filter {
+        match self {
+            Self::BSpline => FILTER_BSPLINE,
+            Self::Robidoux => FILTER_ROBIDOUX,
+            Self::RobidouxSharp => FILTER_ROBIDOUX_SHARP,
+            Self::Lanczos2 => FILTER_LANCZOS2,
+            Self::Lanczos4 => FILTER_LANCZOS4,
+            Self::Blackman3 => FILTER_BLACKMAN3,
+        }
+    }
+}
@@ -5,7 +5,7 @@ use ::core::{
     iter::*,
     mem::size_of,
     slice,
-    slice::{ChunksExact, ChunksExactMut},
+    slice::{Chunks, ChunksExactMut},
 };
 
 use super::*;
@@ -35,7 +35,7 @@ impl<'a, P: Pixel, I: ParIter<Item = &'a mut P::Repr>> ParPixelIterMut<'a, P> fo
 #[inline]
 fn checked_pixels_len<P: Pixel>(width: usize, height: usize) -> usize {
     let len = width.max(1).checked_mul(height.max(1)).expect(OVERFLOW_PANIC_MSG);
-    if len.checked_mul(::std::mem::size_of::<P::Repr>()).expect(OVERFLOW_PANIC_MSG) > MAX_IMAGE_SIZE {
+    if len.checked_mul(::core::mem::size_of::<P::Repr>()).expect(OVERFLOW_PANIC_MSG) > MAX_IMAGE_SIZE {
         panic!("{}", OVERFLOW_PANIC_MSG);
     }
     len
@@ -162,7 +162,9 @@ macro_rules! impl_image_ops {
     impl$(< $( $lt $( : $clt )? ),+ >)? $name$(< $($lt),+ >)? {
         /// Create an [`ImageView`] of the image
         #[inline(always)]
-        pub fn view<'b>(&'b self) -> ImageView<'b, P> { ImageView { data: self.data(), width: self.width, height: self.height } }
+        pub fn view<'b>(&'b self) -> ImageView<'b, P> {
+            ImageView { data: self.data(), width: self.width, height: self.height, stride: self.stride() }
+        }
         /// The number of pixels in each row of the image
         #[inline(always)]
         pub fn width(&self) -> usize { self.width }
@@ -172,6 +174,9 @@ macro_rules! impl_image_ops {
         /// Width and Height of the image
         #[inline(always)]
         pub fn dimensions(&self) -> (usize, usize) { (self.width, self.height) }
+        /// Whether the rows of the image are packed without any padding between them
+        #[inline(always)]
+        pub fn is_contiguous(&self) -> bool { self.stride() == self.width }
         /// Get the pixel at (x, y)
         #[inline]
         pub fn get(&self, x: usize, y: usize) -> Option<&P::Repr> {
@@ -184,22 +189,25 @@ macro_rules! impl_image_ops {
         /// Get the pixel at (x, y) unchecked
         #[inline]
         pub unsafe fn get_unchecked(&self, x: usize, y: usize) -> &P::Repr {
-            self.pixels().get_unchecked(y * self.width + x)
+            self.pixels().get_unchecked(y * self.stride() + x)
         }
         /// iterate over rows of pixels
         #[inline]
-        pub fn rows(&self) -> ChunksExact<P::Repr> { self.pixels().chunks_exact(self.width) }
+        pub fn rows(&self) -> Rows<'_, P> { Rows { chunks: self.pixels().chunks(self.stride()), width: self.width } }
         /// iterate over rows of pixels in parallel with the width of the image
         #[inline]
         #[cfg(feature = "rayon")]
-        pub fn par_rows(&self) -> ParChunksExact<P::Repr> { self.pixels().par_chunks_exact(self.width) }
+        pub fn par_rows(&self) -> impl ParIter<Item = &[P::Repr]> {
+            let width = self.width;
+            self.pixels().par_chunks(self.stride()).map(move |row| &row[..width])
+        }
         /// iterate over columns of pixels with the width of the image
         #[inline]
         pub fn columns(&self) -> impl Iter<Item = impl PixelIter<P>> {
             let mut len = self.pixels().len();
-            let width = self.width();
+            let (width, stride) = (self.width(), self.stride());
             self.pixels().iter().take(width).map(move |p| {
-                let column = unsafe { ::core::slice::from_raw_parts(p as *const P::Repr, len) }.iter().step_by(width);
+                let column = unsafe { ::core::slice::from_raw_parts(p as *const P::Repr, len) }.iter().step_by(stride);
                 len -= 1;
                 column
             })
@@ -209,23 +217,23 @@ macro_rules! impl_image_ops {
         #[cfg(feature = "rayon")]
         pub fn par_columns(&self) -> impl ParIter<Item = impl PixelIter<P>> {
             let len = self.pixels().len();
-            let width = self.width();
+            let (width, stride) = (self.width(), self.stride());
             self.pixels()
                 .par_iter()
                 .take(width)
                 .enumerate()
-                .map(move |(i, p)| unsafe { ::core::slice::from_raw_parts(p as *const P::Repr, len - i) }.iter().step_by(width))
+                .map(move |(i, p)| unsafe { ::core::slice::from_raw_parts(p as *const P::Repr, len - i) }.iter().step_by(stride))
         }
         /// Resize the image to the new dimensions, not preserving aspect ratio.
         ///
         /// use [`fit_dimensions`] or [`fill_dimensions`] to preserve aspect ratio.
         #[inline]
-        pub fn resize(&self, width: usize, height: usize, filter: &FilterType) -> Image<P> {
+        pub fn resize(&self, width: usize, height: usize, filter: impl Into<crate::filter::Filter>) -> Image<P> {
             if (width, height) == self.dimensions() {
-                return Image { data: self.data().into(), width, height };
+                return Image::from(self.view());
             }
             let mut buf = unsafe { Image::new_uninit_unchecked(width, height) };
-            sample(filter.filter(), self.view(), buf.view_mut());
+            sample(filter.into(), self.view(), buf.view_mut());
             buf
         }
         /// Resize the image to the new dimensions, not preserving aspect ratio.
@@ -233,12 +241,12 @@ macro_rules! impl_image_ops {
         /// use [`fit_dimensions`] or [`fill_dimensions`] to preserve aspect ratio.
         #[inline]
         #[cfg(feature = "rayon")]
-        pub fn par_resize(&self, width: usize, height: usize, filter: &FilterType) -> Image<P> {
+        pub fn par_resize(&self, width: usize, height: usize, filter: impl Into<crate::filter::Filter>) -> Image<P> {
             if (width, height) == self.dimensions() {
-                return Image { data: self.data().into(), width, height };
+                return Image::from(self.view());
             }
             let mut buf = unsafe { Image::new_uninit_unchecked(width, height) };
-            par_sample(filter.filter(), self.view(), buf.view_mut());
+            par_sample(filter.into(), self.view(), buf.view_mut());
             buf
         }
         /// Resize the image to the new dimensions, not preserving aspect ratio,
@@ -248,12 +256,12 @@ macro_rules! impl_image_ops {
         ///
         /// use [`fit_dimensions`] or [`fill_dimensions`] to preserve aspect ratio.
         #[inline]
-        pub fn supersize(&self, width: usize, height: usize, filter: &FilterType, multiplicity: f32) -> Image<P> {
+        pub fn supersize(&self, width: usize, height: usize, filter: impl Into<crate::filter::Filter>, multiplicity: f32) -> Image<P> {
             if (width, height) == self.dimensions() {
-                return Image { data: self.data().into(), width, height };
+                return Image::from(self.view());
             }
             let mut buf = unsafe { Image::new_uninit_unchecked(width, height) };
-            supersample(filter.filter(), self.view(), buf.view_mut(), multiplicity);
+            supersample(filter.into(), self.view(), buf.view_mut(), multiplicity);
             buf
         }
         /// Resize the image to the new dimensions, not preserving aspect ratio,
@@ -264,12 +272,12 @@ macro_rules! impl_image_ops {
         /// use [`fit_dimensions`] or [`fill_dimensions`] to preserve aspect ratio.
         #[inline]
         #[cfg(feature = "rayon")]
-        pub fn par_supersize(&self, width: usize, height: usize, filter: &FilterType, multiplicity: f32) -> Image<P> {
+        pub fn par_supersize(&self, width: usize, height: usize, filter: impl Into<crate::filter::Filter>, multiplicity: f32) -> Image<P> {
             if (width, height) == self.dimensions() {
-                return Image { data: self.data().into(), width, height };
+                return Image::from(self.view());
             }
             let mut buf = unsafe { Image::new_uninit_unchecked(width, height) };
-            par_supersample(filter.filter(), self.view(), buf.view_mut(), multiplicity);
+            par_supersample(filter.into(), self.view(), buf.view_mut(), multiplicity);
             buf
         }
         /// [`resize`](Self::resize) using SIMD.
@@ -281,16 +289,18 @@ macro_rules! impl_image_ops {
             P: CompatPixel,
         {
             if (width, height) == self.dimensions() {
-                return Image { data: self.data().into(), width, height };
+                return Image::from(self.view());
             }
             let mut buf = unsafe { Image::new_uninit_unchecked(width, height) };
             let mut resizer = ::fast_image_resize::Resizer::new(filter.algorithm());
-            let v = P::fir_view(self.view());
+            let packed;
+            let v = P::fir_view(if self.is_contiguous() { self.view() } else { packed = Image::from(self.view()); packed.view() });
             let mut mv = P::fir_view_mut(buf.view_mut());
             resizer.resize(&v, &mut mv).expect(concat!("something went wrong: ", module_path!(), "::ImageOps::fir_resize"));
             buf
         }
-        /// [`supersize`](Self::supersize) using SIMD.
+        /// [`supersize`](Self::supersize) using SIMD, averaging the area of the source pixels first
+        /// if it is more than [`AREA_THRESHOLD`](crate::sample::AREA_THRESHOLD) times larger.
         #[cfg(feature = "fir")]
         pub fn fir_supersize(&self, width: usize, height: usize, filter: &FilterType, multiplicity: u8) -> Image<P>
         where
@@ -299,11 +309,22 @@ macro_rules! impl_image_ops {
             P: CompatPixel,
         {
             if (width, height) == self.dimensions() {
-                return Image { data: self.data().into(), width, height };
+                return Image::from(self.view());
+            }
+            // average the area of huge downscales before filtering, instead of fir's nearest neighbor
+            let ratio = Weight::min(self.width() as Weight / width as Weight, self.height() as Weight / height as Weight);
+            if *filter != FilterType::Nearest && ratio > AREA_THRESHOLD.max(multiplicity as Weight) {
+                let mut area = unsafe { Image::new_uninit_unchecked(width * multiplicity as usize, height * multiplicity as usize) };
+                #[cfg(feature = "rayon")]
+                par_area_average(self.view(), area.view_mut());
+                #[cfg(not(feature = "rayon"))]
+                area_average(self.view(), area.view_mut());
+                return area.fir_resize(width, height, filter);
             }
             let mut buf = unsafe { Image::new_uninit_unchecked(width, height) };
             let mut resizer = ::fast_image_resize::Resizer::new(filter.ss_algorithm(multiplicity));
-            let v = P::fir_view(self.view());
+            let packed;
+            let v = P::fir_view(if self.is_contiguous() { self.view() } else { packed = Image::from(self.view()); packed.view() });
             let mut mv = P::fir_view_mut(buf.view_mut());
             // The only error that can (should) occur is if memory is corrupted, which is a bug.
             resizer.resize(&v, &mut mv).expect(concat!("something went wrong: ", module_path!(), "::ImageOps::fir_supersize"));
@@ -351,6 +372,121 @@ macro_rules! impl_image_ops_mut {
             let width = self.width();
             self.pixels_mut().par_chunks_exact_mut(width)
         }
+        /// Set every pixel to `pixel`
+        #[inline]
+        pub fn fill(&mut self, pixel: P::Repr) { self.pixels_mut().fill(pixel) }
+        /// Sharpen the image in place with an [`unsharp_mask`] of `amount` (typically `0.0..=1.0`)
+        #[inline]
+        pub fn sharpen(&mut self, amount: Weight) { unsharp_mask(self.view_mut(), amount) }
+        /// Sharpen the image in place with an [`unsharp_mask`] of `amount` (typically `0.0..=1.0`), in parallel rows
+        #[inline]
+        #[cfg(feature = "rayon")]
+        pub fn par_sharpen(&mut self, amount: Weight) { par_unsharp_mask(self.view_mut(), amount) }
+        /// Apply `f` to the pixels of the image overlapped by `src` with its top left corner at (x, y),
+        /// clipping whatever falls outside, in parallel rows with the `rayon` feature
+        fn composite<Q: Pixel>(
+            &mut self,
+            src: ImageView<Q>,
+            x: usize,
+            y: usize,
+            f: impl Fn(&mut P::Repr, &Q::Repr) + Send + Sync,
+        ) {
+            if x >= self.width || y >= self.height {
+                return;
+            }
+            let width = src.width().min(self.width - x);
+            let rows = src.height().min(self.height - y);
+            let row = |(dst, src): (&mut [P::Repr], &[Q::Repr])| {
+                dst[x..x + width].iter_mut().zip(&src[..width]).for_each(|(d, s)| f(d, s))
+            };
+            #[cfg(feature = "rayon")]
+            self.par_rows_mut().skip(y).take(rows).zip(src.par_rows()).for_each(row);
+            #[cfg(not(feature = "rayon"))]
+            self.rows_mut().skip(y).take(rows).zip(src.rows()).for_each(row);
+        }
+        /// Copy `src` onto the image with its top left corner at (x, y), clipping whatever falls outside.
+        /// ```
+        /// use image::Rgb;
+        /// use viuwa_image::Image;
+        /// let mut sheet = Image::<Rgb<u8>>::new_with(4, 4, [0; 3]);
+        /// let tile = Image::<Rgb<u8>>::new_with(2, 2, [255; 3]);
+        /// sheet.paste(tile.view(), 3, 1);
+        /// assert_eq!(sheet.get(3, 1), Some(&[255; 3]));
+        /// assert_eq!(sheet.get(3, 3), Some(&[0; 3]));
+        /// assert_eq!(sheet.get(2, 1), Some(&[0; 3]));
+        /// ```
+        #[inline]
+        pub fn paste(&mut self, src: ImageView<P>, x: usize, y: usize) { self.composite(src, x, y, |d, s| *d = *s) }
+        /// [`paste`](Self::paste) `src` blended with a uniform `opacity` in `0.0..=1.0`
+        /// ```
+        /// use image::Rgb;
+        /// use viuwa_image::Image;
+        /// let mut image = Image::<Rgb<u8>>::new_with(2, 2, [0; 3]);
+        /// image.paste_blend(Image::<Rgb<u8>>::new_with(1, 1, [200; 3]).view(), 0, 0, 0.5);
+        /// assert_eq!(image.get(0, 0), Some(&[100; 3]));
+        /// ```
+        #[inline]
+        pub fn paste_blend(&mut self, src: ImageView<P>, x: usize, y: usize, opacity: Weight) {
+            let opacity = opacity.clamp(0., 1.);
+            self.composite(src, x, y, |d, s| {
+                for (d, &s) in d.as_slice_mut().iter_mut().zip(s.as_slice()) {
+                    *d = lerp(*d, s, opacity);
+                }
+            })
+        }
+        /// [`paste`](Self::paste) `src` blended by its own alpha channel (e.g. `Rgba` onto `Rgb`)
+        /// ```
+        /// use image::{Rgb, Rgba};
+        /// use viuwa_image::Image;
+        /// let mut image = Image::<Rgb<u8>>::new_with(2, 1, [0; 3]);
+        /// let overlay = Image::<Rgba<u8>>::from_raw(vec![255, 255, 255, 255, 255, 0, 0, 0], 2, 1).unwrap();
+        /// image.paste_over(overlay.view(), 0, 0);
+        /// assert_eq!(image.get(0, 0), Some(&[255; 3]));
+        /// assert_eq!(image.get(1, 0), Some(&[0; 3]));
+        /// ```
+        #[inline]
+        pub fn paste_over<Q: Alpha<Opaque = P, Scalar = P::Scalar>>(&mut self, src: ImageView<Q>, x: usize, y: usize) {
+            self.composite(src, x, y, |d, s| {
+                let alpha = Q::alpha(s);
+                for (d, &s) in d.as_slice_mut().iter_mut().zip(s.as_slice()) {
+                    *d = lerp(*d, s, alpha);
+                }
+            })
+        }
+        /// Draw `text` with the built-in [`glyph`]s in `color` with its top left corner at (x, y),
+        /// each pixel of a glyph `scale` pixels wide, clipping whatever falls outside
+        /// ```
+        /// use image::Luma;
+        /// use viuwa_image::Image;
+        /// let mut image = Image::<Luma<u8>>::new_with(8, 8, 0);
+        /// image.draw_text("|", 0, 0, 1, 255);
+        /// assert_eq!(image.get(2, 0), Some(&255));
+        /// assert_eq!(image.get(2, 6), Some(&255));
+        /// assert_eq!(image.get(2, 7), Some(&0));
+        /// assert_eq!(image.get(1, 0), Some(&0));
+        /// ```
+        pub fn draw_text(&mut self, text: &str, x: usize, y: usize, scale: usize, color: P::Repr) {
+            for (line, text) in text.lines().enumerate() {
+                let top = y + line * ADVANCE.1 * scale;
+                for (i, c) in text.chars().enumerate() {
+                    let left = x + i * ADVANCE.0 * scale;
+                    if left >= self.width || top >= self.height {
+                        break;
+                    }
+                    for (gx, column) in glyph(c).into_iter().enumerate() {
+                        for gy in (0..GLYPH.1).filter(|gy| column >> gy & 1 == 1) {
+                            for py in top + gy * scale..top + (gy + 1) * scale {
+                                for px in left + gx * scale..left + (gx + 1) * scale {
+                                    if let Some(p) = self.get_mut(px, py) {
+                                        *p = color;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
         /// iterate over columns of pixels with the width of the image
         #[inline(always)]
         pub fn columns_mut(&mut self) -> impl Iter<Item = impl PixelIterMut<P>> {
@@ -387,6 +523,9 @@ pub struct Image<P: Pixel> {
     pub(crate) height: usize,
 }
 /// Explicitly immutable image view for use with unowned data
+///
+/// The rows of a view may be strided, e.g. a [`subview`](Self::subview) or a padded external buffer,
+/// in which case [`data`](Self::data) and [`pixels`](Self::pixels) include the padding between rows.
 #[derive(Clone)]
 pub struct ImageView<'a, P: Pixel> {
     /// The image data
@@ -395,13 +534,45 @@ pub struct ImageView<'a, P: Pixel> {
     pub(crate) width: usize,
     /// The pixel height of the image
     pub(crate) height: usize,
+    /// The number of pixels from the start of one row to the start of the next, at least the width
+    pub(crate) stride: usize,
 }
 impl<'a, P: Pixel> From<&'a Image<P>> for ImageView<'a, P> {
-    fn from(Image { data, width, height }: &'a Image<P>) -> Self { ImageView { data, width: *width, height: *height } }
+    fn from(Image { data, width, height }: &'a Image<P>) -> Self {
+        ImageView { data, width: *width, height: *height, stride: *width }
+    }
 }
 impl<'a, P: Pixel> From<ImageView<'a, P>> for Image<P> {
-    fn from(ImageView { data, width, height }: ImageView<'a, P>) -> Self { Image { data: data.into(), width, height } }
+    fn from(view: ImageView<'a, P>) -> Self {
+        let data =
+            if view.is_contiguous() { view.data.into() } else { flatten_box::<P>(view.rows().flatten().copied().collect()) };
+        Image { data, width: view.width, height: view.height }
+    }
+}
+/// Iterator over the rows of pixels of an image, skipping the padding of strided rows
+#[derive(Clone)]
+pub struct Rows<'a, P: Pixel> {
+    pub(crate) chunks: Chunks<'a, P::Repr>,
+    pub(crate) width: usize,
 }
+impl<'a, P: Pixel> Iterator for Rows<'a, P> {
+    type Item = &'a [P::Repr];
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> { self.chunks.next().map(|row| &row[..self.width]) }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.chunks.size_hint() }
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> { self.chunks.nth(n).map(|row| &row[..self.width]) }
+}
+impl<'a, P: Pixel> DoubleEndedIterator for Rows<'a, P> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> { self.chunks.next_back().map(|row| &row[..self.width]) }
+}
+impl<'a, P: Pixel> ExactSizeIterator for Rows<'a, P> {
+    #[inline]
+    fn len(&self) -> usize { self.chunks.len() }
+}
+impl<'a, P: Pixel> FusedIterator for Rows<'a, P> {}
 /// Explicitly mutable image view for use with unowned data
 pub struct ImageViewMut<'a, P: Pixel> {
     /// The image data
@@ -423,7 +594,9 @@ impl<'a, P: Pixel> From<ImageViewMut<'a, P>> for Image<P> {
     }
 }
 impl<'a, P: Pixel> From<ImageViewMut<'a, P>> for ImageView<'a, P> {
-    fn from(ImageViewMut { data, width, height }: ImageViewMut<'a, P>) -> Self { ImageView { data, width, height } }
+    fn from(ImageViewMut { data, width, height }: ImageViewMut<'a, P>) -> Self {
+        ImageView { data, width, height, stride: width }
+    }
 }
 impl<P: Pixel> Image<P> {
     /// Create a new image with default pixel.
@@ -494,18 +667,49 @@ impl<P: Pixel> Image<P> {
     /// Get the flattened mutable pixels
     #[inline]
     pub fn pixels_mut(&mut self) -> &mut [P::Repr] { pixelate_mut::<P>(self.data_mut()) }
+    /// The number of pixels from the start of one row to the start of the next, always the width
+    #[inline(always)]
+    pub fn stride(&self) -> usize { self.width }
 }
 impl_image_ops!(impl<P: Pixel> Image);
 impl_image_ops_mut!(impl<P: Pixel> Image);
 impl<'a, P: Pixel> ImageView<'a, P> {
     /// Create a new image view with the given data
-    pub fn new(image: &'a Image<P>) -> Self { Self { data: image.data.as_ref(), width: image.width, height: image.height } }
+    pub fn new(image: &'a Image<P>) -> Self {
+        Self { data: image.data.as_ref(), width: image.width, height: image.height, stride: image.width }
+    }
     /// Create a new image view with the given data
     /// # Errors
     /// If the data is not of the correct length (width * height * channels)
     pub fn from_raw(data: &'a [<P::Repr as PixelRepr>::Scalar], width: usize, height: usize) -> Option<Self> {
         if data.len() == width.max(1) * height.max(1) * P::Repr::CHANNELS {
-            Some(Self { data, width, height })
+            Some(Self { data, width, height, stride: width })
+        } else {
+            None
+        }
+    }
+    /// Create a new image view with the given data, where each row starts `stride` pixels after the previous row,
+    /// e.g. a padded buffer read back from a GPU or a Cairo surface
+    /// ```
+    /// use image::Rgb;
+    /// use viuwa_image::ImageView;
+    /// // 2x2 pixels, padded to 3 pixels per row
+    /// let data = [1u8, 1, 1, 2, 2, 2, 0, 0, 0, 3, 3, 3, 4, 4, 4];
+    /// let view = ImageView::<Rgb<u8>>::from_raw_strided(&data, 2, 2, 3).unwrap();
+    /// assert_eq!(view[(0, 1)], [3, 3, 3]);
+    /// ```
+    /// # Errors
+    /// If the stride is less than the width,
+    /// or the data is too short to hold every row ((height - 1) * stride + width) * channels)
+    pub fn from_raw_strided(
+        data: &'a [<P::Repr as PixelRepr>::Scalar],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Option<Self> {
+        let len = (height.max(1) - 1).checked_mul(stride)?.checked_add(width.max(1))?.checked_mul(P::Repr::CHANNELS)?;
+        if stride >= width.max(1) && data.len() >= len {
+            Some(Self { data, width, height, stride })
         } else {
             None
         }
@@ -515,11 +719,43 @@ impl<'a, P: Pixel> ImageView<'a, P> {
     /// The data must be of the correct length
     /// (width * height * channels)
     pub unsafe fn from_raw_unchecked(data: &'a [<P::Repr as PixelRepr>::Scalar], width: usize, height: usize) -> Self {
-        Self { data, width, height }
+        Self { data, width, height, stride: width }
     }
+    /// Borrow the `width` x `height` region of the image starting at (x, y) as a strided view, without copying.
+    ///
+    /// Returns `None` if the region is out of bounds.
+    /// ```
+    /// use image::Rgb;
+    /// use viuwa_image::Image;
+    /// let image = Image::<Rgb<u8>>::new_with(4, 4, [1, 2, 3]);
+    /// let sub = image.view().subview(1, 1, 2, 3).unwrap();
+    /// assert_eq!(sub.dimensions(), (2, 3));
+    /// assert_eq!(sub.rows().len(), 3);
+    /// assert!(image.view().subview(3, 0, 2, 1).is_none());
+    /// ```
+    pub fn subview(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Self> {
+        if x.checked_add(width)? > self.width || y.checked_add(height)? > self.height {
+            return None;
+        }
+        let data = if width == 0 || height == 0 {
+            &[]
+        } else {
+            let start = (y * self.stride + x) * P::Repr::CHANNELS;
+            let end = ((y + height - 1) * self.stride + x + width) * P::Repr::CHANNELS;
+            &self.data[start..end]
+        };
+        Some(Self { data, width, height, stride: self.stride })
+    }
+    /// Consume the view into an iterator over its rows of pixels, that borrows the underlying data instead of the view
+    #[inline]
+    pub fn into_rows(self) -> Rows<'a, P> { Rows { chunks: self.pixels().chunks(self.stride), width: self.width } }
+    /// The number of pixels from the start of one row to the start of the next
+    #[inline(always)]
+    pub fn stride(&self) -> usize { self.stride }
+    /// Get the flattened pixel scalars, including any padding between strided rows
     #[inline(always)]
     pub fn data(&self) -> &'a [P::Scalar] { self.data }
-    /// Get the flattened pixels
+    /// Get the flattened pixels, including any padding between strided rows
     #[inline]
     pub fn pixels(&self) -> &'a [P::Repr] { pixelate::<P>(self.data) }
 }
@@ -558,6 +794,9 @@ impl<'a, P: Pixel> ImageViewMut<'a, P> {
     /// Get the flattened mutable pixels
     #[inline]
     pub fn pixels_mut(&mut self) -> &mut [P::Repr] { pixelate_mut::<P>(self.data_mut()) }
+    /// The number of pixels from the start of one row to the start of the next, always the width
+    #[inline(always)]
+    pub fn stride(&self) -> usize { self.width }
 }
 impl_image_ops!(impl<'a, P: Pixel> ImageViewMut);
 impl_image_ops_mut!(impl<'a, P: Pixel> ImageViewMut);
@@ -571,7 +810,7 @@ macro_rules! impl_Index {
         #[inline(always)]
         fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
             assert!(x < self.width && y < self.height);
-            unsafe { self.pixels().get_unchecked(y * self.width + x) }
+            unsafe { self.pixels().get_unchecked(y * self.stride() + x) }
         }
     };
 }
@@ -641,7 +880,7 @@ mod compat_image {
         #[inline(always)]
         fn from(image: &'a ::image::ImageBuffer<P, C>) -> Self {
             let (w, h) = image.dimensions();
-            Self { data: image.as_ref(), width: w as usize, height: h as usize }
+            Self { data: image.as_ref(), width: w as usize, height: h as usize, stride: w as usize }
         }
     }
     impl<
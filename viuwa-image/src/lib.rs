@@ -1,10 +1,19 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec, vec::Vec};
+
 #[cfg(feature = "rayon")]
 use ::rayon::{
     iter::{IndexedParallelIterator, ParallelIterator},
     prelude::*,
-    slice::{ChunksExact as ParChunksExact, ChunksExactMut as ParChunksExactMut},
+    slice::ChunksExactMut as ParChunksExactMut,
 };
+// without std, the float methods missing from core are those of libm
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 // NOTE: prefer to leave as much possible as pub,
 // so that users can use specific functionality if necessary.
@@ -19,15 +28,19 @@ mod private {
 }
 use private::Sealed;
 pub mod filter;
-#[doc(inline)]
-pub use crate::filter::FilterType;
 use crate::filter::*;
+#[doc(inline)]
+pub use crate::filter::{Filter, FilterPreset, FilterType};
 pub mod sample;
 use crate::sample::*;
 mod image;
 pub use crate::image::*;
 mod pixel;
 pub use crate::pixel::*;
+mod text;
+pub use crate::text::*;
+mod ops;
+pub use crate::ops::*;
 
 /// The maximum size of an image (in bytes) that is processed by this library.
 ///
@@ -0,0 +1,277 @@
+//! Operations applied to an image in order before it's displayed, parsed from expressions like
+//! `crop=10,10,200,200;rotate=90;gray=rec709;resize=fit`.
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(all(not(feature = "std"), feature = "parse"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+#[cfg(feature = "parse")]
+use core::str::FromStr;
+
+use super::*;
+
+/// A clockwise rotation by a multiple of 90 degrees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+/// The axis an image is mirrored across
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flip {
+    /// Left to right
+    Horizontal,
+    /// Top to bottom
+    Vertical,
+}
+
+/// The weights of the red, green, and blue channels of a gray conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrayWeights {
+    /// BT.709 luma, for sRGB
+    Rec709,
+    /// BT.601 luma, as of JPEG and older video
+    Rec601,
+    /// The mean of the channels
+    Average,
+}
+impl GrayWeights {
+    /// The weights of red, green, and blue, summing to 1
+    #[inline]
+    pub const fn weights(self) -> [Weight; 3] {
+        match self {
+            Self::Rec709 => [0.2126, 0.7152, 0.0722],
+            Self::Rec601 => [0.299, 0.587, 0.114],
+            Self::Average => [1. / 3.; 3],
+        }
+    }
+}
+
+/// The dimensions an image is resized to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resize {
+    /// Down to fit within the dimensions it's displayed at, never larger
+    Fit,
+    /// To fit within a width and height, keeping its aspect ratio
+    Within(usize, usize),
+    /// By a percentage of its dimensions
+    Percent(Weight),
+}
+
+/// An operation on an image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    /// Keep the region of `width` and `height` pixels from `x` and `y`, within the image
+    Crop {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    Rotate(Rotation),
+    Flip(Flip),
+    /// Set every color channel to the weighted gray of the color, single-channel images are left as they are
+    Gray(GrayWeights),
+    Resize(Resize),
+}
+impl Op {
+    /// Apply the operation, resizing with `filter` to fit within `fit` for [`Resize::Fit`]
+    pub fn apply<P: Pixel>(&self, image: Image<P>, fit: (usize, usize), filter: Filter) -> Image<P> {
+        let (w, h) = image.dimensions();
+        match *self {
+            Op::Crop { x, y, width, height } => {
+                let (x, y) = (x.min(w - 1), y.min(h - 1));
+                let (width, height) = (width.clamp(1, w - x), height.clamp(1, h - y));
+                match image.view().subview(x, y, width, height) {
+                    Some(view) => Image::from(view),
+                    None => image,
+                }
+            }
+            Op::Rotate(rotation) => {
+                let src = image.pixels();
+                let (nw, nh) = if rotation == Rotation::Cw180 { (w, h) } else { (h, w) };
+                let mut out = Image::<P>::new(nw, nh);
+                for (i, px) in out.pixels_mut().iter_mut().enumerate() {
+                    let (x, y) = (i % nw, i / nw);
+                    *px = match rotation {
+                        Rotation::Cw90 => src[(h - 1 - x) * w + y],
+                        Rotation::Cw180 => src[(h - 1 - y) * w + (w - 1 - x)],
+                        Rotation::Cw270 => src[x * w + (w - 1 - y)],
+                    };
+                }
+                out
+            }
+            Op::Flip(Flip::Horizontal) => {
+                let mut image = image;
+                image.rows_mut().for_each(|row| row.reverse());
+                image
+            }
+            Op::Flip(Flip::Vertical) => {
+                let mut image = image;
+                let rows = image.pixels_mut();
+                for y in 0..h / 2 {
+                    let (top, bottom) = rows.split_at_mut((h - 1 - y) * w);
+                    top[y * w..(y + 1) * w].swap_with_slice(&mut bottom[..w]);
+                }
+                image
+            }
+            Op::Gray(gray) if P::Repr::CHANNELS >= 3 => {
+                let mut image = image;
+                let [wr, wg, wb] = gray.weights();
+                for px in image.pixels_mut() {
+                    let c = px.as_slice_mut();
+                    let v = P::Scalar::scalar(c[0].weight() * wr + c[1].weight() * wg + c[2].weight() * wb);
+                    c[..3].fill(v);
+                }
+                image
+            }
+            Op::Gray(_) => image,
+            Op::Resize(resize) => {
+                let (nw, nh) = match resize {
+                    Resize::Fit if w <= fit.0 && h <= fit.1 => return image,
+                    Resize::Fit => fit_dimensions((w, h), fit),
+                    Resize::Within(nw, nh) => fit_dimensions((w, h), (nw.max(1), nh.max(1))),
+                    Resize::Percent(p) => {
+                        let scale = |n: usize| ((n as Weight * p / 100.).round() as usize).max(1);
+                        (scale(w), scale(h))
+                    }
+                };
+                image.resize(nw, nh, filter)
+            }
+        }
+    }
+}
+#[cfg(feature = "parse")]
+impl FromStr for Op {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = s.split_once('=').map_or((s, None), |(name, arg)| (name.trim(), Some(arg.trim())));
+        let invalid = || format!("{s:?} is not a valid operation");
+        let nums = |arg: &str| arg.split(',').map(|n| n.trim().parse::<usize>()).collect::<Result<Vec<_>, _>>();
+        match (name, arg) {
+            ("crop", Some(arg)) => match nums(arg).as_deref() {
+                Ok(&[x, y, width, height]) if width > 0 && height > 0 => Ok(Op::Crop { x, y, width, height }),
+                _ => Err(format!("{s:?} is not a valid crop, must be crop=x,y,width,height")),
+            },
+            ("rotate", Some(arg)) => match arg {
+                "90" | "-270" => Ok(Op::Rotate(Rotation::Cw90)),
+                "180" | "-180" => Ok(Op::Rotate(Rotation::Cw180)),
+                "270" | "-90" => Ok(Op::Rotate(Rotation::Cw270)),
+                _ => Err(format!("{s:?} is not a valid rotation, must be rotate=90, 180, or 270")),
+            },
+            ("flip", Some(arg)) => match arg {
+                "h" | "horizontal" => Ok(Op::Flip(Flip::Horizontal)),
+                "v" | "vertical" => Ok(Op::Flip(Flip::Vertical)),
+                _ => Err(format!("{s:?} is not a valid flip, must be flip=h or flip=v")),
+            },
+            ("gray" | "grey", None) => Ok(Op::Gray(GrayWeights::Rec709)),
+            ("gray" | "grey", Some(arg)) => match arg {
+                "rec709" => Ok(Op::Gray(GrayWeights::Rec709)),
+                "rec601" => Ok(Op::Gray(GrayWeights::Rec601)),
+                "average" | "avg" => Ok(Op::Gray(GrayWeights::Average)),
+                _ => Err(format!("{s:?} is not a valid gray, must be gray=rec709, rec601, or average")),
+            },
+            ("resize", Some(arg)) => {
+                let resize = match arg.strip_suffix('%') {
+                    _ if arg == "fit" => Some(Resize::Fit),
+                    Some(p) => p.parse::<Weight>().ok().filter(|p| *p > 0. && p.is_finite()).map(Resize::Percent),
+                    None => match arg.split_once('x').map(|(w, h)| (w.parse::<usize>(), h.parse::<usize>())) {
+                        Some((Ok(w), Ok(h))) if w > 0 && h > 0 => Some(Resize::Within(w, h)),
+                        _ => None,
+                    },
+                };
+                resize.map(Op::Resize).ok_or_else(|| format!("{s:?} is not a valid resize, must be resize=fit, WxH, or N%"))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for Op {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            Op::Crop { x, y, width, height } => write!(f, "crop={x},{y},{width},{height}"),
+            Op::Rotate(Rotation::Cw90) => f.write_str("rotate=90"),
+            Op::Rotate(Rotation::Cw180) => f.write_str("rotate=180"),
+            Op::Rotate(Rotation::Cw270) => f.write_str("rotate=270"),
+            Op::Flip(Flip::Horizontal) => f.write_str("flip=h"),
+            Op::Flip(Flip::Vertical) => f.write_str("flip=v"),
+            Op::Gray(GrayWeights::Rec709) => f.write_str("gray=rec709"),
+            Op::Gray(GrayWeights::Rec601) => f.write_str("gray=rec601"),
+            Op::Gray(GrayWeights::Average) => f.write_str("gray=average"),
+            Op::Resize(Resize::Fit) => f.write_str("resize=fit"),
+            Op::Resize(Resize::Within(w, h)) => write!(f, "resize={w}x{h}"),
+            Op::Resize(Resize::Percent(p)) => write!(f, "resize={p}%"),
+        }
+    }
+}
+
+/// Operations applied to an image in order, separated by `;` in their expression
+/// ```
+/// use image::Rgb;
+/// use viuwa_image::{FilterType, Image, Ops};
+/// let ops: Ops = "crop=1,0,2,2; rotate=90".parse().unwrap();
+/// assert_eq!(ops.to_string(), "crop=1,0,2,2;rotate=90");
+/// let image = Image::<Rgb<u8>>::from_raw((0..18).collect::<Vec<u8>>(), 3, 2).unwrap();
+/// let done = ops.apply(image, (3, 2), FilterType::Nearest.into());
+/// assert_eq!(done.dimensions(), (2, 2));
+/// // the bottom left of the crop is rotated to the top left
+/// assert_eq!(done.pixels()[0], [12, 13, 14]);
+/// assert!("rotate=45".parse::<Ops>().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ops(pub Vec<Op>);
+impl Ops {
+    /// Whether there are no operations
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+    /// Apply every operation in order, see [`Op::apply`]
+    #[inline]
+    pub fn apply<P: Pixel>(&self, image: Image<P>, fit: (usize, usize), filter: Filter) -> Image<P> {
+        self.0.iter().fold(image, |image, op| op.apply(image, fit, filter))
+    }
+}
+#[cfg(feature = "parse")]
+impl FromStr for Ops {
+    type Err = String;
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(';').map(str::trim).filter(|op| !op.is_empty()).map(str::parse).collect::<Result<_, _>>().map(Self)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Ops {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(::serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "parse")]
+impl ::core::fmt::Display for Ops {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        for (i, op) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(";")?;
+            }
+            ::core::fmt::Display::fmt(op, f)?;
+        }
+        Ok(())
+    }
+}
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Ops {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
@@ -93,6 +93,12 @@ macro_rules! def_Scalar {
             fn weight(self) -> Weight;
             /// Weight (float) as self
             fn scalar(weight: Weight) -> Self;
+            /// The type sums of this scalar are accumulated in, wide enough that area averaging can't overflow
+            type Sum: 'static + Clone + Copy + Send + Sync + Default + ::core::ops::AddAssign;
+            /// self widened to the sum type
+            fn widen(self) -> Self::Sum;
+            /// The rounded mean of a sum of `count` scalars
+            fn mean(sum: Self::Sum, count: u32) -> Self;
         }
     };
 }
@@ -122,6 +128,23 @@ mod compat_image {
         type Scalar = T;
         type Repr = [T; 2];
     }
+    macro_rules! impl_Alpha {
+        ($($T:ty),+) => {
+            $(
+                impl Alpha for ::image::Rgba<$T> {
+                    type Opaque = ::image::Rgb<$T>;
+                    #[inline(always)]
+                    fn alpha(repr: &Self::Repr) -> Weight { repr[3] as Weight / <$T>::MAX as Weight }
+                }
+                impl Alpha for ::image::LumaA<$T> {
+                    type Opaque = ::image::Luma<$T>;
+                    #[inline(always)]
+                    fn alpha(repr: &Self::Repr) -> Weight { repr[1] as Weight / <$T>::MAX as Weight }
+                }
+            )+
+        };
+    }
+    impl_Alpha!(u8, u16);
 }
 pub use self::compat_image::*;
 
@@ -274,7 +297,7 @@ mod compat_fir {
 pub use self::compat_fir::*;
 
 macro_rules! impl_Scalar_int {
-    ($($t:ty),*) => {
+    ($($t:ty => $sum:ty),*) => {
         $(
             impl Sealed for $t {}
             impl Scalar for $t {
@@ -286,6 +309,11 @@ macro_rules! impl_Scalar_int {
                 fn weight(self) -> Weight { self as Weight }
                 #[inline(always)]
                 fn scalar(weight: Weight) -> Self { weight as Self }
+                type Sum = $sum;
+                #[inline(always)]
+                fn widen(self) -> $sum { self as $sum }
+                #[inline(always)]
+                fn mean(sum: $sum, count: u32) -> Self { ((sum + (count / 2) as $sum) / count as $sum) as Self }
             }
         )*
     }
@@ -303,11 +331,16 @@ macro_rules! impl_Scalar_float {
                 fn weight(self) -> Weight { self as Weight }
                 #[inline(always)]
                 fn scalar(weight: Weight) -> Self { weight as Self }
+                type Sum = f64;
+                #[inline(always)]
+                fn widen(self) -> f64 { self as f64 }
+                #[inline(always)]
+                fn mean(sum: f64, count: u32) -> Self { (sum / count as f64) as Self }
             }
         )*
     }
 }
-impl_Scalar_int!(u8, u16, u32, u64, i8, i16, i32, i64, isize, usize);
+impl_Scalar_int!(u8 => u64, u16 => u64, u32 => u64, u64 => u128, usize => u128, i8 => i64, i16 => i64, i32 => i64, i64 => i128, isize => i128);
 impl_Scalar_float!(f32, f64);
 
 /// Representations of a pixel as an array of scalars
@@ -368,3 +401,23 @@ pub trait Pixel: Sized {
     /// This is to reserve `Default` trait for your pixels to use how you please.
     const DEFAULT: Self::Repr = Self::Repr::ZERO;
 }
+
+/// Pixels with an alpha channel after their color channels, that can be composited onto their [`Opaque`](Alpha::Opaque) pixels
+pub trait Alpha: Pixel {
+    /// The pixel without the alpha channel (e.g. `Rgb` for `Rgba`)
+    type Opaque: Pixel<Scalar = Self::Scalar>;
+    /// The opacity of the pixel, in `0.0..=1.0`
+    fn alpha(repr: &Self::Repr) -> Weight;
+}
+
+/// Linearly interpolate from `a` to `b` by `t` in `0.0..=1.0`, rounding to the nearest value for integer scalars
+#[inline(always)]
+pub fn lerp<T: Scalar>(a: T, b: T, t: Weight) -> T {
+    let v = a.weight() + (b.weight() - a.weight()) * t;
+    // integer scalars truncate a half to zero, so round them first
+    if T::scalar(0.5) == T::ZERO {
+        T::scalar(v.round())
+    } else {
+        T::scalar(v)
+    }
+}
@@ -95,7 +95,8 @@ pub fn par_sample<P: Pixel>(filter: Filter, src: ImageView<P>, mut dst: ImageVie
 }
 
 /// sample src image into dst image using a given filter, dst image may be uninitialized.
-/// If the source image is larger than the destination image * multiplicity, the source image will be downsampled with a nearest neighbor filter first.
+/// If the source image is larger than the destination image * multiplicity, the source image will be downsampled with a nearest neighbor filter first,
+/// or by [`area_average`] if it is more than [`AREA_THRESHOLD`] times larger and the filter isn't nearest neighbor.
 ///
 /// Currently, this means we give up on some memory savings, but it may significantly improve performance if the source image is very large.
 pub fn supersample<P: Pixel>(filter: Filter, src: ImageView<P>, dst: ImageViewMut<P>, multiplicity: f32) {
@@ -104,7 +105,11 @@ pub fn supersample<P: Pixel>(filter: Filter, src: ImageView<P>, dst: ImageViewMu
         let mut buf = unsafe {
             Image::<P>::new_uninit((dst.width as f32 * multiplicity) as usize, (dst.height as f32 * multiplicity) as usize)
         };
-        sample::<P>(FILTER_NEAREST, src, buf.view_mut());
+        if filter.support > 0. && Weight::min(s.ratio.0, s.ratio.1) > AREA_THRESHOLD {
+            area_average::<P>(src, buf.view_mut());
+        } else {
+            sample::<P>(FILTER_NEAREST, src, buf.view_mut());
+        }
         sample::<P>(filter, buf.view(), dst);
     } else {
         sample::<P>(filter, src, dst);
@@ -112,7 +117,8 @@ pub fn supersample<P: Pixel>(filter: Filter, src: ImageView<P>, dst: ImageViewMu
 }
 
 /// sample src image into dst image using a given filter, dst image may be uninitialized.
-/// If the source image is larger than the destination image * multiplicity, the source image will be downsampled with a nearest neighbor filter first.
+/// If the source image is larger than the destination image * multiplicity, the source image will be downsampled with a nearest neighbor filter first,
+/// or by [`par_area_average`] if it is more than [`AREA_THRESHOLD`] times larger and the filter isn't nearest neighbor.
 ///
 /// Currently, this means we give up on some memory savings, but it may significantly improve performance if the source image is very large.
 #[cfg(feature = "rayon")]
@@ -122,13 +128,190 @@ pub fn par_supersample<P: Pixel>(filter: Filter, src: ImageView<P>, dst: ImageVi
         let mut buf = unsafe {
             Image::<P>::new_uninit((dst.width as f32 * multiplicity) as usize, (dst.height as f32 * multiplicity) as usize)
         };
-        par_sample::<P>(FILTER_NEAREST, src, buf.view_mut());
+        if filter.support > 0. && Weight::min(s.ratio.0, s.ratio.1) > AREA_THRESHOLD {
+            par_area_average::<P>(src, buf.view_mut());
+        } else {
+            par_sample::<P>(FILTER_NEAREST, src, buf.view_mut());
+        }
         par_sample::<P>(filter, buf.view(), dst);
     } else {
         par_sample::<P>(filter, src, dst);
     }
 }
 
+/// The downscale factor beyond which supersampling averages the area of the source pixels instead of taking the nearest,
+/// since nearest neighbor aliases badly when skipping that many pixels
+pub const AREA_THRESHOLD: Weight = 8.;
+
+/// The range of source pixels that output pixel `i` covers on an axis, at least one pixel
+#[inline]
+fn area_span(i: usize, src_len: usize, dst_len: usize) -> (usize, usize) {
+    let start = i * src_len / dst_len;
+    (start, ((i + 1) * src_len / dst_len).max(start + 1))
+}
+
+/// Average the source pixels covered by one row of output pixels, accumulating the sums of the columns in `sums`
+#[inline]
+fn area_row<P: Pixel>(
+    src: &ImageView<P>,
+    (top, bottom): (usize, usize),
+    cols: &[(usize, usize)],
+    sums: &mut [<P::Scalar as Scalar>::Sum],
+    dst_row: &mut [P::Repr],
+) {
+    sums.fill(Default::default());
+    for row in src.rows().skip(top).take(bottom - top) {
+        for (&(left, right), sum) in zip(cols, sums.chunks_exact_mut(P::Repr::CHANNELS)) {
+            for px in &row[left..right] {
+                for (s, &c) in zip(sum.iter_mut(), px.as_slice()) {
+                    *s += c.widen();
+                }
+            }
+        }
+    }
+    for ((&(left, right), sum), dst_px) in zip(zip(cols, sums.chunks_exact(P::Repr::CHANNELS)), dst_row) {
+        let count = ((right - left) * (bottom - top)) as u32;
+        for (d, &s) in zip(dst_px.as_slice_mut(), sum) {
+            *d = Scalar::mean(s, count);
+        }
+    }
+}
+
+/// Downscale src image into dst image by averaging the source pixels each output pixel covers,
+/// accumulating integers without weights, dst image may be uninitialized.
+///
+/// Much faster than [`sample`] and without the aliasing of nearest neighbor for large downscales,
+/// but blocky when dst is not much smaller than src.
+/// ```
+/// use image::Rgb;
+/// use viuwa_image::{sample::area_average, Image};
+/// let src = Image::<Rgb<u8>>::from_raw((0..48).map(|i| if i % 6 < 3 { 0 } else { 255 }).collect::<Vec<u8>>(), 4, 4).unwrap();
+/// let mut dst = Image::<Rgb<u8>>::new_with(1, 1, [0; 3]);
+/// area_average(src.view(), dst.view_mut());
+/// assert_eq!(dst.pixels()[0], [128; 3]);
+/// ```
+pub fn area_average<P: Pixel>(src: ImageView<P>, mut dst: ImageViewMut<P>) {
+    let (w, h) = dst.dimensions();
+    let cols: Vec<_> = (0..w).map(|x| area_span(x, src.width, w)).collect();
+    let mut sums = vec![<P::Scalar as Scalar>::Sum::default(); w * P::Repr::CHANNELS];
+    for (y, dst_row) in dst.rows_mut().enumerate() {
+        area_row(&src, area_span(y, src.height, h), &cols, &mut sums, dst_row);
+    }
+}
+
+/// [`area_average`] with the rows of dst in parallel
+#[cfg(feature = "rayon")]
+pub fn par_area_average<P: Pixel>(src: ImageView<P>, mut dst: ImageViewMut<P>) {
+    let (w, h) = dst.dimensions();
+    let cols: Vec<_> = (0..w).map(|x| area_span(x, src.width, w)).collect();
+    dst.par_rows_mut().enumerate().for_each_init(
+        || vec![<P::Scalar as Scalar>::Sum::default(); w * P::Repr::CHANNELS],
+        |sums, (y, dst_row)| area_row(&src, area_span(y, src.height, h), &cols, sums, dst_row),
+    );
+}
+
+/// The standard deviation (in pixels) of the gaussian blur subtracted by [`unsharp_mask`]
+pub const SHARPEN_SIGMA: Weight = 1.;
+
+/// The normalized weights of a gaussian kernel, three standard deviations to either side
+fn gaussian_weights(sigma: Weight) -> Vec<Weight> {
+    let radius = (sigma * 3.).ceil() as i32;
+    let weights: Vec<Weight> = (-radius..=radius).map(|i| (-(i * i) as Weight / (2. * sigma * sigma)).exp()).collect();
+    let sum: Weight = weights.iter().sum();
+    weights.into_iter().map(|w| w / sum).collect()
+}
+
+/// Blur a row of pixels horizontally, clamping at the edges
+#[inline]
+fn blur_row<P: Pixel>(weights: &[Weight], src: &[P::Repr], dst: &mut [<P::Repr as PixelRepr>::Weights]) {
+    let (radius, last) = (weights.len() / 2, src.len() - 1);
+    for (x, dst_px) in dst.iter_mut().enumerate() {
+        *dst_px = <P::Repr as PixelRepr>::Weights::ZERO;
+        for (i, &w) in weights.iter().enumerate() {
+            let src_px = &src[(x + i).saturating_sub(radius).min(last)];
+            for (d, s) in zip(dst_px.as_slice_mut(), src_px.as_slice()) {
+                *d += s.weight() * w;
+            }
+        }
+    }
+}
+
+/// Blur the horizontally blurred rows around row `y` vertically into `buf`,
+/// then add the difference of each pixel of the row from its blur, scaled by `amount`
+#[inline]
+fn sharpen_row<P: Pixel>(
+    weights: &[Weight],
+    blurred: &[<P::Repr as PixelRepr>::Weights],
+    (y, row): (usize, &mut [P::Repr]),
+    buf: &mut [<P::Repr as PixelRepr>::Weights],
+    amount: Weight,
+) {
+    let (radius, width) = (weights.len() / 2, row.len());
+    let last = blurred.len() / width - 1;
+    buf.fill(<P::Repr as PixelRepr>::Weights::ZERO);
+    for (i, &w) in weights.iter().enumerate() {
+        let start = (y + i).saturating_sub(radius).min(last) * width;
+        for (buf_px, blurred_px) in zip(buf.iter_mut(), &blurred[start..start + width]) {
+            for (d, s) in zip(buf_px.as_slice_mut(), blurred_px.as_slice()) {
+                *d += s * w;
+            }
+        }
+    }
+    // integer scalars truncate, so round them first
+    let round = <P::Scalar as Scalar>::scalar(0.5) == <P::Scalar as Scalar>::ZERO;
+    for (px, blur) in zip(row, &*buf) {
+        for (p, &b) in zip(px.as_slice_mut(), blur.as_slice()) {
+            let v = p.weight() + (p.weight() - b) * amount;
+            let v = if round { v.round() } else { v };
+            *p = Scalar::scalar(v.clamp(P::Scalar::MIN.weight(), P::Scalar::MAX.weight()));
+        }
+    }
+}
+
+/// Sharpen an image in place with an unsharp mask, adding the difference of each pixel
+/// from a separable gaussian blur of [`SHARPEN_SIGMA`] scaled by `amount` (typically `0.0..=1.0`).
+///
+/// Recovers some of the perceived detail lost by downscaling, every channel (including alpha) is sharpened.
+/// ```
+/// use image::Luma;
+/// use viuwa_image::{sample::unsharp_mask, Image};
+/// let mut image = Image::<Luma<u8>>::from_raw([[50; 6], [200; 6]].concat(), 12, 1).unwrap();
+/// unsharp_mask(image.view_mut(), 1.);
+/// assert!(image.pixels()[5] < 50 && image.pixels()[6] > 200);
+/// assert_eq!((image.pixels()[0], image.pixels()[11]), (50, 200));
+/// ```
+pub fn unsharp_mask<P: Pixel>(mut image: ImageViewMut<P>, amount: Weight) {
+    if amount <= 0. || image.width == 0 || image.height == 0 {
+        return;
+    }
+    let weights = gaussian_weights(SHARPEN_SIGMA);
+    let width = image.width;
+    let mut blurred = vec![<P::Repr as PixelRepr>::Weights::ZERO; width * image.height];
+    for (src, dst) in zip(image.rows(), blurred.chunks_exact_mut(width)) {
+        blur_row::<P>(&weights, src, dst);
+    }
+    let mut buf = vec![<P::Repr as PixelRepr>::Weights::ZERO; width];
+    for row in image.rows_mut().enumerate() {
+        sharpen_row::<P>(&weights, &blurred, row, &mut buf, amount);
+    }
+}
+
+/// [`unsharp_mask`] with the rows of the image in parallel
+#[cfg(feature = "rayon")]
+pub fn par_unsharp_mask<P: Pixel>(mut image: ImageViewMut<P>, amount: Weight) {
+    if amount <= 0. || image.width == 0 || image.height == 0 {
+        return;
+    }
+    let weights = gaussian_weights(SHARPEN_SIGMA);
+    let width = image.width;
+    let mut blurred = vec![<P::Repr as PixelRepr>::Weights::ZERO; width * image.height];
+    image.par_rows().zip(blurred.par_chunks_exact_mut(width)).for_each(|(src, dst)| blur_row::<P>(&weights, src, dst));
+    image.par_rows_mut().enumerate().for_each_init(
+        || vec![<P::Repr as PixelRepr>::Weights::ZERO; width],
+        |buf, row| sharpen_row::<P>(&weights, &blurred, row, buf, amount),
+    );
+}
+
 /// A sampler that can be used to sample a single row of pixels from an image at a time, as an iterator.
 /// This is useful for streaming image processing.
 ///
@@ -173,11 +356,11 @@ impl<'a, P: Pixel> RowSampler<'a, P> {
         #[allow(invalid_value)]
         let vert_weights = vec![uninit!(Weight); sample.max_span.1];
         #[allow(invalid_value)]
-        let mut hori_weights = vec![uninit!(Weight); sample.max_span.0 * src.width];
+        let mut hori_weights = vec![uninit!(Weight); sample.max_span.0 * new_dimensions.0];
         #[allow(invalid_value)]
         let buf = vec![uninit!(<P::Repr as PixelRepr>::Weights); src.width];
         #[allow(invalid_value)]
-        let mut bounds = vec![uninit!(Bound); src.width];
+        let mut bounds = vec![uninit!(Bound); new_dimensions.0];
 
         // precompute horizontal weights
         hori_weights.chunks_exact_mut(sample.max_span.0).zip(bounds.iter_mut()).enumerate().for_each(
@@ -273,11 +456,11 @@ impl<'a, P: Pixel> ParRowSampler<'a, P> {
         #[allow(invalid_value)]
         let vert_weights = vec![uninit!(Weight); sample.max_span.1];
         #[allow(invalid_value)]
-        let mut hori_weights = vec![uninit!(Weight); sample.max_span.0 * src.width];
+        let mut hori_weights = vec![uninit!(Weight); sample.max_span.0 * new_dimensions.0];
         #[allow(invalid_value)]
         let buf = vec![uninit!(<P::Repr as PixelRepr>::Weights); src.width];
         #[allow(invalid_value)]
-        let mut bounds = vec![uninit!(Bound); src.width];
+        let mut bounds = vec![uninit!(Bound); new_dimensions.0];
 
         // precompute horizontal weights
         hori_weights.par_chunks_exact_mut(sample.max_span.0).zip(bounds.par_iter_mut()).enumerate().for_each(